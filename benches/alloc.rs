@@ -0,0 +1,98 @@
+//! Allocations per generation, once the buffers have grown to fit the pattern
+//!
+//! Run with `cargo bench --bench alloc`. A counting allocator wraps the system one, and each
+//! case steps the same seeded 256x256 soup for a few warmup generations before counting the
+//! allocations of the next ones. Collecting every generation into a fresh `Vec` is how the engine
+//! used to step, as a baseline. The few allocations left with reused buffers are the row cursors
+//! of the step, which don't grow with the pattern.
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, fill::Fill, Pos2};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting every allocation and reallocation
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ROUNDS: usize = 5;
+const WARMUP: usize = 20;
+const GENS: usize = 100;
+const SIZE: i32 = 256;
+
+/// A way of computing the next generation
+type Step = fn(&mut GameOfLife, &mut Vec<Pos2>);
+
+/// The allocations per generation after [`WARMUP`] generations, and the best time per generation
+/// in microseconds
+fn measure(soup: &[Pos2], step: Step) -> (f64, f64) {
+    let warm = || {
+        let mut game = GameOfLife::from_alive(soup.to_vec());
+        let mut scratch = Vec::new();
+        for _ in 0..WARMUP {
+            step(&mut game, &mut scratch);
+        }
+        (game, scratch)
+    };
+
+    let (mut game, mut scratch) = warm();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..GENS {
+        step(&mut game, &mut scratch);
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let time = common::best_of(ROUNDS, warm, |(mut game, mut scratch)| {
+        for _ in 0..GENS {
+            step(&mut game, &mut scratch);
+        }
+    });
+    (
+        allocations as f64 / GENS as f64,
+        time.as_secs_f64() * 1e6 / GENS as f64,
+    )
+}
+
+fn main() {
+    let soup = Fill::Random.cells_with_rng(SIZE, SIZE, &mut StdRng::seed_from_u64(0));
+    let cases: [(&str, Step); 3] = [
+        ("fresh vec", |game, _| {
+            *game = GameOfLife::from_alive(game.next_generation_iter().collect());
+        }),
+        ("next_generation", |game, _| game.next_generation()),
+        ("next_generation_into", |game, scratch| {
+            game.next_generation_into(scratch)
+        }),
+    ];
+
+    println!("{}x{} soup, {} generations", SIZE, SIZE, GENS);
+    for (name, step) in cases {
+        let (allocations, time) = measure(&soup, step);
+        println!(
+            "{:<22} {:>8.2} allocs/gen {:>10.1} us/gen",
+            name, allocations, time
+        );
+    }
+}
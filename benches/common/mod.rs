@@ -0,0 +1,18 @@
+//! Timing shared by the benchmarks, which are plain binaries rather than a bench harness
+
+use std::time::{Duration, Instant};
+
+/// The best time over `rounds` rounds of `run` on a fresh `setup()`
+///
+/// Only `run` is timed, so every round starts from the same state without paying for it.
+pub fn best_of<T>(rounds: usize, mut setup: impl FnMut() -> T, mut run: impl FnMut(T)) -> Duration {
+    (0..rounds)
+        .map(|_| {
+            let input = setup();
+            let start = Instant::now();
+            run(input);
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
@@ -0,0 +1,101 @@
+//! A micro-benchmark of decoding RLE with the byte-level scanner
+//!
+//! Run with `cargo bench --bench decode`. Each case decodes an encoded pattern and prints the
+//! best of a few timed rounds. The old decoder, which compiled a regex on every call and matched
+//! the runs with it, is kept here as a baseline and has to decode the same cells.
+
+mod common;
+
+use cgolrs::{
+    enc::{PositionEncoder, RunLengthEncoded},
+    fill::Fill,
+    patterns, Pos2,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+use std::hint::black_box;
+
+const ROUNDS: usize = 5;
+
+/// Decodes `value` the way the decoder did before the scanner
+fn old_decode(value: &str) -> Vec<Pos2> {
+    let re = Regex::new(r"(\d*)([bo$!])").unwrap();
+
+    let mut alive = Vec::new();
+    let mut cursor = Pos2 { x: 0, y: 0 };
+    'lines_loop: for mut line in value.split('\n') {
+        if let Some(i) = line.find('#') {
+            line = &line[..i];
+        }
+
+        for (_, [run_str, state]) in re.captures_iter(line).map(|x| x.extract()) {
+            let run = run_str.parse::<i32>().unwrap_or(1);
+            match state {
+                "!" => break 'lines_loop,
+                "o" => {
+                    for _ in 0..run {
+                        alive.push(cursor);
+                        cursor.x += 1;
+                    }
+                }
+                "b" => cursor.x += run,
+                "$" => {
+                    cursor.x = 0;
+                    cursor.y += run;
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+    alive
+}
+
+/// The best time over [`ROUNDS`] rounds of `decode` `iters` times, in microseconds per decoding
+fn time_decode(iters: usize, decode: impl Fn() -> Vec<Pos2>) -> f64 {
+    let time = common::best_of(
+        ROUNDS,
+        || (),
+        |()| {
+            for _ in 0..iters {
+                black_box(decode());
+            }
+        },
+    );
+    time.as_secs_f64() * 1e6 / iters as f64
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let cases = [
+        ("glider", patterns::find("glider").unwrap().cells(), 10_000),
+        (
+            "soup 512x512",
+            Fill::Random.cells_with_rng(512, 512, &mut rng),
+            20,
+        ),
+        (
+            "soup 1448x1448",
+            Fill::Random.cells_with_rng(1448, 1448, &mut rng),
+            5,
+        ),
+    ];
+    for (name, cells, iters) in &cases {
+        // the old decoder didn't read the position of the pattern
+        let rle = RunLengthEncoded::default()
+            .set_absolute_position(false)
+            .encode(cells);
+        let decode = || RunLengthEncoded::default().decode(black_box(&rle));
+        assert_eq!(decode(), old_decode(&rle), "{}", name);
+
+        let time = time_decode(*iters, decode);
+        let old_time = time_decode(*iters, || old_decode(black_box(&rle)));
+        println!(
+            "{:<16} {:>8} cells {:>10.1} us | old {:>10.1} us {:>6.2}x",
+            name,
+            cells.len(),
+            time,
+            old_time,
+            old_time / time
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! A micro-benchmark of encoding RLE, whose runs are written without allocating
+//!
+//! Run with `cargo bench --bench encode`. Each case encodes a seeded soup into a reused buffer
+//! and prints the best of a few timed rounds, along with the allocations of one encoding. The old
+//! encoder, which built the output in a `String` with a `format!` per run, is kept here as a
+//! baseline and has to produce the same bytes.
+
+mod common;
+
+use cgolrs::{enc::RunLengthEncoded, fill::Fill, transform, Pos2};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting every allocation and reallocation
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ROUNDS: usize = 5;
+const ITERS: usize = 20;
+/// The line width of the default encoder
+const LINE_WIDTH: usize = 70;
+
+/// Appends the runs to a `String`, formatting each one on its own like the old encoder
+struct OldRuns {
+    sequence: String,
+    line_len: usize,
+}
+impl OldRuns {
+    fn push_run(&mut self, run: i32, c: char) {
+        let append = match run {
+            0 => String::new(),
+            1 => c.to_string(),
+            n => format!("{}{}", n, c),
+        };
+        if self.line_len > 0 && self.line_len + append.len() > LINE_WIDTH {
+            self.sequence.push('\n');
+            self.line_len = 0;
+        }
+        self.line_len += append.len();
+        self.sequence.push_str(&append);
+    }
+}
+
+/// Encodes `cells` the way the encoder did before it wrote to an [`io::Write`](std::io::Write)
+fn old_encode(cells: &[Pos2]) -> String {
+    let tl = transform::top_left(cells);
+    let mut runs = OldRuns {
+        sequence: String::new(),
+        line_len: 0,
+    };
+    let mut last = tl - Pos2::new(1, 0);
+    let mut alive_run = 0;
+    for pos in cells {
+        if last.y == pos.y && last.x + 1 == pos.x {
+            alive_run += 1;
+            last = *pos;
+            continue;
+        }
+        let lines_run = pos.y - last.y;
+        let dead_run = match lines_run {
+            0 => pos.x - last.x - 1,
+            _ => pos.x - tl.x,
+        };
+        runs.push_run(alive_run, 'o');
+        runs.push_run(lines_run, '$');
+        runs.push_run(dead_run, 'b');
+        alive_run = 1;
+        last = *pos;
+    }
+    runs.push_run(alive_run, 'o');
+    runs.sequence.push('!');
+    format!("{}\n{}\n", "x = 0, y = 0, rule = 23/3", runs.sequence)
+}
+
+/// The best time over [`ROUNDS`] rounds of `encode` [`ITERS`] times in microseconds per
+/// encoding, and the allocations of one encoding
+fn time_encode(mut encode: impl FnMut()) -> (f64, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    encode();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let time = common::best_of(
+        ROUNDS,
+        || (),
+        |()| {
+            for _ in 0..ITERS {
+                encode();
+            }
+        },
+    );
+    (time.as_secs_f64() * 1e6 / ITERS as f64, allocations)
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let cases = [
+        (
+            "soup 512x512",
+            Fill::Random.cells_with_rng(512, 512, &mut rng),
+        ),
+        (
+            "soup 1448x1448",
+            Fill::Random.cells_with_rng(1448, 1448, &mut rng),
+        ),
+        ("all 512x512", Fill::All.cells(512, 512)),
+        ("checkerboard 512x512", Fill::Checkerboard.cells(512, 512)),
+    ];
+    // the old encoder didn't write the position of the pattern
+    let encoder = RunLengthEncoded::default().set_absolute_position(false);
+    for (name, cells) in &cases {
+        let mut buf = Vec::new();
+        encoder.encode_to(cells, &mut buf).unwrap();
+        assert_eq!(buf, old_encode(cells).as_bytes(), "{}", name);
+
+        let (time, allocations) = time_encode(|| {
+            buf.clear();
+            encoder.encode_to(black_box(cells), &mut buf).unwrap();
+            black_box(&buf);
+        });
+        let (old_time, old_allocations) = time_encode(|| {
+            black_box(old_encode(black_box(cells)));
+        });
+        println!(
+            "{:<22} {:>8} cells {:>10.1} us {:>8} allocs | old {:>10.1} us {:>8} allocs {:>6.2}x",
+            name,
+            cells.len(),
+            time,
+            allocations,
+            old_time,
+            old_allocations,
+            old_time / time
+        );
+    }
+}
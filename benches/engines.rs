@@ -0,0 +1,67 @@
+//! The dense engine against the sparse one on a random soup
+//!
+//! Run with `cargo bench --bench engines`. Both engines step the same seeded 256x256 soup, and
+//! the dense grid leaves enough margin around it that nothing reaches its edge, so they have to
+//! end up with the same cells.
+
+mod common;
+
+use cgolrs::{
+    engine::{DenseGrid, Engine, GameOfLife},
+    fill::Fill,
+    Pos2, Rect,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+const ROUNDS: usize = 5;
+const GENS: usize = 100;
+/// Farther than anything can travel in [`GENS`] generations
+const MARGIN: i32 = GENS as i32;
+const SIZE: i32 = 256;
+
+/// Steps `engine` for [`GENS`] generations, returning the best time per generation in
+/// microseconds and the cells at the end
+fn time_engine<E: Engine + Clone>(engine: &E) -> (f64, Vec<Pos2>) {
+    let time = common::best_of(
+        ROUNDS,
+        || engine.clone(),
+        |mut engine| {
+            for _ in 0..GENS {
+                engine.next_generation();
+            }
+        },
+    );
+    let mut engine = engine.clone();
+    for _ in 0..GENS {
+        engine.next_generation();
+    }
+    (
+        time.as_secs_f64() * 1e6 / GENS as f64,
+        engine.iter().collect(),
+    )
+}
+
+fn main() {
+    let soup = Fill::Random.cells_with_rng(SIZE, SIZE, &mut StdRng::seed_from_u64(0));
+    let bounds = Rect::from_size(
+        Pos2::new(-MARGIN, -MARGIN),
+        (SIZE + 2 * MARGIN) as u32,
+        (SIZE + 2 * MARGIN) as u32,
+    );
+    let (sparse_time, sparse) = time_engine(&GameOfLife::from_alive(soup.clone()));
+    let (dense_time, dense) = time_engine(&DenseGrid::from_alive(bounds, soup));
+    assert_eq!(
+        sparse, dense,
+        "the engines disagree after {} generations",
+        GENS
+    );
+
+    println!("{}x{} soup, {} generations", SIZE, SIZE, GENS);
+    println!("{:<8} {:>10.1} us/gen", "sparse", sparse_time);
+    println!(
+        "{:<8} {:>10.1} us/gen {:>6.1}x",
+        "dense",
+        dense_time,
+        sparse_time / dense_time
+    );
+}
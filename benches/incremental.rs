@@ -0,0 +1,74 @@
+//! Incremental steps against full ones, on a field of ash that barely changes
+//!
+//! Run with `cargo bench --bench incremental`. The field is a grid of blocks with a blinker in
+//! place of every 15th one, so about 95% of the cells are stable and an incremental step only
+//! recomputes the rows around the blinkers.
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, Pos2};
+
+const ROUNDS: usize = 5;
+const GENS: usize = 200;
+/// The objects per side of the field
+const SIDE: i32 = 40;
+/// The distance between objects, far enough apart that they never interact
+const SPACING: i32 = 8;
+
+/// A `SIDE x SIDE` grid of blocks, where every 15th one is a blinker instead
+fn ash_field() -> Vec<Pos2> {
+    let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    let blinker = [(0, 1), (1, 1), (2, 1)];
+    let mut cells = (0..SIDE * SIDE)
+        .flat_map(|i| {
+            let corner = Pos2::new(i % SIDE * SPACING, i / SIDE * SPACING);
+            let object = if i % 15 == 0 {
+                &blinker[..]
+            } else {
+                &block[..]
+            };
+            object
+                .iter()
+                .map(move |&offset| corner + Pos2::from(offset))
+        })
+        .collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells
+}
+
+/// The best time per generation over [`GENS`] generations, in microseconds
+fn time_steps(cells: &[Pos2], incremental: bool) -> f64 {
+    let time = common::best_of(
+        ROUNDS,
+        || {
+            let mut game = GameOfLife::from_alive(cells.to_vec());
+            game.set_incremental(incremental);
+            game
+        },
+        |mut game| game.step_n(GENS as u64),
+    );
+    time.as_secs_f64() * 1e6 / GENS as f64
+}
+
+fn main() {
+    let cells = ash_field();
+    let mut game = GameOfLife::from_alive(cells.clone());
+    game.next_generation();
+    let stable = cells.iter().filter(|&&pos| game.is_alive(pos)).count();
+
+    let full = time_steps(&cells, false);
+    let incremental = time_steps(&cells, true);
+    println!(
+        "ash field, {} cells, {:.1}% stable, {} generations",
+        cells.len(),
+        stable as f64 * 100.0 / cells.len() as f64,
+        GENS
+    );
+    println!("{:<12} {:>10.1} us/gen", "full", full);
+    println!(
+        "{:<12} {:>10.1} us/gen {:>6.2}x",
+        "incremental",
+        incremental,
+        full / incremental
+    );
+}
@@ -0,0 +1,205 @@
+//! The parallel step, split into chunks on a pattern whose cost is badly skewed, and run on a
+//! worker pool on a pattern too small to hide the cost of threads
+//!
+//! Run with `cargo bench --bench parallel`. The first pattern is a gun next to a distant field of
+//! ash, where equal chunks per thread leave some threads idle while others finish theirs. Both
+//! splits below spawn their threads every generation and compute each chunk from just the rows
+//! around it, so they only differ in how the chunks are handed out. The engine's own parallel
+//! step is timed with them for reference.
+//!
+//! The second is a 64x64 soup over many generations, where spawning threads every generation
+//! costs more than computing it, against the engine's pool of threads that stay alive.
+
+mod common;
+
+use cgolrs::{
+    engine::{GameOfLife, DEFAULT_MIN_CHUNK_CELLS},
+    fill::Fill,
+    patterns, Pos2,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+const ROUNDS: usize = 5;
+const GENS: usize = 100;
+const THREADS: usize = 4;
+/// The generations of the small soup, enough for the per-generation overhead to add up
+const SMALL_GENS: usize = 10_000;
+
+/// A Gosper glider gun and a 64x64 field of blinkers far below and to the right of it
+fn gun_and_ash() -> Vec<Pos2> {
+    let gun = patterns::find("gosper-glider-gun").unwrap().cells();
+    let ash = (0..64)
+        .flat_map(|y| (0..64).map(move |x| Pos2::new(x * 5 + 500, y * 5 + 500)))
+        .flat_map(|pos| (0..3).map(move |dx| pos + Pos2::new(dx, 0)));
+    let mut cells = gun.into_iter().chain(ash).collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells
+}
+
+/// Splits sorted cells into `chunks` runs of rows with about as many cells each
+fn row_chunks(alive: &[Pos2], chunks: usize) -> Vec<Range<i32>> {
+    let target = alive.len().div_ceil(chunks).max(1);
+    let mut starts = vec![i32::MIN];
+    let mut i = target;
+    while i < alive.len() {
+        // move forward to the start of the next row
+        while i < alive.len() && alive[i].y == alive[i - 1].y {
+            i += 1;
+        }
+        if i < alive.len() {
+            starts.push(alive[i].y);
+        }
+        i += target;
+    }
+    let ends = starts.iter().skip(1).copied().chain([i32::MAX]);
+    starts.iter().zip(ends).map(|(&lo, hi)| lo..hi).collect()
+}
+
+/// The next generation of the cells in `rows`, computed from the rows around them only
+fn chunk_generation(alive: &[Pos2], rows: &Range<i32>) -> Vec<Pos2> {
+    let lo = alive.partition_point(|pos| pos.y < rows.start.saturating_sub(1));
+    let hi = alive.partition_point(|pos| pos.y <= rows.end);
+    GameOfLife::from_alive(alive[lo..hi].to_vec())
+        .next_generation_iter()
+        .filter(|pos| rows.contains(&pos.y))
+        .collect()
+}
+
+/// The next generation with one chunk per thread
+fn step_naive(alive: &[Pos2]) -> Vec<Pos2> {
+    let chunks = row_chunks(alive, THREADS);
+    thread::scope(|scope| {
+        let handles = chunks
+            .iter()
+            .map(|rows| scope.spawn(move || chunk_generation(alive, rows)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    })
+}
+
+/// The next generation with four chunks per thread, which threads take until none are left
+fn step_balanced(alive: &[Pos2]) -> Vec<Pos2> {
+    let chunks = row_chunks(alive, THREADS * 4);
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let handles = (0..THREADS)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let c = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(rows) = chunks.get(c) else {
+                            break done;
+                        };
+                        done.push((c, chunk_generation(alive, rows)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    // chunks are in row order, so putting them back in order keeps the output sorted
+    results.sort_unstable_by_key(|&(c, _)| c);
+    results.into_iter().flat_map(|(_, cells)| cells).collect()
+}
+
+/// The best time per generation over `gens` generations of `step`, in microseconds
+fn time_split(cells: &[Pos2], gens: usize, step: fn(&[Pos2]) -> Vec<Pos2>) -> f64 {
+    let time = common::best_of(
+        ROUNDS,
+        || cells.to_vec(),
+        |mut alive| {
+            for _ in 0..gens {
+                alive = step(&alive);
+            }
+        },
+    );
+    time.as_secs_f64() * 1e6 / gens as f64
+}
+
+/// The best time per generation over `gens` generations of the engine on [`THREADS`] threads, in
+/// microseconds
+fn time_engine(cells: &[Pos2], gens: usize, min_chunk_cells: usize) -> f64 {
+    let time = common::best_of(
+        ROUNDS,
+        || {
+            let mut game = GameOfLife::from_alive(cells.to_vec());
+            game.set_threads(THREADS);
+            game.set_min_chunk_cells(min_chunk_cells);
+            game
+        },
+        |mut game| game.step_n(gens as u64),
+    );
+    time.as_secs_f64() * 1e6 / gens as f64
+}
+
+/// Times the naive and balanced splits of the gun and ash field
+fn partition() {
+    let cells = gun_and_ash();
+    // both splits have to agree with the serial engine
+    let mut serial = GameOfLife::from_alive(cells.clone());
+    serial.next_generation();
+    assert_eq!(step_naive(&cells), serial.alive());
+    assert_eq!(step_balanced(&cells), serial.alive());
+
+    let naive = time_split(&cells, GENS, step_naive);
+    let balanced = time_split(&cells, GENS, step_balanced);
+    let engine = time_engine(&cells, GENS, DEFAULT_MIN_CHUNK_CELLS);
+
+    println!(
+        "gun and ash, {} cells, {} threads, {} generations",
+        cells.len(),
+        THREADS,
+        GENS
+    );
+    println!("{:<10} {:>10.1} us/gen", "naive", naive);
+    println!(
+        "{:<10} {:>10.1} us/gen {:>6.2}x",
+        "balanced",
+        balanced,
+        naive / balanced
+    );
+    println!(
+        "{:<10} {:>10.1} us/gen {:>6.2}x",
+        "engine",
+        engine,
+        naive / engine
+    );
+}
+
+/// Times spawning threads every generation against the worker pool, on a small soup
+fn pool() {
+    let soup = Fill::Random.cells_with_rng(64, 64, &mut StdRng::seed_from_u64(0));
+    // every chunk is worth a thread, so both pay for the threads at every generation
+    let spawn = time_split(&soup, SMALL_GENS, step_balanced);
+    let pool = time_engine(&soup, SMALL_GENS, 1);
+
+    println!(
+        "64x64 soup, {} threads, {} generations",
+        THREADS, SMALL_GENS
+    );
+    println!("{:<10} {:>10.1} us/gen", "spawn", spawn);
+    println!(
+        "{:<10} {:>10.1} us/gen {:>6.2}x",
+        "pool",
+        pool,
+        spawn / pool
+    );
+}
+
+fn main() {
+    partition();
+    println!();
+    pool();
+}
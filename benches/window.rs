@@ -0,0 +1,47 @@
+//! Iterating a console-sized window over patterns of growing population
+//!
+//! Run with `cargo bench --bench window`. A 200x50 window in the middle of seeded soups of
+//! growing size is iterated the way a frame is rendered, against filtering every alive cell,
+//! which is how windows used to be iterated. Only the filter should grow with the population.
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, fill::Fill, Pos2, Rect};
+use rand::{rngs::StdRng, SeedableRng};
+use std::hint::black_box;
+
+const ROUNDS: usize = 5;
+const FRAMES: usize = 100;
+
+/// The best time per frame over [`FRAMES`] frames of counting the cells `iter` yields, in
+/// microseconds
+fn time_frames<I: Iterator>(iter: impl Fn() -> I) -> f64 {
+    let time = common::best_of(
+        ROUNDS,
+        || (),
+        |()| {
+            for _ in 0..FRAMES {
+                black_box(iter().count());
+            }
+        },
+    );
+    time.as_secs_f64() * 1e6 / FRAMES as f64
+}
+
+fn main() {
+    println!("200x50 window, {} frames", FRAMES);
+    for size in [256, 1024, 4096] {
+        let soup = Fill::Random.cells_with_rng(size, size, &mut StdRng::seed_from_u64(0));
+        let game = GameOfLife::from_alive(soup);
+        let rect = Rect::from_size(Pos2::new(size / 2 - 100, size / 2 - 25), 200, 50);
+
+        let window = time_frames(|| game.window(rect).iter());
+        let filter = time_frames(|| game.alive().iter().filter(|&&pos| rect.contains(pos)));
+        println!(
+            "{:>9} cells {:>8.1} us/frame {:>10.1} us/frame filtered",
+            game.alive_count(),
+            window,
+            filter
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! Any input to the RLE decoder, which has to return sorted cells or an error without panicking
+#![no_main]
+
+use cgolrs::enc::{Format, RunLengthEncoded};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let (cells, dying) = RunLengthEncoded::default().decode_states(&text);
+    assert!(cells.windows(2).all(|w| w[0] < w[1]));
+    assert!(dying.windows(2).all(|w| w[0].0 < w[1].0));
+
+    if let Ok(pattern) = Format::Rle.decode(data) {
+        assert_eq!(pattern.cells, cells);
+    }
+});
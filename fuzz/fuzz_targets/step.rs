@@ -0,0 +1,16 @@
+//! Arbitrary games, which have to step the same on one thread as on many
+#![no_main]
+
+use cgolrs::engine::GameOfLife;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|game: GameOfLife| {
+    let mut serial = game.clone();
+    let mut parallel = game;
+    parallel.set_min_chunk_cells(1);
+    for _ in 0..4 {
+        serial.next_generation();
+        parallel.next_generation_parallel(3);
+        assert_eq!(serial.alive(), parallel.alive());
+    }
+});
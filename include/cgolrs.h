@@ -0,0 +1,94 @@
+#ifndef CGOLRS_H
+#define CGOLRS_H
+
+/* Generated with cbindgen from src/ffi.rs, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of the functions that don't return a value
+typedef enum cgol_status {
+  CGOL_STATUS_OK = 0,
+  // A pointer argument was null
+  CGOL_STATUS_NULL_POINTER = 1,
+  // The engine panicked, in which case the game may be in any valid state
+  CGOL_STATUS_PANIC = 2,
+} cgol_status;
+
+// A game of life, with its rule and its generation, called `cgol_t` in C
+typedef struct cgol_t cgol_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+// Decodes an RLE pattern, along with its rule, generation and the dying cells of Generations
+// rules if it records them
+//
+// ## Returns
+// A new game freed with [`cgol_free`], or null if `rle` is null, not UTF-8, or has a rule the
+// engine doesn't support
+//
+// # Safety
+// `rle` must be null or a nul-terminated string
+cgol_t *cgol_new_from_rle(const char *rle);
+
+// Frees a game, where null is ignored
+//
+// # Safety
+// `handle` must be null or a game that hasn't been freed yet
+void cgol_free(cgol_t *handle);
+
+// Computes the next `n` generations
+//
+// # Safety
+// `handle` must be null or a valid game
+cgol_status cgol_step(cgol_t *handle, uint64_t n);
+
+// The number of alive cells, or -1 if `handle` is null
+//
+// Like [`cgol_cells`], this counts the dead cells if the background is alive.
+//
+// # Safety
+// `handle` must be null or a valid game
+int64_t cgol_population(const cgol_t *handle);
+
+// The alive cells, sorted by y and then x, as their x and y coordinates one after the other
+//
+// `*out` is set to a buffer of `*len` coordinates, twice the number of cells, which is freed
+// with [`cgol_cells_free`]. Without cells it's null with a length of 0. Like
+// [`GameOfLife::alive`], these are the dead cells if the background is alive.
+//
+// # Safety
+// `handle` must be null or a valid game, and `out` and `len` null or valid for writes
+cgol_status cgol_cells(const cgol_t *handle, int32_t **out, size_t *len);
+
+// Frees the coordinates returned by [`cgol_cells`], where null is ignored
+//
+// # Safety
+// `cells` must be null or a buffer from [`cgol_cells`] that hasn't been freed yet, with the
+// length it was returned with
+void cgol_cells_free(int32_t *cells, size_t len);
+
+// Encodes the game as RLE, with its rule, generation and dying cells
+//
+// ## Returns
+// A nul-terminated string freed with [`cgol_string_free`], or null if `handle` is null
+//
+// # Safety
+// `handle` must be null or a valid game
+char *cgol_to_rle(const cgol_t *handle);
+
+// Frees a string returned by [`cgol_to_rle`], where null is ignored
+//
+// # Safety
+// `rle` must be null or a string from [`cgol_to_rle`] that hasn't been freed yet
+void cgol_string_free(char *rle);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CGOLRS_H */
@@ -0,0 +1,168 @@
+//! Python bindings of the engine, built into the `cgolrs` module with
+//! [maturin](https://www.maturin.rs/)
+//!
+//! ```python
+//! import cgolrs
+//!
+//! game = cgolrs.GameOfLife.from_rle("x = 3, y = 3\nbo$2bo$3o!")
+//! game.step(4)
+//! print(game.alive)  # [(2, 1), (3, 2), (1, 3), (2, 3), (3, 3)]
+//! print(game.analyze())  # spaceship, period 4, moving (1, 1) at c/4 diagonal
+//! ```
+// the `#[pymethods]` of pyo3 0.22 convert the `PyResult`s returned by fallible methods into
+// themselves
+#![allow(clippy::useless_conversion)]
+
+use cgolrs::{
+    enc::{Format, PositionEncoder, RunLengthEncoded},
+    engine, Pos2, Rect,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// The largest window [`GameOfLife::window`] builds, since every cell becomes a Python object
+const MAX_WINDOW_CELLS: u64 = 1 << 24;
+
+/// A game of life, with its rule and its generation
+#[pyclass(module = "cgolrs")]
+pub struct GameOfLife {
+    game: engine::GameOfLife,
+}
+
+#[pymethods]
+impl GameOfLife {
+    /// Decodes an RLE pattern, along with its rule, generation and the dying cells of
+    /// Generations rules if it records them
+    #[staticmethod]
+    fn from_rle(rle: &str) -> PyResult<Self> {
+        let pattern = Format::Rle
+            .decode(rle)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut game = engine::GameOfLife::from_alive(pattern.cells);
+        game.set_rule(pattern.rule.unwrap_or_default())
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        game.set_dying(pattern.dying);
+        game.set_generation(pattern.generation);
+        Ok(Self { game })
+    }
+
+    /// Computes the next `n` generations
+    #[pyo3(signature = (n = 1))]
+    fn step(&mut self, n: u64) {
+        self.game.step_n(n);
+    }
+
+    /// The alive cells as `(x, y)` tuples, sorted by y and then x
+    ///
+    /// Like the engine, these are the dead cells if the background is alive.
+    #[getter]
+    fn alive(&self) -> Vec<(i32, i32)> {
+        self.game.alive().iter().map(|pos| (pos.x, pos.y)).collect()
+    }
+
+    /// The number of alive cells, or of the dead ones if the background is alive like `alive`
+    #[getter]
+    fn population(&self) -> usize {
+        self.game.alive_count()
+    }
+
+    /// The current generation, counting from the one the pattern was saved at
+    #[getter]
+    fn generation(&self) -> u64 {
+        self.game.generation()
+    }
+
+    /// Encodes the game as RLE, with its rule, generation and dying cells
+    fn to_rle(&self) -> String {
+        let game = &self.game;
+        RunLengthEncoded::default()
+            .with_rule(&game.rule())
+            .set_generation(game.generation())
+            .set_dying(game.dying().to_vec())
+            .encode(&game.pattern_cells())
+    }
+
+    /// Whether each cell from `(x0, y0)` up to but excluding `(x1, y1)` is alive, as a list of
+    /// rows
+    fn window(&self, x0: i32, y0: i32, x1: i32, y1: i32) -> PyResult<Vec<Vec<bool>>> {
+        if x1 < x0 || y1 < y0 {
+            return Err(PyValueError::new_err("the window ends before it starts"));
+        }
+        let rect = Rect::new(Pos2::new(x0, y0), Pos2::new(x1, y1));
+        if rect.area() > MAX_WINDOW_CELLS {
+            return Err(PyValueError::new_err(format!(
+                "the window has more than {} cells",
+                MAX_WINDOW_CELLS
+            )));
+        }
+        let bitmap = self.game.window(rect).to_bitmap();
+        Ok((0..bitmap.height)
+            .map(|y| bitmap.iter_row(y).collect())
+            .collect())
+    }
+
+    /// Steps a copy of the game until it repeats, for up to `max_generations` generations, and
+    /// tells whether it's a still life, an oscillator or a spaceship
+    #[pyo3(signature = (max_generations = 1000))]
+    fn analyze(&self, max_generations: usize) -> Analysis {
+        Analysis(self.game.clone().analyze(max_generations))
+    }
+
+    fn __len__(&self) -> usize {
+        self.game.alive_count()
+    }
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.game)
+    }
+}
+
+/// What kind of pattern a game is, returned by `GameOfLife.analyze`
+#[pyclass(module = "cgolrs", frozen)]
+pub struct Analysis(engine::Analysis);
+
+#[pymethods]
+impl Analysis {
+    /// `"still life"`, `"oscillator"`, `"spaceship"`, or `"unresolved"` if the pattern died
+    /// out or didn't repeat in time
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.0 {
+            engine::Analysis::StillLife => "still life",
+            engine::Analysis::Oscillator { .. } => "oscillator",
+            engine::Analysis::Spaceship { .. } => "spaceship",
+            engine::Analysis::Unresolved => "unresolved",
+        }
+    }
+    /// The generations it takes to repeat, or `None` if unresolved
+    #[getter]
+    fn period(&self) -> Option<usize> {
+        self.0.period()
+    }
+    /// How far the pattern moves every period as `(x, y)`, or `None` if unresolved
+    #[getter]
+    fn displacement(&self) -> Option<(i32, i32)> {
+        self.0.displacement().map(|pos| (pos.x, pos.y))
+    }
+    /// The speed of a spaceship, like `"c/4 diagonal"`, or `None` for anything else
+    #[getter]
+    fn speed(&self) -> Option<String> {
+        match self.0 {
+            engine::Analysis::Spaceship { speed, .. } => Some(speed.to_string()),
+            _ => None,
+        }
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        format!("<Analysis: {}>", self.0)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "cgolrs")]
+fn cgolrs_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<GameOfLife>()?;
+    module.add_class::<Analysis>()?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+"""Runs against the built module, after `maturin develop` in the python directory"""
+
+import pytest
+
+import cgolrs
+
+GLIDER = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"
+
+
+def test_gliders_move_diagonally():
+    game = cgolrs.GameOfLife.from_rle(GLIDER)
+    start = game.alive
+    assert start == [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+
+    game.step(4)
+    assert game.alive == [(x + 1, y + 1) for x, y in start]
+    assert game.generation == 4
+    assert game.population == len(game) == 5
+
+
+def test_rle_round_trips():
+    game = cgolrs.GameOfLife.from_rle(GLIDER)
+    game.step(3)
+    resumed = cgolrs.GameOfLife.from_rle(game.to_rle())
+    assert resumed.generation == 3
+    assert resumed.alive == game.alive
+
+
+def test_window():
+    game = cgolrs.GameOfLife.from_rle(GLIDER)
+    assert game.window(0, 0, 3, 3) == [
+        [False, True, False],
+        [False, False, True],
+        [True, True, True],
+    ]
+    assert game.window(5, 5, 5, 8) == [[], [], []]
+    with pytest.raises(ValueError):
+        game.window(3, 3, 0, 0)
+
+
+def test_analyze():
+    analysis = cgolrs.GameOfLife.from_rle(GLIDER).analyze()
+    assert analysis.kind == "spaceship"
+    assert analysis.period == 4
+    assert analysis.displacement == (1, 1)
+    assert analysis.speed == "c/4 diagonal"
+    assert str(analysis) == "spaceship, period 4, moving (1, 1) at c/4 diagonal"
+
+    blinker = cgolrs.GameOfLife.from_rle("x = 3, y = 1\n3o!").analyze()
+    assert (blinker.kind, blinker.period, blinker.speed) == ("oscillator", 2, None)
+
+    # analyzing doesn't step the game itself
+    game = cgolrs.GameOfLife.from_rle(GLIDER)
+    game.analyze()
+    assert game.generation == 0
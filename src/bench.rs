@@ -0,0 +1,149 @@
+//! The `bench` subcommand, which measures the speed of the engines on this machine
+//!
+//! Every engine and thread count starts over from the same seeded soup, runs a few untimed
+//! warmup generations and is then timed over `--gens` generations. Only computing the
+//! generations is timed, through the same [`stats::timed`] phases as a run.
+
+use crate::commands;
+use cgolrs::{
+    engine::{self, GameOfLife, GenerationEvent},
+    stats::{self, Phase, Recorder},
+    Pos2, Rect,
+};
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+/// Adds up the time spent computing generations, the only phase a benchmark times
+#[derive(Default)]
+struct ComputeTime(Duration);
+impl Recorder for ComputeTime {
+    fn record(&mut self, _event: &GenerationEvent<'_>) {}
+
+    fn times_phases(&self) -> bool {
+        true
+    }
+    fn add_phase(&mut self, phase: Phase, time: Duration) {
+        if phase == Phase::Compute {
+            self.0 += time;
+        }
+    }
+}
+
+/// The timing of one engine at one thread count
+struct BenchResult {
+    engine: String,
+    threads: usize,
+    time: Duration,
+    /// The generations per second of the single-threaded run of the same engine
+    baseline: f64,
+}
+impl BenchResult {
+    fn gens_per_sec(&self, gens: u64) -> f64 {
+        gens as f64 / self.time.as_secs_f64()
+    }
+}
+
+/// Times `gens` generations of the scan engine with `threads` threads after `warmup` untimed ones
+fn time_scan(alive: &[Pos2], threads: usize, warmup: u64, gens: u64) -> Duration {
+    let mut game = GameOfLife::from_unsorted(alive.to_vec());
+    game.set_threads(threads);
+    game.step_n(warmup);
+    let mut compute = ComputeTime::default();
+    for _ in 0..gens {
+        stats::timed(&mut compute, Phase::Compute, || game.next_generation());
+    }
+    compute.0
+}
+
+/// Times `gens` generations of HashLife in one step after `warmup` untimed ones, since it only
+/// pays off over long spans
+fn time_hashlife(alive: &[Pos2], warmup: u64, gens: u64) -> Duration {
+    let mut life = engine::HashLife::from_alive(alive);
+    life.advance(warmup);
+    let mut compute = ComputeTime::default();
+    stats::timed(&mut compute, Phase::Compute, || life.advance(gens));
+    compute.0
+}
+
+/// Runs the `bench` subcommand: prints a table of the speed of each engine and thread count,
+/// with the speedup over running single-threaded
+pub fn run(args: &commands::BenchArgs) -> Result<(), crate::setup::CommandError> {
+    let alive = if args.custom_pattern() {
+        if crate::setup::print_lists(&args.pattern()) {
+            return Ok(());
+        }
+        crate::args_to_alive(&args.pattern())?.cells
+    } else {
+        println!("soup: {0}x{0} of seed {1}", args.size(), args.seed());
+        let rect = Rect::from_size(Pos2::zero(), args.size(), args.size());
+        GameOfLife::random(rect, 0.5, args.seed())?.alive().to_vec()
+    };
+    let (warmup, gens) = (args.warmup(), args.generations());
+    println!("alive: {}", alive.len());
+    println!(
+        "timing {} generations after {} warmup generations",
+        gens, warmup
+    );
+
+    let mut results = Vec::new();
+    for engine in args.engines() {
+        // HashLife doesn't use threads, so it only has its baseline
+        let threads = match engine.as_str() {
+            "hashlife" => vec![1],
+            _ => args.threads(),
+        };
+        let mut baseline = None;
+        for threads in threads {
+            let time = match engine.as_str() {
+                "hashlife" => time_hashlife(&alive, warmup, gens),
+                _ => time_scan(&alive, threads, warmup, gens),
+            };
+            let baseline = *baseline.get_or_insert(gens as f64 / time.as_secs_f64());
+            results.push(BenchResult {
+                engine: engine.clone(),
+                threads,
+                time,
+                baseline,
+            });
+        }
+    }
+
+    println!(
+        "{:<10}{:>8}{:>12}{:>14}{:>9}",
+        "engine", "threads", "time", "gens/s", "speedup"
+    );
+    for result in &results {
+        let gens_per_sec = result.gens_per_sec(gens);
+        println!(
+            "{:<10}{:>8}{:>11.3}s{:>14.1}{:>8.2}x",
+            result.engine,
+            result.threads,
+            result.time.as_secs_f64(),
+            gens_per_sec,
+            gens_per_sec / result.baseline
+        );
+    }
+
+    if let Some(path) = args.csv_file() {
+        let mut csv = io::BufWriter::new(std::fs::File::create(&path)?);
+        writeln!(csv, "engine,threads,gens,seconds,gens_per_sec,speedup")?;
+        for result in &results {
+            let gens_per_sec = result.gens_per_sec(gens);
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                result.engine,
+                result.threads,
+                gens,
+                result.time.as_secs_f64(),
+                gens_per_sec,
+                gens_per_sec / result.baseline
+            )?;
+        }
+        csv.flush()?;
+        println!("saved the results to {}", path);
+    }
+    Ok(())
+}
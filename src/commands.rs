@@ -0,0 +1,512 @@
+//! The subcommands of `gol` besides `run`, and the options of `run` that only the terminal
+//! reads, which `cgol-gui` doesn't take
+
+use std::{str::FromStr, time::Duration};
+
+use crate::options::{
+    parse_age_gradient, parse_value, pattern_options, ArgsError, Flags, PatternArgs, RunArgs,
+    COMMANDS, COUNT,
+};
+
+/// Parses a comma separated list of values of a flag, like `1,2,4,8`
+fn parse_list<T: FromStr>(
+    flag: &'static str,
+    list: &str,
+    expected: &'static str,
+) -> Result<Vec<T>, ArgsError> {
+    list.split(',')
+        .map(|item| parse_value(flag, item, expected))
+        .collect::<Result<_, _>>()
+        // the error names the whole list, like the other list flags
+        .map_err(|_| ArgsError::InvalidValue {
+            flag,
+            value: list.to_owned(),
+            expected,
+        })
+}
+
+/// A parsed subcommand with its options
+pub enum Command {
+    /// Simulates a pattern, which is the default without a command
+    Run(RunArgs),
+    /// Converts a pattern file to another format
+    Convert(ConvertArgs),
+    /// Prints what a pattern file contains
+    Info(InfoArgs),
+    /// Runs many random soups and aggregates a census
+    Soup(SoupArgs),
+    /// Times the engine on a starting pattern
+    Bench(BenchArgs),
+}
+
+impl Command {
+    /// Parses and validates the arguments, or returns `None` if a help was printed
+    ///
+    /// The first argument picks the command. Anything else is passed to `run` as a whole, so
+    /// invocations from before the subcommands keep working.
+    fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let (name, args) = match args.split_first() {
+            Some((name, rest)) if COMMANDS.contains(&name.as_ref()) => (name.as_ref(), rest),
+            _ => ("run", args),
+        };
+        Ok(match name {
+            "convert" => ConvertArgs::new(args)?.map(Self::Convert),
+            "info" => InfoArgs::new(args)?.map(Self::Info),
+            "soup" => SoupArgs::new(args)?.map(Self::Soup),
+            "bench" => BenchArgs::new(args)?.map(Self::Bench),
+            _ => RunArgs::new(args)?.map(Self::Run),
+        })
+    }
+    /// The level of `--log-level`, if given
+    #[cfg(feature = "tracing")]
+    pub fn log_level(&self) -> Option<tracing::Level> {
+        let flags = match self {
+            Self::Run(args) => &args.flags,
+            Self::Convert(args) => &args.flags,
+            Self::Info(args) => &args.flags,
+            Self::Soup(args) => &args.flags,
+            Self::Bench(args) => &args.flags,
+        };
+        flags.value("log-level")
+    }
+    /// Parses the arguments of the process, also returning the command that points to the help
+    /// for any errors, like `gol soup --help`
+    pub fn from_env() -> (Result<Option<Self>, ArgsError>, String) {
+        let env = std::env::args().skip(1).collect::<Vec<_>>();
+        let help = match env.first() {
+            Some(name) if COMMANDS.contains(&name.as_str()) => format!("gol {} --help", name),
+            _ => "gol --help".to_owned(),
+        };
+        (Self::new(&env), help)
+    }
+}
+/// The options of the run loop and console mode, which the window doesn't have
+impl RunArgs {
+    /// The age gradient as `(max_age, color)` pairs in ascending order
+    pub fn age_gradient(&self) -> Option<Vec<(u32, crossterm::style::Color)>> {
+        let list = self.flags.opt_str("age-gradient")?;
+        Some(parse_age_gradient(&list).expect("the age gradient is validated by Command::new"))
+    }
+    pub fn census(&self) -> bool {
+        self.flags.opt_present("census")
+    }
+    /// The wall time after which the generations loop stops, if any
+    pub fn duration(&self) -> Option<Duration> {
+        self.flags.value("duration").map(Duration::from_secs_f64)
+    }
+    /// The time between periodic stats reports outside of console mode
+    pub fn report_interval(&self) -> Duration {
+        Duration::from_millis(self.flags.value("report-interval").unwrap_or(500))
+    }
+    /// The stats files and their formats, `csv` or `json` for NDJSON
+    ///
+    /// The format is `--stats-format` if given, or else `json` for files ending in `.ndjson`,
+    /// `.jsonl` or `.json` and `csv` for everything else. Without `--stats-out`, the stats are
+    /// written to `perf.csv`, or `perf.ndjson` for `--stats-format json`.
+    pub fn stats_files(&self) -> Vec<(String, &'static str)> {
+        let format = self.flags.opt_str("stats-format");
+        let mut files = self.flags.opt_strs("stats-out");
+        if files.is_empty() {
+            let default = match format.as_deref() {
+                Some("json") => "perf.ndjson",
+                _ => "perf.csv",
+            };
+            files.push(default.to_owned());
+        }
+        files
+            .into_iter()
+            .map(|path| {
+                let json = match format.as_deref() {
+                    Some(format) => format == "json",
+                    None => [".ndjson", ".jsonl", ".json"]
+                        .iter()
+                        .any(|ext| path.ends_with(ext)),
+                };
+                (path, if json { "json" } else { "csv" })
+            })
+            .collect()
+    }
+    pub fn quiet(&self) -> bool {
+        self.flags.opt_present("quiet")
+    }
+    /// The number of generations between autosaves, if autosaving
+    pub fn autosave(&self) -> Option<u64> {
+        self.flags.value("autosave")
+    }
+    /// The autosave file name template, where `{gen}` is replaced with the generation
+    pub fn autosave_path(&self) -> String {
+        self.flags
+            .opt_str("autosave-path")
+            .unwrap_or_else(|| "autosave-{gen}.rle".to_owned())
+    }
+    /// The number of latest autosaves to keep, or `None` to keep them all
+    pub fn autosave_keep(&self) -> Option<usize> {
+        self.flags.value("autosave-keep")
+    }
+    /// How far from the center of the starting pattern cells are kept, if culling
+    pub fn cull_radius(&self) -> Option<u32> {
+        self.flags.value("cull-radius")
+    }
+    /// The number of generations between culls
+    pub fn cull_every(&self) -> u64 {
+        self.flags.value("cull-every").unwrap_or(100)
+    }
+    /// The file to record the run to
+    #[cfg(feature = "record")]
+    pub fn record_file(&self) -> Option<String> {
+        self.flags.opt_str("record")
+    }
+    #[cfg(feature = "record")]
+    pub fn record_every(&self) -> u64 {
+        self.flags.value("record-every").unwrap_or(1)
+    }
+    /// The recorded area, given as `x,y,width,height`
+    #[cfg(feature = "record")]
+    pub fn record_window(&self) -> Option<cgolrs::Rect> {
+        let window = self.flags.opt_str("record-window")?;
+        Some(
+            crate::options::parse_window(&window)
+                .expect("the record window is validated by Command::new"),
+        )
+    }
+    pub fn snapshot_dir(&self) -> String {
+        self.flags
+            .opt_str("snapshot-dir")
+            .unwrap_or_else(|| ".".to_owned())
+    }
+}
+
+/// The options of `gol convert IN OUT`, which converts a pattern file to another format
+pub struct ConvertArgs {
+    flags: Flags,
+}
+
+impl ConvertArgs {
+    fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let mut opts = getopts::Options::new();
+        opts.optopt(
+            "",
+            "format",
+            "output format: rle, life105, life106, plaintext or bin (default by extension)",
+            "NAME",
+        );
+        opts.optopt(
+            "",
+            "rotate",
+            "rotate the pattern clockwise by 90, 180 or 270 degrees",
+            "DEGREES",
+        );
+        opts.optflag("", "flip-x", "mirror the pattern horizontally");
+        opts.optflag("", "recenter", "move the pattern to the origin");
+        opts.optflag("", "force", "allow overwriting the input file");
+
+        let usage = "usage: gol convert IN OUT [options]";
+        let Some(flags) = Flags::parse(opts, args, usage)? else {
+            return Ok(None);
+        };
+        if flags.free.len() < 2 {
+            return Err(ArgsError::Missing {
+                argument: "IN and OUT files",
+                required_by: "the convert command",
+            });
+        }
+        flags.check_free(2)?;
+        flags.check_format()?;
+        if let Some(degrees) = flags.check::<u32>("rotate", "90, 180 or 270")? {
+            if !degrees.is_multiple_of(90) {
+                return Err(flags.invalid("rotate", "90, 180 or 270"));
+            }
+        }
+        Ok(Some(Self { flags }))
+    }
+
+    pub fn input(&self) -> &str {
+        &self.flags.free[0]
+    }
+    pub fn output(&self) -> &str {
+        &self.flags.free[1]
+    }
+    /// The output format, if given explicitly
+    pub fn format(&self) -> Option<cgolrs::enc::Format> {
+        self.flags.format()
+    }
+    /// The clockwise rotation in degrees, a multiple of 90
+    pub fn rotate(&self) -> u32 {
+        self.flags.value::<u32>("rotate").unwrap_or(0) % 360
+    }
+    pub fn recenter(&self) -> bool {
+        self.flags.opt_present("recenter")
+    }
+    pub fn flip_x(&self) -> bool {
+        self.flags.opt_present("flip-x")
+    }
+    pub fn force(&self) -> bool {
+        self.flags.opt_present("force")
+    }
+}
+
+/// The options of `gol info FILE`, which prints what a pattern file contains
+pub struct InfoArgs {
+    flags: Flags,
+}
+
+impl InfoArgs {
+    fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let mut opts = getopts::Options::new();
+        opts.optopt(
+            "",
+            "format",
+            "pattern file format: rle, life105, life106, plaintext or bin (default detected)",
+            "NAME",
+        );
+        opts.optflag(
+            "",
+            "census",
+            "print the objects of the pattern by their apgcode",
+        );
+        opts.optflag(
+            "",
+            "analyze",
+            "classify the pattern as a still life, oscillator or spaceship",
+        );
+
+        let usage = "usage: gol info FILE [options]";
+        let Some(flags) = Flags::parse(opts, args, usage)? else {
+            return Ok(None);
+        };
+        if flags.free.is_empty() {
+            return Err(ArgsError::Missing {
+                argument: "a FILE",
+                required_by: "the info command",
+            });
+        }
+        flags.check_free(1)?;
+        flags.check_format()?;
+        Ok(Some(Self { flags }))
+    }
+
+    pub fn file(&self) -> &str {
+        &self.flags.free[0]
+    }
+    /// The pattern file format, if given explicitly
+    pub fn format(&self) -> Option<cgolrs::enc::Format> {
+        self.flags.format()
+    }
+    pub fn census(&self) -> bool {
+        self.flags.opt_present("census")
+    }
+    pub fn analyze(&self) -> bool {
+        self.flags.opt_present("analyze")
+    }
+}
+
+/// The options of `gol soup`, which runs many random soups and aggregates a census
+pub struct SoupArgs {
+    flags: Flags,
+}
+
+impl SoupArgs {
+    fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let mut opts = getopts::Options::new();
+        opts.optopt(
+            "",
+            "count",
+            "the number of soups to run (default 100)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "size",
+            "the width and height of each soup (default 16)",
+            "CELLS",
+        );
+        opts.optopt("", "seed", "the seed of the first soup (default 0)", "SEED");
+        opts.optopt(
+            "t",
+            "threads",
+            "the number of threads running soups (default 1)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "snapshot-dir",
+            "directory for the census and the longest-lived and largest soups (default .)",
+            "DIR",
+        );
+
+        let Some(flags) = Flags::parse(opts, args, "usage: gol soup [options]")? else {
+            return Ok(None);
+        };
+        flags.check_free(0)?;
+        flags.check_positive::<u64>("count")?;
+        flags.check::<u32>("size", COUNT)?;
+        flags.check::<u64>("seed", COUNT)?;
+        flags.check_positive::<usize>("threads")?;
+        Ok(Some(Self { flags }))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.flags.value("count").unwrap_or(100)
+    }
+    pub fn size(&self) -> u32 {
+        self.flags.value("size").unwrap_or(16)
+    }
+    pub fn seed(&self) -> u64 {
+        self.flags.value("seed").unwrap_or(0)
+    }
+    pub fn threads(&self) -> usize {
+        self.flags.value("threads").unwrap_or(1)
+    }
+    pub fn snapshot_dir(&self) -> String {
+        self.flags
+            .opt_str("snapshot-dir")
+            .unwrap_or_else(|| ".".to_owned())
+    }
+}
+
+/// The options of `gol bench`, which times the engine on a seeded soup
+pub struct BenchArgs {
+    flags: Flags,
+}
+
+impl BenchArgs {
+    fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let mut opts = getopts::Options::new();
+        opts.optopt(
+            "",
+            "size",
+            "the width and height of the seeded soup (default 256)",
+            "CELLS",
+        );
+        opts.optopt("", "seed", "the seed of the soup (default 0)", "SEED");
+        pattern_options(&mut opts);
+        opts.optopt(
+            "g",
+            "gens",
+            "the number of timed generations (default 1000)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "warmup",
+            "the number of untimed generations before them (default 100)",
+            "COUNT",
+        );
+        opts.optopt(
+            "t",
+            "threads",
+            "the thread counts to compare, e.g. 1,2,4,8 (default 1)",
+            "LIST",
+        );
+        opts.optopt(
+            "",
+            "engine",
+            "the engines to compare: scan (default), hashlife or scan,hashlife",
+            "LIST",
+        );
+        opts.optopt("", "csv", "also write the results to a CSV file", "FILE");
+
+        let Some(flags) = Flags::parse(opts, args, "usage: gol bench [options]")? else {
+            return Ok(None);
+        };
+        let args = Self { flags };
+        args.validate()?;
+        Ok(Some(args))
+    }
+
+    fn validate(&self) -> Result<(), ArgsError> {
+        let flags = &self.flags;
+        flags.check_free(0)?;
+        self.pattern().validate()?;
+        flags.check::<u32>("size", COUNT)?;
+        flags.check::<u64>("seed", COUNT)?;
+        flags.check_positive::<u64>("gens")?;
+        flags.check::<u64>("warmup", COUNT)?;
+        if let Some(list) = flags.opt_str("threads") {
+            let threads = parse_list::<usize>("threads", &list, "a list of thread counts")?;
+            if threads.contains(&0) {
+                return Err(flags.invalid("threads", "a list of thread counts"));
+            }
+        }
+        if let Some(list) = flags.opt_str("engine") {
+            let engines = parse_list::<String>("engine", &list, "scan, hashlife or both")?;
+            if !engines
+                .iter()
+                .all(|engine| engine == "scan" || engine == "hashlife")
+            {
+                return Err(flags.invalid("engine", "scan, hashlife or both"));
+            }
+        }
+        // the soup is the default starting pattern, which the pattern options replace
+        for (name, flag) in [("size", "--size"), ("seed", "--seed")] {
+            for (source, other) in [
+                ("input", "--input"),
+                ("pattern", "--pattern"),
+                ("fill", "--fill"),
+            ] {
+                if flags.opt_present(name) && flags.opt_present(source) {
+                    return Err(ArgsError::Conflict { flag, other });
+                }
+            }
+        }
+        for (name, flag) in [("width", "--width"), ("height", "--height")] {
+            if flags.opt_present(name) && !flags.opt_present("fill") {
+                return Err(ArgsError::Missing {
+                    argument: "--fill",
+                    required_by: flag,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The options that pick another starting pattern than the soup
+    pub fn pattern(&self) -> PatternArgs<'_> {
+        PatternArgs {
+            flags: &self.flags,
+            console: false,
+        }
+    }
+    /// Whether the pattern options replace the seeded soup
+    pub fn custom_pattern(&self) -> bool {
+        ["input", "pattern", "fill"]
+            .iter()
+            .any(|name| self.flags.opt_present(name))
+    }
+    pub fn size(&self) -> u32 {
+        self.flags.value("size").unwrap_or(256)
+    }
+    pub fn seed(&self) -> u64 {
+        self.flags.value("seed").unwrap_or(0)
+    }
+    pub fn generations(&self) -> u64 {
+        self.flags.value("gens").unwrap_or(1000)
+    }
+    pub fn warmup(&self) -> u64 {
+        self.flags.value("warmup").unwrap_or(100)
+    }
+    /// The thread counts in the order they were given, starting with 1 for the baseline
+    pub fn threads(&self) -> Vec<usize> {
+        let mut threads = match self.flags.opt_str("threads") {
+            Some(list) => parse_list("threads", &list, "")
+                .expect("the thread counts are validated by Command::new"),
+            None => Vec::new(),
+        };
+        threads.retain(|&count| count != 1);
+        threads.insert(0, 1);
+        threads
+    }
+    /// The engines to compare, `scan` and/or `hashlife`
+    pub fn engines(&self) -> Vec<String> {
+        match self.flags.opt_str("engine") {
+            Some(list) => {
+                parse_list("engine", &list, "").expect("the engines are validated by Command::new")
+            }
+            None => vec!["scan".to_owned()],
+        }
+    }
+    /// The CSV file to write the results to
+    pub fn csv_file(&self) -> Option<String> {
+        self.flags.opt_str("csv")
+    }
+}
@@ -1,61 +1,692 @@
-use crate::{engine::GameOfLife, Pos2};
+use cgolrs::{
+    engine::{GameOfLife, COORD_RANGE},
+    Pos2, Rect,
+};
 use crossterm::{
     cursor,
-    event::{self, KeyCode, KeyEvent, KeyModifiers},
-    queue, terminal,
-    execute,
+    event::{self, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    execute, queue,
+    style::{self, Color},
+    terminal,
 };
-use std::io;
+use std::io::{self, IsTerminal};
 
 pub enum ConsoleCommand {
     Exit,
+    TogglePause,
+    NextPattern,
+    /// Request to enter the pattern editor (only honored while paused)
+    Edit,
+    /// Leave the pattern editor and resume the simulation
+    Resume,
+    ToggleCell(Pos2),
+    SetCell(Pos2, bool),
+    SaveSnapshot,
+    /// Save the visible part of the grid as an image
+    SaveImage(Rect),
+    /// Go back one generation
+    Rewind,
+    /// Go forward one generation
+    StepForward,
     Handled,
 }
 
+/// What happens to the cells the editor cursor moves over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaintMode {
+    Off,
+    Draw,
+    Erase,
+}
+impl PaintMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Draw,
+            Self::Draw => Self::Erase,
+            Self::Erase => Self::Off,
+        }
+    }
+}
+impl std::fmt::Display for PaintMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Draw => "draw",
+            Self::Erase => "erase",
+        })
+    }
+}
+
+struct EditState {
+    cursor: Pos2,
+    paint: PaintMode,
+    /// The corner a selection is being stretched from, while selecting
+    anchor: Option<Pos2>,
+}
+impl EditState {
+    /// The selection between the anchor and the cursor, if selecting
+    fn selection(&self) -> Option<Rect> {
+        let anchor = self.anchor?;
+        let min = Pos2::new(anchor.x.min(self.cursor.x), anchor.y.min(self.cursor.y));
+        let max = Pos2::new(anchor.x.max(self.cursor.x), anchor.y.max(self.cursor.y));
+        Some(Rect::new(min, max + Pos2::one()))
+    }
+    /// The command for painting the cell under the cursor, if painting at all
+    fn paint_command(&self) -> ConsoleCommand {
+        match self.paint {
+            PaintMode::Off => ConsoleCommand::Handled,
+            PaintMode::Draw => ConsoleCommand::SetCell(self.cursor, true),
+            PaintMode::Erase => ConsoleCommand::SetCell(self.cursor, false),
+        }
+    }
+}
+
+/// A key binding shown in the help overlay
+struct KeyBinding {
+    keys: &'static str,
+    action: &'static str,
+}
+
+/// Every key binding understood by [`ConsoleRender::poll_events`]
+///
+/// Keep this in sync when adding commands, the help overlay is generated from it
+const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding {
+        keys: "arrows",
+        action: "pan the grid (move the cursor while editing)",
+    },
+    KeyBinding {
+        keys: "shift+arrows, pgup/pgdn",
+        action: "pan the grid by a full screen",
+    },
+    KeyBinding {
+        keys: "home",
+        action: "center the origin",
+    },
+    KeyBinding {
+        keys: "g",
+        action: "go to a position",
+    },
+    KeyBinding {
+        keys: "space",
+        action: "pause/resume (toggle a cell while editing)",
+    },
+    KeyBinding {
+        keys: "e",
+        action: "edit the pattern while paused",
+    },
+    KeyBinding {
+        keys: "p",
+        action: "cycle the paint mode while editing",
+    },
+    KeyBinding {
+        keys: "v",
+        action: "select a region to limit the run to while editing",
+    },
+    KeyBinding {
+        keys: "enter",
+        action: "leave the editor and run",
+    },
+    KeyBinding {
+        keys: "s",
+        action: "save a snapshot to an RLE file",
+    },
+    KeyBinding {
+        keys: "i",
+        action: "save the visible grid to a PNG file",
+    },
+    KeyBinding {
+        keys: "[, ]",
+        action: "pause and step back, forward one generation",
+    },
+    KeyBinding {
+        keys: "z, Z",
+        action: "zoom out, in",
+    },
+    KeyBinding {
+        keys: "n",
+        action: "next pattern (demo only)",
+    },
+    KeyBinding {
+        keys: "mouse",
+        action: "click to toggle a cell while paused, drag to pan",
+    },
+    KeyBinding {
+        keys: "wheel, shift+wheel",
+        action: "pan vertically, horizontally",
+    },
+    KeyBinding {
+        keys: "?",
+        action: "show this help",
+    },
+    KeyBinding {
+        keys: "q, ctrl+c",
+        action: "exit",
+    },
+];
+
+/// The default colors for cells by age, from newborn (bright) to ash (dim blue)
+///
+/// Each entry is the maximum age (inclusive) the color is used for
+const AGE_GRADIENT: &[(u32, Color)] = &[
+    (0, Color::White),
+    (1, Color::Yellow),
+    (4, Color::Red),
+    (16, Color::Magenta),
+    (64, Color::Blue),
+    (u32::MAX, Color::DarkBlue),
+];
+
+/// The colors of the dying cells of Generations rules, from just died to almost dead
+///
+/// The dying states are spread over the colors, so rules with many states share them.
+const DYING_COLORS: &[Color] = &[
+    Color::Red,
+    Color::DarkRed,
+    Color::DarkMagenta,
+    Color::DarkBlue,
+];
+
+/// The two colors of the cells of Immigration
+const IMMIGRATION_COLORS: [Color; 2] = [Color::Red, Color::Blue];
+
+/// The nearest position to `pos` that a cell can be at, see [`COORD_RANGE`]
+fn clamp_to_grid(pos: Pos2) -> Pos2 {
+    let (min, max) = (*COORD_RANGE.start(), *COORD_RANGE.end());
+    Pos2::new(pos.x.clamp(min, max), pos.y.clamp(min, max))
+}
+
+/// The characters for zoomed out blocks, from empty to completely alive
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
 pub struct ConsoleRender {
     tl: Pos2,
     report: String,
+    hint: String,
+    /// A one-off message shown in the footer until the next key press
+    message: Option<String>,
+    edit: Option<EditState>,
+    /// The region the run is limited to, see [`ConsoleRender::selection`]
+    selection: Option<Rect>,
+    /// The text typed so far into the goto prompt, if it is open
+    prompt: Option<String>,
+    help: bool,
+    /// The last screen position of a left button drag and whether it has moved since the press
+    drag: Option<(u16, u16, bool)>,
+    age_gradient: Vec<(u32, Color)>,
+    /// The number of cells along each side of the block a character shows
+    zoom: u32,
 }
 impl ConsoleRender {
+    /// The number of cells a single scroll wheel notch pans the grid
+    const SCROLL_STEP: i32 = 3;
+    /// The most cells along each side of the block a character shows
+    const MAX_ZOOM: u32 = 64;
+
+    /// Takes over the terminal, failing if stdout isn't one, since the frames would only garble
+    /// a file or pipe
     pub fn new() -> io::Result<Self> {
+        if !io::stdout().is_terminal() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "console mode needs a terminal",
+            ));
+        }
         terminal::enable_raw_mode()?;
-        execute!(io::stdout(), cursor::Hide)?;
+        execute!(io::stdout(), cursor::Hide, event::EnableMouseCapture)?;
         Ok(Self {
             tl: Pos2::default(),
             report: String::new(),
+            hint: String::new(),
+            message: None,
+            edit: None,
+            selection: None,
+            prompt: None,
+            help: false,
+            drag: None,
+            age_gradient: AGE_GRADIENT.to_vec(),
+            zoom: 1,
         })
     }
 
+    /// Moves the viewport so that `pos` is in the center of the terminal
+    ///
+    /// Positions past the edge of the grid center on the nearest cell of it instead.
+    pub fn center_on(&mut self, pos: Pos2) -> io::Result<()> {
+        let (cols, rows) = terminal::size()?;
+        self.tl = clamp_to_grid(pos).saturating_add(
+            -Pos2 {
+                x: cols as i32 / 2,
+                y: rows as i32 / 2,
+            } * self.zoom as i32,
+        );
+        Ok(())
+    }
+
+    /// Moves the viewport by `delta` cells, stopping at the bounds of the coordinates
+    fn pan(&mut self, delta: Pos2) {
+        self.tl = self.tl.saturating_add(delta);
+    }
+
+    /// Changes the zoom level, keeping the center of the viewport in place
+    fn set_zoom(&mut self, zoom: u32) -> io::Result<()> {
+        let (cols, rows) = terminal::size()?;
+        let center = self.screen_to_world(cols / 2, rows / 2);
+        self.zoom = zoom.clamp(1, Self::MAX_ZOOM);
+        self.center_on(center)
+    }
+
+    /// Enters the pattern editor with the cursor in the center of the viewport
+    pub fn begin_edit(&mut self) -> io::Result<()> {
+        // the editor works on single cells
+        self.set_zoom(1)?;
+        let (cols, rows) = terminal::size()?;
+        let cursor = clamp_to_grid(self.screen_to_world(cols / 2, rows / 2));
+        self.edit = Some(EditState {
+            cursor,
+            paint: PaintMode::Off,
+            anchor: None,
+        });
+        Ok(())
+    }
+    pub fn end_edit(&mut self) {
+        self.edit = None;
+    }
+
+    /// The region selected in the editor, which the run is limited to while everything outside
+    /// of it stays frozen
+    pub fn selection(&self) -> Option<Rect> {
+        self.selection
+    }
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+        if let Some(ref mut edit) = self.edit {
+            edit.anchor = None;
+        }
+    }
+    /// Starts a selection at the cursor, finishes the one being stretched or clears the
+    /// finished one
+    fn cycle_selection(&mut self) {
+        let Some(ref mut edit) = self.edit else {
+            return;
+        };
+        match (edit.anchor, self.selection) {
+            (Some(_), _) => edit.anchor = None,
+            (None, Some(_)) => self.selection = None,
+            (None, None) => {
+                edit.anchor = Some(edit.cursor);
+                self.selection = edit.selection();
+            }
+        }
+    }
+
+    /// The area of the grid covered by the terminal
+    ///
+    /// The viewport can be panned right up to the edge of the coordinate space, where it's cut
+    /// short instead of overflowing.
+    fn viewport(&self) -> io::Result<Rect> {
+        let (cols, rows) = terminal::size()?;
+        let zoom = self.zoom;
+        Ok(Rect::from_size(
+            self.tl,
+            cols as u32 * zoom,
+            rows as u32 * zoom,
+        ))
+    }
+
+    /// Pans the viewport just enough for `pos` to be visible
+    fn scroll_to(&mut self, pos: Pos2) -> io::Result<()> {
+        let (cols, rows) = terminal::size()?;
+        let page = Pos2::new(cols.max(1) as i32, rows.max(1) as i32) * self.zoom as i32;
+        self.tl.x = self.tl.x.clamp(pos.x.saturating_sub(page.x - 1), pos.x);
+        self.tl.y = self.tl.y.clamp(pos.y.saturating_sub(page.y - 1), pos.y);
+        Ok(())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(generation = game.generation()))
+    )]
     pub fn render(&self, game: &GameOfLife) -> io::Result<()> {
         let (cols, rows) = terminal::size()?;
-        let br = self.tl
-            + Pos2 {
-                x: cols as i32,
-                y: rows as i32,
-            };
+        let viewport = self.viewport()?;
         let mut stdout = io::stdout();
         queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
-        for cell in game.window(self.tl, br).iter() {
-            let cell = *cell - self.tl;
-            queue!(stdout, cursor::MoveTo(cell.x as u16, cell.y as u16))?;
-            io::Write::write_all(&mut stdout, b"\xE2\x96\x88")?;
+        if self.help {
+            return Self::render_help(&mut stdout, cols, rows);
+        }
+        // the frame goes around the selection, so cells on it are drawn over it
+        if let Some(selection) = self.selection {
+            self.render_frame(&mut stdout, selection, cols, rows)?;
+        }
+        if self.zoom > 1 {
+            self.render_zoomed(&mut stdout, game, viewport)?;
+        } else if game.background() {
+            // the stored cells are the dead ones, so draw every other cell without ages
+            for (cell, _) in game
+                .window(viewport)
+                .iter_cells()
+                .filter(|&(_, alive)| alive)
+            {
+                let cell = cell - self.tl;
+                queue!(stdout, cursor::MoveTo(cell.x as u16, cell.y as u16))?;
+                io::Write::write_all(&mut stdout, b"\xE2\x96\x88")?;
+            }
+        } else if game.colors().is_some() {
+            for (cell, color) in game.window(viewport).iter_with_colors() {
+                let cell = *cell - self.tl;
+                let color = IMMIGRATION_COLORS[usize::from(color.unwrap_or(0))];
+                queue!(
+                    stdout,
+                    cursor::MoveTo(cell.x as u16, cell.y as u16),
+                    style::SetForegroundColor(color)
+                )?;
+                io::Write::write_all(&mut stdout, b"\xE2\x96\x88")?;
+            }
+            queue!(stdout, style::ResetColor)?;
+        } else {
+            for (cell, age) in game.window(viewport).iter_with_ages() {
+                let cell = *cell - self.tl;
+                queue!(stdout, cursor::MoveTo(cell.x as u16, cell.y as u16))?;
+                if let Some(age) = age {
+                    queue!(stdout, style::SetForegroundColor(self.age_color(age)))?;
+                }
+                io::Write::write_all(&mut stdout, b"\xE2\x96\x88")?;
+            }
+            if game.ages().is_some() {
+                queue!(stdout, style::ResetColor)?;
+            }
+        }
+        if self.zoom == 1 && !game.dying().is_empty() {
+            let dying_states = usize::from(game.rule().states() - 2);
+            for (cell, state) in game.window(viewport).iter_dying() {
+                let cell = cell - self.tl;
+                let color =
+                    DYING_COLORS[usize::from(state - 2) * DYING_COLORS.len() / dying_states];
+                queue!(
+                    stdout,
+                    cursor::MoveTo(cell.x as u16, cell.y as u16),
+                    style::SetForegroundColor(color)
+                )?;
+                io::Write::write_all(&mut stdout, b"\xE2\x96\x92")?;
+            }
+            queue!(stdout, style::ResetColor)?;
         }
 
         // write footer
         queue!(stdout, cursor::MoveTo(0, rows))?;
         io::Write::write_all(&mut stdout, self.report.as_bytes())?;
+        if self.zoom > 1 {
+            io::Write::write_all(&mut stdout, format!(" | zoom 1:{}", self.zoom).as_bytes())?;
+        }
+        if let Some(selection) = self.selection {
+            let size = format!(" | {}x{} region", selection.width(), selection.height());
+            io::Write::write_all(&mut stdout, size.as_bytes())?;
+        }
+        if let Some(ref prompt) = self.prompt {
+            let line = format!(" | goto x,y: {}", prompt);
+            io::Write::write_all(&mut stdout, line.as_bytes())?;
+        } else if let Some(ref message) = self.message {
+            io::Write::write_all(&mut stdout, b" | ")?;
+            io::Write::write_all(&mut stdout, message.as_bytes())?;
+        } else if let Some(ref edit) = self.edit {
+            let state = if game.is_alive(edit.cursor) {
+                "alive"
+            } else {
+                "dead"
+            };
+            let status = format!(
+                " | edit {} ({}) paint:{} | arrows move, space toggle, p paint, v select, \
+                 enter run",
+                edit.cursor, state, edit.paint
+            );
+            io::Write::write_all(&mut stdout, status.as_bytes())?;
+        } else if !self.hint.is_empty() {
+            io::Write::write_all(&mut stdout, b" | ")?;
+            io::Write::write_all(&mut stdout, self.hint.as_bytes())?;
+        }
+
+        // show the terminal cursor over the edited cell, unless it's been panned out of view
+        let cursor = self.edit.as_ref().map(|edit| edit.cursor);
+        if let Some(cell) = cursor.filter(|&cursor| viewport.contains(cursor)) {
+            let cell = cell - self.tl;
+            queue!(
+                stdout,
+                cursor::MoveTo(cell.x as u16, cell.y as u16),
+                cursor::Show
+            )?;
+        } else {
+            queue!(stdout, cursor::Hide)?;
+        }
 
         io::Write::flush(&mut stdout)
     }
 
+    /// Renders a frame around the cells of `region`, clipped to the screen
+    fn render_frame(
+        &self,
+        stdout: &mut io::Stdout,
+        region: Rect,
+        cols: u16,
+        rows: u16,
+    ) -> io::Result<()> {
+        let zoom = self.zoom as i64;
+        // the screen positions of the frame, one character outside of the region
+        let to_screen = |world: i32, tl: i32| (world as i64 - tl as i64).div_euclid(zoom);
+        let left = to_screen(region.min.x, self.tl.x) - 1;
+        let top = to_screen(region.min.y, self.tl.y) - 1;
+        let right = to_screen(region.max.x - 1, self.tl.x) + 1;
+        let bottom = to_screen(region.max.y - 1, self.tl.y) + 1;
+        // the last row is the footer
+        let on_screen =
+            |x: i64, y: i64| (0..cols as i64).contains(&x) && (0..rows as i64 - 1).contains(&y);
+
+        queue!(stdout, style::SetForegroundColor(Color::DarkGrey))?;
+        let mut put = |x: i64, y: i64, c: char| -> io::Result<()> {
+            if on_screen(x, y) {
+                queue!(stdout, cursor::MoveTo(x as u16, y as u16))?;
+                io::Write::write_all(stdout, c.encode_utf8(&mut [0; 4]).as_bytes())?;
+            }
+            Ok(())
+        };
+        // only the visible part of each edge is drawn, so huge regions stay cheap
+        for x in (left + 1).max(0)..right.min(cols as i64) {
+            put(x, top, '─')?;
+            put(x, bottom, '─')?;
+        }
+        for y in (top + 1).max(0)..bottom.min(rows as i64) {
+            put(left, y, '│')?;
+            put(right, y, '│')?;
+        }
+        put(left, top, '┌')?;
+        put(right, top, '┐')?;
+        put(left, bottom, '└')?;
+        put(right, bottom, '┘')?;
+        queue!(stdout, style::ResetColor)
+    }
+
+    /// Renders each `zoom × zoom` block of the viewport as a single character shaded by how many
+    /// of its cells are alive
+    fn render_zoomed(
+        &self,
+        stdout: &mut io::Stdout,
+        game: &GameOfLife,
+        viewport: Rect,
+    ) -> io::Result<()> {
+        let blocks = game
+            .window(viewport)
+            .downsample(self.zoom)
+            .map_err(io::Error::other)?;
+        let area = self.zoom * self.zoom;
+        for (block, count) in blocks.iter() {
+            // any alive cell is at least the lightest shade
+            let shade = 1 + (count - 1) as usize * (SHADES.len() - 2) / (area - 1) as usize;
+            queue!(stdout, cursor::MoveTo(block.x as u16, block.y as u16))?;
+            io::Write::write_all(stdout, SHADES[shade].encode_utf8(&mut [0; 4]).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// The color of a cell with the given age according to the age gradient
+    fn age_color(&self, age: u32) -> Color {
+        self.age_gradient
+            .iter()
+            .find(|(max_age, _)| age <= *max_age)
+            .or(self.age_gradient.last())
+            .map(|&(_, color)| color)
+            .unwrap_or(Color::Reset)
+    }
+    /// Sets the colors used for cells by age when the engine tracks ages
+    ///
+    /// Each entry is the maximum age (inclusive) the color is used for, in ascending order
+    pub fn set_age_gradient(&mut self, gradient: Vec<(u32, Color)>) {
+        self.age_gradient = gradient;
+    }
+
+    /// Renders the key bindings in a box centered on the screen
+    fn render_help(stdout: &mut io::Stdout, cols: u16, rows: u16) -> io::Result<()> {
+        let keys_w = KEY_BINDINGS.iter().map(|b| b.keys.len()).max().unwrap_or(0);
+        let action_w = KEY_BINDINGS
+            .iter()
+            .map(|b| b.action.len())
+            .max()
+            .unwrap_or(0);
+        let inner_w = keys_w + 2 + action_w;
+        let mut lines = vec![
+            format!("{:^1$}", "keys (press any key to close)", inner_w),
+            String::new(),
+        ];
+        for binding in KEY_BINDINGS {
+            lines.push(format!("{:<2$}  {}", binding.keys, binding.action, keys_w));
+        }
+
+        // the box has a 1 cell border and 1 cell of padding on each side
+        let box_w = inner_w + 4;
+        let box_h = lines.len() + 2;
+        let left = (cols as usize).saturating_sub(box_w) / 2;
+        let top = (rows as usize).saturating_sub(box_h) / 2;
+
+        let border = "─".repeat(inner_w + 2);
+        queue!(stdout, cursor::MoveTo(left as u16, top as u16))?;
+        io::Write::write_all(stdout, format!("┌{}┐", border).as_bytes())?;
+        for (i, line) in lines.iter().enumerate() {
+            queue!(stdout, cursor::MoveTo(left as u16, (top + 1 + i) as u16))?;
+            io::Write::write_all(stdout, format!("│ {:<1$} │", line, inner_w).as_bytes())?;
+        }
+        queue!(
+            stdout,
+            cursor::MoveTo(left as u16, (top + box_h - 1) as u16)
+        )?;
+        io::Write::write_all(stdout, format!("└{}┘", border).as_bytes())?;
+
+        queue!(stdout, cursor::Hide)?;
+        io::Write::flush(stdout)
+    }
+
+    /// Translates a terminal cell into a position in the world
+    ///
+    /// This is the single place mapping screen to world coordinates, so render modes that pack
+    /// multiple cells into a character only need to adjust it here
+    fn screen_to_world(&self, column: u16, row: u16) -> Pos2 {
+        self.tl.saturating_add(
+            Pos2 {
+                x: column as i32,
+                y: row as i32,
+            } * self.zoom as i32,
+        )
+    }
+
+    /// Handles a mouse event, returning the command it produced
+    fn mouse_event(&mut self, ev: MouseEvent, rows: u16) -> ConsoleCommand {
+        // the grid isn't visible behind the help overlay
+        if self.help {
+            return ConsoleCommand::Handled;
+        }
+        // the last row is the footer, which isn't part of the grid
+        let on_grid = ev.row < rows.saturating_sub(1);
+        let horizontal = ev.modifiers.contains(KeyModifiers::SHIFT);
+        let zoom = self.zoom as i32;
+        let step = Self::SCROLL_STEP * zoom;
+        match ev.kind {
+            MouseEventKind::Down(MouseButton::Left) if on_grid => {
+                self.drag = Some((ev.column, ev.row, false));
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((column, row, _)) = self.drag {
+                    // move the grid along with the mouse
+                    self.pan(Pos2::new(
+                        (column as i32 - ev.column as i32) * zoom,
+                        (row as i32 - ev.row as i32) * zoom,
+                    ));
+                    self.drag = Some((ev.column, ev.row, true));
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                // a press without any movement is a click
+                if let Some((column, row, false)) = self.drag.take() {
+                    return ConsoleCommand::ToggleCell(self.screen_to_world(column, row));
+                }
+            }
+            MouseEventKind::ScrollUp if horizontal => self.pan(Pos2::new(-step, 0)),
+            MouseEventKind::ScrollDown if horizontal => self.pan(Pos2::new(step, 0)),
+            MouseEventKind::ScrollUp => self.pan(Pos2::new(0, -step)),
+            MouseEventKind::ScrollDown => self.pan(Pos2::new(0, step)),
+            _ => {}
+        }
+        ConsoleCommand::Handled
+    }
+
+    /// Handles a key press while the goto prompt is open
+    fn prompt_key(&mut self, key: KeyEvent) -> io::Result<()> {
+        let Some(ref mut prompt) = self.prompt else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Char(c) => prompt.push(c),
+            KeyCode::Backspace => {
+                prompt.pop();
+            }
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Enter => {
+                match prompt.parse::<Pos2>() {
+                    Ok(pos) => self.center_on(pos)?,
+                    Err(e) => self.message = Some(e.to_string()),
+                }
+                self.prompt = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub fn poll_events(&mut self) -> io::Result<Option<ConsoleCommand>> {
         // make sure event is preset for us to take
         if !event::poll(std::time::Duration::from_secs(0))? {
             return Ok(None);
         }
 
+        let ev = event::read()?;
+        if let event::Event::Key(key) = ev {
+            self.message = None;
+            // any key dismisses the help overlay
+            if self.help {
+                self.help = false;
+                return Ok(Some(ConsoleCommand::Handled));
+            }
+            // the goto prompt captures all keys until it's closed (except CTRL+C)
+            if self.prompt.is_some()
+                && !(key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL)
+            {
+                self.prompt_key(key)?;
+                return Ok(Some(ConsoleCommand::Handled));
+            }
+        }
+
+        let (cols, rows) = terminal::size()?;
+        // a full screen of cells
+        let page = Pos2::new(cols as i32, rows as i32) * self.zoom as i32;
         let mut outp = Ok(Some(ConsoleCommand::Handled));
-        match event::read()? {
+        match ev {
             // CTRL+C
             event::Event::Key(KeyEvent {
                 code: KeyCode::Char('c'),
@@ -64,19 +695,177 @@ impl ConsoleRender {
             }) => {
                 outp = Ok(Some(ConsoleCommand::Exit));
             }
-            // arrows to move grid
+            // q to quit
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('q'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::Exit));
+            }
+            // space to toggle the cell under the cursor (edit mode)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            }) if self.edit.is_some() => {
+                let cursor = self.edit.as_ref().map(|e| e.cursor).unwrap_or_default();
+                outp = Ok(Some(ConsoleCommand::ToggleCell(cursor)));
+            }
+            // p to cycle the paint mode (edit mode)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('p'),
+                ..
+            }) if self.edit.is_some() => {
+                if let Some(ref mut edit) = self.edit {
+                    edit.paint = edit.paint.next();
+                    outp = Ok(Some(edit.paint_command()));
+                }
+            }
+            // v to select a region to limit the run to (edit mode)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('v'),
+                ..
+            }) if self.edit.is_some() => {
+                self.cycle_selection();
+            }
+            // enter to leave edit mode and run the simulation
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            }) if self.edit.is_some() => {
+                outp = Ok(Some(ConsoleCommand::Resume));
+            }
+            // space to pause/resume the simulation
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::TogglePause));
+            }
+            // e to enter edit mode (while paused)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('e'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::Edit));
+            }
+            // s to save a snapshot
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('s'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::SaveSnapshot));
+            }
+            // i to save an image of the visible grid
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('i'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::SaveImage(self.viewport()?)));
+            }
+            // [ and ] to scrub back and forth through the generations
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('['),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::Rewind));
+            }
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char(']'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::StepForward));
+            }
+            // z and Z to zoom out and in (not while editing)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('z'),
+                ..
+            }) if self.edit.is_none() => {
+                self.set_zoom(self.zoom * 2)?;
+            }
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('Z'),
+                ..
+            }) if self.edit.is_none() => {
+                self.set_zoom(self.zoom / 2)?;
+            }
+            // n to cycle to the next pattern (demo mode)
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('n'),
+                ..
+            }) => {
+                outp = Ok(Some(ConsoleCommand::NextPattern));
+            }
+            // ? to show the help overlay
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('?'),
+                ..
+            }) => {
+                self.help = true;
+            }
+            // g to open the goto prompt
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Char('g'),
+                ..
+            }) => {
+                self.prompt = Some(String::new());
+            }
+            // home to center the origin
+            event::Event::Key(KeyEvent {
+                code: KeyCode::Home,
+                ..
+            }) => {
+                self.center_on(Pos2::zero())?;
+            }
+            // page up/down and shift+arrows to move grid by a full screen
+            event::Event::Key(KeyEvent {
+                code: KeyCode::PageUp,
+                ..
+            }) => self.pan(Pos2::new(0, -page.y)),
+            event::Event::Key(KeyEvent {
+                code: KeyCode::PageDown,
+                ..
+            }) => self.pan(Pos2::new(0, page.y)),
             event::Event::Key(
                 ev @ KeyEvent {
                     code: KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right,
+                    modifiers: KeyModifiers::SHIFT,
                     ..
                 },
             ) => match ev.code {
-                KeyCode::Up => self.tl.y -= 1,
-                KeyCode::Down => self.tl.y += 1,
-                KeyCode::Left => self.tl.x -= 1,
-                KeyCode::Right => self.tl.x += 1,
+                KeyCode::Up => self.pan(Pos2::new(0, -page.y)),
+                KeyCode::Down => self.pan(Pos2::new(0, page.y)),
+                KeyCode::Left => self.pan(Pos2::new(-page.x, 0)),
+                KeyCode::Right => self.pan(Pos2::new(page.x, 0)),
                 _ => {}
             },
+            // arrows to move grid (or the cursor in edit mode)
+            event::Event::Key(
+                ev @ KeyEvent {
+                    code: KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right,
+                    ..
+                },
+            ) => {
+                let delta = match ev.code {
+                    KeyCode::Up => Pos2 { x: 0, y: -1 },
+                    KeyCode::Down => Pos2 { x: 0, y: 1 },
+                    KeyCode::Left => Pos2 { x: -1, y: 0 },
+                    _ => Pos2 { x: 1, y: 0 },
+                };
+                if let Some(ref mut edit) = self.edit {
+                    edit.cursor = clamp_to_grid(edit.cursor.saturating_add(delta));
+                    outp = Ok(Some(edit.paint_command()));
+                    let cursor = edit.cursor;
+                    if edit.anchor.is_some() {
+                        self.selection = edit.selection();
+                    }
+                    self.scroll_to(cursor)?;
+                } else {
+                    self.pan(delta * self.zoom as i32);
+                }
+            }
+            event::Event::Mouse(ev) => {
+                outp = Ok(Some(self.mouse_event(ev, rows)));
+            }
             _ => {}
         }
         outp
@@ -85,11 +874,19 @@ impl ConsoleRender {
     pub fn set_report(&mut self, report: String) {
         self.report = report;
     }
+    /// Shows a message in the footer until the next key press
+    pub fn flash<T: Into<String>>(&mut self, message: T) {
+        self.message = Some(message.into());
+    }
+    /// Sets a hint that is shown in the footer after the report
+    pub fn set_hint<T: Into<String>>(&mut self, hint: T) {
+        self.hint = hint.into();
+    }
 }
 impl Drop for ConsoleRender {
     fn drop(&mut self) {
         // if we can enable it, we should be able to disable it
         terminal::disable_raw_mode().expect("disable raw mode");
-        execute!(io::stdout(), cursor::Show).expect("enable cursor");
+        execute!(io::stdout(), cursor::Show, event::DisableMouseCapture).expect("enable cursor");
     }
 }
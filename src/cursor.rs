@@ -0,0 +1,327 @@
+//! Cursors that scan sorted cells column by column, remembering the last 64 columns as bits
+//!
+//! The cells are sorted by y and then x like [`GameOfLife::alive`], so each row of the grid is
+//! a run of the slice. A [`PosCursor`] walks along one row, and a [`MultiRowPosCursor`] walks
+//! rows below each other in lockstep, which is how the engine sees the 3×3 neighborhood of a
+//! cell: one bit buffer per row, where bit 0 is the column of the cursor and bit `k` the column
+//! `k` cells to its left.
+//!
+//! Every position a cursor visits has to be within [`COORD_RANGE`], so stepping right never
+//! overflows.
+//!
+//! # Example
+//! Counting the neighbors of the cells of a blinker, by stepping over the columns next to
+//! cells and skipping the empty ones in between, like the engine does:
+//! ```rust
+//! use cgolrs::{cursor::MultiRowPosCursor, engine::GameOfLife, Pos2};
+//!
+//! let game = GameOfLife::from_grid(&[[true, true, true]]);
+//! let mut cursor = MultiRowPosCursor::new(game.alive(), 3);
+//! let mut counts = Vec::new();
+//! loop {
+//!     let buffers = if cursor.buffers().iter().all(|&b| b & 0b111 == 0) {
+//!         match cursor.seek_closest() {
+//!             Some(buffers) => buffers,
+//!             None => break,
+//!         }
+//!     } else {
+//!         cursor.next()
+//!     };
+//!     // the 3×3 block left of and above the cursor is in bits 0 to 2 of the buffers
+//!     let block = buffers.iter().map(|b| (b & 0b111).count_ones()).sum::<u32>();
+//!     let center = cursor.cursor() - Pos2::one();
+//!     if game.is_alive(center) {
+//!         counts.push(block - 1);
+//!     }
+//! }
+//! assert_eq!(counts, [1, 2, 1]);
+//! ```
+//!
+//! [`GameOfLife::alive`]: crate::engine::GameOfLife::alive
+//! [`COORD_RANGE`]: crate::engine::COORD_RANGE
+
+use crate::{pos::Pos2, prelude::*};
+use core::fmt;
+
+/// A cursor over one row at a time of a sorted slice of [`Pos2`]s
+///
+/// The cursor treats the slice as a grid, where each position is either present (in the
+/// slice) or absent, and scans it one column at a time, keeping whether each of the last 64
+/// columns of its row is present as the bits of a [`u64`]. It stays on its row while stepping
+/// with [`PosCursor::next`], and moves to any other position with [`PosCursor::seek`].
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{cursor::PosCursor, Pos2};
+///
+/// let cells = [(0, 0), (2, 0), (3, 0), (1, 1)].map(Pos2::from);
+/// let mut cursor = PosCursor::new(&cells, Pos2::new(0, 0));
+/// assert_eq!(cursor.bit_buffer(), 0b1);
+/// assert_eq!(cursor.next(), 0b10);
+/// assert_eq!(cursor.next(), 0b101);
+/// assert_eq!(cursor.next(), 0b1011);
+/// assert_eq!(cursor.next_present(), Some(Pos2::new(1, 1)));
+///
+/// // seeking works forwards and backwards
+/// assert_eq!(cursor.seek(Pos2::new(1, 1)), 0b1);
+/// assert_eq!(cursor.seek(Pos2::new(2, 0)), 0b101);
+/// assert_eq!(cursor.cursor(), Pos2::new(2, 0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PosCursor<'a> {
+    slice: &'a [Pos2],
+    /// The index of the first position in the slice past the cursor
+    next_idx: usize,
+    cursor: Pos2,
+    buffer: u64,
+}
+
+impl<'a> PosCursor<'a> {
+    /// Creates a cursor over `slice`, which must be sorted by y and then x, at `cursor`
+    pub fn new(slice: &'a [Pos2], cursor: Pos2) -> Self {
+        let mut value = Self {
+            slice,
+            // these fields will be overwritten by `seek`
+            next_idx: 0,
+            cursor: Pos2::default(),
+            buffer: 0,
+        };
+        value.seek(cursor);
+        value
+    }
+
+    /// Moves the cursor one column to the right, staying on its row
+    ///
+    /// ## Returns
+    /// The new [bit buffer](PosCursor::bit_buffer), which is the previous one shifted left by
+    /// one with the state of the new column in bit 0
+    // not an `Iterator`, since the cursor never runs out of columns
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u64 {
+        self.buffer <<= 1;
+        self.cursor.x += 1;
+
+        if let Some(&next) = self.slice.get(self.next_idx) {
+            if next == self.cursor {
+                self.next_idx += 1;
+                self.buffer |= 1;
+            }
+        }
+        self.buffer
+    }
+
+    /// Moves the cursor to any position, forwards or backwards
+    ///
+    /// Seeking to the column right after the cursor is as cheap as [`PosCursor::next`], and
+    /// anything between the cursor and the next present position doesn't search the slice
+    /// either, since the buffer is only shifted along. Any other position takes a binary
+    /// search, and rebuilding the buffer from up to 64 positions before it.
+    ///
+    /// ## Returns
+    /// The [bit buffer](PosCursor::bit_buffer) at the new position
+    pub fn seek(&mut self, cursor: Pos2) -> u64 {
+        match self.slice.get(self.next_idx) {
+            // keep the same idx if the cursor is in-between the previous and next positions
+            Some(&next) if self.cursor <= cursor && next > cursor => {
+                self.buffer = self.shifted_buffer(cursor);
+            }
+            // if the cursor is the next position, only increment the idx
+            Some(&next) if next == cursor => {
+                self.next_idx += 1;
+                self.buffer = self.shifted_buffer(cursor) | 1;
+            }
+            // the new cursor could be anywhere in the slice
+            _ => {
+                self.next_idx = match self.slice.binary_search(&cursor) {
+                    Ok(i) => i + 1, // +1 because we want the _next_ index from the cursor
+                    Err(i) => i,
+                };
+                self.cursor = cursor;
+                self.reset_buffer();
+                return self.buffer;
+            }
+        }
+        self.cursor = cursor;
+        self.buffer
+    }
+    /// The bit buffer moved along to `cursor`, which is past the cursor without any present
+    /// positions in between
+    fn shifted_buffer(&self, cursor: Pos2) -> u64 {
+        if cursor.y != self.cursor.y {
+            // the new row has nothing before the cursor
+            return 0;
+        }
+        let offset = (cursor.x - self.cursor.x) as u32;
+        // offset >= 64 shifts every column out of the buffer
+        self.buffer.checked_shl(offset).unwrap_or(0)
+    }
+    /// Rebuilds the bit buffer from the positions in the slice right before the cursor
+    fn reset_buffer(&mut self) {
+        self.buffer = 0;
+        for &pos in self.slice[..self.next_idx].iter().rev() {
+            let offset = self.cursor.x - pos.x;
+            // offset >= 64 is out of the scope of a 64-bit buffer
+            if pos.y != self.cursor.y || offset >= 64 {
+                break;
+            }
+            debug_assert!(offset >= 0, "negative offset (out of bounds)");
+            self.buffer |= 1 << offset;
+        }
+    }
+
+    /// Whether each of the last 64 columns of the row is present, as the bits of a [`u64`]
+    ///
+    /// Bit 0 is the column of the cursor, bit 1 the column right before it, and so on up to
+    /// bit 63, 63 columns to the left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{cursor::PosCursor, Pos2};
+    ///
+    /// let cells = [(-64, 3), (-63, 3), (-3, 3), (0, 3)].map(Pos2::from);
+    /// let cursor = PosCursor::new(&cells, Pos2::new(0, 3));
+    /// let buffer = cursor.bit_buffer();
+    /// assert!(buffer & (1 << 0) != 0); // at the cursor
+    /// assert!(buffer & (1 << 1) == 0); // right behind it
+    /// assert!(buffer & (1 << 3) != 0); // 3 columns behind it
+    /// assert!(buffer & (1 << 63) != 0); // as far back as the buffer goes
+    /// // and (-64, 3) is too far behind to be in the buffer
+    /// assert_eq!(buffer, 1 << 63 | 0b1001);
+    /// ```
+    #[inline]
+    pub fn bit_buffer(&self) -> u64 {
+        self.buffer
+    }
+
+    /// The first present position past the cursor, on any row
+    #[inline]
+    pub fn next_present(&self) -> Option<Pos2> {
+        self.slice.get(self.next_idx).copied()
+    }
+    /// The current position of the cursor
+    #[inline]
+    pub fn cursor(&self) -> Pos2 {
+        self.cursor
+    }
+}
+
+/// [`PosCursor`]s over rows right below each other, which move together
+///
+/// The cursors are ordered from the top row to the bottom one, and the position of the bottom
+/// one is the position of the whole cursor. The engine uses 3 of them to see the
+/// neighborhoods of the cells in the row above its position.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{cursor::MultiRowPosCursor, Pos2};
+///
+/// let cells = [(0, 0), (0, 1), (1, 1), (20, 1)].map(Pos2::from);
+/// let mut cursor = MultiRowPosCursor::new(&cells, 2);
+/// // it starts one column before the first cell
+/// assert_eq!(cursor.cursor(), Pos2::new(-1, 0));
+/// assert_eq!(cursor.buffers(), [0, 0]);
+/// assert_eq!(cursor.next(), [0, 1]);
+/// assert_eq!(cursor.next(), [0, 0b10]);
+///
+/// // the closest of the next present positions of the rows, moved to the bottom row
+/// assert_eq!(cursor.seek_closest(), Some(&[0b1, 0b1][..]));
+/// assert_eq!(cursor.cursor(), Pos2::new(0, 1));
+/// assert_eq!(cursor.seek_closest(), Some(&[0b10, 0b11][..]));
+/// assert_eq!(cursor.cursor(), Pos2::new(1, 1));
+/// // the cells 19 and 20 columns back are still in the buffers
+/// assert_eq!(cursor.seek_closest(), Some(&[1 << 20, 1 << 20 | 1 << 19 | 1][..]));
+/// assert_eq!(cursor.cursor(), Pos2::new(20, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiRowPosCursor<'a> {
+    cursors: Vec<PosCursor<'a>>,
+    buffers: Vec<u64>,
+}
+impl<'a> MultiRowPosCursor<'a> {
+    /// The offsets of the cursors from the bottom one, which is the last and has an offset of 0
+    fn offset_iter(n: usize) -> impl Iterator<Item = Pos2> {
+        (0..n).rev().map(|y_offset| Pos2 {
+            x: 0,
+            y: -(y_offset as i32),
+        })
+    }
+
+    /// Creates `n_cursors` cursors over `slice`, which must be sorted by y and then x
+    ///
+    /// The bottom cursor starts one column before the first cell of the slice, and the others
+    /// on the rows above it.
+    pub fn new(slice: &'a [Pos2], n_cursors: usize) -> Self {
+        // start one column before the first cell, so the first seek computes the cells around it
+        // like any other's, since with rules like B1 even its top-left neighbor can be born
+        let start = slice
+            .first()
+            .map_or(Pos2::default(), |&first| first - Pos2::new(1, 0));
+
+        let cursors: Vec<PosCursor<'_>> = Self::offset_iter(n_cursors)
+            .map(|offset| PosCursor::new(slice, start + offset))
+            .collect();
+        let buffers = cursors.iter().map(PosCursor::bit_buffer).collect();
+
+        Self { cursors, buffers }
+    }
+
+    /// The [bit buffer](PosCursor::bit_buffer) of each row, from the top one to the bottom one
+    #[inline]
+    pub fn buffers(&self) -> &[u64] {
+        &self.buffers
+    }
+
+    /// Moves every cursor one column to the right, see [`PosCursor::next`]
+    ///
+    /// ## Returns
+    /// The new [buffers](MultiRowPosCursor::buffers)
+    // not an `Iterator`, since the buffers are borrowed from the cursor
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> &[u64] {
+        for (i, cursor) in self.cursors.iter_mut().enumerate() {
+            self.buffers[i] = cursor.next();
+        }
+        self.buffers()
+    }
+
+    /// Skips ahead to the first position where any of the rows has a present position
+    ///
+    /// Of the next present position of every row, the earliest one counted from the bottom
+    /// row is where every cursor seeks to, so the bit 0 of at least one buffer is set.
+    ///
+    /// ## Returns
+    /// The new [buffers](MultiRowPosCursor::buffers), or `None` if there are no present
+    /// positions left in any row, in which case the cursors don't move
+    pub fn seek_closest(&mut self) -> Option<&[u64]> {
+        let closest_next = Self::offset_iter(self.cursors.len())
+            .zip(&self.cursors)
+            .filter_map(|(offset, cursor)| Some(cursor.next_present()? - offset))
+            .min()?;
+
+        for (i, (offset, cursor)) in Self::offset_iter(self.cursors.len())
+            .zip(self.cursors.iter_mut())
+            .enumerate()
+        {
+            self.buffers[i] = cursor.seek(closest_next + offset);
+        }
+        Some(self.buffers())
+    }
+
+    /// The position of the bottom cursor
+    #[inline]
+    pub fn cursor(&self) -> Pos2 {
+        self.cursors
+            .last()
+            .map(PosCursor::cursor)
+            .unwrap_or_default()
+    }
+}
+impl fmt::Display for MultiRowPosCursor<'_> {
+    /// Writes the buffers in binary from the top row to the bottom one
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pc in &self.cursors {
+            write!(f, "{:064b} ", pc.bit_buffer())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! apgcodes, the object names used by Catagolue (`xs4_33` for the block)
+//!
+//! An apgcode is a prefix for the kind of object, `xs<population>` for still lifes,
+//! `xp<period>` for oscillators and `xq<period>` for spaceships, followed by `_` and the object
+//! in the extended Wechsler format:
+//! - the rows are split into strips of 5, separated by `z`
+//! - each column of a strip is one character, `0`-`9` and `a`-`v` for the 5 bits of its cells
+//!   (top row first)
+//! - runs of empty columns are shortened to `w` (2), `x` (3) or `y` and a character (4 to 39),
+//!   and empty columns at the end of a strip are left out
+//!
+//! The code is canonical: of all 8 orientations (and every phase of oscillators and spaceships),
+//! the shortest code is picked, and then the first in lexicographic order.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{enc::{apgcode, RunLengthEncoded, PositionEncoder}, patterns};
+//!
+//! let code = |name| apgcode::encode(&patterns::find(name).unwrap().cells());
+//! assert_eq!(code("blinker"), "xp2_7");
+//! assert_eq!(code("glider"), "xq4_153");
+//! assert_eq!(code("lwss"), "xq4_6frc");
+//!
+//! let block = RunLengthEncoded::default().decode("2o$2o!");
+//! assert_eq!(apgcode::encode(&block), "xs4_33");
+//! let eater = RunLengthEncoded::default().decode("2o$obo$2bo$2b2o!");
+//! assert_eq!(apgcode::encode(&eater), "xs7_178c");
+//!
+//! // decoding gives the canonical orientation, anchored at the origin
+//! assert_eq!(apgcode::decode("xs4_33"), block);
+//! assert_eq!(apgcode::encode(&apgcode::decode("xs7_178c")), "xs7_178c");
+//! ```
+
+use crate::{engine::GameOfLife, prelude::*, transform, Pos2};
+
+/// The characters for column values and the lengths of `y` runs
+const CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// The most generations searched for the period of an object
+const MAX_PERIOD: usize = 1 << 12;
+
+/// The apgcode of an object, or `PATHOLOGICAL` if it doesn't repeat within 4096 generations
+///
+/// The cells must be sorted. An empty pattern is `xs0_0`.
+pub fn encode(cells: &[Pos2]) -> String {
+    if cells.is_empty() {
+        return "xs0_0".to_owned();
+    }
+    let mut game = GameOfLife::from_alive(cells.to_vec());
+    let Some(info) = game.find_period(MAX_PERIOD) else {
+        return "PATHOLOGICAL".to_owned();
+    };
+
+    // the game is back in the first phase of its cycle, so the next `period` states are the phases
+    let mut best: Option<String> = None;
+    for _ in 0..info.period {
+        let wechsler = canonical(game.alive());
+        if best
+            .as_ref()
+            .is_none_or(|best| (wechsler.len(), &wechsler) < (best.len(), best))
+        {
+            best = Some(wechsler);
+        }
+        game.next_generation();
+    }
+
+    let prefix = if info.is_spaceship() {
+        format!("xq{}", info.period)
+    } else if info.period > 1 {
+        format!("xp{}", info.period)
+    } else {
+        format!("xs{}", game.alive_count())
+    };
+    // objects have at least 1 phase, so there's always a best code
+    format!("{}_{}", prefix, best.unwrap_or_default())
+}
+
+/// The canonical extended Wechsler code over all 8 orientations of the cells
+fn canonical(cells: &[Pos2]) -> String {
+    let mut best = String::new();
+    let mut rotated = cells.to_vec();
+    for _ in 0..4 {
+        for orientation in [transform::recenter(&rotated), transform::flip_x(&rotated)] {
+            let code = wechsler(&transform::recenter(&orientation));
+            if best.is_empty() || (code.len(), &code) < (best.len(), &best) {
+                best = code;
+            }
+        }
+        rotated = transform::rotate_cw(&rotated);
+    }
+    best
+}
+
+/// The extended Wechsler code of the cells, which are anchored at the origin
+fn wechsler(cells: &[Pos2]) -> String {
+    let width = cells.iter().map(|p| p.x + 1).max().unwrap_or_default() as usize;
+    let strips = cells.last().map_or(0, |p| p.y as usize / 5 + 1);
+    let mut columns = vec![0u8; width * strips];
+    for &pos in cells {
+        let (x, y) = (pos.x as usize, pos.y as usize);
+        columns[y / 5 * width + x] |= 1 << (y % 5);
+    }
+
+    let mut code = String::new();
+    for (i, strip) in columns.chunks(width).enumerate() {
+        if i > 0 {
+            code.push('z');
+        }
+        let mut zeros = 0;
+        for &column in strip {
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_zeros(&mut code, zeros);
+            zeros = 0;
+            code.push(CHARS[column as usize] as char);
+        }
+    }
+    code
+}
+
+/// Writes a run of empty columns in as few characters as possible
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    while zeros > 0 {
+        let run = zeros.min(39);
+        match run {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                code.push('y');
+                code.push(CHARS[run - 4] as char);
+            }
+        }
+        zeros -= run;
+    }
+}
+
+/// Reads the cells of an apgcode, anchored at the origin
+///
+/// The prefix up to the first `_` is skipped, so this reads still lifes, oscillators and
+/// spaceships alike. Unknown characters are ignored.
+pub fn decode(code: &str) -> Vec<Pos2> {
+    let body = code.split_once('_').map_or(code, |(_, body)| body);
+    let value = |c: u8| CHARS.iter().position(|&d| d == c);
+
+    let mut cells = Vec::new();
+    let (mut x, mut strip) = (0, 0);
+    let mut chars = body.bytes();
+    while let Some(c) = chars.next() {
+        match c {
+            b'w' => x += 2,
+            b'x' => x += 3,
+            b'y' => x += 4 + chars.next().and_then(value).unwrap_or(0) as i32,
+            b'z' => {
+                x = 0;
+                strip += 1;
+            }
+            _ => {
+                let Some(bits) = value(c).filter(|&bits| bits < 32) else {
+                    continue;
+                };
+                for row in (0..5).filter(|row| bits & (1 << row) != 0) {
+                    cells.push(Pos2::new(x, strip * 5 + row));
+                }
+                x += 1;
+            }
+        }
+    }
+    cells.sort_unstable();
+    cells
+}
@@ -0,0 +1,343 @@
+use super::{DecodeError, DecodeLimits, Format, Limit, Pattern, PatternMeta};
+use crate::{trace, Pos2, Rule};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+/// A compact binary format for snapshots, much smaller and faster than text for large patterns
+///
+/// The layout is:
+/// - the magic bytes `CGOL`, a version byte, and a flags byte
+/// - the rule as a length-prefixed string
+/// - the generation and the cell count as varints
+/// - every cell as the zig-zag varint difference to the previous cell, starting from (0, 0)
+/// - a CRC-32 of everything before it, if the checksum flag is set
+///
+/// Since the cells are sorted, the differences are small and most cells take 2 bytes.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc::{Binary, Format}, Pos2, Rule};
+///
+/// let cells = [(-5, -300), (-4, -300), (70000, -300), (-70000, 12)].map(Pos2::from);
+/// let mut bytes = Vec::new();
+/// Binary::default().set_generation(1234).encode_to(&cells, &mut bytes).unwrap();
+/// assert!(bytes.starts_with(b"CGOL"));
+///
+/// let pattern = Binary::decode_from(&bytes[..]).unwrap();
+/// assert_eq!(pattern.format, Format::Binary);
+/// assert_eq!(pattern.cells, cells);
+/// assert_eq!(pattern.generation, 1234);
+/// assert_eq!(pattern.rule, Some(Rule::CONWAY));
+///
+/// // an empty pattern
+/// let mut bytes = Vec::new();
+/// Binary::default().encode_to(&[], &mut bytes).unwrap();
+/// assert!(Binary::decode_from(&bytes[..]).unwrap().cells.is_empty());
+///
+/// // truncated and corrupted files are detected
+/// let mut bytes = Vec::new();
+/// Binary::default().encode_to(&cells, &mut bytes).unwrap();
+/// assert!(Binary::decode_from(&bytes[..bytes.len() - 1]).is_err());
+/// bytes[12] ^= 1;
+/// assert!(Binary::decode_from(&bytes[..]).is_err());
+///
+/// // so are deltas past the range of the coordinates, without a checksum to catch them
+/// let mut bytes = b"CGOL\x01\x00\x06B3/S23\x00\x02\x0a\x00".to_vec();
+/// bytes.extend([0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x00]);
+/// let err = Binary::decode_from(&bytes[..]).unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+/// ```
+pub struct Binary {
+    rule: Rule,
+    generation: u64,
+    checksum: bool,
+}
+impl Binary {
+    /// The bytes every binary snapshot starts with
+    pub const MAGIC: &'static [u8; 4] = super::BINARY_MAGIC;
+    const VERSION: u8 = 1;
+    /// The flag for a trailing CRC-32
+    const CHECKSUM: u8 = 1 << 0;
+
+    /// Records the generation, so the run can be resumed from it
+    pub fn set_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+    /// The rule recorded with the cells, [`Rule::CONWAY`] by default
+    pub fn with_rule(mut self, rule: &Rule) -> Self {
+        self.rule = *rule;
+        self
+    }
+    /// Whether a CRC-32 is written to detect truncated or corrupted files, `true` by default
+    pub fn with_checksum(mut self, checksum: bool) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// Writes the sorted cells to `w` through a [`BufWriter`]
+    pub fn encode_to<W: Write>(&self, cells: &[Pos2], w: W) -> io::Result<()> {
+        let w = trace::counted(w, Format::Binary, cells.len());
+        let mut w = Crc32Writer::new(BufWriter::new(w));
+        let flags = if self.checksum { Self::CHECKSUM } else { 0 };
+        w.write_all(Self::MAGIC)?;
+        w.write_all(&[Self::VERSION, flags])?;
+        let rule = self.rule.to_string();
+        write_varint(&mut w, rule.len() as u64)?;
+        w.write_all(rule.as_bytes())?;
+        write_varint(&mut w, self.generation)?;
+        write_varint(&mut w, cells.len() as u64)?;
+
+        let mut last = Pos2::zero();
+        for &pos in cells {
+            write_varint(&mut w, zigzag(pos.x as i64 - last.x as i64))?;
+            write_varint(&mut w, zigzag(pos.y as i64 - last.y as i64))?;
+            last = pos;
+        }
+
+        let (mut w, crc) = w.finish();
+        if self.checksum {
+            w.write_all(&crc.to_le_bytes())?;
+        }
+        w.flush()
+    }
+
+    /// Reads a snapshot from `r` through a [`BufReader`], within the default [`DecodeLimits`]
+    ///
+    /// Bad magic bytes, an unknown version, a checksum mismatch, or out of range cells are
+    /// [`io::ErrorKind::InvalidData`] errors, and truncated files without a checksum are
+    /// [`io::ErrorKind::UnexpectedEof`] errors.
+    pub fn decode_from<R: Read>(r: R) -> io::Result<Pattern> {
+        Self::decode_limited(r, DecodeLimits::default())
+    }
+    /// Like [`Binary::decode_from`], within `limits`
+    ///
+    /// The cell count is checked as soon as it's read, and every cell as it's read, so a
+    /// snapshot past the limits fails before its cells are. The error is an
+    /// [`io::ErrorKind::InvalidData`] error wrapping a [`DecodeError::LimitExceeded`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{Binary, DecodeError, DecodeLimits, Limit}, Pos2};
+    ///
+    /// let cells = [(0, 0), (5000, 0)].map(Pos2::from);
+    /// let mut bytes = Vec::new();
+    /// Binary::default().encode_to(&cells, &mut bytes).unwrap();
+    ///
+    /// let limits = DecodeLimits { max_coord: 1000, ..DecodeLimits::default() };
+    /// let err = Binary::decode_limited(&bytes[..], limits).unwrap_err();
+    /// assert_eq!(
+    ///     err.into_inner().unwrap().downcast::<DecodeError>().unwrap(),
+    ///     Box::new(DecodeError::LimitExceeded { limit: Limit::Coord, max: 1000 })
+    /// );
+    /// ```
+    pub fn decode_limited<R: Read>(r: R, limits: DecodeLimits) -> io::Result<Pattern> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut r = Crc32Reader::new(BufReader::new(r));
+        let mut header = [0; 6];
+        r.read_exact(&mut header)?;
+        if &header[..4] != Self::MAGIC {
+            return Err(invalid("not a binary snapshot"));
+        }
+        if header[4] != Self::VERSION {
+            return Err(invalid("unsupported binary snapshot version"));
+        }
+        let flags = header[5];
+
+        let rule_len = read_varint(&mut r)?;
+        let mut rule = Vec::new();
+        (&mut r).take(rule_len).read_to_end(&mut rule)?;
+        if rule.len() as u64 != rule_len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let rule = std::str::from_utf8(&rule)
+            .ok()
+            .and_then(|rule| rule.parse().ok())
+            .ok_or_else(|| invalid("invalid rule"))?;
+        let generation = read_varint(&mut r)?;
+        let count = read_varint(&mut r)?;
+        limits.check(Limit::Cells, count).map_err(limit_error)?;
+
+        // don't trust the count for more than a modest preallocation
+        let mut cells = Vec::with_capacity(count.min(1 << 20) as usize);
+        let mut last = Pos2::zero();
+        for _ in 0..count {
+            // a corrupt delta can be anywhere in the range of i64, so the sum can overflow it
+            let mut coord = |last: i32| -> io::Result<i32> {
+                (last as i64)
+                    .checked_add(unzigzag(read_varint(&mut r)?))
+                    .and_then(|coord| coord.try_into().ok())
+                    .ok_or_else(|| invalid("cell out of range"))
+            };
+            let pos = Pos2::new(coord(last.x)?, coord(last.y)?);
+            limits.check_pos(pos).map_err(limit_error)?;
+            if !cells.is_empty() && pos <= last {
+                return Err(invalid("cells are not sorted"));
+            }
+            cells.push(pos);
+            last = pos;
+        }
+
+        if flags & Self::CHECKSUM != 0 {
+            let (mut r, crc) = r.finish();
+            let mut expected = [0; 4];
+            r.read_exact(&mut expected)?;
+            if u32::from_le_bytes(expected) != crc {
+                return Err(invalid("checksum mismatch"));
+            }
+        }
+
+        Ok(Pattern {
+            format: Format::Binary,
+            cells,
+            dying: Vec::new(),
+            meta: PatternMeta::default(),
+            generation,
+            rule: Some(rule),
+        })
+    }
+}
+impl Default for Binary {
+    fn default() -> Self {
+        Self {
+            rule: Rule::CONWAY,
+            generation: 0,
+            checksum: true,
+        }
+    }
+}
+
+/// The I/O error of a snapshot past its [`DecodeLimits`]
+fn limit_error(e: DecodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+/// The [`DecodeError`] of a snapshot that failed to decode, or the error reading it if it's
+/// neither truncated nor corrupted
+pub(super) fn decode_error(e: io::Error) -> Result<DecodeError, io::Error> {
+    match e.kind() {
+        // the snapshot reports truncation and corruption as I/O errors of these kinds
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => {
+            match e
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<DecodeError>())
+            {
+                Some(inner) => Ok(inner.clone()),
+                None => Ok(DecodeError::Corrupt(e.to_string())),
+            }
+        }
+        _ => Err(e),
+    }
+}
+
+/// Maps signed integers to unsigned ones so that small magnitudes stay small
+#[inline]
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+#[inline]
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+/// Writes `n` as a LEB128 varint, 7 bits per byte
+fn write_varint<W: Write>(w: &mut W, mut n: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    w.write_all(&buf[..len])
+}
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        r.read_exact(&mut byte)?;
+        n |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "varint is too long",
+    ))
+}
+
+/// The lookup table for the CRC-32 (IEEE) of a byte
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!crc, |crc, &b| {
+        CRC_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// A writer computing the CRC-32 of everything written through it
+struct Crc32Writer<W> {
+    inner: W,
+    crc: u32,
+}
+impl<W: Write> Crc32Writer<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, crc: 0 }
+    }
+    fn finish(self) -> (W, u32) {
+        (self.inner, self.crc)
+    }
+}
+impl<W: Write> Write for Crc32Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc = crc32_update(self.crc, &buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader computing the CRC-32 of everything read through it
+struct Crc32Reader<R> {
+    inner: R,
+    crc: u32,
+}
+impl<R: Read> Crc32Reader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, crc: 0 }
+    }
+    fn finish(self) -> (R, u32) {
+        (self.inner, self.crc)
+    }
+}
+impl<R: Read> Read for Crc32Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.crc = crc32_update(self.crc, &buf[..n]);
+        Ok(n)
+    }
+}
@@ -0,0 +1,123 @@
+use super::{binary, detect, Binary, DecodeError, Format, Pattern};
+use crate::Result;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// The magic bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether the file name ends in `.gz`
+fn is_gz(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+#[cfg(feature = "compress")]
+fn gz_decoder(reader: BufReader<File>) -> Result<Box<dyn Read>> {
+    Ok(Box::new(flate2::bufread::GzDecoder::new(reader)))
+}
+#[cfg(feature = "compress")]
+fn gz_encoder(writer: BufWriter<File>) -> Result<Box<dyn Write>> {
+    let level = flate2::Compression::default();
+    Ok(Box::new(flate2::write::GzEncoder::new(writer, level)))
+}
+
+#[cfg(not(feature = "compress"))]
+fn gz_decoder(_reader: BufReader<File>) -> Result<Box<dyn Read>> {
+    Err(DecodeError::GzipUnsupported.into())
+}
+#[cfg(not(feature = "compress"))]
+fn gz_encoder(_writer: BufWriter<File>) -> Result<Box<dyn Write>> {
+    Err(super::EncodeError::GzipUnsupported.into())
+}
+
+/// Opens a pattern file for reading, decompressing it on the fly if it's gzipped
+///
+/// A file is gzipped if its name ends in `.gz` or it starts with the gzip magic bytes. Without
+/// the `compress` feature, gzipped files are a [`DecodeError::GzipUnsupported`] error.
+pub fn open_file(path: impl AsRef<Path>) -> Result<Box<dyn Read>> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    if is_gz(path) || reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        gz_decoder(reader)
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Creates a pattern file for writing, compressing it on the fly if its name ends in `.gz`
+///
+/// The writer is buffered, and the gzip stream is finished when it's dropped. Without the
+/// `compress` feature, `.gz` names are an [`EncodeError::GzipUnsupported`] error.
+pub fn create_file(path: impl AsRef<Path>) -> Result<Box<dyn Write>> {
+    let path = path.as_ref();
+    let writer = BufWriter::new(File::create(path)?);
+    if is_gz(path) {
+        gz_encoder(writer)
+    } else {
+        Ok(Box::new(writer))
+    }
+}
+
+/// Reads and decodes a pattern file, which may be gzipped (see [`open_file`])
+///
+/// The format is `format` if given, or else the detected format, with the file extension (before
+/// any `.gz`) as a fallback. Binary snapshots are decoded as they're read, while the text formats
+/// are read fully first.
+///
+/// Files that can't be read are [`Error::Io`](crate::Error::Io) errors, while contents that
+/// can't be decoded are [`Error::Decode`](crate::Error::Decode) errors.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc::{self, Format}, Pos2};
+///
+/// let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/glider.rle.gz");
+/// # #[cfg(feature = "compress")]
+/// # {
+/// let pattern = enc::decode_file(fixture, None).unwrap();
+/// assert_eq!(pattern.format, Format::Rle);
+/// assert_eq!(pattern.meta.name.as_deref(), Some("Glider"));
+/// assert_eq!(pattern.cells, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(Pos2::from));
+///
+/// // round trip through a gzipped binary snapshot
+/// let path = std::env::temp_dir().join("cgolrs-decode-file-doctest.bin.gz");
+/// let mut file = enc::create_file(&path).unwrap();
+/// enc::Binary::default().encode_to(&pattern.cells, &mut file).unwrap();
+/// drop(file);
+/// let snapshot = enc::decode_file(&path, None).unwrap();
+/// assert_eq!(snapshot.format, Format::Binary);
+/// assert_eq!(snapshot.cells, pattern.cells);
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// # #[cfg(not(feature = "compress"))]
+/// # assert!(matches!(
+/// #     enc::decode_file(fixture, None),
+/// #     Err(cgolrs::Error::Decode(enc::DecodeError::GzipUnsupported))
+/// # ));
+/// ```
+pub fn decode_file(path: impl AsRef<Path>, format: Option<Format>) -> Result<Pattern> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(open_file(path)?);
+
+    let binary = match format {
+        Some(format) => format == Format::Binary,
+        None => reader.fill_buf()?.starts_with(Binary::MAGIC),
+    };
+    if binary {
+        return Binary::decode_from(reader).map_err(|e| match binary::decode_error(e) {
+            Ok(e) => e.into(),
+            Err(e) => e.into(),
+        });
+    }
+
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+    let format = format
+        .or_else(|| detect(&content))
+        .or_else(|| Format::from_path(path))
+        .ok_or(DecodeError::UnknownFormat)?;
+    Ok(format.decode(content)?)
+}
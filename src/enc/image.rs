@@ -0,0 +1,115 @@
+//! Starting patterns from images, where dark pixels are alive cells
+//!
+//! This needs the `image` feature.
+
+use crate::Pos2;
+use std::{fmt, path::Path};
+
+/// The default maximum width and height of an imported image, which is already 16 million cells
+pub const MAX_DIMENSION: u32 = 4096;
+
+/// The error returned when an image can't be imported
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ImageError {
+    /// The image couldn't be read or decoded
+    Decode(::image::ImageError),
+    /// The image is wider or taller than the cap
+    TooLarge { width: u32, height: u32, max: u32 },
+}
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(e) => write!(f, "failed to decode image: {}", e),
+            Self::TooLarge { width, height, max } => write!(
+                f,
+                "a {}x{} image is over the limit of {} pixels on each side",
+                width, height, max
+            ),
+        }
+    }
+}
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(e) => Some(e),
+            Self::TooLarge { .. } => None,
+        }
+    }
+}
+impl From<::image::ImageError> for ImageError {
+    fn from(e: ::image::ImageError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+/// Reads the alive cells from an image, with pixel (x, y) becoming the cell at (x, y)
+///
+/// A pixel is alive if its brightness is below `threshold` and it's at least half opaque, so
+/// transparent pixels are always dead. Images wider or taller than [`MAX_DIMENSION`] are
+/// rejected, see [`decode_image_capped`] for a different cap.
+///
+/// ## Returns
+/// The alive cells, sorted for [`GameOfLife::from_alive`](crate::engine::GameOfLife::from_alive)
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc, engine::{GameOfLife, Theme}, patterns, Pos2, Rect};
+///
+/// let glider = patterns::find("glider").unwrap().cells();
+/// let game = GameOfLife::from_alive(glider.clone());
+/// let path = std::env::temp_dir().join("cgolrs-decode-image-doctest.png");
+/// game.window(Rect::new(Pos2::new(0, 0), Pos2::new(3, 3)))
+///     .to_png(&path, 1, Theme::LIGHT)
+///     .unwrap();
+/// assert_eq!(enc::image::decode_image(&path, 128).unwrap(), glider);
+///
+/// // the light cells instead
+/// game.window(Rect::new(Pos2::new(0, 0), Pos2::new(3, 3)))
+///     .to_png(&path, 1, Theme::DARK)
+///     .unwrap();
+/// assert_eq!(enc::image::decode_image(&path, 128).unwrap().len(), 4);
+///
+/// // images over the cap are rejected
+/// assert!(enc::image::decode_image_capped(&path, 128, 2).is_err());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn decode_image(path: impl AsRef<Path>, threshold: u8) -> Result<Vec<Pos2>, ImageError> {
+    decode_image_capped(path, threshold, MAX_DIMENSION)
+}
+
+/// Reads the alive cells from an image like [`decode_image`], rejecting images wider or taller
+/// than `max_dimension`
+///
+/// The size is read from the image header, so an oversized image is never decoded.
+pub fn decode_image_capped(
+    path: impl AsRef<Path>,
+    threshold: u8,
+    max_dimension: u32,
+) -> Result<Vec<Pos2>, ImageError> {
+    let path = path.as_ref();
+    let (width, height) = ::image::image_dimensions(path)?;
+    if width > max_dimension || height > max_dimension {
+        return Err(ImageError::TooLarge {
+            width,
+            height,
+            max: max_dimension,
+        });
+    }
+
+    // pixels are enumerated row by row, so the cells come out sorted
+    let image = ::image::open(path)?.to_luma_alpha8();
+    let alive = image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| {
+            let [luma, alpha] = pixel.0;
+            luma < threshold && alpha >= 128
+        })
+        .map(|(x, y, _)| Pos2::new(x as i32, y as i32))
+        .collect::<Vec<_>>();
+    debug_assert!(
+        alive.windows(2).all(|w| w[0] < w[1]),
+        "output is not properly sorted"
+    );
+    Ok(alive)
+}
@@ -0,0 +1,40 @@
+//! The part of `std::io` the RLE encoder writes through, so that it also works without `std`
+//!
+//! Without `std`, the only writer is a `Vec<u8>`, which can't fail.
+
+#[cfg(feature = "std")]
+pub(super) use std::io::{Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub(super) use self::alloc_only::{Result, Write};
+
+#[cfg(not(feature = "std"))]
+mod alloc_only {
+    use crate::prelude::*;
+    use core::{convert::Infallible, fmt};
+
+    pub type Result<T> = core::result::Result<T, Infallible>;
+
+    pub trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+
+        /// Makes `write!` work on the writer
+        fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+            match args.as_str() {
+                Some(s) => self.write_all(s.as_bytes()),
+                None => self.write_all(alloc::fmt::format(args).as_bytes()),
+            }
+        }
+    }
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+    }
+}
@@ -0,0 +1,1703 @@
+pub mod apgcode;
+#[cfg(feature = "std")]
+mod binary;
+#[cfg(feature = "std")]
+mod file;
+#[cfg(feature = "image")]
+pub mod image;
+mod io;
+
+#[cfg(feature = "std")]
+pub use self::binary::Binary;
+#[cfg(feature = "std")]
+pub use self::file::{create_file, decode_file, open_file};
+use self::io::Write;
+use crate::{pos::Pos2, prelude::*, trace, transform, Rule};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::{io::BufWriter, path::Path};
+
+/// The bytes every binary snapshot starts with, see [`Binary::MAGIC`]
+const BINARY_MAGIC: &[u8; 4] = b"CGOL";
+
+pub trait PositionEncoder {
+    fn encode(self, positions: &[Pos2]) -> String;
+    fn decode(self, value: &str) -> Vec<Pos2>;
+}
+
+/// Writes `n` in decimal without going through [`fmt`] or allocating
+fn write_int<W: Write>(w: &mut W, mut n: u64) -> io::Result<usize> {
+    let mut buf = [0; 20];
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    w.write_all(&buf[i..])?;
+    Ok(buf.len() - i)
+}
+/// The number of decimal digits in `n`
+fn digits(n: u64) -> usize {
+    n.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+struct RunEncoder<W: Write> {
+    out: W,
+    line_len: usize,
+    max_line_len: usize,
+}
+impl<W: Write> RunEncoder<W> {
+    fn new(out: W, max_line_len: usize) -> Self {
+        Self {
+            out,
+            line_len: 0,
+            max_line_len,
+        }
+    }
+
+    fn push_run(&mut self, run: i32, c: char) -> io::Result<()> {
+        self.push_token_run(run, &[c as u8])
+    }
+    /// Like [`RunEncoder::push_run`], for tokens of more than one character like the `pA` of
+    /// state 25
+    fn push_token_run(&mut self, run: i32, token: &[u8]) -> io::Result<()> {
+        let len = match run {
+            0 => return Ok(()),
+            1 => token.len(),
+            n => digits(n as u64) + token.len(),
+        };
+        // a run longer than a line can't fit on any line, so it starts one instead of leaving
+        // an empty one behind
+        if self.max_line_len > 0 && self.line_len > 0 && self.line_len + len > self.max_line_len {
+            self.out.write_all(b"\n")?;
+            self.line_len = 0;
+        }
+        self.line_len += len;
+        if run > 1 {
+            write_int(&mut self.out, run as u64)?;
+        }
+        self.out.write_all(token)
+    }
+
+    pub fn end(mut self) -> io::Result<W> {
+        self.out.write_all(b"!")?;
+        Ok(self.out)
+    }
+}
+
+/// The provenance of a pattern, kept in the `#N`, `#O` and `#C` lines of an RLE file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternMeta {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// The comment lines in order
+    pub comments: Vec<String>,
+}
+impl PatternMeta {
+    /// Reads the metadata lines of an encoded RLE pattern
+    fn read_rle(value: &str) -> Self {
+        let mut meta = Self::default();
+        for line in value.lines().map(str::trim_end) {
+            let Some(rest) = line.strip_prefix('#') else {
+                continue;
+            };
+            let (tag, text) = rest.split_at(rest.len().min(1));
+            let text = text.strip_prefix(' ').unwrap_or(text);
+            match tag {
+                "N" => meta.name = Some(text.to_owned()),
+                "O" => meta.author = Some(text.to_owned()),
+                // the `#CXRLE` header is read by `read_generation`, it isn't a comment
+                "C" if text.starts_with("XRLE") => {}
+                "C" | "c" => meta.comments.push(text.to_owned()),
+                _ => {}
+            }
+        }
+        meta
+    }
+}
+
+/// The token of a cell state in multi-state RLE and its length: `.` for dead, `A` to `X` for
+/// states 1 to 24 and a prefix from `p` to `y` for the higher states, like `pA` for 25
+fn state_token(state: u8) -> ([u8; 2], usize) {
+    match state {
+        0 => ([b'.', 0], 1),
+        1..=24 => ([b'A' + state - 1, 0], 1),
+        _ => ([b'p' + (state - 25) / 24, b'A' + (state - 25) % 24], 2),
+    }
+}
+
+/// Merges the sorted alive cells and the sorted dying cells, which never share a cell
+///
+/// Alive cells are in state 1, or in state 1 plus their color if they have `colors`.
+fn merge_states<'a>(
+    alive: &'a [Pos2],
+    colors: &'a [u8],
+    dying: &'a [(Pos2, u8)],
+) -> impl Iterator<Item = (Pos2, u8)> + 'a {
+    let color = |i| colors.get(i).copied().unwrap_or(0);
+    let alive = alive
+        .iter()
+        .enumerate()
+        .map(move |(i, &pos)| (pos, 1 + color(i)));
+    let mut alive = alive.peekable();
+    let mut dying = dying.iter().copied().peekable();
+    core::iter::from_fn(move || match (alive.peek(), dying.peek()) {
+        (Some(a), Some(d)) if a.0 < d.0 => alive.next(),
+        (Some(_), None) => alive.next(),
+        _ => dying.next(),
+    })
+}
+
+/// Splits `text` into lines of at most `width` characters at spaces, keeping words longer than
+/// the width whole
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if line.is_empty() {
+            line.push_str(word);
+        } else if line.chars().count() + 1 + word.chars().count() <= width {
+            line.push(' ');
+            line.push_str(word);
+        } else {
+            lines.push(core::mem::replace(&mut line, word.to_owned()));
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// The extended RLE format (`.rle`) used by most pattern collections
+///
+/// # Example
+/// Decoding skips comments and the header line, and encoding round-trips:
+/// ```rust
+/// use cgolrs::{
+///     enc::{PositionEncoder, RunLengthEncoded},
+///     patterns, Pos2,
+/// };
+///
+/// let rle = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2b\no$ # a comment\n3o! trailing";
+/// let glider = RunLengthEncoded::default().decode(rle);
+/// assert_eq!(glider, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(Pos2::from));
+///
+/// // runs can span multiple digits, and `A` is alive in the multi-state letters
+/// let cells = RunLengthEncoded::default().decode("12bo$3A2$3o!");
+/// assert_eq!(cells.len(), 7);
+/// assert_eq!(cells[1..4], [(0, 1), (1, 1), (2, 1)].map(Pos2::from));
+///
+/// for pattern in patterns::PATTERNS {
+///     let cells = pattern.cells();
+///     let encoded = RunLengthEncoded::default().set_name(pattern.name).encode(&cells);
+///     assert_eq!(RunLengthEncoded::default().decode(&encoded), cells);
+/// }
+/// ```
+pub struct RunLengthEncoded {
+    meta: PatternMeta,
+    line_width: usize,
+    rule: Option<Rule>,
+    generation: Option<u64>,
+    header: bool,
+    absolute: bool,
+    dying: Vec<(Pos2, u8)>,
+    colors: Vec<u8>,
+    lenient: bool,
+    limits: DecodeLimits,
+}
+impl RunLengthEncoded {
+    /// An encoder decoding the messy RLE of files in the wild, which strict decoding rejects
+    ///
+    /// Lenient decoding strips carriage returns, skips whitespace between a run count and its
+    /// tag, accepts any spelling of the header line and ignores everything after the final `!`.
+    /// Unless the header names a rule with more than 2 states, the uppercase `B` and `O` are read
+    /// as `b` and `o` rather than the multi-state letters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::RunLengthEncoded, Pos2};
+    ///
+    /// let rle = "x=3,y=3,rule=23/3\r\nbO$2B O$3 O!\r\nthat's a glider";
+    /// let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(Pos2::from);
+    /// assert_eq!(RunLengthEncoded::lenient().try_decode(rle), Ok(glider.to_vec()));
+    /// assert!(RunLengthEncoded::default().try_decode(rle).is_err());
+    /// ```
+    pub fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Self::default()
+        }
+    }
+    pub fn set_name<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.meta.name = Some(name.as_ref().to_owned());
+        self
+    }
+    /// Sets the name, author, and comments written to the header, replacing any set name
+    pub fn set_meta(mut self, meta: PatternMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+    /// Adds a `#C` comment line, wrapped to the line width
+    pub fn with_comment(mut self, comment: &str) -> Self {
+        self.meta.comments.push(comment.to_owned());
+        self
+    }
+    /// The longest line written, 70 by default, which comments are wrapped to as well
+    ///
+    /// A width of 0 never wraps.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, Pos2, Rule};
+    ///
+    /// let soup = (0..40)
+    ///     .flat_map(|y| (0..40).map(move |x| Pos2::new(x, y)))
+    ///     .filter(|p| (p.x * 7 + p.y * 13) % 5 < 2)
+    ///     .collect::<Vec<_>>();
+    /// let rle = RunLengthEncoded::default()
+    ///     .line_width(30)
+    ///     .with_rule(&Rule::CONWAY)
+    ///     .with_comment("a comment that is much too long to fit on one line of thirty")
+    ///     .encode(&soup);
+    /// assert!(rle.lines().all(|line| line.len() <= 30));
+    /// assert_eq!(RunLengthEncoded::default().decode(&rle), soup);
+    ///
+    /// let rle = RunLengthEncoded::default().line_width(0).encode(&soup);
+    /// assert_eq!(rle.lines().count(), 3);
+    /// ```
+    pub fn line_width(mut self, width: usize) -> Self {
+        self.line_width = width;
+        self
+    }
+    /// Whether the metadata and `x = ` header lines are written, `true` by default
+    ///
+    /// Without them the output is only the cells, like the patterns in [`crate::patterns`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, patterns};
+    ///
+    /// let glider = patterns::find("glider").unwrap();
+    /// let rle = RunLengthEncoded::default()
+    ///     .with_header(false)
+    ///     .set_name("ignored")
+    ///     .encode(&glider.cells());
+    /// assert_eq!(rle, "bo$2bo$3o!");
+    /// ```
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+    /// The rule written to the `x = ` header line, `23/3` by default
+    pub fn with_rule(mut self, rule: &Rule) -> Self {
+        self.rule = Some(*rule);
+        self
+    }
+    /// The dying cells of a Generations rule to encode along with the alive cells, sorted by y
+    /// and then x
+    ///
+    /// With dying cells or a rule with more than 2 states, the cells are written with the
+    /// multi-state letters: `.` for dead, `A` for alive and `B` and on for the dying states.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, Pos2, Rule};
+    ///
+    /// let alive = [(0, 0), (1, 0)].map(Pos2::from);
+    /// let dying = [((3, 0).into(), 2), ((0, 1).into(), 2), ((1, 1).into(), 30)];
+    /// let brain = Rule::from_name("brians-brain").unwrap();
+    /// let rle = RunLengthEncoded::default()
+    ///     .with_header(false)
+    ///     .with_rule(&brain)
+    ///     .set_dying(dying.to_vec())
+    ///     .encode(&alive);
+    /// assert_eq!(rle, "2A.B$BpF!");
+    /// let (decoded, decoded_dying) = RunLengthEncoded::default().decode_states(&rle);
+    /// assert_eq!((&decoded[..], &decoded_dying[..]), (&alive[..], &dying[..]));
+    ///
+    /// // life-like rules without dying cells keep the two-state letters
+    /// let rle = RunLengthEncoded::default().with_header(false).encode(&alive);
+    /// assert_eq!(rle, "2o!");
+    /// ```
+    pub fn set_dying(mut self, dying: Vec<(Pos2, u8)>) -> Self {
+        self.dying = dying;
+        self
+    }
+    /// The colors of Immigration for the alive cells to encode, parallel to them, see
+    /// [`GameOfLife::colors`](crate::engine::GameOfLife::colors)
+    ///
+    /// The cells are written with the multi-state letters `A` for color 0 and `B` for color 1,
+    /// under the `Immigration` rule of Golly unless another rule is set. They're decoded by
+    /// [`RunLengthEncoded::decode_colored`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, engine::GameOfLife, Pos2};
+    ///
+    /// let cells = [((0, 0).into(), 0), ((1, 0).into(), 1), ((3, 1).into(), 1)];
+    /// let game = GameOfLife::from_alive_colored(cells.to_vec());
+    /// let rle = RunLengthEncoded::default()
+    ///     .set_colors(game.colors().unwrap().to_vec())
+    ///     .encode(game.alive());
+    /// assert!(rle.ends_with("x = 0, y = 0, rule = Immigration\nAB$3.B!\n"));
+    /// assert_eq!(RunLengthEncoded::default().decode_colored(&rle), cells);
+    ///
+    /// // files of Immigration keep their colors when decoded leniently
+    /// assert_eq!(RunLengthEncoded::lenient().decode_colored(&rle), cells);
+    /// ```
+    pub fn set_colors(mut self, colors: Vec<u8>) -> Self {
+        self.colors = colors;
+        self
+    }
+    /// Records the generation in a `#CXRLE Gen=` header line, so the run can be resumed from it
+    pub fn set_generation(mut self, generation: u64) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+    /// Whether the position of the pattern is kept, `true` by default
+    ///
+    /// Encoding records the top-left corner of the cells in a `#CXRLE Pos=` header line and
+    /// decoding moves the cells back there. Without it, patterns are encoded and decoded with
+    /// their top-left corner at (0, 0).
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{
+    ///     enc::{PositionEncoder, RunLengthEncoded},
+    ///     Pos2,
+    /// };
+    ///
+    /// let cells = [(-2999, 500), (-2998, 501), (-3000, 502)].map(Pos2::from);
+    /// let rle = RunLengthEncoded::default().set_generation(12).encode(&cells);
+    /// assert!(rle.starts_with("#CXRLE Pos=-3000,500 Gen=12\n"));
+    /// assert_eq!(RunLengthEncoded::default().decode(&rle), cells);
+    ///
+    /// // normalized to the origin, either when encoding or decoding
+    /// let normalized = [(1, 0), (2, 1), (0, 2)].map(Pos2::from);
+    /// let relative = RunLengthEncoded::default().set_absolute_position(false);
+    /// assert_eq!(relative.decode(&rle), normalized);
+    /// let rle = RunLengthEncoded::default().set_absolute_position(false).encode(&cells);
+    /// assert_eq!(RunLengthEncoded::default().decode(&rle), normalized);
+    /// ```
+    pub fn set_absolute_position(mut self, absolute: bool) -> Self {
+        self.absolute = absolute;
+        self
+    }
+
+    /// The bounds on the cells decoded, [`DecodeLimits::default`] unless set
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Reads the generation from the `#CXRLE Gen=` metadata of an encoded pattern, if any
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::RunLengthEncoded;
+    ///
+    /// let rle = "#CXRLE Pos=0,0 Gen=1000\nx = 1, y = 1, rule = 23/3\no!\n";
+    /// assert_eq!(RunLengthEncoded::read_generation(rle), Some(1000));
+    /// assert_eq!(RunLengthEncoded::read_generation("o!"), None);
+    /// ```
+    pub fn read_generation(value: &str) -> Option<u64> {
+        Self::read_cxrle(value, "Gen=")
+    }
+    /// Reads the top-left corner of the cells from the `#CXRLE Pos=` metadata of an encoded
+    /// pattern, if any
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::RunLengthEncoded, Pos2};
+    ///
+    /// let rle = "#CXRLE Pos=-3000,500 Gen=1000\nx = 1, y = 1, rule = 23/3\no!\n";
+    /// assert_eq!(RunLengthEncoded::read_position(rle), Some(Pos2::new(-3000, 500)));
+    /// assert_eq!(RunLengthEncoded::read_position("o!"), None);
+    /// ```
+    pub fn read_position(value: &str) -> Option<Pos2> {
+        Self::read_cxrle(value, "Pos=")
+    }
+    /// Reads the rule from the `x = ` header line of an encoded pattern, if any
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::RunLengthEncoded, Rule};
+    ///
+    /// let rle = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+    /// assert_eq!(RunLengthEncoded::read_rule(rle), Some(Rule::CONWAY));
+    /// assert_eq!(RunLengthEncoded::read_rule("x = 3, y = 3\nbo$2bo$3o!"), None);
+    /// ```
+    pub fn read_rule(value: &str) -> Option<Rule> {
+        Self::rule_field(value)?.parse().ok()
+    }
+    /// The `rule = ` field of the `x = ` header line as it's written
+    fn rule_field(value: &str) -> Option<&str> {
+        let header = value
+            .lines()
+            .find(|line| line.trim_start().starts_with('x'))?;
+        header.split(',').find_map(|field| {
+            let (key, rule) = field.split_once('=')?;
+            (key.trim() == "rule").then(|| rule.trim())
+        })
+    }
+    /// Parses the value of the first `#CXRLE` field starting with `key`
+    fn read_cxrle<T: FromStr>(value: &str, key: &str) -> Option<T> {
+        value
+            .lines()
+            .filter_map(|line| line.strip_prefix("#CXRLE"))
+            .flat_map(str::split_whitespace)
+            .find_map(|field| field.strip_prefix(key)?.parse().ok())
+    }
+
+    fn encode_header<W: Write>(&self, w: &mut W, tl: Pos2) -> io::Result<()> {
+        if let Some(name) = &self.meta.name {
+            writeln!(w, "#N {}", name)?;
+        }
+        if let Some(author) = &self.meta.author {
+            writeln!(w, "#O {}", author)?;
+        }
+        for comment in &self.meta.comments {
+            if self.line_width == 0 {
+                writeln!(w, "#C {}", comment)?;
+                continue;
+            }
+            for line in wrap(comment, self.line_width.saturating_sub(3)) {
+                writeln!(w, "#C {}", line)?;
+            }
+        }
+        if self.absolute || self.generation.is_some() {
+            w.write_all(b"#CXRLE")?;
+            if self.absolute {
+                write!(w, " Pos={},{}", tl.x, tl.y)?;
+            }
+            if let Some(generation) = self.generation {
+                w.write_all(b" Gen=")?;
+                write_int(w, generation)?;
+            }
+            w.write_all(b"\n")?;
+        }
+        match self.rule {
+            Some(rule) if self.colors.is_empty() || rule != Rule::CONWAY => {
+                write!(w, "x = 0, y = 0, rule = {}", rule)
+            }
+            _ if !self.colors.is_empty() => write!(w, "x = 0, y = 0, rule = {}", IMMIGRATION),
+            _ => w.write_all(b"x = 0, y = 0, rule = 23/3"),
+        }
+    }
+    fn encode_cells<W: Write>(&self, alive_cells: &[Pos2], tl: Pos2, w: W) -> io::Result<W> {
+        let mut last_y = tl.y;
+        let mut seq = RunEncoder::new(w, self.line_width);
+        for (y, row) in transform::rows(alive_cells) {
+            seq.push_run(y - last_y, '$')?;
+            let mut x = tl.x;
+            // each run of adjacent alive cells, after the dead ones before it
+            for run in row.chunk_by(|a, b| a.x + 1 == b.x) {
+                seq.push_run(run[0].x - x, 'b')?;
+                seq.push_run(run.len() as i32, 'o')?;
+                x = run[run.len() - 1].x + 1;
+            }
+            last_y = y;
+        }
+        seq.end()
+    }
+    /// Like [`RunLengthEncoded::encode_cells`] with the multi-state letters, for the alive cells
+    /// along with the dying ones
+    fn encode_states<W: Write>(&self, alive: &[Pos2], tl: Pos2, w: W) -> io::Result<W> {
+        let mut last = tl - Pos2 { x: 1, y: 0 };
+        let (mut run, mut run_state) = (0, 0);
+        let mut seq = RunEncoder::new(w, self.line_width);
+        let push = |seq: &mut RunEncoder<W>, run: i32, state: u8| {
+            let (token, len) = state_token(state);
+            seq.push_token_run(run, &token[..len])
+        };
+        for (pos, state) in merge_states(alive, &self.colors, &self.dying) {
+            // extend the run of the same state
+            if last.y == pos.y && (last.x + 1) == pos.x && state == run_state {
+                run += 1;
+                last = pos;
+                continue;
+            }
+
+            let lines_run = pos.y - last.y;
+            let dead_run = match lines_run {
+                0 => pos.x - last.x - 1,
+                _ => pos.x - tl.x,
+            };
+            push(&mut seq, run, run_state)?;
+            seq.push_run(lines_run, '$')?;
+            push(&mut seq, dead_run, 0)?;
+
+            (run, run_state) = (1, state);
+            last = pos;
+        }
+
+        push(&mut seq, run, run_state)?;
+        seq.end()
+    }
+
+    /// Writes the encoded cells straight to `w` through a [`BufWriter`], without building the
+    /// whole output in memory first
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, Pos2};
+    ///
+    /// let cells = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(Pos2::from);
+    /// let mut out = Vec::new();
+    /// RunLengthEncoded::default().encode_to(&cells, &mut out).unwrap();
+    /// assert_eq!(out, RunLengthEncoded::default().encode(&cells).as_bytes());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn encode_to<W: Write>(&self, cells: &[Pos2], w: W) -> io::Result<()> {
+        let w = trace::counted(w, Format::Rle, cells.len());
+        self.write_rle(cells, BufWriter::new(w))?.flush()
+    }
+    /// Writes the encoded cells to `w`, returning it afterwards
+    fn write_rle<W: Write>(&self, cells: &[Pos2], mut w: W) -> io::Result<W> {
+        // top-left
+        let dying = self.dying.iter().map(|&(pos, _)| pos);
+        let tl = Pos2 {
+            x: cells
+                .iter()
+                .copied()
+                .chain(dying)
+                .map(|p| p.x)
+                .min()
+                .unwrap_or_default(),
+            // because the cells are sorted, the first cells will always have the lowest y-value
+            y: match (cells.first(), self.dying.first()) {
+                (Some(a), Some(d)) => a.y.min(d.0.y),
+                (a, d) => a.copied().or(d.map(|d| d.0)).unwrap_or_default().y,
+            },
+        };
+        let states = !self.dying.is_empty()
+            || !self.colors.is_empty()
+            || self.rule.is_some_and(|rule| rule.states() > 2);
+
+        if self.header {
+            self.encode_header(&mut w, tl)?;
+            w.write_all(b"\n")?;
+        }
+        let mut w = match states {
+            true => self.encode_states(cells, tl, w)?,
+            false => self.encode_cells(cells, tl, w)?,
+        };
+        if self.header {
+            w.write_all(b"\n")?;
+        }
+        Ok(w)
+    }
+
+    /// Decodes the cells along with the name, author, and comments of the pattern
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::{PatternMeta, PositionEncoder, RunLengthEncoded};
+    ///
+    /// let rle = "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\n#C\nbo$2bo$3o!";
+    /// let (cells, meta) = RunLengthEncoded::default().decode_with_meta(rle);
+    /// assert_eq!(cells.len(), 5);
+    /// assert_eq!(meta.name.as_deref(), Some("Glider"));
+    /// assert_eq!(meta.author.as_deref(), Some("Richard K. Guy"));
+    /// assert_eq!(meta.comments, ["The smallest spaceship.", ""]);
+    ///
+    /// // long comments are wrapped to the line width
+    /// let meta = PatternMeta {
+    ///     comments: vec!["a glider travels diagonally by one cell every four gens".to_owned()],
+    ///     ..meta
+    /// };
+    /// let encoded = RunLengthEncoded::default()
+    ///     .line_width(30)
+    ///     .set_meta(meta.clone())
+    ///     .encode(&cells);
+    /// let comments = "#C a glider travels diagonally\n#C by one cell every four gens\n";
+    /// assert!(encoded.contains(comments));
+    /// let (decoded, decoded_meta) = RunLengthEncoded::default().decode_with_meta(&encoded);
+    /// assert_eq!(decoded, cells);
+    /// assert_eq!(decoded_meta.name, meta.name);
+    /// assert_eq!(decoded_meta.author, meta.author);
+    /// ```
+    pub fn decode_with_meta(self, value: &str) -> (Vec<Pos2>, PatternMeta) {
+        let meta = PatternMeta::read_rle(value);
+        (self.decode(value), meta)
+    }
+}
+impl Default for RunLengthEncoded {
+    fn default() -> Self {
+        Self {
+            meta: PatternMeta::default(),
+            line_width: 70,
+            rule: None,
+            generation: None,
+            header: true,
+            absolute: true,
+            dying: Vec::new(),
+            colors: Vec::new(),
+            lenient: false,
+            limits: DecodeLimits::default(),
+        }
+    }
+}
+
+impl PositionEncoder for RunLengthEncoded {
+    fn encode(self, cells: &[Pos2]) -> String {
+        let mut out = Vec::new();
+        // writing to a Vec can't fail, and every part of the encoding is UTF-8
+        let _ = self.write_rle(cells, &mut out);
+        trace::encoded(Format::Rle, cells.len(), out.len());
+        String::from_utf8(out)
+            .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+    }
+
+    fn decode(self, value: &str) -> Vec<Pos2> {
+        self.decode_states(value).0
+    }
+}
+impl RunLengthEncoded {
+    /// Decodes the alive cells along with the dying cells of a Generations rule, which are
+    /// written with the multi-state letters `B` and on
+    ///
+    /// Like [`PositionEncoder::decode`], this never fails: characters that don't belong in RLE
+    /// are skipped, and a pattern past the [`DecodeLimits`] decodes to no cells at all. See
+    /// [`RunLengthEncoded::set_dying`] for an example.
+    pub fn decode_states(self, value: &str) -> (Vec<Pos2>, Vec<(Pos2, u8)>) {
+        self.parse(value, false).unwrap_or_default()
+    }
+    /// Decodes the alive cells with their color of Immigration, written as the multi-state
+    /// letters `A` (or `o`) for color 0 and `B` for color 1
+    ///
+    /// The other states are dropped. See [`RunLengthEncoded::set_colors`] for an example.
+    pub fn decode_colored(self, value: &str) -> Vec<(Pos2, u8)> {
+        let (alive, dying) = self.decode_states(value);
+        let second = dying
+            .into_iter()
+            .filter(|&(_, state)| state == 2)
+            .collect::<Vec<_>>();
+        // the states 1 and 2 are the colors 0 and 1
+        merge_states(&alive, &[], &second)
+            .map(|(pos, state)| (pos, state - 1))
+            .collect()
+    }
+    /// Decodes the alive cells, returning an error at the first character that isn't valid RLE
+    ///
+    /// See [`RunLengthEncoded::try_decode_states`] for what is rejected.
+    pub fn try_decode(self, value: &str) -> Result<Vec<Pos2>, DecodeError> {
+        self.try_decode_states(value).map(|(alive, _)| alive)
+    }
+    /// Like [`RunLengthEncoded::decode_states`], returning an error at the first character that
+    /// isn't valid RLE unless the encoder is [`RunLengthEncoded::lenient`]
+    ///
+    /// Strict decoding rejects carriage returns, whitespace between a run count and its tag, a
+    /// header line that isn't spelled `x = 3, y = 3, rule = B3/S23`, states the rule of the
+    /// header doesn't have, unknown characters, and anything but whitespace after the final
+    /// `!`. Patterns past the [`DecodeLimits`] fail either way.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::{DecodeError, RunLengthEncoded};
+    ///
+    /// let rle = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    /// assert_eq!(RunLengthEncoded::default().try_decode(rle).unwrap().len(), 5);
+    ///
+    /// let err = RunLengthEncoded::default().try_decode("x = 3, y = 3\nbo$2bo$3z!");
+    /// assert_eq!(
+    ///     err,
+    ///     Err(DecodeError::Syntax {
+    ///         line: 2,
+    ///         column: 9,
+    ///         reason: "unexpected character `z`".to_owned(),
+    ///     })
+    /// );
+    /// ```
+    pub fn try_decode_states(self, value: &str) -> Result<DecodedStates, DecodeError> {
+        self.parse(value, !self.lenient)
+    }
+
+    /// Decodes the cells of `value`, which fails if it's past the limits or if it's `strict`
+    fn parse(&self, value: &str, strict: bool) -> Result<DecodedStates, DecodeError> {
+        let error = |offset: usize, reason: String| Err(syntax_error(value, offset, reason));
+        if let Some(offset) = value.find('\r').filter(|_| strict) {
+            return error(
+                offset,
+                "carriage return, lines have to end with `\\n` alone".to_owned(),
+            );
+        }
+        let origin = Self::read_position(value)
+            .filter(|_| self.absolute)
+            .unwrap_or_default();
+        let limits = &self.limits;
+        limits.check_pos(origin)?;
+        let states = Self::read_rule(value).map(|rule| rule.states());
+        // uppercase tags are the usual typo of two-state patterns, not the multi-state letters,
+        // which Immigration uses for its colors
+        let immigration = Self::rule_field(value) == Some(IMMIGRATION);
+        let fold_case = self.lenient && !immigration && states.unwrap_or(2) <= 2;
+        let mut alive = Vec::new();
+        let mut dying = Vec::new();
+        // the states above 24 start with a prefix from `p` to `y`
+        let mut prefix = None;
+        let mut cursor = origin;
+        // the run count typed so far, which is 1 when there are no digits
+        let mut run: Option<i32> = None;
+        let mut bytes = value.bytes().enumerate();
+        let mut line_start = true;
+        while let Some((offset, mut byte)) = bytes.next() {
+            if fold_case && matches!(byte, b'B' | b'O') {
+                byte = byte.to_ascii_lowercase();
+            }
+            if strict && prefix.is_some() && !matches!(byte, b'A'..=b'X') {
+                return error(
+                    offset,
+                    "a state prefix has to be followed by a letter".to_owned(),
+                );
+            }
+            match byte {
+                // comments, names, and the `x = ...` header are metadata, skip to the next line
+                b'#' if line_start || !strict => {
+                    bytes.by_ref().find(|&(_, b)| b == b'\n');
+                    line_start = true;
+                    continue;
+                }
+                b'x' if line_start => {
+                    let line = value[offset..].lines().next().unwrap_or_default();
+                    if strict {
+                        if let Err((column, reason)) = check_rle_header(line) {
+                            return error(offset + column, reason);
+                        }
+                    }
+                    bytes.by_ref().find(|&(_, b)| b == b'\n');
+                    continue;
+                }
+                b if strict && b.is_ascii_whitespace() && run.is_some() => {
+                    return error(
+                        offset,
+                        "whitespace between a run count and its tag".to_owned(),
+                    );
+                }
+                b'\n' => {
+                    line_start = true;
+                    continue;
+                }
+                b if b.is_ascii_whitespace() => continue,
+                b @ b'0'..=b'9' => {
+                    let digit = (b - b'0') as i32;
+                    let count = run.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                    limits.check(Limit::Run, count as u64)?;
+                    run = Some(count);
+                }
+                b'o' => {
+                    let run = run.take().unwrap_or(1);
+                    // checked before the run is pushed, which could take forever otherwise
+                    limits.check(
+                        Limit::Cells,
+                        (alive.len() + dying.len()) as u64 + run as u64,
+                    )?;
+                    let end = limits.advance(cursor.x, run)?;
+                    alive.extend((cursor.x..end).map(|x| Pos2::new(x, cursor.y)));
+                    cursor.x = end;
+                }
+                b'b' | b'.' => cursor.x = limits.advance(cursor.x, run.take().unwrap_or(1))?,
+                b @ b'p'..=b'y' => {
+                    prefix = Some(b - b'p');
+                    continue;
+                }
+                b @ b'A'..=b'X' => {
+                    let state = match prefix.take() {
+                        Some(prefix) => 25 + prefix as u32 * 24 + (b - b'A') as u32,
+                        None => 1 + (b - b'A') as u32,
+                    };
+                    match states {
+                        Some(states) if strict && state >= states as u32 => {
+                            let reason = format!("state {} in a rule of {} states", state, states);
+                            return error(offset, reason);
+                        }
+                        _ => {}
+                    }
+                    let run = run.take().unwrap_or(1);
+                    limits.check(
+                        Limit::Cells,
+                        (alive.len() + dying.len()) as u64 + run as u64,
+                    )?;
+                    let end = limits.advance(cursor.x, run)?;
+                    for x in cursor.x..end {
+                        let pos = Pos2::new(x, cursor.y);
+                        match state {
+                            1 => alive.push(pos),
+                            // states past what a cell can have are dropped
+                            2..=255 => dying.push((pos, state as u8)),
+                            _ => {}
+                        }
+                    }
+                    cursor.x = end;
+                }
+                b'$' => {
+                    cursor.x = origin.x;
+                    cursor.y = limits.advance(cursor.y, run.take().unwrap_or(1))?;
+                }
+                b'!' => {
+                    let rest = &value[offset + 1..];
+                    match rest.find(|c: char| !c.is_ascii_whitespace()) {
+                        Some(junk) if strict => {
+                            return error(
+                                offset + 1 + junk,
+                                "characters after the final `!`".to_owned(),
+                            );
+                        }
+                        _ => return Ok((alive, dying)),
+                    }
+                }
+                _ if strict => {
+                    let c = value[offset..].chars().next().unwrap_or_default();
+                    return error(offset, format!("unexpected character `{}`", c));
+                }
+                // other characters aren't supported, drop them along with their run
+                _ => run = None,
+            }
+            prefix = None;
+            line_start = false;
+        }
+
+        if strict {
+            return error(value.len(), "missing the final `!`".to_owned());
+        }
+        Ok((alive, dying))
+    }
+}
+
+/// The rule Golly writes the two colors of Immigration under, which is Life with the colors as
+/// the states 1 and 2
+const IMMIGRATION: &str = "Immigration";
+
+/// The alive cells and the dying cells with their state, decoded from RLE
+type DecodedStates = (Vec<Pos2>, Vec<(Pos2, u8)>);
+
+/// The error at byte `offset` of `value`, located by its line and column
+fn syntax_error(value: &str, offset: usize, reason: String) -> DecodeError {
+    let before = &value[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    DecodeError::Syntax {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        reason,
+    }
+}
+
+/// Checks that the header line of an RLE pattern is spelled `x = 3, y = 3, rule = B3/S23`, or
+/// returns the byte offset in the line of the first mistake
+fn check_rle_header(line: &str) -> Result<(), (usize, String)> {
+    let mut offset = 0;
+    for (i, field) in line.split(',').enumerate() {
+        let expected = ["x", "y", "rule"];
+        let spaced = if i == 0 {
+            Some(field)
+        } else {
+            field.strip_prefix(' ')
+        };
+        let (key, value) = match spaced.and_then(|field| field.split_once(" = ")) {
+            Some(pair) if expected.get(i) == Some(&pair.0) => pair,
+            _ => {
+                let reason = match expected.get(i) {
+                    Some(key) => format!("expected `{} = ` in the header", key),
+                    None => "too many fields in the header".to_owned(),
+                };
+                return Err((offset, reason));
+            }
+        };
+        let valid = match key {
+            "rule" => value == IMMIGRATION || value.parse::<Rule>().is_ok(),
+            _ => value.parse::<u64>().is_ok(),
+        };
+        if !valid {
+            let value_offset = offset + field.len() - value.len();
+            return Err((value_offset, format!("invalid `{}` in the header", key)));
+        }
+        offset += field.len() + 1;
+    }
+    Ok(())
+}
+
+/// The Life 1.05 format (`.lif`), where cells are drawn as `*` and `.` rows in `#P` blocks
+///
+/// Decoding handles any number of blocks at arbitrary offsets, while encoding writes a single
+/// block anchored at the top-left corner of the bounding box.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc::{Life105, PositionEncoder}, Pos2};
+///
+/// let lif = "#Life 1.05\n#D two blocks\n#R 23/3\n#P -1 -1\n.*\n*.*\n#P 5 0\n**\n";
+/// let cells = Life105::default().decode(lif);
+/// assert_eq!(
+///     cells,
+///     [(0, -1), (-1, 0), (1, 0), (5, 0), (6, 0)].map(Pos2::from)
+/// );
+/// assert_eq!(Life105::read_rule(lif).as_deref(), Some("23/3"));
+///
+/// let encoded = Life105::default().set_description("round trip").encode(&cells);
+/// assert!(encoded.starts_with("#Life 1.05\n#D round trip\n#N\n#P -1 -1\n"));
+/// assert_eq!(Life105::default().decode(&encoded), cells);
+/// ```
+#[derive(Default)]
+pub struct Life105 {
+    description: Option<String>,
+}
+impl Life105 {
+    /// The signature on the first line of a Life 1.05 file
+    pub const SIGNATURE: &'static str = "#Life 1.05";
+
+    /// Adds a `#D` description line
+    pub fn set_description<T: AsRef<str>>(mut self, description: T) -> Self {
+        self.description = Some(description.as_ref().to_owned());
+        self
+    }
+
+    /// Reads the rule of an encoded pattern from its `#R` line, where `#N` is the normal 23/3
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::Life105;
+    ///
+    /// assert_eq!(Life105::read_rule("#Life 1.05\n#N\n*").as_deref(), Some("23/3"));
+    /// assert_eq!(Life105::read_rule("#R 125/36\n*").as_deref(), Some("125/36"));
+    /// assert_eq!(Life105::read_rule("*"), None);
+    /// ```
+    pub fn read_rule(value: &str) -> Option<String> {
+        value.lines().find_map(|line| match line.trim_end() {
+            "#N" => Some("23/3".to_owned()),
+            line => Some(line.strip_prefix("#R")?.trim().to_owned()),
+        })
+    }
+
+    /// Decodes the cells of a pattern, failing as soon as it's past `limits`
+    ///
+    /// Cells past the edge of the coordinates are dropped, but `#P` offsets are bounded by
+    /// `limits` like the cells.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::{DecodeError, DecodeLimits, Life105, Limit};
+    ///
+    /// let far = "#Life 1.05\n#P 2000000000 0\n*\n";
+    /// assert_eq!(
+    ///     Life105::default().try_decode(far, DecodeLimits::default()),
+    ///     Err(DecodeError::LimitExceeded { limit: Limit::Coord, max: 1_000_000_000 })
+    /// );
+    /// ```
+    pub fn try_decode(self, value: &str, limits: DecodeLimits) -> Result<Vec<Pos2>, DecodeError> {
+        let mut alive = Vec::new();
+        // the top-left corner of the current block and the row within it
+        let mut origin = Pos2::zero();
+        let mut row = 0;
+        for line in value.lines().map(str::trim_end) {
+            if let Some(offset) = line.strip_prefix("#P") {
+                let mut coords = offset.split_whitespace().map(|n| n.parse().unwrap_or(0));
+                origin = Pos2::new(coords.next().unwrap_or(0), coords.next().unwrap_or(0));
+                row = 0;
+                continue;
+            }
+            // the signature, descriptions, and rules don't affect the cells
+            if line.starts_with('#') {
+                continue;
+            }
+            // a block near the edge of the coordinates can run past it, and those cells are dropped
+            for (x, c) in line.chars().enumerate() {
+                if c != '*' {
+                    continue;
+                }
+                let x = i32::try_from(x).unwrap_or(i32::MAX);
+                if let Some(pos) = origin.checked_add(Pos2::new(x, row)) {
+                    limits.check_pos(pos)?;
+                    alive.push(pos);
+                    limits.check(Limit::Cells, alive.len() as u64)?;
+                }
+            }
+            row = row.saturating_add(1);
+        }
+
+        alive.sort_unstable();
+        alive.dedup();
+        Ok(alive)
+    }
+}
+
+impl PositionEncoder for Life105 {
+    fn encode(self, cells: &[Pos2]) -> String {
+        let mut out = format!("{}\n", Self::SIGNATURE);
+        if let Some(description) = &self.description {
+            out.push_str(&format!("#D {}\n", description));
+        }
+        out.push_str("#N\n");
+
+        let tl = Pos2 {
+            x: cells.iter().map(|p| p.x).min().unwrap_or_default(),
+            // because the cells are sorted, the first cells will always have the lowest y-value
+            y: cells.first().map(|p| p.y).unwrap_or_default(),
+        };
+        out.push_str(&format!("#P {} {}\n", tl.x, tl.y));
+        let mut rows = cells.chunk_by(|a, b| a.y == b.y).peekable();
+        let last_y = cells.last().map_or(tl.y - 1, |p| p.y);
+        for y in tl.y..=last_y {
+            let Some(row) = rows.next_if(|row| row[0].y == y) else {
+                // a row can't be blank, or it would read as the end of the block
+                out.push_str(".\n");
+                continue;
+            };
+            let mut x = tl.x;
+            for pos in row {
+                out.extend(core::iter::repeat_n('.', (pos.x - x) as usize));
+                out.push('*');
+                x = pos.x + 1;
+            }
+            out.push('\n');
+        }
+        trace::encoded(Format::Life105, cells.len(), out.len());
+        out
+    }
+
+    fn decode(self, value: &str) -> Vec<Pos2> {
+        // nothing is past unlimited limits
+        self.try_decode(value, DecodeLimits::UNLIMITED)
+            .unwrap_or_default()
+    }
+}
+
+/// The Life 1.06 format (`.lif`), which lists the coordinates of every alive cell on its own line
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc::{Life106, PositionEncoder}, Pos2};
+///
+/// let lif = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+/// let cells = Life106.decode(lif);
+/// assert_eq!(cells, [(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)].map(Pos2::from));
+/// assert_eq!(Life106.encode(&cells), lif);
+/// ```
+pub struct Life106;
+impl Life106 {
+    /// The signature on the first line of a Life 1.06 file
+    pub const SIGNATURE: &'static str = "#Life 1.06";
+
+    /// Parses a line of two whitespace separated coordinates
+    fn parse_line(line: &str) -> Option<Pos2> {
+        let mut coords = line.split_whitespace().map(str::parse);
+        match (coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => Some(Pos2::new(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Decodes the cells of a pattern, failing as soon as it's past `limits`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::{DecodeError, DecodeLimits, Life106, Limit};
+    ///
+    /// let limits = DecodeLimits { max_cells: 2, ..DecodeLimits::default() };
+    /// assert_eq!(Life106.try_decode("0 0\n1 0\n", limits).unwrap().len(), 2);
+    /// assert_eq!(
+    ///     Life106.try_decode("0 0\n1 0\n2 0\n", limits),
+    ///     Err(DecodeError::LimitExceeded { limit: Limit::Cells, max: 2 })
+    /// );
+    /// ```
+    pub fn try_decode(self, value: &str, limits: DecodeLimits) -> Result<Vec<Pos2>, DecodeError> {
+        let mut alive = Vec::new();
+        let cells = value
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(Self::parse_line);
+        for pos in cells {
+            limits.check_pos(pos)?;
+            alive.push(pos);
+            limits.check(Limit::Cells, alive.len() as u64)?;
+        }
+        alive.sort_unstable();
+        alive.dedup();
+        Ok(alive)
+    }
+}
+
+impl PositionEncoder for Life106 {
+    fn encode(self, cells: &[Pos2]) -> String {
+        let mut out = format!("{}\n", Self::SIGNATURE);
+        for pos in cells {
+            out.push_str(&format!("{} {}\n", pos.x, pos.y));
+        }
+        trace::encoded(Format::Life106, cells.len(), out.len());
+        out
+    }
+
+    fn decode(self, value: &str) -> Vec<Pos2> {
+        // nothing is past unlimited limits
+        self.try_decode(value, DecodeLimits::UNLIMITED)
+            .unwrap_or_default()
+    }
+}
+
+/// The plaintext format (`.cells`), where cells are drawn as `O` and `.` rows after `!` comments
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{enc::{Plaintext, PositionEncoder}, Pos2};
+///
+/// let cells = Plaintext::default().decode("!Name: Glider\n!\n.O\n..O\nOOO\n");
+/// assert_eq!(cells, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(Pos2::from));
+///
+/// let encoded = Plaintext::default().set_name("Glider").encode(&cells);
+/// assert_eq!(encoded, "!Name: Glider\n.O\n..O\nOOO\n");
+/// ```
+#[derive(Default)]
+pub struct Plaintext {
+    name: Option<String>,
+}
+impl Plaintext {
+    /// Adds a `!Name:` comment line
+    pub fn set_name<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.name = Some(name.as_ref().to_owned());
+        self
+    }
+
+    /// Decodes the cells of a pattern, failing as soon as it's past `limits`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::{DecodeError, DecodeLimits, Limit, Plaintext};
+    ///
+    /// let limits = DecodeLimits { max_coord: 3, ..DecodeLimits::default() };
+    /// assert!(Plaintext::default().try_decode("...O\n", limits).is_ok());
+    /// assert_eq!(
+    ///     Plaintext::default().try_decode("....O\n", limits),
+    ///     Err(DecodeError::LimitExceeded { limit: Limit::Coord, max: 3 })
+    /// );
+    /// ```
+    pub fn try_decode(self, value: &str, limits: DecodeLimits) -> Result<Vec<Pos2>, DecodeError> {
+        let mut alive = Vec::new();
+        let rows = value.lines().filter(|line| !line.starts_with('!'));
+        for (y, line) in rows.enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if matches!(c, 'O' | '*') {
+                    alive.push(Pos2::new(limits.coord(x)?, limits.coord(y)?));
+                    limits.check(Limit::Cells, alive.len() as u64)?;
+                }
+            }
+        }
+        Ok(alive)
+    }
+}
+
+impl PositionEncoder for Plaintext {
+    fn encode(self, cells: &[Pos2]) -> String {
+        let mut out = String::new();
+        if let Some(name) = &self.name {
+            out.push_str(&format!("!Name: {}\n", name));
+        }
+
+        let tl = Pos2 {
+            x: cells.iter().map(|p| p.x).min().unwrap_or_default(),
+            // because the cells are sorted, the first cells will always have the lowest y-value
+            y: cells.first().map(|p| p.y).unwrap_or_default(),
+        };
+        let mut rows = cells.chunk_by(|a, b| a.y == b.y).peekable();
+        let last_y = cells.last().map_or(tl.y - 1, |p| p.y);
+        for y in tl.y..=last_y {
+            let Some(row) = rows.next_if(|row| row[0].y == y) else {
+                out.push_str(".\n");
+                continue;
+            };
+            let mut x = tl.x;
+            for pos in row {
+                out.extend(core::iter::repeat_n('.', (pos.x - x) as usize));
+                out.push('O');
+                x = pos.x + 1;
+            }
+            out.push('\n');
+        }
+        trace::encoded(Format::Plaintext, cells.len(), out.len());
+        out
+    }
+
+    fn decode(self, value: &str) -> Vec<Pos2> {
+        // nothing is past unlimited limits
+        self.try_decode(value, DecodeLimits::UNLIMITED)
+            .unwrap_or_default()
+    }
+}
+
+/// The pattern file formats that can be read and written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Extended RLE, see [`RunLengthEncoded`]
+    Rle,
+    /// Life 1.05, see [`Life105`]
+    Life105,
+    /// Life 1.06, see [`Life106`]
+    Life106,
+    /// Plaintext, see [`Plaintext`]
+    Plaintext,
+    /// Binary snapshots, see [`Binary`]
+    Binary,
+}
+impl Format {
+    /// The format usually stored with a file extension, where `lif` is assumed to be Life 1.05
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::Format;
+    ///
+    /// assert_eq!(Format::from_extension("RLE"), Some(Format::Rle));
+    /// assert_eq!(Format::from_extension("cells"), Some(Format::Plaintext));
+    /// assert_eq!(Format::from_extension("txt"), None);
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(Self::Rle),
+            "lif" | "life" => Some(Self::Life105),
+            "cells" => Some(Self::Plaintext),
+            "bin" => Some(Self::Binary),
+            _ => None,
+        }
+    }
+    /// The format of a file by its extension, looking past a `.gz` extension
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::enc::Format;
+    ///
+    /// assert_eq!(Format::from_path("glider.cells"), Some(Format::Plaintext));
+    /// assert_eq!(Format::from_path("snapshots/glider.bin.gz"), Some(Format::Binary));
+    /// assert_eq!(Format::from_path("glider.gz"), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+        let path = match path.extension() {
+            Some(ext) if ext == "gz" => Path::new(path.file_stem()?),
+            _ => path,
+        };
+        Self::from_extension(path.extension()?.to_str()?)
+    }
+
+    /// Decodes the cells and metadata of a pattern in this format, within the default
+    /// [`DecodeLimits`]
+    ///
+    /// Text formats are decoded leniently, so they only fail past the limits, while binary
+    /// snapshots also fail when they're truncated or corrupted. Binary snapshots are read through
+    /// `std::io`, so they always fail to decode without the `std` feature.
+    pub fn decode(self, content: impl AsRef<[u8]>) -> Result<Pattern, DecodeError> {
+        self.decode_limited(content, DecodeLimits::default())
+    }
+    /// Like [`Format::decode`], within `limits`
+    pub fn decode_limited(
+        self,
+        content: impl AsRef<[u8]>,
+        limits: DecodeLimits,
+    ) -> Result<Pattern, DecodeError> {
+        let content = content.as_ref();
+        let pattern = self.decode_bytes(content, limits)?;
+        trace::decoded(self, content.len(), pattern.cells.len());
+        Ok(pattern)
+    }
+    /// Like [`Format::decode_limited`], without logging the pattern
+    fn decode_bytes(self, content: &[u8], limits: DecodeLimits) -> Result<Pattern, DecodeError> {
+        #[cfg(feature = "std")]
+        if self == Self::Binary {
+            return Binary::decode_limited(content, limits).map_err(|e| {
+                binary::decode_error(e).unwrap_or_else(|e| DecodeError::Corrupt(e.to_string()))
+            });
+        }
+        let content = &*String::from_utf8_lossy(content);
+        let mut dying = Vec::new();
+        let (cells, meta, generation) = match self {
+            Self::Rle => {
+                let generation = RunLengthEncoded::read_generation(content).unwrap_or(0);
+                let cells;
+                // lenient decoding only fails past the limits
+                (cells, dying) = RunLengthEncoded::lenient()
+                    .with_limits(limits)
+                    .try_decode_states(content)?;
+                (cells, PatternMeta::read_rle(content), generation)
+            }
+            Self::Life105 => {
+                let comments = content
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("#D"))
+                    .map(|line| line.trim().to_owned())
+                    .collect();
+                let meta = PatternMeta {
+                    comments,
+                    ..PatternMeta::default()
+                };
+                (Life105::default().try_decode(content, limits)?, meta, 0)
+            }
+            Self::Life106 => (
+                Life106.try_decode(content, limits)?,
+                PatternMeta::default(),
+                0,
+            ),
+            Self::Plaintext => {
+                let mut meta = PatternMeta::default();
+                for comment in content.lines().filter_map(|line| line.strip_prefix('!')) {
+                    match comment.strip_prefix("Name:") {
+                        Some(name) => meta.name = Some(name.trim().to_owned()),
+                        None => meta.comments.push(comment.trim().to_owned()),
+                    }
+                }
+                (Plaintext::default().try_decode(content, limits)?, meta, 0)
+            }
+            // decoded above with `std`
+            Self::Binary => return Err(DecodeError::Corrupt("not a text format".to_owned())),
+        };
+        let rule = match self {
+            Self::Rle => RunLengthEncoded::read_rule(content),
+            Self::Life105 => Life105::read_rule(content).and_then(|rule| rule.parse().ok()),
+            _ => None,
+        };
+        Ok(Pattern {
+            format: self,
+            cells,
+            dying,
+            meta,
+            generation,
+            rule,
+        })
+    }
+}
+
+/// Detects the format of an encoded pattern from its contents
+///
+/// Binary snapshots are recognized by their magic bytes, then signature lines are checked
+/// (`#Life 1.06`, `#Life 1.05`, `!` comments, and the `x = ` header of RLE). Without one, the
+/// format is guessed from the characters of the body, which returns [`None`] if it doesn't look
+/// like any format.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::enc::{self, Format};
+///
+/// assert_eq!(enc::detect("#Life 1.05\n#P 0 0\n*\n"), Some(Format::Life105));
+/// assert_eq!(enc::detect("#Life 1.06\n0 0\n"), Some(Format::Life106));
+/// assert_eq!(enc::detect("!Name: Glider\n.O\n..O\nOOO\n"), Some(Format::Plaintext));
+/// assert_eq!(enc::detect("#N Glider\nx = 3, y = 3\nbo$2bo$3o!\n"), Some(Format::Rle));
+/// assert_eq!(enc::detect(b"CGOL\x01\x01"), Some(Format::Binary));
+///
+/// // without any signature or header
+/// assert_eq!(enc::detect("0 -1\n1 0\n-1 1\n0 1\n1 1\n"), Some(Format::Life106));
+/// assert_eq!(enc::detect("bo$2bo$\n3o!"), Some(Format::Rle));
+/// assert_eq!(enc::detect(".O\n..O\nOOO\n"), Some(Format::Plaintext));
+/// assert_eq!(enc::detect(".*\n..*\n***\n"), Some(Format::Life105));
+/// assert_eq!(enc::detect("hello world"), None);
+/// assert_eq!(enc::detect(""), None);
+/// ```
+pub fn detect(content: impl AsRef<[u8]>) -> Option<Format> {
+    let content = content.as_ref();
+    if content.starts_with(BINARY_MAGIC) {
+        return Some(Format::Binary);
+    }
+    let content = &*String::from_utf8_lossy(content);
+    let first = content.lines().next().unwrap_or_default().trim_end();
+    if first == Life106::SIGNATURE {
+        return Some(Format::Life106);
+    }
+    if first == Life105::SIGNATURE {
+        return Some(Format::Life105);
+    }
+    if first.starts_with('!') {
+        return Some(Format::Plaintext);
+    }
+
+    let body = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>();
+    let is_header = |line: &&str| line.starts_with('x') && line.contains('=');
+    if body.iter().any(is_header) {
+        return Some(Format::Rle);
+    }
+    let all_chars = |allowed: &str| {
+        body.iter()
+            .all(|line| line.chars().all(|c| allowed.contains(c)))
+    };
+    if body.is_empty() {
+        None
+    } else if body.iter().all(|line| Life106::parse_line(line).is_some()) {
+        Some(Format::Life106)
+    } else if all_chars(".O") {
+        Some(Format::Plaintext)
+    } else if all_chars(".*") {
+        Some(Format::Life105)
+    } else if all_chars("0123456789bo$! \t") {
+        Some(Format::Rle)
+    } else {
+        None
+    }
+}
+
+/// A decoded pattern file, created by [`decode_any`] or [`Format::decode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The format the pattern was decoded from
+    pub format: Format,
+    /// The alive cells, sorted by y and then x
+    pub cells: Vec<Pos2>,
+    /// The dying cells of a Generations rule with their state, sorted by y and then x, which only
+    /// RLE files record
+    pub dying: Vec<(Pos2, u8)>,
+    pub meta: PatternMeta,
+    /// The generation the pattern was saved at, which is 0 unless the format records it
+    pub generation: u64,
+    /// The rule the pattern was saved with, if the format records it
+    pub rule: Option<Rule>,
+}
+
+/// The bounds on what a decoder builds from a pattern, so a small hostile or corrupt file can't
+/// run it out of memory or time
+///
+/// A run in RLE like `2000000000o` takes a few bytes but makes billions of cells, so the cells,
+/// their coordinates and the runs are checked as they're decoded, before anything is allocated
+/// for them. The defaults are far beyond any real pattern. Every format is decoded within them
+/// by [`Format::decode`], or within others by [`Format::decode_limited`].
+///
+/// # Example
+/// ```rust
+/// use cgolrs::enc::{DecodeError, DecodeLimits, Limit, RunLengthEncoded};
+///
+/// let bomb = "2000000000$2000000000o!";
+/// assert_eq!(
+///     RunLengthEncoded::default().try_decode(bomb),
+///     Err(DecodeError::LimitExceeded { limit: Limit::Run, max: 100_000_000 })
+/// );
+///
+/// let limits = DecodeLimits { max_cells: 100, ..DecodeLimits::default() };
+/// let rle = RunLengthEncoded::default().with_limits(limits);
+/// assert_eq!(
+///     rle.try_decode("200o!"),
+///     Err(DecodeError::LimitExceeded { limit: Limit::Cells, max: 100 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The most cells, alive and dying, 50 million by default
+    pub max_cells: usize,
+    /// The largest magnitude of a coordinate, 1 billion by default
+    pub max_coord: u32,
+    /// The longest run of RLE, 100 million by default
+    pub max_run: u32,
+}
+impl DecodeLimits {
+    /// No limits beyond the size of the coordinates, for trusted files
+    pub const UNLIMITED: Self = Self {
+        max_cells: usize::MAX,
+        max_coord: u32::MAX,
+        max_run: u32::MAX,
+    };
+
+    /// Fails if `value` is past the maximum of `limit`
+    fn check(&self, limit: Limit, value: u64) -> Result<(), DecodeError> {
+        let max = match limit {
+            Limit::Cells => self.max_cells as u64,
+            Limit::Coord => self.max_coord as u64,
+            Limit::Run => self.max_run as u64,
+        };
+        if value > max {
+            return Err(DecodeError::LimitExceeded { limit, max });
+        }
+        Ok(())
+    }
+    /// Fails if a coordinate of `pos` is past [`DecodeLimits::max_coord`]
+    fn check_pos(&self, pos: Pos2) -> Result<(), DecodeError> {
+        self.check(
+            Limit::Coord,
+            pos.x.unsigned_abs().max(pos.y.unsigned_abs()) as u64,
+        )
+    }
+    /// The coordinate at an index into the rows or columns of a pattern, failing if it's past
+    /// [`DecodeLimits::max_coord`]
+    fn coord(&self, index: usize) -> Result<i32, DecodeError> {
+        self.check(Limit::Coord, index as u64)?;
+        // the check keeps it within `i32`, unless the limit is beyond it
+        index.try_into().map_err(|_| DecodeError::LimitExceeded {
+            limit: Limit::Coord,
+            max: i32::MAX as u64,
+        })
+    }
+    /// Moves `coord` forward by `run`, failing if it moves past [`DecodeLimits::max_coord`]
+    fn advance(&self, coord: i32, run: i32) -> Result<i32, DecodeError> {
+        let next = coord as i64 + run as i64;
+        self.check(Limit::Coord, next.unsigned_abs())?;
+        // the check keeps it within `i32`, unless the limit is beyond it
+        next.try_into().map_err(|_| DecodeError::LimitExceeded {
+            limit: Limit::Coord,
+            max: i32::MAX as u64,
+        })
+    }
+}
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_cells: 50_000_000,
+            max_coord: 1_000_000_000,
+            max_run: 100_000_000,
+        }
+    }
+}
+
+/// Which of the [`DecodeLimits`] a pattern exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// [`DecodeLimits::max_cells`]
+    Cells,
+    /// [`DecodeLimits::max_coord`]
+    Coord,
+    /// [`DecodeLimits::max_run`]
+    Run,
+}
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cells => "cells",
+            Self::Coord => "coordinate magnitude",
+            Self::Run => "run length",
+        })
+    }
+}
+
+/// The error returned when a pattern can't be decoded, by [`decode_any`] or [`decode_file`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The contents don't look like any supported format
+    UnknownFormat,
+    /// A binary snapshot is truncated or corrupted
+    Corrupt(String),
+    /// Strictly decoded RLE has a mistake at a line and column, both counted from 1
+    Syntax {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+    /// The file is gzipped, which needs the `compress` feature
+    GzipUnsupported,
+    /// The pattern is bigger than the [`DecodeLimits`] of the decoder
+    LimitExceeded { limit: Limit, max: u64 },
+}
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "unknown pattern format"),
+            Self::Corrupt(reason) => write!(f, "corrupt pattern: {}", reason),
+            Self::Syntax {
+                line,
+                column,
+                reason,
+            } => write!(f, "line {}, column {}: {}", line, column, reason),
+            Self::LimitExceeded { limit, max } => {
+                write!(f, "pattern exceeds the limit of {} {}", max, limit)
+            }
+            Self::GzipUnsupported => {
+                write!(f, "gzipped pattern files require the `compress` feature")
+            }
+        }
+    }
+}
+impl core::error::Error for DecodeError {}
+
+/// The error returned when a pattern can't be encoded, by [`create_file`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The file name ends in `.gz`, which needs the `compress` feature
+    GzipUnsupported,
+}
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GzipUnsupported => {
+                write!(f, "gzipped pattern files require the `compress` feature")
+            }
+        }
+    }
+}
+impl core::error::Error for EncodeError {}
+
+/// Decodes a pattern in whichever format [`detect`] finds
+///
+/// # Example
+/// ```rust
+/// use cgolrs::enc::{self, DecodeError, Format};
+///
+/// let pattern = enc::decode_any("#CXRLE Gen=12\n#N Glider\nbo$2bo$3o!").unwrap();
+/// assert_eq!(pattern.format, Format::Rle);
+/// assert_eq!((pattern.cells.len(), pattern.generation), (5, 12));
+/// assert_eq!(pattern.meta.name.as_deref(), Some("Glider"));
+///
+/// let plaintext = enc::decode_any("!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+/// let life106 = enc::decode_any("#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2").unwrap();
+/// assert_eq!(plaintext.cells, life106.cells);
+/// assert_eq!(enc::decode_any("hello world"), Err(DecodeError::UnknownFormat));
+/// ```
+pub fn decode_any(content: impl AsRef<[u8]>) -> Result<Pattern, DecodeError> {
+    let content = content.as_ref();
+    let format = detect(content).ok_or(DecodeError::UnknownFormat)?;
+    format.decode(content)
+}
+
+/// The error returned when parsing an unknown [`Format`] name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFormatError {
+    input: String,
+}
+impl fmt::Display for ParseFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown format `{}`, expected `rle`, `life105`, `life106`, `plaintext` or `bin`",
+            self.input
+        )
+    }
+}
+impl core::error::Error for ParseFormatError {}
+
+/// Writes the name of the format as accepted by [`Format::from_str`]
+///
+/// # Example
+/// ```rust
+/// use cgolrs::enc::Format;
+///
+/// assert_eq!(Format::Life106.to_string(), "life106");
+/// assert_eq!("plaintext".parse::<Format>(), Ok(Format::Plaintext));
+/// ```
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Rle => "rle",
+            Self::Life105 => "life105",
+            Self::Life106 => "life106",
+            Self::Plaintext => "plaintext",
+            Self::Binary => "bin",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Format {
+    type Err = ParseFormatError;
+
+    /// Parses a format from its name, `rle`, `life105` (also `lif`), `life106`, `plaintext`
+    /// (also `cells`), or `bin` (also `binary`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rle" => Ok(Self::Rle),
+            "life105" | "lif" => Ok(Self::Life105),
+            "life106" => Ok(Self::Life106),
+            "plaintext" | "cells" => Ok(Self::Plaintext),
+            "bin" | "binary" => Ok(Self::Binary),
+            _ => Err(ParseFormatError {
+                input: s.to_owned(),
+            }),
+        }
+    }
+}
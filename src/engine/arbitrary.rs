@@ -0,0 +1,32 @@
+use super::{GameOfLife, COORD_RANGE};
+use crate::{prelude::*, Pos2};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// The width and height of the area the cells of an arbitrary game are in
+///
+/// Spreading the cells over all of [`COORD_RANGE`] would leave them without neighbors, so they
+/// are kept within a small area that's placed anywhere in it, including against its edges.
+const AREA: i32 = 256;
+
+impl<'a> Arbitrary<'a> for GameOfLife {
+    /// A game at an arbitrary generation, with its alive cells sorted, deduplicated and within
+    /// [`COORD_RANGE`]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let origin = Pos2::arbitrary(u)?;
+        let origin = Pos2::new(
+            origin
+                .x
+                .clamp(*COORD_RANGE.start(), *COORD_RANGE.end() - (AREA - 1)),
+            origin
+                .y
+                .clamp(*COORD_RANGE.start(), *COORD_RANGE.end() - (AREA - 1)),
+        );
+        let alive = u
+            .arbitrary_iter::<(u8, u8)>()?
+            .map(|cell| cell.map(|(x, y)| origin + Pos2::new(x as i32, y as i32)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut game = GameOfLife::from_unsorted(alive);
+        game.set_generation(u.arbitrary()?);
+        Ok(game)
+    }
+}
@@ -0,0 +1,93 @@
+use crate::prelude::*;
+
+/// A rectangle of cells packed into bits, created by
+/// [`GameEngineWindow::to_bitmap`](super::GameEngineWindow::to_bitmap)
+///
+/// Each row starts on a new [`u64`] word, with the least significant bit of a word being the
+/// leftmost cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    /// The cells in row-major order, [`Bitmap::words_per_row`] words per row
+    pub rows: Vec<u64>,
+}
+
+impl Bitmap {
+    /// Creates a bitmap with every cell dead
+    pub fn new(width: u32, height: u32) -> Self {
+        let words_per_row = Self::words_for(width);
+        Self {
+            width,
+            height,
+            rows: vec![0; words_per_row * height as usize],
+        }
+    }
+
+    #[inline]
+    fn words_for(width: u32) -> usize {
+        (width as usize).div_ceil(64)
+    }
+    /// The number of [`u64`] words each row takes up in [`Bitmap::rows`]
+    #[inline]
+    pub fn words_per_row(&self) -> usize {
+        Self::words_for(self.width)
+    }
+
+    /// The words of row `y`
+    #[inline]
+    pub fn row(&self, y: u32) -> &[u64] {
+        let words = self.words_per_row();
+        &self.rows[y as usize * words..(y as usize + 1) * words]
+    }
+
+    /// Whether each cell of row `y` is alive, from left to right
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::new(70, 2);
+    /// bitmap.set(0, 1, true);
+    /// bitmap.set(66, 1, true);
+    /// // cells outside of the bitmap are ignored
+    /// bitmap.set(70, 1, true);
+    /// bitmap.set(0, 2, true);
+    /// let row = bitmap.iter_row(1).collect::<Vec<_>>();
+    /// assert_eq!(row.len(), 70);
+    /// let alive = row.iter().enumerate().filter(|(_, &alive)| alive).map(|(x, _)| x);
+    /// assert_eq!(alive.collect::<Vec<_>>(), [0, 66]);
+    /// ```
+    pub fn iter_row(&self, y: u32) -> impl Iterator<Item = bool> + '_ {
+        let row = self.row(y);
+        (0..self.width as usize).map(move |x| row[x / 64] & (1 << (x % 64)) != 0)
+    }
+
+    /// Whether the cell at (`x`, `y`) is alive, where (0, 0) is the top-left cell
+    ///
+    /// Cells outside of the bitmap are dead.
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        self.row(y)[x as usize / 64] & (1 << (x % 64)) != 0
+    }
+    /// Sets the state of the cell at (`x`, `y`)
+    ///
+    /// Cells outside of the bitmap are ignored, like the engine ignores positions it can't
+    /// represent.
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, alive: bool) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let i = y as usize * self.words_per_row() + x as usize / 64;
+        let bit = 1 << (x % 64);
+        if alive {
+            self.rows[i] |= bit;
+        } else {
+            self.rows[i] &= !bit;
+        }
+    }
+}
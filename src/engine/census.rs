@@ -0,0 +1,136 @@
+use super::{GameOfLife, Map};
+use crate::{enc::apgcode, prelude::*, Pos2};
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+/// The largest cluster [`GameOfLife::census`] classifies, bigger ones are counted as `unknown`
+pub const MAX_CENSUS_OBJECT: usize = 512;
+
+/// The objects of a pattern by their apgcode, created by [`GameOfLife::census`]
+///
+/// The apgcode prefix tells the kind of object: `xs` for still lifes, `xp` for oscillators, `xq`
+/// for spaceships, and `PATHOLOGICAL` for clusters that didn't repeat in isolation. Clusters over
+/// [`MAX_CENSUS_OBJECT`] cells aren't classified and are counted as `unknown`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Census {
+    counts: BTreeMap<String, usize>,
+}
+impl Census {
+    /// The number of objects with the apgcode
+    pub fn count(&self, code: &str) -> usize {
+        self.counts.get(code).copied().unwrap_or(0)
+    }
+    /// The total number of objects
+    pub fn objects(&self) -> usize {
+        self.counts.values().sum()
+    }
+    /// Whether there are no objects at all
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+    /// Adds the objects of another census, like the census of another soup
+    pub fn merge(&mut self, other: &Census) {
+        for (code, &count) in &other.counts {
+            *self.counts.entry(code.clone()).or_default() += count;
+        }
+    }
+    /// The apgcodes and their counts, the most common first and then by apgcode
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        let mut counts = self
+            .counts
+            .iter()
+            .map(|(code, &count)| (code.as_str(), count))
+            .collect::<Vec<_>>();
+        counts.sort_by_key(|&(_, count)| core::cmp::Reverse(count));
+        counts.into_iter()
+    }
+}
+
+impl fmt::Display for Census {
+    /// Writes the objects like `4 x xs4_33, 2 x xp2_7`, the most common first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (code, count)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} x {}", count, code)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the root of a cell's cluster, halving the path on the way
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+impl GameOfLife {
+    /// Splits the alive cells into objects and counts them by their apgcode
+    ///
+    /// Cells are in the same object if they're at most 2 cells apart in both directions, so
+    /// objects can have a gap of 1 dead cell, like in apgsearch. Each object is then evolved on
+    /// its own to classify it, see [`apgcode::encode`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{enc::{PositionEncoder, RunLengthEncoded}, engine::GameOfLife};
+    ///
+    /// // 2 blocks, a blinker, a glider, and a beehive
+    /// let rle = "2o5b2o$2o5b2o3$3o5bo$9bo$7b3o3$b2o$o2bo$b2o!";
+    /// let game = GameOfLife::from_alive(RunLengthEncoded::default().decode(rle));
+    /// let census = game.census();
+    /// assert_eq!(census.objects(), 5);
+    /// assert_eq!(census.count("xs4_33"), 2);
+    /// assert_eq!(census.count("xq4_153"), 1);
+    /// assert_eq!(
+    ///     census.to_string(),
+    ///     "2 x xs4_33, 1 x xp2_7, 1 x xq4_153, 1 x xs6_696"
+    /// );
+    /// ```
+    pub fn census(&self) -> Census {
+        let alive = &self.alive;
+        let index = alive
+            .iter()
+            .enumerate()
+            .map(|(i, &pos)| (pos, i))
+            .collect::<Map<_, _>>();
+
+        let mut parents = (0..alive.len()).collect::<Vec<_>>();
+        for (i, &pos) in alive.iter().enumerate() {
+            for dy in -2..=2 {
+                for dx in -2..=2 {
+                    let Some(&j) = pos
+                        .checked_add(Pos2::new(dx, dy))
+                        .and_then(|neighbor| index.get(&neighbor))
+                    else {
+                        continue;
+                    };
+                    let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                    parents[a] = b;
+                }
+            }
+        }
+
+        // the cells of each cluster stay sorted since they're visited in order
+        let mut clusters: Map<usize, Vec<Pos2>> = Map::new();
+        for (i, &pos) in alive.iter().enumerate() {
+            let root = find(&mut parents, i);
+            clusters.entry(root).or_default().push(pos);
+        }
+
+        let mut census = Census::default();
+        for cells in clusters.into_values() {
+            let code = if cells.len() > MAX_CENSUS_OBJECT {
+                "unknown".to_owned()
+            } else {
+                apgcode::encode(&cells)
+            };
+            *census.counts.entry(code).or_default() += 1;
+        }
+        census
+    }
+}
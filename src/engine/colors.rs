@@ -0,0 +1,109 @@
+//! The two colors of the Immigration variant of Life
+//!
+//! Every alive cell has one of two colors, 0 or 1, kept parallel to the alive cells like their
+//! ages. Which cells live and die doesn't depend on the colors, but a birth takes the color most
+//! of its parents have. Games without colors don't pay for any of this.
+
+use super::{hash, GameOfLife};
+use crate::{prelude::*, Pos2};
+
+impl GameOfLife {
+    /// Creates a game of Immigration from alive cells with their color, which are **already
+    /// sorted** by y and then x without duplicates, like [`GameOfLife::from_alive`]
+    ///
+    /// Colors other than 0 are 1.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// // a blinker with two cells of color 1 comes out of it with two births of color 1
+    /// let cells = [((0, 0).into(), 1), ((1, 0).into(), 0), ((2, 0).into(), 1)];
+    /// let mut game = GameOfLife::from_alive_colored(cells.to_vec());
+    /// game.next_generation();
+    /// assert_eq!(game.alive(), [(1, -1), (1, 0), (1, 1)].map(Pos2::from));
+    /// assert_eq!(game.colors(), Some(&[1, 0, 1][..]));
+    /// assert_eq!(game.color(Pos2::new(1, 0)), Some(0));
+    /// ```
+    pub fn from_alive_colored(cells: Vec<(Pos2, u8)>) -> Self {
+        let alive = cells.iter().map(|&(pos, _)| pos).collect::<Vec<_>>();
+        let mut game = Self::from_alive(alive);
+        // cells out of range were dropped, so their colors have to be dropped with them
+        let mut kept = game.alive.iter().peekable();
+        let colors = cells
+            .into_iter()
+            .filter(|&(pos, _)| kept.next_if_eq(&&pos).is_some())
+            .map(|(_, color)| (color != 0) as u8);
+        game.colors = Some(colors.collect());
+        game
+    }
+
+    /// Enables or disables the two colors of Immigration
+    ///
+    /// When enabled on a game without colors, the alive cells get one of the two colors at
+    /// random, picked by a hash of their position so the same pattern is always colored the same.
+    /// A game that already has colors keeps them.
+    pub fn track_colors(&mut self, enabled: bool) {
+        if !enabled {
+            self.colors = None;
+        } else if self.colors.is_none() {
+            let colors = self
+                .alive
+                .iter()
+                .map(|&pos| (hash::cell_hash(pos) >> 32) as u8 & 1);
+            self.colors = Some(colors.collect());
+        }
+    }
+    /// The color of every alive cell (parallel to [`GameOfLife::alive`]), if the game has colors
+    #[inline]
+    pub fn colors(&self) -> Option<&[u8]> {
+        self.colors.as_deref()
+    }
+    /// The color of the cell at `pos`, if it's alive and the game has colors
+    pub fn color(&self, pos: Pos2) -> Option<u8> {
+        let colors = self.colors.as_ref()?;
+        self.alive.binary_search(&pos).ok().map(|i| colors[i])
+    }
+
+    /// Computes the colors for the `next` generation from the previous one
+    ///
+    /// Survivors keep their color, and a birth takes the color of most of its alive neighbors in
+    /// the previous generation, or color 0 on a tie. Since both generations are sorted, the
+    /// survivors are found in a single merge pass, while the parents of a birth are looked up.
+    pub(super) fn next_colors(prev: &[Pos2], prev_colors: &[u8], next: &[Pos2]) -> Vec<u8> {
+        let color = |pos| prev.binary_search(&pos).ok().map(|i| prev_colors[i]);
+        let mut i = 0;
+        next.iter()
+            .map(|&pos| {
+                while prev.get(i).is_some_and(|&p| p < pos) {
+                    i += 1;
+                }
+                if prev.get(i) == Some(&pos) {
+                    return prev_colors[i];
+                }
+                // births are within the coordinate range, so their neighbors can't overflow
+                let mut votes = 0i32;
+                for (dx, dy) in NEIGHBORS {
+                    match color(Pos2::new(pos.x + dx, pos.y + dy)) {
+                        Some(0) => votes -= 1,
+                        Some(_) => votes += 1,
+                        None => {}
+                    }
+                }
+                (votes > 0) as u8
+            })
+            .collect()
+    }
+}
+
+/// The offsets of the 8 neighbors of a cell
+const NEIGHBORS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
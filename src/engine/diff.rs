@@ -0,0 +1,102 @@
+use super::GameOfLife;
+use crate::{prelude::*, Pos2};
+use core::cmp::Ordering;
+
+/// The cells that differ between two games, created by [`GameOfLife::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// The cells alive only in the game `diff` was called on, sorted by y and then x
+    pub only_in_self: Vec<Pos2>,
+    /// The cells alive only in the other game, sorted by y and then x
+    pub only_in_other: Vec<Pos2>,
+}
+impl StateDiff {
+    /// Whether both games have the same alive cells
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty()
+    }
+    /// The number of cells that differ
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.only_in_self.len() + self.only_in_other.len()
+    }
+}
+
+impl GameOfLife {
+    /// The cells alive in only one of this game and `other`, found with a single merge pass
+    /// over the two sorted generations
+    ///
+    /// Like [`GameOfLife::alive`], these are the dead cells if the background is alive, so
+    /// games with different [backgrounds](GameOfLife::background) can't be compared this way.
+    /// Dying cells of Generations rules count as dead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let before = GameOfLife::from_grid(&[[true, true, true]]);
+    /// game.next_generation();
+    /// let diff = game.diff(&before);
+    /// assert_eq!(diff.only_in_self, [Pos2::new(1, -1), Pos2::new(1, 1)]);
+    /// assert_eq!(diff.only_in_other, [Pos2::new(0, 0), Pos2::new(2, 0)]);
+    /// ```
+    pub fn diff(&self, other: &GameOfLife) -> StateDiff {
+        let (a, b) = (&self.alive[..], &other.alive[..]);
+        let mut diff = StateDiff::default();
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                }
+                Ordering::Less => {
+                    diff.only_in_self.push(a[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    diff.only_in_other.push(b[j]);
+                    j += 1;
+                }
+            }
+        }
+        diff.only_in_self.extend_from_slice(&a[i..]);
+        diff.only_in_other.extend_from_slice(&b[j..]);
+        diff
+    }
+
+    /// Whether this game and `other` are in the same state and at the same generation, under
+    /// the same rule
+    ///
+    /// `==` compares only the states and ignores the generation and the rule, like
+    /// [`GameOfLife::state_hash`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::GameOfLife;
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let blinker = GameOfLife::from_grid(&[[true, true, true]]);
+    /// game.step_n(2);
+    /// assert!(game == blinker);
+    /// assert!(!game.eq_strict(&blinker));
+    /// ```
+    pub fn eq_strict(&self, other: &GameOfLife) -> bool {
+        self == other && self.generation == other.generation && self.rule == other.rule
+    }
+}
+
+/// Games are equal if they have the same alive cells, background and dying cells, no matter
+/// their generation, rule or other settings, see [`GameOfLife::eq_strict`]
+impl PartialEq for GameOfLife {
+    fn eq(&self, other: &Self) -> bool {
+        // differing hashes rule out equal states without looking at the cells
+        self.hash == other.hash
+            && self.background == other.background
+            && self.alive == other.alive
+            && self.dying == other.dying
+    }
+}
+impl Eq for GameOfLife {}
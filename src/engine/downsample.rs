@@ -0,0 +1,60 @@
+use crate::{prelude::*, Pos2};
+
+/// The alive cells of a window counted in `factor × factor` blocks, created by
+/// [`GameEngineWindow::downsample`](super::GameEngineWindow::downsample)
+///
+/// Block (0, 0) covers the top-left corner of the window. When the window size isn't a multiple
+/// of the factor, the blocks along the right and bottom edges only count the cells inside the
+/// window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownsampledWindow {
+    factor: u32,
+    width: u32,
+    height: u32,
+    /// The blocks with at least one alive cell, sorted by y and then x
+    blocks: Vec<(Pos2, u32)>,
+}
+
+impl DownsampledWindow {
+    pub(super) fn new(factor: u32, width: u32, height: u32, blocks: Vec<(Pos2, u32)>) -> Self {
+        debug_assert!(
+            blocks.windows(2).all(|w| w[0].0 < w[1].0),
+            "output is not properly sorted"
+        );
+        Self {
+            factor,
+            width,
+            height,
+            blocks,
+        }
+    }
+
+    /// The number of cells along each side of a block
+    #[inline]
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+    /// The number of blocks in a row
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    /// The number of rows of blocks
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of alive cells in the block at `block`
+    pub fn count(&self, block: Pos2) -> u32 {
+        self.blocks
+            .binary_search_by_key(&block, |&(pos, _)| pos)
+            .map_or(0, |i| self.blocks[i].1)
+    }
+
+    /// Every block with at least one alive cell and its count, sorted by y and then x
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Pos2, u32)> + '_ {
+        self.blocks.iter().copied()
+    }
+}
@@ -0,0 +1,106 @@
+use super::GameOfLife;
+use crate::Pos2;
+
+/// Mixed into the hash while the background is alive
+const BACKGROUND: u64 = mix(u64::MAX);
+
+/// The SplitMix64 output for state `z`, which spreads every input bit over the whole output
+///
+/// The increment keeps 0 from hashing to 0, which would leave the origin out of the hash.
+#[inline]
+const fn mix(z: u64) -> u64 {
+    let mut z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The hash of an alive cell (or a dead one on an alive background), XORed into the state hash
+/// while it's in [`GameOfLife::alive`]
+#[inline]
+pub(super) fn cell_hash(pos: Pos2) -> u64 {
+    mix((pos.x as u32 as u64) << 32 | pos.y as u32 as u64)
+}
+
+/// The hash of a dying cell in its state, XORed into the state hash while it's dying
+#[inline]
+fn dying_hash(pos: Pos2, state: u8) -> u64 {
+    mix(cell_hash(pos) ^ state as u64)
+}
+
+/// The combined hash of some dying cells
+pub(super) fn dying_cells_hash(dying: &[(Pos2, u8)]) -> u64 {
+    dying
+        .iter()
+        .fold(0, |hash, &(pos, state)| hash ^ dying_hash(pos, state))
+}
+
+/// The state hash of a game from scratch, see [`GameOfLife::state_hash`]
+pub(super) fn state_hash(alive: &[Pos2], background: bool, dying: &[(Pos2, u8)]) -> u64 {
+    translated_hash(alive, background, dying, Pos2::zero())
+}
+
+/// The state hash of a game with every cell moved by `-origin`, so that a state hashes the same
+/// wherever it is
+pub(super) fn translated_hash(
+    alive: &[Pos2],
+    background: bool,
+    dying: &[(Pos2, u8)],
+    origin: Pos2,
+) -> u64 {
+    let alive = alive
+        .iter()
+        .fold(0, |hash, &pos| hash ^ cell_hash(pos - origin));
+    let dying = dying.iter().fold(0, |hash, &(pos, state)| {
+        hash ^ dying_hash(pos - origin, state)
+    });
+    let background = if background { BACKGROUND } else { 0 };
+    alive ^ background ^ dying
+}
+
+impl GameOfLife {
+    /// A 64-bit hash of the current state: the alive cells, the background and the dying cells
+    ///
+    /// It's kept up to date as the game changes, with every cell that's born or dies XORed in
+    /// or out while the next generation is computed, so it costs nothing to ask for every
+    /// generation. Equal states have equal hashes no matter how they were reached, while the
+    /// generation, the rule and the other settings don't count.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let blinker = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// game.next_generation();
+    /// assert_ne!(game.state_hash(), blinker.state_hash());
+    /// game.next_generation();
+    /// assert_eq!(game.state_hash(), blinker.state_hash());
+    ///
+    /// game.set_cell(Pos2::new(5, 5), true);
+    /// assert_ne!(game.state_hash(), blinker.state_hash());
+    /// game.set_cell(Pos2::new(5, 5), false);
+    /// assert_eq!(game.state_hash(), blinker.state_hash());
+    /// ```
+    #[inline]
+    pub fn state_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Recomputes the state hash from scratch, after the state was replaced wholesale
+    pub(super) fn rehash(&mut self) {
+        self.hash = state_hash(&self.alive, self.background, &self.dying);
+    }
+    /// Flips `pos` in the state hash, when it's added to or removed from the alive cells
+    #[inline]
+    pub(super) fn toggle_hash(&mut self, pos: Pos2) {
+        self.hash ^= cell_hash(pos);
+    }
+    /// Moves the state hash to the next background, which may have flipped
+    pub(super) fn set_background(&mut self, background: bool) {
+        if background != self.background {
+            self.hash ^= BACKGROUND;
+        }
+        self.background = background;
+    }
+}
@@ -0,0 +1,447 @@
+use super::{index, Engine, GameOfLife, Map};
+use crate::{prelude::*, Pos2, Rect};
+
+type NodeId = u32;
+
+/// The leaf for a dead cell
+const DEAD: NodeId = 0;
+/// The leaf for an alive cell
+const ALIVE: NodeId = 1;
+
+/// A square quadtree node of `2^level × 2^level` cells
+///
+/// Level 0 nodes are single cells, every other node is made up of 4 nodes one level below it.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// The `[nw, ne, sw, se]` quadrants of this node
+    children: [NodeId; 4],
+    level: u8,
+    population: u64,
+}
+
+/// A Game of Life engine using Gosper's HashLife algorithm
+///
+/// The universe is stored as a quadtree where identical nodes are shared (hash-consing), and the
+/// future of every node is memoized. Repetitive patterns like guns and spaceships can then be
+/// advanced by billions of generations in the time it takes [`GameOfLife`] to step a few
+/// thousand, though single generation steps are much slower.
+///
+/// # Example
+/// A glider advanced 4096 generations lands exactly where the scan-based engine puts it:
+/// ```rust
+/// use cgolrs::{
+///     engine::{GameOfLife, HashLife},
+///     Pos2,
+/// };
+///
+/// let glider = vec![
+///     Pos2::new(1, 0),
+///     Pos2::new(2, 1),
+///     Pos2::new(0, 2),
+///     Pos2::new(1, 2),
+///     Pos2::new(2, 2),
+/// ];
+/// let mut naive = GameOfLife::from_alive(glider.clone());
+/// naive.step_n(4096);
+/// let mut hashlife = HashLife::from_alive(&glider);
+/// hashlife.advance(4096);
+///
+/// assert_eq!(hashlife.generation(), 4096);
+/// assert_eq!(hashlife.to_alive(), naive.alive());
+/// assert_eq!(hashlife.to_alive()[0], Pos2::new(1025, 1024));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    nodes: Vec<Node>,
+    /// Canonical node for each combination of children, so identical nodes are only stored once
+    table: Map<[NodeId; 4], NodeId>,
+    /// Memoized successors, keyed by the node and the log2 of the generations advanced
+    results: Map<(NodeId, u8), NodeId>,
+    /// The empty node of each level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// The coordinate of the top-left cell of the root node
+    origin: (i64, i64),
+    generation: u64,
+}
+
+impl HashLife {
+    /// The furthest generation a universe can be advanced to
+    ///
+    /// Patterns grow by up to a cell per generation, so this keeps every node and its origin
+    /// well within `i64` coordinates.
+    pub const MAX_GENERATION: u64 = 1 << 58;
+
+    /// Creates a universe containing the given alive cells
+    pub fn from_alive(alive: &[Pos2]) -> Self {
+        let mut life = Self {
+            nodes: vec![
+                Node {
+                    children: [DEAD; 4],
+                    level: 0,
+                    population: 0,
+                },
+                Node {
+                    children: [DEAD; 4],
+                    level: 0,
+                    population: 1,
+                },
+            ],
+            table: Map::new(),
+            results: Map::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (0, 0),
+            generation: 0,
+        };
+
+        // the smallest square containing every cell, but at least a level 3 node
+        let min_x = alive.iter().map(|p| p.x).min().unwrap_or(0) as i64;
+        let min_y = alive.first().map(|p| p.y).unwrap_or(0) as i64;
+        let max_x = alive.iter().map(|p| p.x).max().unwrap_or(0) as i64;
+        let max_y = alive.last().map(|p| p.y).unwrap_or(0) as i64;
+        let size = (max_x - min_x).max(max_y - min_y) as u64 + 1;
+        let level = (size.next_power_of_two().trailing_zeros() as u8).max(3);
+
+        let cells = alive
+            .iter()
+            .map(|p| (p.x as i64 - min_x, p.y as i64 - min_y))
+            .collect::<Vec<_>>();
+        life.root = life.build(level, &cells);
+        life.origin = (min_x, min_y);
+        life
+    }
+
+    /// Builds a node of the given level from cells relative to its top-left corner
+    fn build(&mut self, level: u8, cells: &[(i64, i64)]) -> NodeId {
+        if cells.is_empty() {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return ALIVE;
+        }
+
+        let half = 1i64 << (level - 1);
+        let mut quadrants: [Vec<(i64, i64)>; 4] = Default::default();
+        for &(x, y) in cells {
+            let i = (y >= half) as usize * 2 + (x >= half) as usize;
+            quadrants[i].push((x % half, y % half));
+        }
+        let children = quadrants.map(|q| self.build(level - 1, &q));
+        self.join(children)
+    }
+
+    /// The canonical node with the given `[nw, ne, sw, se]` children
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.table.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            children,
+            level: self.nodes[children[0] as usize].level + 1,
+            population: children
+                .iter()
+                .map(|&c| self.nodes[c as usize].population)
+                .sum(),
+        });
+        self.table.insert(children, id);
+        id
+    }
+
+    /// The empty node of the given level
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().expect("level 0 is always present");
+            let id = self.join([below; 4]);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    #[inline]
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    /// Surrounds the root with empty space, doubling its size while keeping it centered
+    fn expand(&mut self) {
+        let root = self.node(self.root);
+        debug_assert!(root.level < 62, "the universe outgrew i64 coordinates");
+        let e = self.empty(root.level - 1);
+        let [nw, ne, sw, se] = root.children;
+        let children = [
+            self.join([e, e, e, nw]),
+            self.join([e, e, ne, e]),
+            self.join([e, sw, e, e]),
+            self.join([se, e, e, e]),
+        ];
+        self.root = self.join(children);
+
+        let quarter = 1i64 << (root.level - 1);
+        self.origin = (self.origin.0 - quarter, self.origin.1 - quarter);
+    }
+
+    /// Whether every alive cell is within the center quarter of the root
+    fn is_padded(&self) -> bool {
+        let root = self.node(self.root);
+        // the innermost grandchild of each quadrant must hold all of the quadrant's population
+        root.children.iter().enumerate().all(|(i, &quadrant)| {
+            let quadrant = self.node(quadrant);
+            let inner = self.node(quadrant.children[3 - i]);
+            self.node(inner.children[3 - i]).population == quadrant.population
+        })
+    }
+
+    /// Computes the 2x2 center of a level 2 node after one generation
+    fn step_level2(&mut self, id: NodeId) -> NodeId {
+        // gather the 4x4 cells into a bit grid, where bit `y * 4 + x` is the cell at (x, y)
+        let mut grid = 0u16;
+        for (i, &quadrant) in self.node(id).children.iter().enumerate() {
+            for (j, &cell) in self.node(quadrant).children.iter().enumerate() {
+                let x = (i % 2) * 2 + j % 2;
+                let y = (i / 2) * 2 + j / 2;
+                grid |= (cell as u16) << (y * 4 + x);
+            }
+        }
+
+        let table = index::get_gol_index();
+        let center = [(1, 1), (2, 1), (1, 2), (2, 2)].map(|(cx, cy)| {
+            // build the 9-bit neighborhood in the same layout as the scanning engine
+            let mut neighborhood = 0;
+            for row in 0..3 {
+                for dx in 0..3 {
+                    let bit = (grid >> ((cy + row - 1) * 4 + cx + dx - 1)) & 1;
+                    neighborhood |= (bit as usize) << (row * 3 + (2 - dx));
+                }
+            }
+            if table[neighborhood] {
+                ALIVE
+            } else {
+                DEAD
+            }
+        });
+        self.join(center)
+    }
+
+    /// The center of a node (one level below it), advanced by `2^step_log` generations
+    ///
+    /// `step_log` must be at most `level - 2`.
+    fn successor(&mut self, id: NodeId, step_log: u8) -> NodeId {
+        let node = self.node(id);
+        debug_assert!(node.level >= 2 && step_log <= node.level - 2);
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, step_log)) {
+            return result;
+        }
+
+        let result = if node.level == 2 {
+            self.step_level2(id)
+        } else {
+            let [nw, ne, sw, se] = node.children.map(|c| self.node(c).children);
+            // the 9 overlapping sub-squares one level below this node, advanced
+            let squares = [
+                nw,
+                [nw[1], ne[0], nw[3], ne[2]],
+                ne,
+                [nw[2], nw[3], sw[0], sw[1]],
+                [nw[3], ne[2], sw[1], se[0]],
+                [ne[2], ne[3], se[0], se[1]],
+                sw,
+                [sw[1], se[0], sw[3], se[2]],
+                se,
+            ];
+            let inner_log = step_log.min(node.level - 3);
+            let mut c = [DEAD; 9];
+            for (i, square) in squares.into_iter().enumerate() {
+                let square = self.join(square);
+                c[i] = self.successor(square, inner_log);
+            }
+
+            let groups = [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]];
+            let mut quadrants = [DEAD; 4];
+            for (i, group) in groups.into_iter().enumerate() {
+                let group = group.map(|g| c[g]);
+                quadrants[i] = if step_log < node.level - 2 {
+                    // only half the time is needed, so take the centers without advancing further
+                    let [a, b, c, d] = group.map(|g| self.node(g).children);
+                    self.join([a[3], b[2], c[1], d[0]])
+                } else {
+                    let square = self.join(group);
+                    self.successor(square, inner_log)
+                };
+            }
+            self.join(quadrants)
+        };
+        self.results.insert((id, step_log), result);
+        result
+    }
+
+    /// Advances the universe by `steps` generations, stopping at [`HashLife::MAX_GENERATION`]
+    ///
+    /// Each set bit of `steps` is a single power-of-two superstep, so this takes roughly the same
+    /// time for one million generations as it does for one thousand on repetitive patterns.
+    pub fn advance(&mut self, steps: u64) {
+        let steps = steps.min(Self::MAX_GENERATION - self.generation);
+        for step_log in 0..u64::BITS as u8 {
+            if steps & (1 << step_log) == 0 {
+                continue;
+            }
+
+            // cells travel at most one cell per generation, so the pattern has to be far enough
+            // from the edge of the root for the successor to contain all of it
+            while self.node(self.root).level < 3 || !self.is_padded() {
+                self.expand();
+            }
+            while self.node(self.root).level < step_log + 3 {
+                self.expand();
+            }
+
+            let level = self.node(self.root).level;
+            self.root = self.successor(self.root, step_log);
+            let quarter = 1i64 << (level - 2);
+            self.origin = (self.origin.0 + quarter, self.origin.1 + quarter);
+        }
+        self.generation += steps;
+    }
+
+    /// The number of generations advanced since construction
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of alive cells
+    #[inline]
+    pub fn population(&self) -> u64 {
+        self.node(self.root).population
+    }
+
+    /// Collects the alive cells of a node within the window, relative to the given origin
+    fn collect(
+        &self,
+        id: NodeId,
+        origin: (i64, i64),
+        window: ((i64, i64), (i64, i64)),
+        out: &mut Vec<Pos2>,
+    ) {
+        let node = self.node(id);
+        let size = 1i64 << node.level;
+        let ((x0, y0), (x1, y1)) = window;
+        let outside =
+            origin.0 >= x1 || origin.1 >= y1 || origin.0 + size <= x0 || origin.1 + size <= y0;
+        if node.population == 0 || outside {
+            return;
+        }
+        if node.level == 0 {
+            out.push(Pos2::new(origin.0 as i32, origin.1 as i32));
+            return;
+        }
+
+        let half = size / 2;
+        for (i, &child) in node.children.iter().enumerate() {
+            let child_origin = (
+                origin.0 + (i % 2) as i64 * half,
+                origin.1 + (i / 2) as i64 * half,
+            );
+            self.collect(child, child_origin, window, out);
+        }
+    }
+
+    /// The alive cells within `rect`, sorted by y and then x
+    pub fn window(&self, rect: Rect) -> Vec<Pos2> {
+        let window = (
+            (rect.min.x as i64, rect.min.y as i64),
+            (rect.max.x as i64, rect.max.y as i64),
+        );
+        let mut cells = Vec::new();
+        self.collect(self.root, self.origin, window, &mut cells);
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Every alive cell that fits in [`Pos2`], sorted by y and then x
+    pub fn to_alive(&self) -> Vec<Pos2> {
+        self.window(Rect::new(
+            Pos2::new(i32::MIN, i32::MIN),
+            Pos2::new(i32::MAX, i32::MAX),
+        ))
+    }
+
+    /// Converts the universe into the scan-based engine
+    pub fn to_game_of_life(&self) -> GameOfLife {
+        GameOfLife::from_alive(self.to_alive())
+    }
+
+    /// Sets the state of the cell at `pos`, growing the universe if needed
+    pub fn set_cell(&mut self, pos: Pos2, alive: bool) {
+        let (x, y) = (pos.x as i64, pos.y as i64);
+        loop {
+            let size = 1i64 << self.node(self.root).level;
+            let (ox, oy) = self.origin;
+            if (ox..ox + size).contains(&x) && (oy..oy + size).contains(&y) {
+                break;
+            }
+            self.expand();
+        }
+        let rel = (x - self.origin.0, y - self.origin.1);
+        self.root = self.set_in(self.root, rel, alive);
+    }
+    /// Rebuilds the path to a cell (relative to the node's top-left corner) with a new state
+    fn set_in(&mut self, id: NodeId, (x, y): (i64, i64), alive: bool) -> NodeId {
+        let node = self.node(id);
+        if node.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1i64 << (node.level - 1);
+        let i = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = node.children;
+        children[i] = self.set_in(children[i], (x % half, y % half), alive);
+        self.join(children)
+    }
+
+    /// Whether the cell at `pos` is alive
+    pub fn get_cell(&self, pos: Pos2) -> bool {
+        let (mut x, mut y) = (pos.x as i64 - self.origin.0, pos.y as i64 - self.origin.1);
+        let mut node = self.node(self.root);
+        let size = 1i64 << node.level;
+        if x < 0 || y < 0 || x >= size || y >= size {
+            return false;
+        }
+        while node.level > 0 {
+            let half = 1i64 << (node.level - 1);
+            let i = (y >= half) as usize * 2 + (x >= half) as usize;
+            node = self.node(node.children[i]);
+            x %= half;
+            y %= half;
+        }
+        node.population == 1
+    }
+}
+
+impl Engine for HashLife {
+    #[inline]
+    fn next_generation(&mut self) {
+        self.advance(1);
+    }
+    #[inline]
+    fn alive_count(&self) -> usize {
+        self.population() as usize
+    }
+    #[inline]
+    fn is_alive(&self, pos: Pos2) -> bool {
+        self.get_cell(pos)
+    }
+    #[inline]
+    fn insert(&mut self, pos: Pos2) {
+        self.set_cell(pos, true);
+    }
+    fn iter(&self) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Box::new(self.to_alive().into_iter())
+    }
+    fn window(&self, rect: Rect) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Box::new(HashLife::window(self, rect).into_iter())
+    }
+}
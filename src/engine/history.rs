@@ -0,0 +1,147 @@
+use super::GameOfLife;
+use crate::{prelude::*, Pos2};
+use alloc::collections::VecDeque;
+
+/// A ring of past states for [`GameOfLife::rewind`]
+#[derive(Debug, Clone)]
+pub(super) struct History {
+    /// The number of generations between checkpoints
+    interval: u64,
+    capacity: usize,
+    /// The oldest checkpoints first
+    checkpoints: VecDeque<Checkpoint>,
+}
+
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    generation: u64,
+    alive: Vec<Pos2>,
+    background: bool,
+    dying: Vec<(Pos2, u8)>,
+    ages: Option<Vec<u32>>,
+    colors: Option<Vec<u8>>,
+    hash: u64,
+}
+
+impl GameOfLife {
+    /// Starts keeping a checkpoint every `interval` generations, so the game can be rewound
+    ///
+    /// At most `capacity` checkpoints are kept, dropping the oldest first, so the game can be
+    /// rewound by up to about `capacity * interval` generations. The current state is the first
+    /// checkpoint.
+    pub fn enable_history(&mut self, capacity: usize, interval: u64) {
+        let mut history = History {
+            interval: interval.max(1),
+            capacity: capacity.max(1),
+            checkpoints: VecDeque::new(),
+        };
+        history.checkpoints.push_back(self.checkpoint());
+        self.history = Some(history);
+    }
+    /// Stops keeping checkpoints and frees the existing ones
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            generation: self.generation,
+            alive: self.alive.clone(),
+            background: self.background,
+            dying: self.dying.clone(),
+            ages: self.ages.clone(),
+            colors: self.colors.clone(),
+            hash: self.hash,
+        }
+    }
+
+    /// Keeps a checkpoint of the current state if it's on the interval
+    pub(super) fn record_history(&mut self) {
+        let Some(ref history) = self.history else {
+            return;
+        };
+        if !self.generation.is_multiple_of(history.interval) {
+            return;
+        }
+        let checkpoint = self.checkpoint();
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        if history.checkpoints.len() == history.capacity {
+            history.checkpoints.pop_front();
+        }
+        history.checkpoints.push_back(checkpoint);
+    }
+    /// Drops every checkpoint and starts over from the current state, since the generations
+    /// before it can't be replayed by stepping
+    pub(super) fn restart_history(&mut self) {
+        if self.history.is_none() {
+            return;
+        }
+        let checkpoint = self.checkpoint();
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        history.checkpoints.clear();
+        history.checkpoints.push_back(checkpoint);
+    }
+
+    /// Goes back `gens` generations by restoring the closest checkpoint before it and stepping
+    /// forward to the exact generation
+    ///
+    /// ## Returns
+    /// Whether the game was rewound, which fails if history isn't enabled or the generation is
+    /// older than the oldest checkpoint
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns};
+    ///
+    /// let mut game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    /// game.enable_history(8, 10);
+    /// game.step_n(55);
+    /// let at_42 = {
+    ///     let mut game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    ///     game.step_n(42);
+    ///     game.take()
+    /// };
+    ///
+    /// assert!(game.rewind(13));
+    /// assert_eq!(game.generation(), 42);
+    /// assert_eq!(game.alive(), at_42);
+    ///
+    /// // only 8 checkpoints 10 generations apart are kept
+    /// game.step_n(100);
+    /// assert!(!game.rewind(100));
+    /// assert_eq!(game.generation(), 142);
+    /// ```
+    pub fn rewind(&mut self, gens: u64) -> bool {
+        let Some(target) = self.generation.checked_sub(gens) else {
+            return false;
+        };
+        let Some(ref mut history) = self.history else {
+            return false;
+        };
+        // the checkpoints after the target are recorded again while replaying
+        let Some(i) = history
+            .checkpoints
+            .iter()
+            .rposition(|c| c.generation <= target)
+        else {
+            return false;
+        };
+        history.checkpoints.truncate(i + 1);
+        let checkpoint = &history.checkpoints[i];
+
+        self.alive.clone_from(&checkpoint.alive);
+        self.background = checkpoint.background;
+        self.dying.clone_from(&checkpoint.dying);
+        self.ages.clone_from(&checkpoint.ages);
+        self.colors.clone_from(&checkpoint.colors);
+        self.hash = checkpoint.hash;
+        self.generation = checkpoint.generation;
+        self.changed = None;
+        self.step_n(target - self.generation);
+        true
+    }
+}
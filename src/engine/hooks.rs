@@ -0,0 +1,138 @@
+use super::GameOfLife;
+use crate::{prelude::*, Pos2};
+use core::fmt;
+
+/// A generation computed by a [`GameOfLife`], as it's passed to the hooks of
+/// [`GameOfLife::on_generation`] and [`GameOfLife::step_with`]
+#[derive(Debug, Clone, Copy)]
+pub struct GenerationEvent<'a> {
+    /// The number of the new generation
+    pub generation: u64,
+    /// The number of alive cells, or of dead cells if `background` is alive, see
+    /// [`GameOfLife::alive_count`]
+    pub population: usize,
+    /// Whether the infinite background is alive, see [`GameOfLife::background`]
+    pub background: bool,
+    /// The alive cells of the new generation, see [`GameOfLife::alive`]
+    pub alive: &'a [Pos2],
+    /// The alive cells of the generation before
+    previous: &'a [Pos2],
+}
+impl GenerationEvent<'_> {
+    /// The number of alive cells, or `None` if infinitely many are alive on an alive background
+    pub fn alive_count(&self) -> Option<usize> {
+        (!self.background).then_some(self.population)
+    }
+    /// The number of cells born and the number of cells that died in the step
+    ///
+    /// They're counted when asked for, in one pass over both generations.
+    pub fn changes(&self) -> (usize, usize) {
+        GameOfLife::count_changes(self.previous, self.alive)
+    }
+}
+
+/// The handle of a hook added with [`GameOfLife::on_generation`], for removing it again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookHandle(u64);
+
+type Hook = Box<dyn FnMut(GenerationEvent<'_>) + Send>;
+
+/// The hooks of a game with the handles they were added with
+#[derive(Default)]
+pub(super) struct Hooks {
+    hooks: Vec<(HookHandle, Hook)>,
+    /// The handle of the next hook, so removed handles are never reused
+    next: u64,
+}
+impl Hooks {
+    #[inline]
+    pub(super) fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+    /// Calls every hook in the order they were added
+    pub(super) fn call(&mut self, event: GenerationEvent<'_>) {
+        for (_, hook) in &mut self.hooks {
+            hook(event);
+        }
+    }
+}
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|(handle, _)| handle))
+            .finish()
+    }
+}
+
+impl GameOfLife {
+    /// Adds a hook that's called with every generation computed from now on, returning the
+    /// handle to remove it with [`GameOfLife::remove_hook`]
+    ///
+    /// Hooks are called in the order they were added, right after each step.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let hook_log = Arc::clone(&log);
+    /// let handle = game.on_generation(move |event| {
+    ///     let (births, deaths) = event.changes();
+    ///     hook_log.lock().unwrap().push((event.generation, event.population, births, deaths));
+    /// });
+    /// game.step_n(2);
+    /// assert!(game.remove_hook(handle));
+    /// game.next_generation();
+    /// assert_eq!(*log.lock().unwrap(), [(1, 6, 2, 1), (2, 7, 3, 2)]);
+    /// assert!(!game.remove_hook(handle));
+    /// ```
+    pub fn on_generation(
+        &mut self,
+        hook: impl FnMut(GenerationEvent<'_>) + Send + 'static,
+    ) -> HookHandle {
+        let handle = HookHandle(self.hooks.next);
+        self.hooks.next += 1;
+        self.hooks.hooks.push((handle, Box::new(hook)));
+        handle
+    }
+    /// Removes a hook added with [`GameOfLife::on_generation`], returning whether it was there
+    pub fn remove_hook(&mut self, handle: HookHandle) -> bool {
+        let len = self.hooks.hooks.len();
+        self.hooks.hooks.retain(|&(h, _)| h != handle);
+        self.hooks.hooks.len() < len
+    }
+
+    /// Computes the next generation like [`GameOfLife::next_generation`] and passes it to `f`,
+    /// for borrowing closures that only need this one step
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::GameOfLife;
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let mut population = 0;
+    /// let changes = game.step_with(|event| {
+    ///     population = event.population;
+    ///     event.changes()
+    /// });
+    /// assert_eq!((population, changes), (3, (2, 2)));
+    /// ```
+    pub fn step_with<T>(&mut self, f: impl FnOnce(GenerationEvent<'_>) -> T) -> T {
+        self.next_generation();
+        // the previous generation is left in the scratch buffer
+        f(self.generation_event(&self.scratch))
+    }
+
+    /// The event of the current generation after a step from `previous`
+    pub(super) fn generation_event<'a>(&'a self, previous: &'a [Pos2]) -> GenerationEvent<'a> {
+        GenerationEvent {
+            generation: self.generation,
+            population: self.alive_count(),
+            background: self.background(),
+            alive: &self.alive,
+            previous,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::{in_bounds, index, GameOfLife};
+use crate::{prelude::*, Pos2};
+
+impl GameOfLife {
+    /// Enables or disables only re-evaluating the cells next to the ones that changed in the
+    /// previous step
+    ///
+    /// Soups that have settled into still lifes and small oscillators are mostly copied straight
+    /// into the next generation instead of being rescanned. The generations are identical either
+    /// way.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2};
+    ///
+    /// for pattern in patterns::PATTERNS {
+    ///     let mut plain = GameOfLife::from_alive(pattern.cells());
+    ///     let mut incremental = GameOfLife::from_alive(pattern.cells());
+    ///     incremental.set_incremental(true);
+    ///     for gen in 0..200 {
+    ///         // edits between steps must be picked up too
+    ///         if gen == 100 {
+    ///             plain.toggle_cell(Pos2::new(3, 3));
+    ///             incremental.toggle_cell(Pos2::new(3, 3));
+    ///         }
+    ///         plain.next_generation();
+    ///         incremental.next_generation();
+    ///         assert_eq!(plain.alive(), incremental.alive(), "{}", pattern.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_incremental(&mut self, enabled: bool) {
+        self.incremental = enabled;
+        self.changed = None;
+    }
+
+    /// Marks a cell as changed outside of stepping, so the next incremental step re-evaluates
+    /// the cells around it
+    pub(super) fn mark_changed(&mut self, pos: Pos2) {
+        if let Some(ref mut changed) = self.changed {
+            if let Err(i) = changed.binary_search(&pos) {
+                changed.insert(i, pos);
+            }
+        }
+    }
+
+    /// Appends the next generation to `out`, only re-evaluating the cells next to a change
+    ///
+    /// A cell can only change if it or one of its neighbors changed in the previous step, so
+    /// everything else is copied over as is. Without a previous step to compare against, every
+    /// cell is evaluated.
+    ///
+    /// ## Returns
+    /// The cells that differ between this generation and the next, sorted by y and then x
+    pub(super) fn extend_incremental(&mut self, out: &mut Vec<Pos2>, threads: usize) -> Vec<Pos2> {
+        let mut next_changed = Vec::new();
+        let Some(ref changed) = self.changed else {
+            self.extend_parallel(out, self.rule, threads);
+            diff_cells(&self.alive, out, &mut next_changed);
+            return next_changed;
+        };
+        self.extend_spans(&dirty_spans(changed), out)
+    }
+
+    /// Appends the next generation to `out`, evaluating only the cells within `spans` and
+    /// copying every other cell as is
+    ///
+    /// The spans are `(y, first_x, last_x)` of a single row each, sorted and without overlaps.
+    ///
+    /// ## Returns
+    /// The cells that differ between this generation and the next, sorted by y and then x
+    pub(super) fn extend_spans(&self, spans: &[(i32, i32, i32)], out: &mut Vec<Pos2>) -> Vec<Pos2> {
+        let mut next_changed = Vec::new();
+        let mut i = 0;
+        // the first cell that can neighbor a span, which only moves forward since the spans are
+        // sorted
+        let mut lower = 0;
+        let index = index::get_rule_index(self.rule);
+        for &(y, first, last) in spans {
+            lower += self.alive[lower..].partition_point(|&p| p < Pos2::new(first - 1, y - 1));
+            let start = i + self.alive[i..].partition_point(|&p| p < Pos2::new(first, y));
+            let end = start + self.alive[start..].partition_point(|&p| p <= Pos2::new(last, y));
+            // everything between the spans stays the same
+            out.extend_from_slice(&self.alive[i..start]);
+
+            let span_start = out.len();
+            evaluate_span(&self.alive[lower..], index, y, first, last, out);
+            diff_cells(
+                &self.alive[start..end],
+                &out[span_start..],
+                &mut next_changed,
+            );
+            i = end;
+        }
+        out.extend_from_slice(&self.alive[i..]);
+        next_changed
+    }
+}
+
+/// The cells within one of a changed cell, as `(y, first_x, last_x)` spans of a single row in
+/// ascending order, without overlaps
+pub(super) fn dirty_spans(changed: &[Pos2]) -> Vec<(i32, i32, i32)> {
+    let mut spans = changed
+        .iter()
+        .flat_map(|p| (-1..=1).map(move |dy| (p.y + dy, p.x - 1, p.x + 1)))
+        .collect::<Vec<_>>();
+    spans.sort_unstable();
+
+    let mut merged: Vec<(i32, i32, i32)> = Vec::with_capacity(spans.len());
+    for (y, first, last) in spans {
+        match merged.last_mut() {
+            Some(prev) if prev.0 == y && prev.2 + 1 >= first => prev.2 = prev.2.max(last),
+            _ => merged.push((y, first, last)),
+        }
+    }
+    merged
+}
+
+/// Appends the alive cells of the next generation in row `y` from `first` to `last` (inclusive)
+///
+/// `alive` may start anywhere before the row above the span.
+fn evaluate_span(
+    alive: &[Pos2],
+    index: &index::RuleIndex,
+    y: i32,
+    first: i32,
+    last: i32,
+    out: &mut Vec<Pos2>,
+) {
+    // the cells of the rows above, at and below `y` that can neighbor the span
+    let rows = [y - 1, y, y + 1].map(|row| {
+        let start = alive.partition_point(|&p| p < Pos2::new(first - 1, row));
+        let end = start + alive[start..].partition_point(|&p| p <= Pos2::new(last + 1, row));
+        &alive[start..end]
+    });
+    let mut idx = [0; 3];
+    let mut present = |r: usize, x: i32| {
+        while rows[r].get(idx[r]).is_some_and(|p| p.x < x) {
+            idx[r] += 1;
+        }
+        rows[r].get(idx[r]).is_some_and(|p| p.x == x)
+    };
+
+    // the same bit buffers as the scanning cursors, where bit 0 is the rightmost column
+    let mut buffers = [0u8; 3];
+    for (r, buffer) in buffers.iter_mut().enumerate() {
+        *buffer = (present(r, first - 1) as u8) << 1 | present(r, first) as u8;
+    }
+    for x in first..=last {
+        let mut grid = 0;
+        for (r, buffer) in buffers.iter_mut().enumerate() {
+            *buffer = (*buffer << 1 | present(r, x + 1) as u8) & 0b111;
+            grid |= (*buffer as usize) << (r * 3);
+        }
+        let pos = Pos2::new(x, y);
+        // cells are never born at the edge of the universe
+        if index.single[grid] && in_bounds(pos) {
+            out.push(pos);
+        }
+    }
+}
+
+/// Appends every cell that is in only one of the two sorted slices
+fn diff_cells(old: &[Pos2], new: &[Pos2], changed: &mut Vec<Pos2>) {
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        match old[i].cmp(&new[j]) {
+            core::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+            core::cmp::Ordering::Less => {
+                changed.push(old[i]);
+                i += 1;
+            }
+            core::cmp::Ordering::Greater => {
+                changed.push(new[j]);
+                j += 1;
+            }
+        }
+    }
+    // the rest of either slice comes after everything pushed so far, and only one is left
+    changed.extend_from_slice(&old[i..]);
+    changed.extend_from_slice(&new[j..]);
+}
@@ -1,34 +1,114 @@
-use std::sync::OnceLock;
+use crate::Rule;
+use alloc::boxed::Box;
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
 
 /// The number of permutations for a 3x3 grid of cells
 const PERMUTATIONS: usize = 1 << 9;
 type GameOfLifeIndex = [bool; PERMUTATIONS];
+/// The number of permutations for a 3x4 grid of cells
+const PAIR_PERMUTATIONS: usize = 1 << 12;
+type GameOfLifePairIndex = [u8; PAIR_PERMUTATIONS];
+
+/// The lookup tables of a rule, for single cells and for pairs of adjacent cells
+pub(super) struct RuleIndex {
+    pub single: GameOfLifeIndex,
+    pub pair: GameOfLifePairIndex,
+}
+impl RuleIndex {
+    const fn new(rule: Rule) -> Self {
+        let single = rule.build_table();
+        let pair = generate_pair_index(&single);
+        Self { single, pair }
+    }
+}
+
+/// The tables of Conway's Life, generated at compile time since nearly every game runs it
+static CONWAY: RuleIndex = RuleIndex::new(Rule::CONWAY);
+
+/// The tables of a rule other than Conway's Life, linked to the ones cached before it
+struct CachedIndex {
+    rule: Rule,
+    index: RuleIndex,
+    next: *mut CachedIndex,
+}
+/// The most recently cached tables, or null before the first rule other than Conway's Life
+static OTHERS: AtomicPtr<CachedIndex> = AtomicPtr::new(ptr::null_mut());
 
 /// Returns a Singleton lookup table for the Game of Life ruleset
 ///
-/// Equivalent to calling [`generate_gol_index`] once and storing the result
+/// Equivalent to calling [`Rule::build_table`] on [`Rule::CONWAY`] once and storing the result
 pub(super) fn get_gol_index() -> &'static GameOfLifeIndex {
-    static CELL: OnceLock<[bool; PERMUTATIONS]> = OnceLock::new();
-    CELL.get_or_init(generate_gol_index)
+    &CONWAY.single
 }
 
-/// Creates a lookup table for the Game of Life ruleset
+/// Returns the lookup tables of `rule`, which are generated the first time a rule is used and
+/// kept for the rest of the program
 ///
-/// The table is indexed by a 9-bit number representing a cell and its neighbors.
-/// The center cell is the middle-most bit, `1 << 4`.
-///
-/// Returns whether the center cell should be alive or dead in its arrangement
-pub(super) fn generate_gol_index() -> GameOfLifeIndex {
-    const CENTER: usize = 0b000_010_000;
+/// The cache is a list that only ever grows, so it needs neither a lock nor `std`: a rule is
+/// looked up without waiting, and a thread that adds a rule at the same time as another one just
+/// tries again.
+pub(super) fn get_rule_index(rule: Rule) -> &'static RuleIndex {
+    if rule == Rule::CONWAY {
+        return &CONWAY;
+    }
+    let mut head = OTHERS.load(Ordering::Acquire);
+    let mut new: Option<Box<CachedIndex>> = None;
+    loop {
+        let mut node = head;
+        // SAFETY: every node was leaked when it was added and is never freed or changed
+        while let Some(cached) = unsafe { node.as_ref() } {
+            if cached.rule == rule {
+                return &cached.index;
+            }
+            node = cached.next;
+        }
+        let mut cached = new.unwrap_or_else(|| {
+            Box::new(CachedIndex {
+                rule,
+                index: RuleIndex::new(rule),
+                next: ptr::null_mut(),
+            })
+        });
+        cached.next = head;
+        // a program only ever runs a handful of rules, so leaking their tables is bounded
+        let leaked = Box::into_raw(cached);
+        match OTHERS.compare_exchange(head, leaked, Ordering::AcqRel, Ordering::Acquire) {
+            // SAFETY: the node was just leaked
+            Ok(_) => return unsafe { &(*leaked).index },
+            Err(current) => {
+                // another thread added a rule first, which may be this one, so look again
+                // SAFETY: the node wasn't published, so this is its only owner
+                new = Some(unsafe { Box::from_raw(leaked) });
+                head = current;
+            }
+        }
+    }
+}
 
-    let mut indices = [false; PERMUTATIONS];
-    for i in 0..indices.len() {
-        let neighbors = (i & !CENTER).count_ones();
-        let alive = i & CENTER != 0;
-        indices[i] = match (alive, neighbors) {
-            (true, 2) | (_, 3) => true,
-            _ => false,
-        };
+/// Creates a lookup table for two horizontally adjacent cells from the table of single cells
+///
+/// The table is indexed by a 12-bit number representing 3 rows of 4 cells, where each row is 4
+/// bits. The two center cells are the middle two bits of the middle row, `1 << 5` and `1 << 6`.
+///
+/// Returns the state of the lower center cell in bit 0, and the higher center cell in bit 1
+pub(super) const fn generate_pair_index(single: &GameOfLifeIndex) -> GameOfLifePairIndex {
+    let mut indices = [0; PAIR_PERMUTATIONS];
+    let mut i = 0;
+    while i < PAIR_PERMUTATIONS {
+        // split the 3x4 grid into the two overlapping 3x3 grids
+        let (mut low, mut high) = (0, 0);
+        let mut row = 0;
+        while row < 3 {
+            let bits = (i >> (row * 4)) & 0b1111;
+            low |= (bits & 0b111) << (row * 3);
+            high |= (bits >> 1) << (row * 3);
+            row += 1;
+        }
+        indices[i] = single[low] as u8 | (single[high] as u8) << 1;
+        i += 1;
     }
     indices
 }
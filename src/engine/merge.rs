@@ -0,0 +1,164 @@
+use super::{hash, in_bounds, GameOfLife};
+use crate::{prelude::*, transform, Pos2};
+
+impl GameOfLife {
+    /// Combines the alive cells of this game with the cells of `other` in a single linear pass
+    ///
+    /// `keep` decides whether a cell is alive from whether it's alive in `self` and in `other`.
+    /// Both games store the cells that differ from their [background](GameOfLife::background),
+    /// so the background of the result is `keep` of the two backgrounds, and it stores the cells
+    /// that differ from that. Stored cells of `self` that mean the same in the result keep their
+    /// age and color, the rest start at 0.
+    fn combine(&self, other: &GameOfLife, keep: impl Fn(bool, bool) -> bool) -> GameOfLife {
+        let background = keep(self.background, other.background);
+        let (other_background, other) = (other.background, &other.alive[..]);
+        let mut alive = Vec::with_capacity(self.alive.len().max(other.len()));
+        let mut ages = self
+            .ages
+            .as_ref()
+            .map(|_| Vec::with_capacity(alive.capacity()));
+        let mut colors = self
+            .colors
+            .as_ref()
+            .map(|_| Vec::with_capacity(alive.capacity()));
+        let (mut i, mut j) = (0, 0);
+        loop {
+            let (pos, in_self, in_other) = match (self.alive.get(i), other.get(j)) {
+                (Some(&a), Some(&b)) if a == b => (a, true, true),
+                (Some(&a), Some(&b)) if a < b => (a, true, false),
+                (Some(&a), None) => (a, true, false),
+                (_, Some(&b)) => (b, false, true),
+                (None, None) => break,
+            };
+            let alive_in_self = in_self != self.background;
+            let alive_in_other = in_other != other_background;
+            if keep(alive_in_self, alive_in_other) != background {
+                alive.push(pos);
+                let kept = in_self && self.background == background;
+                if let Some(ref mut ages) = ages {
+                    let age = self.ages.as_ref().filter(|_| kept).map_or(0, |a| a[i]);
+                    ages.push(age);
+                }
+                if let Some(ref mut colors) = colors {
+                    let color = self.colors.as_ref().filter(|_| kept).map_or(0, |c| c[i]);
+                    colors.push(color);
+                }
+            }
+            i += in_self as usize;
+            j += in_other as usize;
+        }
+
+        debug_assert!(
+            alive.windows(2).all(|w| w[0] < w[1]),
+            "output is not properly sorted"
+        );
+        // a cell that's alive now isn't dying anymore
+        let dying = self
+            .dying
+            .iter()
+            .filter(|(pos, _)| alive.binary_search(pos).is_ok() == background)
+            .copied()
+            .collect::<Vec<_>>();
+        let hash = hash::state_hash(&alive, background, &dying);
+        GameOfLife {
+            alive,
+            ages,
+            colors,
+            generation: self.generation,
+            rule: self.rule,
+            background,
+            dying,
+            threads: self.threads,
+            #[cfg(feature = "std")]
+            pool: None,
+            min_chunk_cells: self.min_chunk_cells,
+            incremental: self.incremental,
+            changed: None,
+            history: None,
+            scratch: Vec::new(),
+            changes: None,
+            track_changes: self.track_changes,
+            hooks: Default::default(),
+            hash,
+        }
+    }
+
+    /// Adds every alive cell of `other` to this game
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let mut game = GameOfLife::from_alive(vec![Pos2::new(-2, -1), Pos2::new(0, 0)]);
+    /// let other = GameOfLife::from_alive(vec![Pos2::new(-1, -1), Pos2::new(0, 0)]);
+    /// game.merge(&other);
+    /// assert_eq!(
+    ///     game.alive(),
+    ///     [Pos2::new(-2, -1), Pos2::new(-1, -1), Pos2::new(0, 0)]
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: &GameOfLife) {
+        let mut merged = self.combine(other, |a, b| a || b);
+        #[cfg(feature = "std")]
+        {
+            merged.pool = self.pool.take();
+        }
+        merged.history = self.history.take();
+        merged.hooks = core::mem::take(&mut self.hooks);
+        *self = merged;
+    }
+    /// The cells alive in this game but not in `other`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_alive(vec![Pos2::new(-3, -3), Pos2::new(0, 0), Pos2::new(1, 0)]);
+    /// let overlapping = GameOfLife::from_alive(vec![Pos2::new(-3, -3), Pos2::new(1, 0)]);
+    /// let disjoint = GameOfLife::from_alive(vec![Pos2::new(5, -5)]);
+    /// assert_eq!(game.difference(&overlapping).alive(), [Pos2::new(0, 0)]);
+    /// assert_eq!(game.difference(&disjoint).alive(), game.alive());
+    /// ```
+    pub fn difference(&self, other: &GameOfLife) -> GameOfLife {
+        self.combine(other, |a, b| a && !b)
+    }
+    /// The cells alive in both this game and `other`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_alive(vec![Pos2::new(-3, -3), Pos2::new(0, 0), Pos2::new(1, 0)]);
+    /// let overlapping = GameOfLife::from_alive(vec![Pos2::new(-3, -3), Pos2::new(2, 0)]);
+    /// let disjoint = GameOfLife::from_alive(vec![Pos2::new(5, -5)]);
+    /// assert_eq!(game.intersection(&overlapping).alive(), [Pos2::new(-3, -3)]);
+    /// assert_eq!(game.intersection(&disjoint).alive_count(), 0);
+    /// ```
+    pub fn intersection(&self, other: &GameOfLife) -> GameOfLife {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// Adds the cells of a pattern, moved by `offset`
+    ///
+    /// The cells don't need to be sorted, and cells that end up outside of
+    /// [`COORD_RANGE`](super::COORD_RANGE) are dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2};
+    ///
+    /// let mut game = GameOfLife::from_alive(vec![Pos2::new(0, 0)]);
+    /// let glider = patterns::find("glider").unwrap().cells();
+    /// game.insert_pattern(&glider, Pos2::new(-10, -10));
+    /// game.insert_pattern(&glider, Pos2::new(-10, -10));
+    /// assert_eq!(game.alive_count(), 6);
+    /// assert_eq!(game.alive()[0], Pos2::new(-9, -10));
+    /// ```
+    pub fn insert_pattern(&mut self, cells: &[Pos2], offset: Pos2) {
+        let mut cells = transform::translate(cells, offset);
+        cells.retain(|&pos| in_bounds(pos));
+        cells.sort_unstable();
+        cells.dedup();
+        self.merge(&GameOfLife::from_alive(cells));
+    }
+}
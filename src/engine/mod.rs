@@ -1,57 +1,1081 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod bitmap;
+mod census;
+mod colors;
+mod dense;
+mod diff;
+mod downsample;
+mod hash;
+mod hashlife;
+mod history;
+mod hooks;
+mod incremental;
 mod index;
-mod scan;
+mod merge;
+mod parallel;
+mod period;
+#[cfg(feature = "image")]
+mod png;
+#[cfg(feature = "std")]
+mod pool;
+mod region;
+mod search;
+#[cfg(feature = "serde")]
+mod serialize;
+mod traits;
 mod window;
 
-use self::scan::MultiRowPosCursor;
-pub use self::window::GameEngineWindow;
-use crate::Pos2;
+pub use self::bitmap::Bitmap;
+pub use self::census::{Census, MAX_CENSUS_OBJECT};
+pub use self::dense::DenseGrid;
+pub use self::diff::StateDiff;
+pub use self::downsample::DownsampledWindow;
+pub use self::hashlife::HashLife;
+pub use self::hooks::{GenerationEvent, HookHandle};
+pub use self::parallel::DEFAULT_MIN_CHUNK_CELLS;
+pub use self::period::{Analysis, Direction, PeriodInfo, Speed};
+#[cfg(feature = "image")]
+pub use self::png::{Theme, MAX_PNG_PIXELS};
+pub use self::window::{GameEngineWindow, WindowDisplay};
+use crate::{cursor::MultiRowPosCursor, prelude::*, trace, transform, Pos2, Rect, Rule};
+use alloc::borrow::Cow;
+use core::{
+    cmp::Ordering,
+    fmt,
+    iter::FusedIterator,
+    ops::{Range, RangeInclusive},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The map of the engine's lookup tables and caches, which is a B-tree without `std`
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as Map;
+#[cfg(feature = "std")]
+use std::collections::HashMap as Map;
+
+/// The range of x and y coordinates that cells can be alive in
+///
+/// The scanning cursors look up to 3 cells past an alive cell (and 2 rows above it), so keeping a
+/// margin to the edges of [`i32`] guarantees their arithmetic never overflows. The universe is
+/// bounded by this range: cells outside of it are dropped and never born.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{
+///     engine::{GameOfLife, COORD_RANGE},
+///     Pos2,
+/// };
+///
+/// // a blinker touching the edge keeps oscillating
+/// let x = *COORD_RANGE.end() - 1;
+/// let blinker = vec![Pos2::new(x - 1, 0), Pos2::new(x, 0), Pos2::new(x + 1, 0)];
+/// let mut game = GameOfLife::from_alive(blinker.clone());
+/// game.next_generation();
+/// assert_eq!(game.alive(), [Pos2::new(x, -1), Pos2::new(x, 0), Pos2::new(x, 1)]);
+/// game.next_generation();
+/// assert_eq!(game.alive(), blinker);
+///
+/// // a blinker past the edge is clipped, but never overflows
+/// let x = i32::MAX - 1;
+/// let blinker = vec![Pos2::new(x - 1, 0), Pos2::new(x, 0), Pos2::new(x + 1, 0)];
+/// let mut game = GameOfLife::from_alive(blinker);
+/// for _ in 0..4 {
+///     game.next_generation();
+///     assert!(game.alive().windows(2).all(|w| w[0] < w[1]));
+/// }
+/// ```
+pub const COORD_RANGE: RangeInclusive<i32> = (i32::MIN + 2)..=(i32::MAX - 3);
+
+/// Whether `pos` is within [`COORD_RANGE`] on both axes
+#[inline]
+pub fn in_bounds(pos: Pos2) -> bool {
+    COORD_RANGE.contains(&pos.x) && COORD_RANGE.contains(&pos.y)
+}
+
+/// A backend for simulating the Game of Life
+///
+/// This is implemented by the sparse [`GameOfLife`] engine, the bounded [`DenseGrid`] and the
+/// memoized [`HashLife`], so the same code can drive (and compare) any of them.
+pub trait Engine {
+    /// Advances the simulation by one generation
+    fn next_generation(&mut self);
+    /// The number of alive cells
+    ///
+    /// On an alive background, a [`GameOfLife`] counts its dead cells instead, like it iterates.
+    fn alive_count(&self) -> usize;
+    /// Whether the cell at `pos` is alive
+    fn is_alive(&self, pos: Pos2) -> bool;
+    /// Sets the cell at `pos` alive, ignoring positions the engine can't represent
+    fn insert(&mut self, pos: Pos2);
+    /// Every alive cell, sorted by y and then x
+    fn iter(&self) -> Box<dyn Iterator<Item = Pos2> + '_>;
+    /// The alive cells within `rect`, sorted by y and then x
+    fn window(&self, rect: Rect) -> Box<dyn Iterator<Item = Pos2> + '_>;
+}
+
+/// The error returned when the engine is asked for something it can't do, like building a game
+/// from unsorted cells with [`GameOfLife::try_from_alive`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvariantError {
+    /// The cell at `index` comes before the cell preceding it
+    Unsorted { index: usize, pos: Pos2 },
+    /// The cell appears more than once
+    Duplicate { pos: Pos2 },
+    /// Generations rules with `B0` aren't supported, see [`GameOfLife::set_rule`]
+    GenerationsB0,
+    /// Rules with `B0` can't be limited to a region, see [`GameOfLife::next_generation_in`]
+    RegionB0,
+    /// The blocks of [`GameEngineWindow::downsample`] are at least 1 cell wide
+    ZeroFactor,
+    /// The density of [`GameOfLife::random`] is a chance between 0 and 1
+    Density,
+}
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsorted { index, pos } => {
+                write!(f, "cell {} at index {} is out of order", pos, index)
+            }
+            Self::Duplicate { pos } => write!(f, "cell {} is alive more than once", pos),
+            Self::GenerationsB0 => write!(f, "Generations rules with B0 aren't supported"),
+            Self::RegionB0 => write!(f, "B0 rules can't be limited to a region"),
+            Self::ZeroFactor => write!(f, "downsample factor must be positive"),
+            Self::Density => write!(f, "density must be between 0 and 1"),
+        }
+    }
+}
+impl core::error::Error for InvariantError {}
+
+/// Why [`GameOfLife::run_until`] stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The predicate returned true
+    Matched,
+    /// The generation limit was reached
+    LimitReached,
+    /// Every cell died
+    Extinct,
+}
 
-#[derive(Debug)]
 pub struct GameOfLife {
     alive: Vec<Pos2>,
+    /// The number of generations each cell in `alive` has survived, if age tracking is enabled
+    ages: Option<Vec<u32>>,
+    /// The color of each cell in `alive` for Immigration, 0 or 1, if the game has colors
+    colors: Option<Vec<u8>>,
+    generation: u64,
+    /// The rule the generations are computed with
+    rule: Rule,
+    /// Whether the infinite background is alive, in which case `alive` holds the dead cells
+    background: bool,
+    /// The cells in the dying states of a Generations rule with their state, sorted by position
+    dying: Vec<(Pos2, u8)>,
+    /// The number of threads [`GameOfLife::next_generation`] uses
+    threads: usize,
+    /// The workers for parallel steps, created on the first one
+    #[cfg(feature = "std")]
+    pool: Option<pool::WorkerPool>,
+    /// The fewest cells per chunk of a parallel step, see [`GameOfLife::set_min_chunk_cells`]
+    min_chunk_cells: usize,
+    /// Whether [`GameOfLife::set_incremental`] is enabled
+    incremental: bool,
+    /// The cells that changed in the last step, if known
+    changed: Option<Vec<Pos2>>,
+    /// Checkpoints for rewinding, if enabled
+    history: Option<history::History>,
+    /// The previous generation, kept around so its capacity can be reused for the next one
+    scratch: Vec<Pos2>,
+    /// The births and deaths of the last step, if change tracking is enabled
+    changes: Option<(usize, usize)>,
+    /// Whether [`GameOfLife::track_changes`] is enabled
+    track_changes: bool,
+    /// The hooks called after every step, see [`GameOfLife::on_generation`]
+    hooks: hooks::Hooks,
+    /// The hash of the current state, see [`GameOfLife::state_hash`]
+    hash: u64,
 }
 
 impl GameOfLife {
+    /// Creates a game from alive cells that are **already sorted** by y and then x, without
+    /// duplicates
+    ///
+    /// The order is only checked in debug builds, and unsorted input silently produces garbage
+    /// generations in release builds. Use [`GameOfLife::try_from_alive`] or
+    /// [`GameOfLife::from_unsorted`] for cells that don't come from the engine itself.
+    ///
+    /// Cells outside of [`COORD_RANGE`] are dropped.
     #[inline]
-    pub fn from_alive(alive: Vec<Pos2>) -> Self {
+    pub fn from_alive(mut alive: Vec<Pos2>) -> Self {
         debug_assert!(
             alive.windows(2).all(|w| w[0] < w[1]),
             "output is not properly sorted"
         );
-        Self { alive }
+        alive.retain(|&pos| in_bounds(pos));
+        let hash = hash::state_hash(&alive, false, &[]);
+        Self {
+            alive,
+            ages: None,
+            colors: None,
+            generation: 0,
+            rule: Rule::CONWAY,
+            background: false,
+            dying: Vec::new(),
+            threads: 1,
+            #[cfg(feature = "std")]
+            pool: None,
+            min_chunk_cells: DEFAULT_MIN_CHUNK_CELLS,
+            incremental: false,
+            changed: None,
+            history: None,
+            scratch: Vec::new(),
+            changes: None,
+            track_changes: false,
+            hooks: hooks::Hooks::default(),
+            hash,
+        }
+    }
+
+    /// Creates a game from sorted alive cells, checking that they are in order and unique
+    ///
+    /// Cells outside of [`COORD_RANGE`] are dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// assert!(GameOfLife::try_from_alive(vec![Pos2::new(1, 0), Pos2::new(0, 1)]).is_ok());
+    /// assert!(GameOfLife::try_from_alive(vec![Pos2::new(0, 1), Pos2::new(1, 0)]).is_err());
+    /// assert!(GameOfLife::try_from_alive(vec![Pos2::new(0, 0), Pos2::new(0, 0)]).is_err());
+    /// ```
+    pub fn try_from_alive(alive: Vec<Pos2>) -> Result<Self, InvariantError> {
+        if let Some(i) = alive.windows(2).position(|w| w[0] >= w[1]) {
+            let (prev, pos) = (alive[i], alive[i + 1]);
+            return Err(if prev == pos {
+                InvariantError::Duplicate { pos }
+            } else {
+                InvariantError::Unsorted { index: i + 1, pos }
+            });
+        }
+        Ok(Self::from_alive(alive))
+    }
+    /// Creates a game from alive cells in any order, removing duplicates
+    ///
+    /// Cells outside of [`COORD_RANGE`] are dropped.
+    pub fn from_unsorted(mut alive: Vec<Pos2>) -> Self {
+        alive.sort_unstable();
+        alive.dedup();
+        Self::from_alive(alive)
+    }
+
+    /// Creates a game from rows of cells, where `true` at `rows[y][x]` is an alive cell at (x, y)
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_grid(&[
+    ///     [false, true, false],
+    ///     [false, false, true],
+    ///     [true, true, true],
+    /// ]);
+    /// assert_eq!(game.alive_count(), 5);
+    /// assert_eq!(game.alive()[0], Pos2::new(1, 0));
+    /// ```
+    pub fn from_grid<R: AsRef<[bool]>>(rows: &[R]) -> Self {
+        // iterating rows and then columns yields the cells in sorted order
+        let alive = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.as_ref()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &alive)| alive)
+                    .map(move |(x, _)| Pos2::new(x as i32, y as i32))
+            })
+            .collect();
+        Self::from_alive(alive)
+    }
+    /// Creates a random soup, where every cell of `rect` is alive with a chance of `density`
+    ///
+    /// The same seed always gives the same soup, see [`GameOfLife::random_with_rng`].
+    ///
+    /// ## Returns
+    /// [`InvariantError::Density`] if `density` isn't between 0 and 1
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let rect = Rect::from_size(Pos2::new(-8, -8), 16, 16);
+    /// let game = GameOfLife::random(rect, 0.3, 42).unwrap();
+    /// assert_eq!(game.alive(), GameOfLife::random(rect, 0.3, 42).unwrap().alive());
+    /// assert!(game.alive().iter().all(|&pos| rect.contains(pos)));
+    /// assert!((20..140).contains(&game.alive_count()));
+    /// assert!(GameOfLife::random(rect, 1.5, 42).is_err());
+    /// ```
+    pub fn random(rect: Rect, density: f64, seed: u64) -> Result<Self, InvariantError> {
+        Self::random_with_rng(rect, density, &mut StdRng::seed_from_u64(seed))
+    }
+    /// Like [`GameOfLife::random`], but takes the chances from `rng`
+    ///
+    /// The cells of `rect` are visited row by row, which is the order they're sorted in, so
+    /// every cell takes one chance from `rng` in that order.
+    ///
+    /// ## Returns
+    /// [`InvariantError::Density`] if `density` isn't between 0 and 1
+    pub fn random_with_rng(
+        rect: Rect,
+        density: f64,
+        rng: &mut impl Rng,
+    ) -> Result<Self, InvariantError> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(InvariantError::Density);
+        }
+        let alive = rect.iter().filter(|_| rng.gen_bool(density)).collect();
+        Ok(Self::from_alive(alive))
     }
 
+    /// The current generation, starting at 0 unless set with [`GameOfLife::set_generation`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::GameOfLife;
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true]]);
+    /// game.set_generation(41);
+    /// game.next_generation();
+    /// assert_eq!(game.generation(), 42);
+    /// ```
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Overrides the generation counter, e.g. when resuming from a pattern saved mid-run
+    #[inline]
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// The rule the generations are computed with, [`Rule::CONWAY`] by default
+    #[inline]
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// Sets the rule the next generations are computed with
+    ///
+    /// Rules with `B0` bring the background to life, see [`GameOfLife::background`], and
+    /// Generations rules leave dying cells behind, see [`GameOfLife::dying`].
+    ///
+    /// ## Returns
+    /// [`InvariantError::GenerationsB0`] for a Generations rule with `B0`, which isn't supported,
+    /// in which case the rule is unchanged
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Rule};
+    ///
+    /// // in Seeds every cell dies, and cells with exactly two neighbors are born
+    /// let mut game = GameOfLife::from_grid(&[[true, true]]);
+    /// game.set_rule(Rule::from_name("seeds").unwrap()).unwrap();
+    /// game.next_generation();
+    /// assert_eq!(game.alive_count(), 4);
+    /// ```
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), InvariantError> {
+        if rule.states() > 2 && rule.is_born(0) {
+            return Err(InvariantError::GenerationsB0);
+        }
+        // fewer states cut the dying cells short
+        self.hash ^= hash::dying_cells_hash(&self.dying);
+        self.dying.retain(|&(_, state)| state < rule.states());
+        self.hash ^= hash::dying_cells_hash(&self.dying);
+        self.rule = rule;
+        Ok(())
+    }
+    /// The cells in the dying states of a Generations rule, with their state from 2 up, sorted
+    /// by y and then x
+    ///
+    /// Dying cells aren't part of [`GameOfLife::alive`], don't count as neighbors and can't be
+    /// born until they're dead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rule};
+    ///
+    /// // in Brian's Brain every alive cell dies, through one dying state
+    /// let mut game = GameOfLife::from_grid(&[[true, true]]);
+    /// game.set_rule(Rule::from_name("brians-brain").unwrap()).unwrap();
+    /// game.next_generation();
+    /// assert_eq!(game.alive_count(), 4);
+    /// assert_eq!(game.dying(), [((0, 0).into(), 2), ((1, 0).into(), 2)]);
+    /// assert_eq!(game.state(Pos2::new(0, 0)), 2);
+    /// assert_eq!(game.state(Pos2::new(0, -1)), 1);
+    ///
+    /// // the dying cells are dead after their one dying state
+    /// game.next_generation();
+    /// assert!(game.dying().iter().all(|&(pos, _)| pos.y != 0));
+    /// assert_eq!(game.state(Pos2::new(0, 0)), 0);
+    /// ```
+    #[inline]
+    pub fn dying(&self) -> &[(Pos2, u8)] {
+        &self.dying
+    }
+    /// Replaces the dying cells, e.g. when resuming a Generations rule from a saved pattern
+    ///
+    /// The cells don't need to be sorted. Cells that are alive, out of [`COORD_RANGE`] or not in
+    /// a dying state of the rule are dropped, so this should be called after
+    /// [`GameOfLife::set_rule`].
+    pub fn set_dying(&mut self, mut dying: Vec<(Pos2, u8)>) {
+        dying.retain(|&(pos, state)| {
+            (2..self.rule.states()).contains(&state)
+                && in_bounds(pos)
+                && self.alive.binary_search(&pos).is_err()
+        });
+        dying.sort_unstable_by_key(|&(pos, _)| pos);
+        dying.dedup_by_key(|&mut (pos, _)| pos);
+        self.hash ^= hash::dying_cells_hash(&self.dying) ^ hash::dying_cells_hash(&dying);
+        self.dying = dying;
+        self.changed = None;
+    }
+    /// The state of the cell at `pos`: 0 if dead, 1 if alive and 2 or more if it's dying
+    #[inline]
+    pub fn state(&self, pos: Pos2) -> u8 {
+        match self.dying.binary_search_by_key(&pos, |&(pos, _)| pos) {
+            Ok(i) => self.dying[i].1,
+            Err(_) => self.is_alive(pos) as u8,
+        }
+    }
+    /// Whether the infinite background is alive, which only happens with `B0` rules
+    ///
+    /// On an alive background, [`GameOfLife::alive`] holds the dead cells instead, while
+    /// [`GameOfLife::is_alive`] and the windows still give the state of the cells.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rule};
+    ///
+    /// // in AntiLife, Life with the states swapped, a block leaves a dead tub behind
+    /// let mut game = GameOfLife::from_grid(&[[true; 3]; 3]);
+    /// game.set_rule("B0123478/S01234678".parse().unwrap()).unwrap();
+    /// game.next_generation();
+    /// assert!(game.background());
+    /// assert_eq!(game.alive_count(), 4);
+    /// assert_eq!(game.alive(), [(1, 0), (0, 1), (2, 1), (1, 2)].map(Pos2::from));
+    /// assert!(!game.is_alive(Pos2::new(1, 0)) && game.is_alive(Pos2::new(1, 1)));
+    /// ```
+    #[inline]
+    pub fn background(&self) -> bool {
+        self.background
+    }
+    /// The rule that steps the stored cells, see [`Rule::for_background`]
+    #[inline]
+    fn step_rule(&self) -> Rule {
+        self.rule.for_background(self.background)
+    }
+
+    /// Enables or disables tracking how many generations each cell has been alive
+    ///
+    /// When enabled, all currently alive cells start with an age of 0
+    pub fn track_ages(&mut self, enabled: bool) {
+        self.ages = enabled.then(|| vec![0; self.alive.len()]);
+    }
+    /// The age of every alive cell (parallel to [`GameOfLife::alive`]), if age tracking is enabled
+    #[inline]
+    pub fn ages(&self) -> Option<&[u32]> {
+        self.ages.as_deref()
+    }
+
+    /// Enables or disables counting the births and deaths of every step
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns};
+    ///
+    /// let mut game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    /// assert_eq!(game.last_changes(), None);
+    /// game.track_changes(true);
+    /// game.next_generation();
+    /// assert_eq!(game.last_changes(), Some((2, 1)));
+    /// assert_eq!(game.alive_count(), 6);
+    /// ```
+    pub fn track_changes(&mut self, enabled: bool) {
+        self.track_changes = enabled;
+        self.changes = None;
+    }
+    /// The number of cells born and the number of cells that died in the last step, if change
+    /// tracking is enabled and there was a step since
+    #[inline]
+    pub fn last_changes(&self) -> Option<(usize, usize)> {
+        self.changes
+    }
+
+    /// Sets the number of threads used to compute each generation, where 1 is single-threaded
+    ///
+    /// Without the `std` feature there are no threads, and every generation is computed on the
+    /// calling one.
+    #[inline]
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Computes the next generation, using multiple threads if enabled with
+    /// [`GameOfLife::set_threads`]
     pub fn next_generation(&mut self) {
-        let next = NextGeneration::new(&self.alive).collect::<Vec<_>>();
+        let mut scratch = core::mem::take(&mut self.scratch);
+        self.next_generation_into(&mut scratch);
+        self.scratch = scratch;
+    }
+    /// Lazily computes the next generation without changing the game
+    ///
+    /// The cells are yielded sorted by y and then x, the same order as [`GameOfLife::alive`], and
+    /// are likewise the dead cells if the next background is alive.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns};
+    ///
+    /// let game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    /// let births = game
+    ///     .next_generation_iter()
+    ///     .filter(|&pos| !game.is_alive(pos))
+    ///     .count();
+    /// assert_eq!(births, 2);
+    /// ```
+    #[inline]
+    pub fn next_generation_iter(&self) -> NextGeneration<'_> {
+        NextGeneration::new(&self.alive, self.step_rule())
+    }
+    /// Computes the next generation into `scratch`, reusing its allocation
+    ///
+    /// Afterwards `scratch` holds the previous generation, so passing the same buffer every step
+    /// means no allocations once both buffers have grown to fit the pattern.
+    pub fn next_generation_into(&mut self, scratch: &mut Vec<Pos2>) {
+        self.step_into(scratch, self.threads);
+    }
+    fn step_into(&mut self, scratch: &mut Vec<Pos2>, threads: usize) {
+        let step = trace::step(self.generation, threads);
+        scratch.clear();
+        // leave some room for growth so a slightly larger generation doesn't reallocate
+        scratch.reserve(self.alive.len() + self.alive.len() / 8 + 16);
+        let rule = self.step_rule();
+        // the cells differing from a background that changes all change, and dead cells can be
+        // born once they're done dying, so incremental steps only work for plain rules
+        if self.incremental && rule == self.rule && self.rule.states() == 2 && self.dying.is_empty()
+        {
+            let changed = self.extend_incremental(scratch, threads);
+            for &pos in &changed {
+                self.toggle_hash(pos);
+            }
+            self.changed = Some(changed);
+        } else {
+            self.changed = None;
+            self.hash ^= self.extend_parallel(scratch, rule, threads);
+        }
+        if self.rule.states() > 2 || !self.dying.is_empty() {
+            self.decay(scratch, None);
+        }
+        self.finish_step(scratch);
+        step.finish(&self.alive, || Self::count_changes(scratch, &self.alive));
+    }
+    /// Makes the generation in `scratch` the current one, leaving the previous one there, and
+    /// updates everything else that follows a step
+    fn finish_step(&mut self, scratch: &mut Vec<Pos2>) {
         // verify integrity of next generation
         debug_assert!(
-            next.windows(2).all(|w| w[0] < w[1]),
+            scratch.windows(2).all(|w| w[0] < w[1]),
+            "output is not properly sorted"
+        );
+        core::mem::swap(&mut self.alive, scratch);
+        if let Some(ref ages) = self.ages {
+            self.ages = Some(Self::next_ages(scratch, ages, &self.alive));
+        }
+        if let Some(ref colors) = self.colors {
+            self.colors = Some(Self::next_colors(scratch, colors, &self.alive));
+        }
+        if self.track_changes {
+            self.changes = Some(Self::count_changes(scratch, &self.alive));
+        }
+        self.set_background(self.rule.next_background(self.background));
+        self.generation += 1;
+        self.record_history();
+        if !self.hooks.is_empty() {
+            let mut hooks = core::mem::take(&mut self.hooks);
+            hooks.call(self.generation_event(scratch));
+            self.hooks = hooks;
+        }
+    }
+
+    /// Applies the dying states of Generations rules to the `next` alive cells
+    ///
+    /// Births on dying cells are undone, alive cells that didn't survive start dying and the
+    /// dying cells move on to their next state, or are dead after the last one. With a `region`,
+    /// only the dying cells within it move on.
+    fn decay(&mut self, next: &mut Vec<Pos2>, region: Option<Rect>) {
+        let states = self.rule.states();
+        // dying cells aren't alive, so they can only be in `next` as births
+        let mut dying = self.dying.iter().map(|&(pos, _)| pos).peekable();
+        let mut undone = 0;
+        next.retain(|&pos| {
+            while dying.next_if(|&p| p < pos).is_some() {}
+            let born = dying.peek() == Some(&pos);
+            if born {
+                undone ^= hash::cell_hash(pos);
+            }
+            !born
+        });
+
+        // the alive cells missing from `next` didn't survive, found with a single merge pass
+        let mut j = 0;
+        let died = self.alive.iter().filter(|&&pos| {
+            j += next[j..].partition_point(|&p| p < pos);
+            next.get(j) != Some(&pos)
+        });
+        let died = died.map(|&pos| (pos, 2)).filter(|_| states > 2);
+        let older = self
+            .dying
+            .iter()
+            .map(|&(pos, state)| match region {
+                Some(region) if !region.contains(pos) => (pos, state),
+                _ => (pos, state.saturating_add(1)),
+            })
+            .filter(|&(_, state)| state < states);
+
+        // both are sorted and never share a cell, since cells are either alive or dying
+        let mut decayed = Vec::with_capacity(self.dying.len());
+        let (mut died, mut older) = (died.peekable(), older.peekable());
+        loop {
+            let cell = match (died.peek(), older.peek()) {
+                (Some(a), Some(b)) if a.0 < b.0 => died.next(),
+                (Some(_), None) => died.next(),
+                (_, Some(_)) => older.next(),
+                (None, None) => break,
+            };
+            decayed.extend(cell);
+        }
+        self.hash ^=
+            undone ^ hash::dying_cells_hash(&self.dying) ^ hash::dying_cells_hash(&decayed);
+        self.dying = decayed;
+    }
+
+    /// Advances the game by `n` generations
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.next_generation();
+        }
+    }
+
+    /// Advances the game until `pred` returns true, the population dies out or `max_gens`
+    /// generations have been computed
+    ///
+    /// The predicate is evaluated after each step, so it never sees the starting state.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::{GameOfLife, RunOutcome};
+    ///
+    /// let blinker = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let outcome = game.run_until(10, |game| game.alive() == blinker.alive());
+    /// assert_eq!(outcome, RunOutcome::Matched);
+    /// assert_eq!(game.generation(), 2);
+    ///
+    /// // a lone cell dies immediately
+    /// let mut game = GameOfLife::from_grid(&[[true]]);
+    /// assert_eq!(game.run_until(10, |_| false), RunOutcome::Extinct);
+    /// assert_eq!(game.generation(), 1);
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// assert_eq!(game.run_until(10, |_| false), RunOutcome::LimitReached);
+    /// assert_eq!(game.generation(), 10);
+    /// ```
+    pub fn run_until(
+        &mut self,
+        max_gens: u64,
+        mut pred: impl FnMut(&GameOfLife) -> bool,
+    ) -> RunOutcome {
+        for _ in 0..max_gens {
+            self.next_generation();
+            if pred(self) {
+                return RunOutcome::Matched;
+            }
+            if !self.background && self.alive_count() == 0 {
+                return RunOutcome::Extinct;
+            }
+        }
+        RunOutcome::LimitReached
+    }
+
+    /// Computes the ages for the `next` generation from the previous one
+    ///
+    /// Survivors age by one and births start at 0. Since both generations are sorted, this is a
+    /// single merge pass over the two.
+    fn next_ages(prev: &[Pos2], prev_ages: &[u32], next: &[Pos2]) -> Vec<u32> {
+        let mut i = 0;
+        next.iter()
+            .map(|pos| {
+                while prev.get(i).is_some_and(|p| p < pos) {
+                    i += 1;
+                }
+                match prev.get(i) {
+                    Some(p) if p == pos => prev_ages[i].saturating_add(1),
+                    _ => 0,
+                }
+            })
+            .collect()
+    }
+
+    /// Counts the cells only in `next` (births) and the cells only in `prev` (deaths) with a
+    /// single merge pass over the sorted generations
+    fn count_changes(prev: &[Pos2], next: &[Pos2]) -> (usize, usize) {
+        let (mut i, mut j) = (0, 0);
+        let mut survivors = 0;
+        while i < prev.len() && j < next.len() {
+            match prev[i].cmp(&next[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    survivors += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        (next.len() - survivors, prev.len() - survivors)
+    }
+
+    /// Whether the cell at `pos` is alive
+    #[inline]
+    pub fn is_alive(&self, pos: Pos2) -> bool {
+        self.alive.binary_search(&pos).is_ok() != self.background
+    }
+    /// The number of alive cells among the 8 cells around `pos`
+    ///
+    /// The three rows are found with binary searches, so this takes O(log n) rather than
+    /// scanning the alive cells. Dying cells of Generations rules count as dead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// let counts = game.alive().iter().map(|&pos| game.count_neighbors(pos));
+    /// assert!(counts.eq([1, 2, 1]));
+    /// // the cells above and below the middle are born next
+    /// assert_eq!(game.count_neighbors(Pos2::new(1, -1)), 3);
+    /// assert_eq!(game.count_neighbors(Pos2::new(1, 1)), 3);
+    /// ```
+    pub fn count_neighbors(&self, pos: Pos2) -> u8 {
+        let min = pos.saturating_add(Pos2::new(-1, -1));
+        let max = pos.saturating_add(Pos2::new(2, 2));
+        let around = self.window(Rect::new(min, max)).alive_count();
+        (around - self.is_alive(pos) as usize) as u8
+    }
+    /// The fraction of the cells within `rect` that are alive, or 0 if `rect` is empty
+    ///
+    /// Like [`GameEngineWindow::alive_count`], this only looks at the rows of `rect`, taking
+    /// O(log n + k) for the k alive cells within it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, true], [true, false]]);
+    /// assert_eq!(game.density(Rect::new(Pos2::new(0, 0), Pos2::new(2, 2))), 0.75);
+    /// assert_eq!(game.density(Rect::new(Pos2::new(0, 0), Pos2::new(4, 4))), 3.0 / 16.0);
+    /// assert_eq!(game.density(Rect::new(Pos2::new(0, 0), Pos2::new(0, 4))), 0.0);
+    /// ```
+    pub fn density(&self, rect: Rect) -> f64 {
+        if rect.is_empty() {
+            return 0.0;
+        }
+        self.window(rect).alive_count() as f64 / rect.area() as f64
+    }
+
+    /// Sets the state of the cell at `pos`, keeping the alive cells sorted
+    ///
+    /// Cells outside of [`COORD_RANGE`] can't be set alive and are ignored.
+    pub fn set_cell(&mut self, pos: Pos2, alive: bool) {
+        if !in_bounds(pos) {
+            return;
+        }
+        self.remove_dying(pos);
+        match (self.alive.binary_search(&pos), alive != self.background) {
+            (Err(i), true) => self.insert_at(i, pos),
+            (Ok(i), false) => self.remove_at(i),
+            _ => {}
+        }
+    }
+    /// Flips the state of the cell at `pos`, keeping the alive cells sorted
+    ///
+    /// Dying cells of Generations rules count as dead, so they come alive.
+    ///
+    /// ## Returns
+    /// Whether the cell is alive after toggling, which is always `false` for cells outside of
+    /// [`COORD_RANGE`]
+    pub fn toggle_cell(&mut self, pos: Pos2) -> bool {
+        if !in_bounds(pos) {
+            return false;
+        }
+        self.remove_dying(pos);
+        match self.alive.binary_search(&pos) {
+            Ok(i) => self.remove_at(i),
+            Err(i) => self.insert_at(i, pos),
+        }
+        self.is_alive(pos)
+    }
+    /// Makes a dying cell dead, so it can be set
+    fn remove_dying(&mut self, pos: Pos2) {
+        if let Ok(i) = self.dying.binary_search_by_key(&pos, |&(pos, _)| pos) {
+            let (_, state) = self.dying.remove(i);
+            self.hash ^= hash::dying_cells_hash(&[(pos, state)]);
+            self.mark_changed(pos);
+        }
+    }
+    fn insert_at(&mut self, i: usize, pos: Pos2) {
+        self.mark_changed(pos);
+        self.toggle_hash(pos);
+        self.alive.insert(i, pos);
+        if let Some(ref mut ages) = self.ages {
+            ages.insert(i, 0);
+        }
+        if let Some(ref mut colors) = self.colors {
+            colors.insert(i, 0);
+        }
+    }
+    fn remove_at(&mut self, i: usize) {
+        let pos = self.alive.remove(i);
+        self.mark_changed(pos);
+        self.toggle_hash(pos);
+        if let Some(ref mut ages) = self.ages {
+            ages.remove(i);
+        }
+        if let Some(ref mut colors) = self.colors {
+            colors.remove(i);
+        }
+    }
+
+    /// Moves every cell by `offset`
+    ///
+    /// Like the other transforms, cells that leave [`COORD_RANGE`] are dropped.
+    pub fn translate(&mut self, offset: Pos2) {
+        self.map_cells(|pos| pos.checked_add(offset));
+    }
+    /// Rotates the pattern 90° clockwise around the origin
+    pub fn rotate_cw(&mut self) {
+        self.map_cells(transform::rotate_cw_pos);
+    }
+    /// Rotates the pattern 90° counter-clockwise around the origin
+    pub fn rotate_ccw(&mut self) {
+        self.map_cells(transform::rotate_ccw_pos);
+    }
+    /// Mirrors the pattern horizontally, negating x
+    pub fn flip_x(&mut self) {
+        self.map_cells(transform::flip_x_pos);
+    }
+    /// Mirrors the pattern vertically, negating y
+    pub fn flip_y(&mut self) {
+        self.map_cells(transform::flip_y_pos);
+    }
+    /// Moves the pattern so the top-left corner of its bounding box is at the origin
+    pub fn recenter(&mut self) {
+        let top_left = transform::top_left(&self.alive);
+        self.map_cells(|pos| pos.checked_sub(top_left));
+    }
+    /// Maps every cell (keeping its age and color), then restores the sorted invariant
+    fn map_cells(&mut self, f: impl Fn(Pos2) -> Option<Pos2>) {
+        // every cell may have moved, so the next incremental step has to start over
+        self.changed = None;
+        let ages = self.ages.take();
+        let colors = self.colors.take();
+        let mut cells = self
+            .alive
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &pos)| {
+                let pos = f(pos).filter(|&pos| in_bounds(pos))?;
+                let age = ages.as_ref().map_or(0, |ages| ages[i]);
+                Some((pos, (age, colors.as_ref().map_or(0, |colors| colors[i]))))
+            })
+            .collect::<Vec<_>>();
+        cells.sort_unstable_by_key(|&(pos, _)| pos);
+        debug_assert!(
+            cells.windows(2).all(|w| w[0].0 < w[1].0),
             "output is not properly sorted"
         );
-        self.alive = next;
+
+        self.alive = cells.iter().map(|&(pos, _)| pos).collect();
+        self.ages = ages.map(|_| cells.iter().map(|&(_, (age, _))| age).collect());
+        self.colors = colors.map(|_| cells.iter().map(|&(_, (_, color))| color).collect());
+
+        let dying = core::mem::take(&mut self.dying);
+        let dying = dying.into_iter().filter_map(|(pos, state)| {
+            f(pos).filter(|&pos| in_bounds(pos)).map(|pos| (pos, state))
+        });
+        self.set_dying(dying.collect());
+        self.rehash();
     }
 
-    pub fn window(&self, top_left: Pos2, bottom_right: Pos2) -> GameEngineWindow<'_> {
-        GameEngineWindow::new(self, top_left, bottom_right)
+    /// A view of the cells within `rect`
+    pub fn window(&self, rect: Rect) -> GameEngineWindow<'_> {
+        GameEngineWindow::new(self, rect)
+    }
+    /// A view of the cells within `top_left` (inclusive) and `bottom_right` (exclusive)
+    #[deprecated(note = "use `GameOfLife::window` with a `Rect` instead")]
+    pub fn window_between(&self, top_left: Pos2, bottom_right: Pos2) -> GameEngineWindow<'_> {
+        self.window(Rect::new(top_left, bottom_right))
     }
 
+    /// The number of alive cells
+    ///
+    /// Like [`GameOfLife::alive`], this counts the dead cells if the
+    /// [background](GameOfLife::background) is alive. Dying cells of Generations rules aren't
+    /// counted.
     #[inline]
     pub fn alive_count(&self) -> usize {
         self.alive.len()
     }
 
+    /// The alive cells, sorted by y and then x
+    ///
+    /// If the [background](GameOfLife::background) is alive, these are the dead cells instead.
+    #[inline]
+    pub fn alive(&self) -> &[Pos2] {
+        &self.alive
+    }
+    /// The [alive cells](GameOfLife::alive) grouped by row, as each row's y value and the cells
+    /// on it, see [`transform::rows`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, false, true], [false; 3], [false, true, false]]);
+    /// let rows = game.rows().map(|(y, row)| (y, row.len())).collect::<Vec<_>>();
+    /// assert_eq!(rows, [(0, 2), (2, 1)]);
+    /// assert_eq!(game.row(2), [Pos2::new(1, 2)]);
+    /// assert!(game.row(1).is_empty());
+    /// ```
+    #[inline]
+    pub fn rows(&self) -> impl Iterator<Item = (i32, &[Pos2])> {
+        transform::rows(&self.alive)
+    }
+    /// The [alive cells](GameOfLife::alive) on row `y`, see [`transform::row`]
+    #[inline]
+    pub fn row(&self, y: i32) -> &[Pos2] {
+        transform::row(&self.alive, y)
+    }
+
+    /// The alive cells as they can be written to a pattern file, sorted by y and then x
+    ///
+    /// On an alive background these are only the alive cells within the bounding box of the dead
+    /// ones, since the background around them can't be stored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true; 3]; 3]);
+    /// game.set_rule("B0123478/S01234678".parse().unwrap()).unwrap();
+    /// assert_eq!(*game.pattern_cells(), *game.alive());
+    /// game.next_generation();
+    /// // the corners and the center of the dead tub's bounding box
+    /// let cells = [(0, 0), (2, 0), (1, 1), (0, 2), (2, 2)].map(Pos2::from);
+    /// assert_eq!(*game.pattern_cells(), cells);
+    /// ```
+    pub fn pattern_cells(&self) -> Cow<'_, [Pos2]> {
+        if !self.background {
+            return Cow::Borrowed(&self.alive);
+        }
+        let bounds = transform::bounding_box(&self.alive);
+        let cells = self.window(bounds).iter_cells();
+        Cow::Owned(
+            cells
+                .filter(|&(_, alive)| alive)
+                .map(|(pos, _)| pos)
+                .collect(),
+        )
+    }
+
     #[inline]
     pub fn take(self) -> Vec<Pos2> {
         self.alive
     }
 }
 
-struct NextGeneration<'a> {
+impl Engine for GameOfLife {
+    #[inline]
+    fn next_generation(&mut self) {
+        GameOfLife::next_generation(self)
+    }
+    #[inline]
+    fn alive_count(&self) -> usize {
+        GameOfLife::alive_count(self)
+    }
+    #[inline]
+    fn is_alive(&self, pos: Pos2) -> bool {
+        GameOfLife::is_alive(self, pos)
+    }
+    #[inline]
+    fn insert(&mut self, pos: Pos2) {
+        self.set_cell(pos, true)
+    }
+    fn iter(&self) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Box::new(self.alive.iter().copied())
+    }
+    fn window(&self, rect: Rect) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        let window = GameOfLife::window(self, rect);
+        Box::new(window.iter().copied())
+    }
+}
+
+/// An iterator over the alive cells of the next generation, created by
+/// [`GameOfLife::next_generation_iter`]
+///
+/// Cells are yielded sorted by y and then x. Once it returns `None` it keeps returning `None`.
+///
+/// By default two horizontally adjacent cells are computed per lookup (see
+/// [`NextGeneration::single_lookup`] for the original one cell path).
+///
+/// # Example
+/// Both paths agree on random soups:
+/// ```rust
+/// use cgolrs::{engine::GameOfLife, Pos2};
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(303);
+/// for _ in 0..20 {
+///     let soup = (0..64)
+///         .flat_map(|y| (0..64).map(move |x| Pos2::new(x - 32, y - 32)))
+///         .filter(|_| rng.gen_bool(0.4))
+///         .collect::<Vec<_>>();
+///     let mut game = GameOfLife::from_alive(soup);
+///     for _ in 0..10 {
+///         let single = game.next_generation_iter().single_lookup().collect::<Vec<_>>();
+///         assert!(game.next_generation_iter().eq(single));
+///         game.next_generation();
+///     }
+/// }
+/// ```
+pub struct NextGeneration<'a> {
     cursor: MultiRowPosCursor<'a>,
+    /// The lookup tables of the rule
+    index: &'static index::RuleIndex,
+    /// Whether to compute one cell per lookup instead of two
+    single: bool,
+    /// The right cell of a pair, waiting to be yielded after the left one
+    pending: Option<Pos2>,
+    /// The rows whose births and deaths are added to `changes_hash`
+    hashed_rows: Range<i32>,
+    /// The hashes of the cells born or died so far XORed together
+    changes_hash: u64,
 }
 impl<'a> NextGeneration<'a> {
-    const ROW_MASK: u8 = 0b111;
-    fn next_cell_state(buffers: &[u8]) -> bool {
+    const ROW_MASK: u64 = 0b111;
+    /// The columns needed to compute two cells at once
+    const PAIR_ROW_MASK: u64 = 0b1111;
+    fn next_cell_state(index: &index::RuleIndex, buffers: &[u64]) -> bool {
         // combine the first 3 bits of each bit buffer into a bit-grid
         let mut grid: usize = 0;
         for (i, &buffer) in buffers.iter().enumerate() {
@@ -59,42 +1083,118 @@ impl<'a> NextGeneration<'a> {
         }
 
         // lookup the grid in the index to get the state of the central cell
-        index::get_gol_index()[grid]
+        index.single[grid]
+    }
+    fn next_pair_state(index: &index::RuleIndex, buffers: &[u64]) -> u8 {
+        // combine the first 4 bits of each bit buffer into a bit-grid
+        let mut grid: usize = 0;
+        for (i, &buffer) in buffers.iter().enumerate() {
+            grid |= ((buffer & Self::PAIR_ROW_MASK) as usize) << (i * 4);
+        }
+
+        // lookup the grid in the index to get the state of both central cells
+        index.pair[grid]
     }
 
-    fn new(alive: &'a [Pos2]) -> Self {
+    fn new(alive: &'a [Pos2], rule: Rule) -> Self {
         let cursor = MultiRowPosCursor::new(alive, 3);
-        Self { cursor }
+        Self {
+            cursor,
+            index: index::get_rule_index(rule),
+            single: false,
+            pending: None,
+            hashed_rows: i32::MIN..i32::MAX,
+            changes_hash: 0,
+        }
+    }
+    /// Computes one cell per table lookup instead of two, for comparing against the original path
+    pub fn single_lookup(mut self) -> Self {
+        self.single = true;
+        self
+    }
+    /// Only adds the births and deaths within `rows` to [`NextGeneration::changes_hash`], for
+    /// chunks that compute a few rows past their own
+    #[cfg(feature = "std")]
+    pub(super) fn hash_rows(mut self, rows: Range<i32>) -> Self {
+        self.hashed_rows = rows;
+        self
+    }
+    /// The hashes of the cells born or died up to the last one yielded, XORed together
+    ///
+    /// XORing it into the state hash of the generation the cells were computed from gives the
+    /// state hash of the next one.
+    pub(super) fn changes_hash(&self) -> u64 {
+        self.changes_hash
     }
 
     fn pos(&self) -> Pos2 {
         // since the returned cursor pos is the bottom most cursor, we have to adjust by one to get to the "center"
         self.cursor.cursor() - Pos2::one()
     }
-    fn step(&mut self) -> Option<(Pos2, bool)> {
-        let is_empty = self
-            .cursor
-            .buffers()
-            .iter()
-            .all(|&b| b & Self::ROW_MASK == 0);
+    /// Advances to the next cell (or pair of cells) that could be alive
+    ///
+    /// ## Returns
+    /// The position of the rightmost cell computed, with its state in bit 0 and the state of the
+    /// cell to its left (if computed) in bit 1, followed by their current states the same way
+    fn step(&mut self) -> Option<(Pos2, u8, u8)> {
+        let mask = if self.single { Self::ROW_MASK } else { 0b11 };
+        let is_empty = self.cursor.buffers().iter().all(|&b| b & mask == 0);
 
-        let next_state = Self::next_cell_state(if is_empty {
-            self.cursor.seek_closest()?
+        // the columns around the next cell are empty, so skip straight to the next alive cell
+        let (next_states, current) = if is_empty {
+            let buffers = self.cursor.seek_closest()?;
+            let next_state = Self::next_cell_state(self.index, buffers);
+            (next_state as u8, Self::current_states(buffers, 0b01))
+        } else if self.single {
+            let buffers = self.cursor.next();
+            let next_state = Self::next_cell_state(self.index, buffers);
+            (next_state as u8, Self::current_states(buffers, 0b01))
         } else {
-            self.cursor.next()
-        });
-        Some((self.pos(), next_state))
+            self.cursor.next();
+            let buffers = self.cursor.next();
+            let next_states = Self::next_pair_state(self.index, buffers);
+            (next_states, Self::current_states(buffers, 0b11))
+        };
+        Some((self.pos(), next_states, current))
+    }
+    /// The current states of the cells just computed, laid out like their next states
+    fn current_states(buffers: &[u64], mask: u64) -> u8 {
+        // the middle row, where bit 0 is the column right of the computed cells
+        ((buffers[1] >> 1) & mask) as u8
     }
 }
 impl Iterator for NextGeneration<'_> {
     type Item = Pos2;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some((pos, alive)) = self.step() {
-            if alive {
-                return Some(pos);
+        if let Some(pos) = self.pending.take() {
+            return Some(pos);
+        }
+        while let Some((pos, states, current)) = self.step() {
+            // cells are never born at the edge of the universe
+            let left = pos - Pos2::new(1, 0);
+            let left_alive = states & 0b10 != 0 && in_bounds(left);
+            let alive = states & 0b01 != 0 && in_bounds(pos);
+            let changed = current ^ ((left_alive as u8) << 1 | alive as u8);
+            if changed != 0 && self.hashed_rows.contains(&pos.y) {
+                if changed & 0b10 != 0 {
+                    self.changes_hash ^= hash::cell_hash(left);
+                }
+                if changed & 0b01 != 0 {
+                    self.changes_hash ^= hash::cell_hash(pos);
+                }
+            }
+            match (left_alive, alive) {
+                (true, alive) => {
+                    self.pending = alive.then_some(pos);
+                    return Some(left);
+                }
+                (false, true) => return Some(pos),
+                (false, false) => {}
             }
         }
         None
     }
 }
+// when no cursor has a cell left, `seek_closest` keeps returning `None` without moving
+impl FusedIterator for NextGeneration<'_> {}
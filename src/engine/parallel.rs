@@ -0,0 +1,189 @@
+#[cfg(feature = "std")]
+use super::pool::WorkerPool;
+use super::{GameOfLife, NextGeneration};
+use crate::{prelude::*, Pos2, Rule};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// How many chunks each thread gets on average, so threads that finish a cheap chunk early can
+/// pick up more work instead of idling
+#[cfg(feature = "std")]
+const CHUNKS_PER_THREAD: usize = 4;
+/// The fewest alive cells per chunk of a parallel step by default, see
+/// [`GameOfLife::set_min_chunk_cells`]
+pub const DEFAULT_MIN_CHUNK_CELLS: usize = 256;
+/// The fewest rows a chunk spans on average, since each chunk also reads the rows around it
+#[cfg(feature = "std")]
+const MIN_CHUNK_ROWS: usize = 4;
+
+impl GameOfLife {
+    /// Computes the next generation across `threads` threads
+    ///
+    /// The alive cells are split into chunks on row boundaries, sized by cell count, and the
+    /// threads of a persistent worker pool take chunks from a shared queue until none are left.
+    /// The result is identical to [`GameOfLife::next_generation`].
+    ///
+    /// Small patterns aren't worth splitting across many threads, so there are only as many
+    /// chunks as have [`GameOfLife::set_min_chunk_cells`] cells and 4 rows each, and the step is
+    /// computed on the calling thread if that's just one.
+    ///
+    /// # Example
+    /// A gun next to a distant field of debris, which is badly balanced when split naively:
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2};
+    ///
+    /// let gun = patterns::find("gosper-glider-gun").unwrap().cells();
+    /// let ash = (0..64)
+    ///     .flat_map(|y| (0..64).map(move |x| Pos2::new(x * 5 + 500, y * 5 + 500)))
+    ///     .flat_map(|pos| (0..3).map(move |dx| pos + Pos2::new(dx, 0)))
+    ///     .collect::<Vec<_>>();
+    /// let mut serial = GameOfLife::from_unsorted([gun, ash].concat());
+    ///
+    /// let mut parallel = serial.clone();
+    /// for _ in 0..60 {
+    ///     serial.next_generation();
+    ///     parallel.next_generation_parallel(4);
+    ///     assert_eq!(serial.alive(), parallel.alive());
+    /// }
+    /// assert_eq!(serial.generation(), parallel.generation());
+    /// ```
+    pub fn next_generation_parallel(&mut self, threads: usize) {
+        let mut scratch = core::mem::take(&mut self.scratch);
+        self.step_into(&mut scratch, threads);
+        self.scratch = scratch;
+    }
+
+    /// Appends the next generation under `rule` to `out`, computed across `threads` threads
+    ///
+    /// The worker pool is created on the first parallel step, and re-created if the number of
+    /// threads changes.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(super) fn extend_parallel(
+        &mut self,
+        out: &mut Vec<Pos2>,
+        rule: Rule,
+        threads: usize,
+    ) -> u64 {
+        #[cfg(feature = "std")]
+        {
+            // serial steps don't split the cells, so they don't allocate the chunks either
+            let chunks = self.useful_chunks(threads);
+            if chunks > 1 {
+                let starts = row_chunks(&self.alive, chunks);
+                if starts.len() > 1 {
+                    return self.extend_chunks(out, rule, threads, &starts);
+                }
+            }
+        }
+        let mut next = NextGeneration::new(&self.alive, rule);
+        out.extend(&mut next);
+        next.changes_hash()
+    }
+    /// Appends the next generation to `out` with the chunks starting at `starts` spread across
+    /// the worker pool
+    #[cfg(feature = "std")]
+    fn extend_chunks(
+        &mut self,
+        out: &mut Vec<Pos2>,
+        rule: Rule,
+        threads: usize,
+        starts: &[usize],
+    ) -> u64 {
+        // each chunk owns the output rows from its first row up to the first row of the next one
+        let rows = (0..starts.len())
+            .map(|c| {
+                let lo = if c == 0 {
+                    i32::MIN
+                } else {
+                    self.alive[starts[c]].y
+                };
+                let hi = starts.get(c + 1).map_or(i32::MAX, |&i| self.alive[i].y);
+                lo..hi
+            })
+            .collect::<Vec<_>>();
+
+        let pool = match self.pool {
+            Some(ref pool) if pool.threads() == threads => pool,
+            _ => self.pool.insert(WorkerPool::new(threads)),
+        };
+        // the workers share the generation for the duration of the step
+        let alive = Arc::new(core::mem::take(&mut self.alive));
+        let results = pool.run(&alive, rule, &rows);
+        self.alive = Arc::try_unwrap(alive).expect("workers released the generation");
+
+        // chunks are in row order, so concatenating them keeps the output sorted
+        let mut changes_hash = 0;
+        for (cells, hash) in results {
+            out.extend(cells);
+            changes_hash ^= hash;
+        }
+        changes_hash
+    }
+
+    /// The number of chunks worth splitting the generation into for `threads` threads, which
+    /// is 1 if it should be computed serially
+    #[cfg(feature = "std")]
+    fn useful_chunks(&self, threads: usize) -> usize {
+        let (Some(first), Some(last)) = (self.alive.first(), self.alive.last()) else {
+            return 1;
+        };
+        if threads <= 1 {
+            return 1;
+        }
+        let rows = (last.y as i64 - first.y as i64 + 1) as usize;
+        (threads * CHUNKS_PER_THREAD)
+            .min(self.alive.len() / self.min_chunk_cells)
+            .min(rows / MIN_CHUNK_ROWS)
+            .max(1)
+    }
+
+    /// Sets the fewest alive cells a chunk of a parallel step gets, which is
+    /// [`DEFAULT_MIN_CHUNK_CELLS`] by default
+    ///
+    /// Every chunk costs a round trip to a worker thread, so patterns with fewer cells than
+    /// this are stepped serially however many threads are set. Lowering it is mostly useful to
+    /// benchmark the overhead, or to exercise the chunk boundaries of small patterns.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::GameOfLife;
+    ///
+    /// let mut serial = GameOfLife::from_grid(&[[true; 30]; 30]);
+    /// let mut parallel = serial.clone();
+    /// parallel.set_threads(16);
+    /// parallel.set_min_chunk_cells(1);
+    /// for _ in 0..10 {
+    ///     serial.next_generation();
+    ///     parallel.next_generation();
+    ///     assert_eq!(serial.alive(), parallel.alive());
+    /// }
+    /// ```
+    pub fn set_min_chunk_cells(&mut self, cells: usize) {
+        self.min_chunk_cells = cells.max(1);
+    }
+}
+
+/// Splits sorted cells into about `chunks` runs of similar length, without splitting a row
+///
+/// ## Returns
+/// The index of the first cell of each run
+#[cfg(feature = "std")]
+fn row_chunks(alive: &[Pos2], chunks: usize) -> Vec<usize> {
+    if alive.is_empty() {
+        return Vec::new();
+    }
+    let target = alive.len().div_ceil(chunks.max(1));
+    let mut starts = vec![0];
+    let mut i = target;
+    while i < alive.len() {
+        // move forward to the start of the next row
+        while i < alive.len() && alive[i].y == alive[i - 1].y {
+            i += 1;
+        }
+        if i < alive.len() {
+            starts.push(i);
+        }
+        i += target;
+    }
+    starts
+}
@@ -0,0 +1,305 @@
+use super::{hash, GameOfLife, Map};
+use crate::Pos2;
+use alloc::collections::VecDeque;
+use core::fmt;
+
+/// The most states [`GameOfLife::find_period`] remembers, which bounds the periods it can detect
+const HISTORY_LIMIT: usize = 1 << 16;
+
+/// The result of [`GameOfLife::find_period`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodInfo {
+    /// The number of generations until the pattern repeats
+    pub period: usize,
+    /// How far the pattern moved in one period, which is zero for still lifes and oscillators
+    pub displacement: Pos2,
+}
+
+impl PeriodInfo {
+    /// Whether the pattern moves, i.e. it's a spaceship
+    #[inline]
+    pub fn is_spaceship(&self) -> bool {
+        self.displacement != Pos2::zero()
+    }
+    /// The velocity of a spaceship as `(dx, dy, period)`, or `None` for stationary patterns
+    pub fn velocity(&self) -> Option<(i32, i32, usize)> {
+        self.is_spaceship()
+            .then_some((self.displacement.x, self.displacement.y, self.period))
+    }
+    /// The speed of a spaceship, or `None` for stationary patterns
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::{Direction, PeriodInfo}, Pos2};
+    ///
+    /// let info = PeriodInfo { period: 4, displacement: Pos2::new(-2, 0) };
+    /// let speed = info.speed().unwrap();
+    /// assert_eq!((speed.cells, speed.generations), (1, 2));
+    /// assert_eq!(speed.direction, Direction::Orthogonal);
+    /// assert_eq!(speed.to_string(), "c/2 orthogonal");
+    /// ```
+    pub fn speed(&self) -> Option<Speed> {
+        if !self.is_spaceship() {
+            return None;
+        }
+        let (dx, dy) = (
+            self.displacement.x.unsigned_abs() as u64,
+            self.displacement.y.unsigned_abs() as u64,
+        );
+        let direction = if dx == 0 || dy == 0 {
+            Direction::Orthogonal
+        } else if dx == dy {
+            Direction::Diagonal
+        } else {
+            Direction::Oblique
+        };
+        let (cells, generations) = (dx.max(dy), self.period as u64);
+        let divisor = gcd(cells, generations);
+        Some(Speed {
+            cells: cells / divisor,
+            generations: generations / divisor,
+            direction,
+        })
+    }
+}
+
+/// The greatest common divisor of two numbers
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The direction a spaceship moves in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Along a row or a column
+    Orthogonal,
+    /// Along a diagonal, as far in x as in y
+    Diagonal,
+    /// Any other direction, like the knight's move of Sir Robin
+    Oblique,
+}
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Orthogonal => "orthogonal",
+            Self::Diagonal => "diagonal",
+            Self::Oblique => "oblique",
+        })
+    }
+}
+
+/// The speed of a spaceship as a fraction of c, the speed of light of one cell per generation
+///
+/// The speed is how far the spaceship moves along the axis it moves furthest along, in lowest
+/// terms, so a spaceship moving 2 cells every 4 generations goes at c/2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Speed {
+    /// The cells moved in [`Speed::generations`]
+    pub cells: u64,
+    /// The generations it takes to move [`Speed::cells`]
+    pub generations: u64,
+    /// The direction it moves in
+    pub direction: Direction,
+}
+impl Speed {
+    /// The speed in cells per generation
+    #[inline]
+    pub fn as_f64(&self) -> f64 {
+        self.cells as f64 / self.generations as f64
+    }
+}
+impl fmt::Display for Speed {
+    /// Writes the speed in the usual notation, like `c/4 diagonal` or `2c/5 orthogonal`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.cells, self.generations) {
+            (1, 1) => write!(f, "c")?,
+            (1, generations) => write!(f, "c/{}", generations)?,
+            (cells, 1) => write!(f, "{}c", cells)?,
+            (cells, generations) => write!(f, "{}c/{}", cells, generations)?,
+        }
+        write!(f, " {}", self.direction)
+    }
+}
+
+/// What kind of pattern a game is, found by [`GameOfLife::analyze`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Analysis {
+    /// A pattern that doesn't change
+    StillLife,
+    /// A pattern that repeats in place every `period` generations
+    Oscillator { period: usize },
+    /// A pattern that repeats every `period` generations, moved by `displacement`
+    Spaceship {
+        period: usize,
+        displacement: Pos2,
+        speed: Speed,
+    },
+    /// A pattern that died out or didn't repeat within the generation limit
+    Unresolved,
+}
+impl From<Option<PeriodInfo>> for Analysis {
+    fn from(info: Option<PeriodInfo>) -> Self {
+        match info {
+            None => Self::Unresolved,
+            Some(info) => match info.speed() {
+                Some(speed) => Self::Spaceship {
+                    period: info.period,
+                    displacement: info.displacement,
+                    speed,
+                },
+                None if info.period == 1 => Self::StillLife,
+                None => Self::Oscillator {
+                    period: info.period,
+                },
+            },
+        }
+    }
+}
+impl Analysis {
+    /// The generations it takes to repeat, which is 1 for still lifes and `None` if unresolved
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2};
+    ///
+    /// let mut glider = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+    /// let analysis = glider.analyze(100);
+    /// assert_eq!(analysis.period(), Some(4));
+    /// assert_eq!(analysis.displacement(), Some(Pos2::new(1, 1)));
+    /// ```
+    pub fn period(&self) -> Option<usize> {
+        match *self {
+            Self::StillLife => Some(1),
+            Self::Oscillator { period } | Self::Spaceship { period, .. } => Some(period),
+            Self::Unresolved => None,
+        }
+    }
+    /// How far the pattern moves every [period](Analysis::period), which is (0, 0) unless it's a
+    /// spaceship and `None` if unresolved
+    pub fn displacement(&self) -> Option<Pos2> {
+        match *self {
+            Self::StillLife | Self::Oscillator { .. } => Some(Pos2::zero()),
+            Self::Spaceship { displacement, .. } => Some(displacement),
+            Self::Unresolved => None,
+        }
+    }
+}
+impl fmt::Display for Analysis {
+    /// Writes the kind of pattern, like `oscillator, period 3` or
+    /// `spaceship, period 4, moving (1, 1) at c/4 diagonal`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StillLife => write!(f, "still life"),
+            Self::Oscillator { period } => write!(f, "oscillator, period {}", period),
+            Self::Spaceship {
+                period,
+                displacement,
+                speed,
+            } => write!(
+                f,
+                "spaceship, period {}, moving {} at {}",
+                period, displacement, speed
+            ),
+            Self::Unresolved => write!(f, "unresolved"),
+        }
+    }
+}
+
+impl GameOfLife {
+    /// Steps the pattern until it repeats a previous state (up to a translation)
+    ///
+    /// ## Returns
+    /// The period and displacement of the pattern, or `None` if it didn't repeat within
+    /// `max_gens` generations or died out
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2};
+    ///
+    /// let period = |name| {
+    ///     let cells = patterns::find(name).unwrap().cells();
+    ///     GameOfLife::from_alive(cells).find_period(100)
+    /// };
+    /// assert_eq!(period("blinker").unwrap().period, 2);
+    /// assert_eq!(period("pulsar").unwrap().period, 3);
+    /// assert_eq!(period("r-pentomino"), None);
+    ///
+    /// let glider = period("glider").unwrap();
+    /// assert_eq!(glider.period, 4);
+    /// assert_eq!(glider.displacement, Pos2::new(1, 1));
+    /// assert_eq!(glider.velocity(), Some((1, 1, 4)));
+    /// ```
+    pub fn find_period(&mut self, max_gens: usize) -> Option<PeriodInfo> {
+        // the generation and top-left corner each normalized state was first seen at
+        let mut history = Map::new();
+        let mut order = VecDeque::new();
+        let mut gen = 0;
+        loop {
+            if !self.background() && self.alive_count() == 0 {
+                return None;
+            }
+            let (hash, top_left) = self.normalized_hash();
+            if let Some(&(start, start_top_left)) = history.get(&hash) {
+                return Some(PeriodInfo {
+                    period: gen - start,
+                    displacement: top_left - start_top_left,
+                });
+            }
+            if order.len() == HISTORY_LIMIT {
+                if let Some(oldest) = order.pop_front() {
+                    history.remove(&oldest);
+                }
+            }
+            history.insert(hash, (gen, top_left));
+            order.push_back(hash);
+
+            if gen == max_gens {
+                return None;
+            }
+            self.next_generation();
+            gen += 1;
+        }
+    }
+
+    /// Steps the pattern until it repeats like [`GameOfLife::find_period`], and tells whether
+    /// it's a still life, an oscillator or a spaceship
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::{Analysis, GameOfLife}, patterns};
+    ///
+    /// let analyze = |name| {
+    ///     let cells = patterns::find(name).unwrap().cells();
+    ///     GameOfLife::from_alive(cells).analyze(100)
+    /// };
+    /// let mut block = GameOfLife::from_grid(&[[true, true], [true, true]]);
+    /// assert_eq!(block.analyze(100), Analysis::StillLife);
+    /// assert_eq!(analyze("pulsar"), Analysis::Oscillator { period: 3 });
+    /// assert_eq!(analyze("r-pentomino"), Analysis::Unresolved);
+    /// assert_eq!(
+    ///     analyze("glider").to_string(),
+    ///     "spaceship, period 4, moving (1, 1) at c/4 diagonal"
+    /// );
+    /// ```
+    pub fn analyze(&mut self, max_gens: usize) -> Analysis {
+        self.find_period(max_gens).into()
+    }
+
+    /// Hashes the alive cells translated so their bounding box starts at the origin
+    ///
+    /// ## Returns
+    /// The hash and the top-left corner of the bounding box
+    fn normalized_hash(&self) -> (u64, Pos2) {
+        let top_left = Pos2 {
+            x: self.alive.iter().map(|p| p.x).min().unwrap_or_default(),
+            // because the cells are sorted, the first cell has the lowest y-value
+            y: self.alive.first().map(|p| p.y).unwrap_or_default(),
+        };
+
+        let hash = hash::translated_hash(&self.alive, self.background, &self.dying, top_left);
+        (hash, top_left)
+    }
+}
@@ -0,0 +1,155 @@
+use super::{GameEngineWindow, GameOfLife};
+use crate::transform;
+use std::{io, path::Path};
+
+/// The most pixels an exported image can have, so a huge bounding box is rejected up front
+/// instead of allocating gigabytes (this is 1 GiB of RGBA pixels)
+pub const MAX_PNG_PIXELS: u64 = 1 << 28;
+
+/// The RGBA colors of alive and dead cells in exported images
+///
+/// # Example
+/// ```rust
+/// use cgolrs::engine::Theme;
+///
+/// assert_eq!(Theme::default(), Theme::LIGHT);
+/// assert_eq!(Theme::DARK.alive, [255, 255, 255, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub alive: [u8; 4],
+    pub dead: [u8; 4],
+}
+impl Theme {
+    /// Black cells on white
+    pub const LIGHT: Theme = Theme {
+        alive: [0, 0, 0, 255],
+        dead: [255, 255, 255, 255],
+    };
+    /// White cells on black, like the console
+    pub const DARK: Theme = Theme {
+        alive: [255, 255, 255, 255],
+        dead: [0, 0, 0, 255],
+    };
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self::LIGHT
+    }
+}
+
+/// Saves the RGBA pixels as a PNG file
+fn save_rgba(path: &Path, width: u32, height: u32, pixels: Vec<u8>) -> io::Result<()> {
+    let image = image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| io::Error::other("the pixels don't match the image size"))?;
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(io::Error::other)
+}
+
+/// Saves a 1×1 transparent PNG file, for when there's nothing to draw
+fn save_empty(path: &Path) -> io::Result<()> {
+    save_rgba(path, 1, 1, vec![0; 4])
+}
+
+impl GameEngineWindow<'_> {
+    /// Saves the window as a PNG file, drawing each cell as a `scale × scale` block of pixels
+    ///
+    /// An empty window is saved as a 1×1 transparent image.
+    ///
+    /// ## Returns
+    /// An [`io::ErrorKind::InvalidInput`] error if `scale` is 0 or the image would have more than
+    /// [`MAX_PNG_PIXELS`] pixels, which is checked before anything is allocated
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::{GameOfLife, Theme}, patterns, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+    /// let path = std::env::temp_dir().join("cgolrs-to-png-doctest.png");
+    /// let window = game.window(Rect::new(Pos2::new(-1, -1), Pos2::new(4, 4)));
+    /// window.to_png(&path, 4, Theme::DARK).unwrap();
+    /// assert!(std::fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+    /// # std::fs::remove_file(&path).unwrap();
+    ///
+    /// assert!(window.to_png(&path, 0, Theme::DARK).is_err());
+    /// let huge = game.window(Rect::new(Pos2::new(0, 0), Pos2::new(100_000, 100_000)));
+    /// assert!(huge.to_png(&path, 1, Theme::DARK).is_err());
+    /// ```
+    pub fn to_png(&self, path: impl AsRef<Path>, scale: u32, colors: Theme) -> io::Result<()> {
+        let path = path.as_ref();
+        if scale == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image scale must be positive",
+            ));
+        }
+        let rect = self.rect();
+        if rect.is_empty() {
+            return save_empty(path);
+        }
+
+        let pixels = rect.area() * scale as u64 * scale as u64;
+        let too_large = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "a {}x{} image at scale {} would be over the limit of {} pixels",
+                    rect.width(),
+                    rect.height(),
+                    scale,
+                    MAX_PNG_PIXELS
+                ),
+            )
+        };
+        if pixels > MAX_PNG_PIXELS {
+            return Err(too_large());
+        }
+        let width = rect.width().checked_mul(scale).ok_or_else(too_large)?;
+        let height = rect.height().checked_mul(scale).ok_or_else(too_large)?;
+
+        // draw one row of pixels per row of cells and repeat it `scale` times
+        let bitmap = self.to_bitmap();
+        let mut rgba = Vec::with_capacity(pixels as usize * 4);
+        let mut row = Vec::with_capacity(width as usize * 4);
+        for y in 0..bitmap.height {
+            row.clear();
+            for x in 0..bitmap.width {
+                let color = if bitmap.get(x, y) {
+                    colors.alive
+                } else {
+                    colors.dead
+                };
+                for _ in 0..scale {
+                    row.extend_from_slice(&color);
+                }
+            }
+            for _ in 0..scale {
+                rgba.extend_from_slice(&row);
+            }
+        }
+        save_rgba(path, width, height, rgba)
+    }
+}
+
+impl GameOfLife {
+    /// Saves the bounding box of the alive cells as a PNG file with the default [`Theme`], see
+    /// [`GameEngineWindow::to_png`]
+    ///
+    /// An empty population is saved as a 1×1 transparent image.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns};
+    ///
+    /// let path = std::env::temp_dir().join("cgolrs-save-png-doctest.png");
+    /// let game = GameOfLife::from_alive(patterns::find("r-pentomino").unwrap().cells());
+    /// game.save_png(&path, 8).unwrap();
+    /// GameOfLife::from_alive(Vec::new()).save_png(&path, 8).unwrap();
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_png(&self, path: impl AsRef<Path>, scale: u32) -> io::Result<()> {
+        let bounds = transform::bounding_box(self.alive());
+        self.window(bounds).to_png(path, scale, Theme::default())
+    }
+}
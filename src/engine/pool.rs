@@ -0,0 +1,126 @@
+use super::NextGeneration;
+use crate::{Pos2, Rule};
+use std::{
+    ops::Range,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+/// A chunk of rows to compute the next generation of
+struct Job {
+    alive: Arc<Vec<Pos2>>,
+    rule: Rule,
+    rows: Range<i32>,
+    chunk: usize,
+}
+
+/// Worker threads that stay alive between generations, so stepping doesn't pay for spawning
+/// threads every time
+///
+/// Jobs are taken from a shared queue, so a worker that finishes a cheap chunk early picks up the
+/// next one. Dropping the pool stops and joins every worker.
+#[derive(Debug)]
+pub(super) struct WorkerPool {
+    jobs: Option<mpsc::Sender<Job>>,
+    results: mpsc::Receiver<(usize, ChunkGeneration)>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    pub fn new(threads: usize) -> Self {
+        let (jobs, job_queue) = mpsc::channel::<Job>();
+        let job_queue = Arc::new(Mutex::new(job_queue));
+        let (result_sender, results) = mpsc::channel();
+
+        let workers = (0..threads)
+            .map(|_| {
+                let job_queue = Arc::clone(&job_queue);
+                let result_sender = result_sender.clone();
+                thread::spawn(move || loop {
+                    // the lock is only held while waiting for a job, not while computing it
+                    let job = job_queue.lock().expect("job queue poisoned").recv();
+                    let Ok(job) = job else {
+                        // the pool was dropped
+                        break;
+                    };
+                    let chunk = rows_generation(&job.alive, job.rule, job.rows);
+                    // release the generation before reporting, so the caller can take it back
+                    drop(job.alive);
+                    if result_sender.send((job.chunk, chunk)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            jobs: Some(jobs),
+            results,
+            workers,
+        }
+    }
+
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Computes the next generation of each chunk of rows under `rule`
+    ///
+    /// Every clone of `alive` handed to the workers is dropped by the time this returns.
+    ///
+    /// ## Returns
+    /// The cells of each chunk with the hash of their changes, in the same order as `rows`
+    pub fn run(
+        &self,
+        alive: &Arc<Vec<Pos2>>,
+        rule: Rule,
+        rows: &[Range<i32>],
+    ) -> Vec<ChunkGeneration> {
+        let jobs = self.jobs.as_ref().expect("pool is running");
+        for (chunk, rows) in rows.iter().enumerate() {
+            let job = Job {
+                alive: Arc::clone(alive),
+                rule,
+                rows: rows.clone(),
+                chunk,
+            };
+            jobs.send(job).expect("worker threads are running");
+        }
+
+        let mut results = vec![(Vec::new(), 0); rows.len()];
+        for _ in 0..rows.len() {
+            let (chunk, cells) = self.results.recv().expect("stepper thread panicked");
+            results[chunk] = cells;
+        }
+        results
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // closing the queue makes every worker stop once it's idle
+        std::mem::drop(self.jobs.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The alive cells of a chunk of rows in the next generation, with the hash of its births and
+/// deaths (see [`NextGeneration::changes_hash`])
+pub(super) type ChunkGeneration = (Vec<Pos2>, u64);
+
+/// Computes the rows of the next generation within `rows`
+pub(super) fn rows_generation(alive: &[Pos2], rule: Rule, rows: Range<i32>) -> ChunkGeneration {
+    // a row of the next generation depends on the rows directly above and below it
+    let first = rows.start.saturating_sub(1);
+    let start = alive.partition_point(|p| p.y < first);
+    let end = alive.partition_point(|p| p.y <= rows.end);
+    let mut next = NextGeneration::new(&alive[start..end], rule).hash_rows(rows.clone());
+    let cells = (&mut next)
+        .skip_while(|p| p.y < rows.start)
+        .take_while(|p| p.y < rows.end)
+        .collect();
+    (cells, next.changes_hash())
+}
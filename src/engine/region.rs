@@ -0,0 +1,137 @@
+use super::{incremental::dirty_spans, GameOfLife, InvariantError};
+use crate::{prelude::*, Pos2, Rect};
+
+impl GameOfLife {
+    /// Computes the next generation only within `region`, while every cell outside of it stays
+    /// frozen as it is
+    ///
+    /// Only the cells whose 3×3 neighborhood intersects the region are looked at, and the
+    /// frozen cells around the edge still count as neighbors of the cells inside. Dying cells
+    /// of Generations rules only move on within the region. It counts as a generation, but since
+    /// the generations before it can't be replayed by stepping, [`GameOfLife::rewind`] can't go
+    /// back past it. The step is computed on a single thread.
+    ///
+    /// ## Returns
+    /// [`InvariantError::RegionB0`] if the rule has `B0`, since the background can't change in
+    /// only part of the universe, in which case nothing is computed
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// // two blinkers, where only the left one is in the region
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true, false, false, true, true, true]]);
+    /// game.next_generation_in(Rect::new(Pos2::new(-1, -1), Pos2::new(4, 2))).unwrap();
+    /// assert_eq!(
+    ///     game.alive(),
+    ///     [(1, -1), (1, 0), (5, 0), (6, 0), (7, 0), (1, 1)].map(Pos2::from)
+    /// );
+    /// assert_eq!(game.generation(), 1);
+    /// ```
+    pub fn next_generation_in(&mut self, region: Rect) -> Result<(), InvariantError> {
+        if self.rule.is_born(0) {
+            return Err(InvariantError::RegionB0);
+        }
+        let mut scratch = core::mem::take(&mut self.scratch);
+        scratch.clear();
+        scratch.reserve(self.alive.len() + 16);
+
+        // the cells that can be alive next are next to an alive cell within the region
+        let rows = region.min.y.saturating_sub(1)..=region.max.y;
+        let columns = region.min.x.saturating_sub(1)..=region.max.x;
+        let start = self.alive.partition_point(|p| p.y < *rows.start());
+        let end = self.alive.partition_point(|p| p.y <= *rows.end());
+        let near = self.alive[start..end]
+            .iter()
+            .filter(|p| columns.contains(&p.x))
+            .copied()
+            .collect::<Vec<_>>();
+        let spans = dirty_spans(&near)
+            .into_iter()
+            .filter(|&(y, _, _)| (region.min.y..region.max.y).contains(&y))
+            .map(|(y, first, last)| {
+                let last_column = region.max.x.saturating_sub(1);
+                (y, first.max(region.min.x), last.min(last_column))
+            })
+            .filter(|&(_, first, last)| first <= last)
+            .collect::<Vec<_>>();
+
+        let changed = self.extend_spans(&spans, &mut scratch);
+        for &pos in &changed {
+            self.toggle_hash(pos);
+        }
+        self.changed = self.incremental.then_some(changed);
+        if self.rule.states() > 2 || !self.dying.is_empty() {
+            self.decay(&mut scratch, Some(region));
+        }
+        self.finish_step(&mut scratch);
+        self.scratch = scratch;
+        self.restart_history();
+        Ok(())
+    }
+
+    /// Drops every alive and dying cell outside of `rect`, keeping the rest sorted
+    ///
+    /// This changes the simulation on purpose: it's an approximation for long runs of guns and
+    /// puffers, where the gliders and debris they leave behind would otherwise grow the pattern
+    /// forever. Whatever was dropped is gone for good, even if it would have come back to
+    /// interact with the cells inside, and cells near the edge lose their neighbors outside.
+    /// On an alive background nothing is dropped, since everything far away is alive.
+    ///
+    /// ## Returns
+    /// The number of alive cells dropped
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect};
+    ///
+    /// let mut game = GameOfLife::from_alive(patterns::find("gosper-glider-gun").unwrap().cells());
+    /// game.step_n(120);
+    /// let population = game.alive_count();
+    /// let removed = game.retain_within(Rect::new(Pos2::new(0, 0), Pos2::new(40, 20)));
+    /// assert!(removed > 0);
+    /// assert_eq!(game.alive_count(), population - removed);
+    /// ```
+    pub fn retain_within(&mut self, rect: Rect) -> usize {
+        if self.background {
+            return 0;
+        }
+        let before = self.alive.len();
+        if let Some(ref mut ages) = self.ages {
+            let mut cells = self.alive.iter();
+            ages.retain(|_| cells.next().is_some_and(|&pos| rect.contains(pos)));
+        }
+        if let Some(ref mut colors) = self.colors {
+            let mut cells = self.alive.iter();
+            colors.retain(|_| cells.next().is_some_and(|&pos| rect.contains(pos)));
+        }
+        self.alive.retain(|&pos| rect.contains(pos));
+        self.dying.retain(|&(pos, _)| rect.contains(pos));
+        // every dropped cell would have to be marked, so the next incremental step starts over
+        self.changed = None;
+        self.rehash();
+        before - self.alive.len()
+    }
+    /// Drops every alive and dying cell more than `radius` cells away from `center` along
+    /// either axis, like [`GameOfLife::retain_within`] with the square around `center`
+    ///
+    /// ## Returns
+    /// The number of alive cells dropped
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let rows = [[true, false, false, true], [false, true, false, true]];
+    /// let mut game = GameOfLife::from_grid(&rows);
+    /// assert_eq!(game.cull_beyond_radius(Pos2::new(0, 0), 2), 2);
+    /// assert_eq!(game.alive(), [Pos2::new(0, 0), Pos2::new(1, 1)]);
+    /// ```
+    pub fn cull_beyond_radius(&mut self, center: Pos2, radius: i32) -> usize {
+        let min =
+            center.saturating_add(Pos2::new(radius.saturating_neg(), radius.saturating_neg()));
+        let size = radius.saturating_add(1);
+        let max = center.saturating_add(Pos2::new(size, size));
+        self.retain_within(Rect::new(min, max))
+    }
+}
@@ -0,0 +1,114 @@
+use super::GameOfLife;
+use crate::{prelude::*, transform, Pos2, Rect};
+
+impl GameOfLife {
+    /// The offsets at which every cell of `needle` is alive, sorted by y and then x
+    ///
+    /// Moving the needle by one of the offsets gives cells that are all alive. If `isolated` is
+    /// set, the cells of the needle's bounding box that aren't in the needle have to be dead
+    /// too, while the cells around the box can be anything. The needle doesn't need to be
+    /// sorted.
+    ///
+    /// Every alive cell is tried as the position of the needle's first cell, and the rest of
+    /// the needle is checked with binary searches, so this takes O(n·m·log n) for a needle of
+    /// m cells. Like [`GameOfLife::alive`], on an alive background this searches the dead
+    /// cells instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// // a block, and a row of four cells to its right
+    /// let game = GameOfLife::from_grid(&[
+    ///     [true, true, false, true, true, true, true],
+    ///     [true, true, false, false, false, false, false],
+    /// ]);
+    /// // two cells with a gap between them
+    /// let gap = [Pos2::new(0, 0), Pos2::new(2, 0)];
+    /// assert_eq!(game.find(&gap, false), [(1, 0), (3, 0), (4, 0)].map(Pos2::from));
+    /// assert_eq!(game.find(&gap, true), [Pos2::new(1, 0)]);
+    ///
+    /// let block = [(0, 0), (1, 0), (0, 1), (1, 1)].map(Pos2::from);
+    /// assert_eq!(game.find(&block, true), [Pos2::new(0, 0)]);
+    /// ```
+    pub fn find(&self, needle: &[Pos2], isolated: bool) -> Vec<Pos2> {
+        let mut needle = needle.to_vec();
+        needle.sort_unstable();
+        needle.dedup();
+        let Some((&anchor, rest)) = needle.split_first() else {
+            return Vec::new();
+        };
+        let bounds = transform::bounding_box(&needle);
+        self.alive
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &pos)| {
+                let offset = pos.checked_sub(anchor)?;
+                // the rest of the needle is sorted after its first cell, so it's after `pos`
+                let after = &self.alive[i + 1..];
+                let found = rest.iter().all(|&cell| {
+                    cell.checked_add(offset)
+                        .is_some_and(|cell| after.binary_search(&cell).is_ok())
+                });
+                if !found {
+                    return None;
+                }
+                if isolated {
+                    let min = bounds.min.checked_add(offset)?;
+                    let max = bounds.max.checked_add(offset)?;
+                    if self.window(Rect::new(min, max)).iter().count() != needle.len() {
+                        return None;
+                    }
+                }
+                Some(offset)
+            })
+            .collect()
+    }
+    /// Like [`GameOfLife::find`], but also looks for the 8 rotations and reflections of
+    /// `needle`
+    ///
+    /// ## Returns
+    /// The cells of every match, sorted. Symmetric needles are only looked for once per
+    /// distinct orientation, so every match is unique.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, transform, Pos2};
+    ///
+    /// // a glider, and another one mirrored 10 cells to the right
+    /// let glider = patterns::find("glider").unwrap().cells();
+    /// let mut game = GameOfLife::from_unsorted(glider.clone());
+    /// game.insert_pattern(&transform::flip_x(&glider), Pos2::new(10, 0));
+    ///
+    /// let matches = game.find_all_orientations(&glider, true);
+    /// assert_eq!(matches.len(), 2);
+    /// assert!(matches.iter().all(|cells| cells.len() == 5));
+    /// assert_eq!(game.find(&glider, true).len(), 1);
+    /// ```
+    pub fn find_all_orientations(&self, needle: &[Pos2], isolated: bool) -> Vec<Vec<Pos2>> {
+        let mut rotated = needle.to_vec();
+        rotated.sort_unstable();
+        rotated.dedup();
+        let mut orientations = Vec::with_capacity(8);
+        for _ in 0..4 {
+            let flipped = transform::flip_x(&rotated);
+            for orientation in [transform::recenter(&rotated), transform::recenter(&flipped)] {
+                if !orientations.contains(&orientation) {
+                    orientations.push(orientation);
+                }
+            }
+            rotated = transform::rotate_cw(&rotated);
+        }
+
+        let mut matches = orientations
+            .iter()
+            .flat_map(|orientation| {
+                self.find(orientation, isolated)
+                    .into_iter()
+                    .map(|offset| transform::translate(orientation, offset))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_unstable();
+        matches
+    }
+}
@@ -0,0 +1,103 @@
+use super::GameOfLife;
+use crate::{prelude::*, Pos2, Rule};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The serialized form of a [`GameOfLife`], borrowing from the engine
+///
+/// The ages and colors are parallel to `alive`, and left out when the game doesn't track them.
+#[derive(Serialize)]
+struct GameOfLifeRef<'a> {
+    alive: &'a [Pos2],
+    generation: u64,
+    rule: Rule,
+    background: bool,
+    dying: &'a [(Pos2, u8)],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ages: Option<&'a [u32]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    colors: Option<&'a [u8]>,
+}
+/// The deserialized form of a [`GameOfLife`], before the invariants are checked
+#[derive(Deserialize)]
+struct GameOfLifeData {
+    alive: Vec<Pos2>,
+    #[serde(default)]
+    generation: u64,
+    #[serde(default)]
+    rule: Rule,
+    #[serde(default)]
+    background: bool,
+    #[serde(default)]
+    dying: Vec<(Pos2, u8)>,
+    #[serde(default)]
+    ages: Option<Vec<u32>>,
+    #[serde(default)]
+    colors: Option<Vec<u8>>,
+}
+
+impl Serialize for GameOfLife {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameOfLifeRef {
+            alive: &self.alive,
+            generation: self.generation,
+            rule: self.rule,
+            background: self.background,
+            dying: &self.dying,
+            ages: self.ages.as_deref(),
+            colors: self.colors.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+impl<'de> Deserialize<'de> for GameOfLife {
+    /// Deserializes the alive cells, re-establishing the sorted and unique invariant since the
+    /// input can't be trusted
+    ///
+    /// The ages and colors move with their cells when they're sorted, and have to be as many as
+    /// the alive cells.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let GameOfLifeData {
+            alive,
+            generation,
+            rule,
+            background,
+            dying,
+            ages,
+            colors,
+        } = GameOfLifeData::deserialize(deserializer)?;
+        for (name, len) in [
+            ("ages", ages.as_ref().map(Vec::len)),
+            ("colors", colors.as_ref().map(Vec::len)),
+        ] {
+            if len.is_some_and(|len| len != alive.len()) {
+                return Err(D::Error::custom(format_args!(
+                    "{} has {} entries for {} alive cells",
+                    name,
+                    len.unwrap_or_default(),
+                    alive.len()
+                )));
+            }
+        }
+
+        // sort the ages and colors along with their cells, keeping the first of any duplicates
+        let mut cells = alive
+            .into_iter()
+            .enumerate()
+            .map(|(i, pos)| (pos, i))
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|&(pos, _)| pos);
+        cells.dedup_by_key(|&mut (pos, _)| pos);
+        let mut game = GameOfLife::from_alive(cells.iter().map(|&(pos, _)| pos).collect());
+        // cells out of range were dropped, so their ages and colors have to be dropped with them
+        let mut kept = game.alive.iter().peekable();
+        cells.retain(|&(pos, _)| kept.next_if_eq(&&pos).is_some());
+        game.ages = ages.map(|ages| cells.iter().map(|&(_, i)| ages[i]).collect());
+        game.colors = colors.map(|colors| cells.iter().map(|&(_, i)| colors[i].min(1)).collect());
+
+        game.set_generation(generation);
+        game.set_rule(rule).map_err(D::Error::custom)?;
+        game.set_background(background);
+        game.set_dying(dying);
+        Ok(game)
+    }
+}
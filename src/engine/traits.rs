@@ -0,0 +1,99 @@
+use super::GameOfLife;
+use crate::{prelude::*, transform};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// Copies the state and the settings, while the copy starts without hooks, which can't be
+/// cloned, and without workers, which it starts its own of on its first parallel step
+impl Clone for GameOfLife {
+    fn clone(&self) -> Self {
+        Self {
+            alive: self.alive.clone(),
+            ages: self.ages.clone(),
+            colors: self.colors.clone(),
+            generation: self.generation,
+            rule: self.rule,
+            background: self.background,
+            dying: self.dying.clone(),
+            threads: self.threads,
+            #[cfg(feature = "std")]
+            pool: None,
+            min_chunk_cells: self.min_chunk_cells,
+            incremental: self.incremental,
+            changed: self.changed.clone(),
+            history: self.history.clone(),
+            scratch: Vec::new(),
+            changes: self.changes,
+            track_changes: self.track_changes,
+            hooks: Default::default(),
+            hash: self.hash,
+        }
+    }
+}
+
+/// Hashes the [state hash](GameOfLife::state_hash), so equal games hash equally
+impl Hash for GameOfLife {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+/// Writes a summary of the game rather than every cell, like
+/// `GameOfLife { alive: 5, bbox: (0, 0)..(3, 3), gen: 0 }`, see [`GameOfLife::dump`]
+///
+/// `alive` is the number of cells in [`GameOfLife::alive`] and `bbox` their bounding box, with
+/// the end excluded, or `none` without cells.
+impl fmt::Debug for GameOfLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = transform::bounding_box(&self.alive);
+        let mut debug = f.debug_struct("GameOfLife");
+        debug.field("alive", &self.alive.len());
+        match bounds.is_empty() {
+            true => debug.field("bbox", &format_args!("none")),
+            false => debug.field("bbox", &format_args!("{}..{}", bounds.min, bounds.max)),
+        };
+        debug.field("gen", &self.generation).finish()
+    }
+}
+
+impl GameOfLife {
+    /// Every field of the game, including all of its cells, which can be huge
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::engine::GameOfLife;
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, true]]);
+    /// assert_eq!(format!("{:?}", game), "GameOfLife { alive: 2, bbox: (0, 0)..(2, 1), gen: 0 }");
+    /// assert!(game.dump().contains("alive: [Pos2 { x: 0, y: 0 }, Pos2 { x: 1, y: 0 }]"));
+    /// ```
+    pub fn dump(&self) -> String {
+        struct Dump<'a>(&'a GameOfLife);
+        impl fmt::Debug for Dump<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let game = self.0;
+                f.debug_struct("GameOfLife")
+                    .field("alive", &game.alive)
+                    .field("ages", &game.ages)
+                    .field("colors", &game.colors)
+                    .field("generation", &game.generation)
+                    .field("rule", &game.rule)
+                    .field("background", &game.background)
+                    .field("dying", &game.dying)
+                    .field("threads", &game.threads)
+                    .field("min_chunk_cells", &game.min_chunk_cells)
+                    .field("incremental", &game.incremental)
+                    .field("changed", &game.changed)
+                    .field("history", &game.history)
+                    .field("changes", &game.changes)
+                    .field("track_changes", &game.track_changes)
+                    .field("hooks", &game.hooks)
+                    .field("hash", &game.hash)
+                    .finish()
+            }
+        }
+        format!("{:?}", Dump(self))
+    }
+}
@@ -1,49 +1,488 @@
-use super::GameOfLife;
-use crate::Pos2;
+use super::{Bitmap, DownsampledWindow, GameOfLife, InvariantError};
+use crate::{prelude::*, transform, Pos2, Rect};
+use core::ops::Range;
 
 pub struct GameEngineWindow<'a> {
-    tl: Pos2,
-    br: Pos2,
+    rect: Rect,
     engine: &'a GameOfLife,
 }
 impl<'a> GameEngineWindow<'a> {
-    pub fn new(engine: &'a GameOfLife, top_left: Pos2, bottom_right: Pos2) -> Self {
-        Self {
-            tl: top_left,
-            br: bottom_right,
-            engine,
-        }
+    pub fn new(engine: &'a GameOfLife, rect: Rect) -> Self {
+        Self { rect, engine }
+    }
+
+    /// The area of the window
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        self.rect
     }
 
+    /// The index ranges of the alive cells within the window, one per non-empty row
+    ///
+    /// Since the cells are sorted y-major, the rows of the window are one contiguous slice, and
+    /// the rows and the columns within them are found with binary searches, so this never looks
+    /// at the cells outside of the window.
+    fn row_ranges(&self) -> impl Iterator<Item = Range<usize>> + 'a {
+        let alive = &self.engine.alive[..];
+        let Rect { min: tl, max: br } = self.rect;
+        let start = alive.partition_point(|p| p.y < tl.y);
+        let end = alive.partition_point(|p| p.y < br.y);
+        transform::rows(&alive[start..end])
+            .scan(start, move |i, (_, row)| {
+                let first = *i + row.partition_point(|p| p.x < tl.x);
+                let last = *i + row.partition_point(|p| p.x < br.x);
+                *i += row.len();
+                Some(first..last)
+            })
+            .filter(|range| !range.is_empty())
+    }
+
+    /// The alive cells within the window, sorted by y and then x
+    ///
+    /// Like [`GameOfLife::alive`], these are the dead cells if the background is alive.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::random(Rect::new(Pos2::new(-50, -50), Pos2::new(50, 50)), 0.4, 7).unwrap();
+    /// let rect = Rect::new(Pos2::new(-10, -60), Pos2::new(20, 5));
+    /// let filtered = game.alive().iter().filter(|&&p| rect.contains(p));
+    /// assert!(game.window(rect).iter().eq(filtered));
+    /// ```
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = &Pos2> {
-        let rx = self.tl.x..self.br.x;
-        let ry = self.tl.y..self.br.y;
-        self.engine
-            .alive
+    pub fn iter(&self) -> impl Iterator<Item = &'a Pos2> {
+        let alive = &self.engine.alive[..];
+        self.row_ranges().flat_map(move |range| &alive[range])
+    }
+
+    /// Each row of the window with its y value and the alive cells on it within the window's
+    /// x-range, from the top one down
+    ///
+    /// Like [`GameOfLife::rows`], rows without cells in the window are skipped, and like
+    /// [`GameEngineWindow::iter`] these are the dead cells if the background is alive.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, false, true], [true, false, false]]);
+    /// let window = game.window(Rect::new(Pos2::new(1, 0), Pos2::new(3, 2)));
+    /// assert!(window.rows().eq([(0, &[Pos2::new(2, 0)][..])]));
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = (i32, &'a [Pos2])> {
+        let alive = &self.engine.alive[..];
+        self.row_ranges()
+            .map(move |range| (alive[range.start].y, &alive[range]))
+    }
+    /// The number of alive cells on each row of [`GameEngineWindow::rows`], like a histogram of
+    /// the window along y
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true; 4], [false; 4], [true, true, false, true]]);
+    /// let window = game.window(Rect::new(Pos2::new(1, 0), Pos2::new(4, 3)));
+    /// assert_eq!(window.row_counts(), [(0, 3), (2, 2)]);
+    /// ```
+    pub fn row_counts(&self) -> Vec<(i32, usize)> {
+        self.row_ranges()
+            .map(|range| (self.engine.alive[range.start].y, range.len()))
+            .collect()
+    }
+
+    /// Every position in the window in row-major order, with whether its cell is alive
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_grid(&[[true, false], [false, true]]);
+    /// let window = game.window(Rect::new(Pos2::new(0, 0), Pos2::new(3, 2)));
+    /// let cells = window
+    ///     .iter_cells()
+    ///     .map(|(_, alive)| if alive { '#' } else { '.' })
+    ///     .collect::<String>();
+    /// assert_eq!(cells, "#...#.");
+    /// assert_eq!(window.alive_count(), 2);
+    /// ```
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Pos2, bool)> + 'a {
+        let mut differing = self.iter().peekable();
+        let background = self.engine.background();
+        self.rect.iter().map(move |pos| {
+            (
+                pos,
+                differing.next_if(|&&p| p == pos).is_some() != background,
+            )
+        })
+    }
+
+    /// The number of alive cells within the window
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// // in AntiLife the background comes alive around the 4 dead cells a block leaves behind
+    /// let mut game = GameOfLife::from_grid(&[[true; 3]; 3]);
+    /// game.set_rule("B0123478/S01234678".parse().unwrap()).unwrap();
+    /// let window = Rect::new(Pos2::new(-1, -1), Pos2::new(4, 4));
+    /// assert_eq!(game.window(window).alive_count(), 9);
+    /// game.next_generation();
+    /// assert_eq!(game.window(window).alive_count(), 25 - 4);
+    /// ```
+    pub fn alive_count(&self) -> usize {
+        let differing = self.row_ranges().map(|range| range.len()).sum();
+        match self.engine.background() {
+            true => self.rect.width() as usize * self.rect.height() as usize - differing,
+            false => differing,
+        }
+    }
+
+    /// Like [`GameEngineWindow::iter`], but also yields the age of each cell if the engine is
+    /// tracking ages, which on an alive background are the ages of the dead cells
+    pub fn iter_with_ages(&self) -> impl Iterator<Item = (&'a Pos2, Option<u32>)> {
+        let alive = &self.engine.alive[..];
+        let ages = self.engine.ages();
+        self.row_ranges()
+            .flatten()
+            .map(move |i| (&alive[i], ages.map(|ages| ages[i])))
+    }
+
+    /// Like [`GameEngineWindow::iter`], but also yields the color of each cell if the game has
+    /// the colors of Immigration, see [`GameOfLife::colors`]
+    pub fn iter_with_colors(&self) -> impl Iterator<Item = (&'a Pos2, Option<u8>)> {
+        let alive = &self.engine.alive[..];
+        let colors = self.engine.colors();
+        self.row_ranges()
+            .flatten()
+            .map(move |i| (&alive[i], colors.map(|colors| colors[i])))
+    }
+
+    /// The dying cells of a Generations rule within the window with their state, sorted by y and
+    /// then x
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true]]);
+    /// game.set_rule("B2/S/C3".parse().unwrap()).unwrap();
+    /// game.next_generation();
+    /// let window = game.window(Rect::new(Pos2::new(1, -1), Pos2::new(3, 2)));
+    /// assert!(window.iter_dying().eq([(Pos2::new(1, 0), 2)]));
+    /// ```
+    pub fn iter_dying(&self) -> impl Iterator<Item = (Pos2, u8)> + 'a {
+        let dying = self.engine.dying();
+        let Rect { min: tl, max: br } = self.rect;
+        let start = dying.partition_point(|(p, _)| p.y < tl.y);
+        let end = dying.partition_point(|(p, _)| p.y < br.y);
+        let rect = self.rect;
+        dying[start..end]
             .iter()
-            .filter(move |pos| rx.contains(&pos.x) && ry.contains(&pos.y))
+            .copied()
+            .filter(move |&(pos, _)| rect.contains(pos))
+    }
+
+    /// Text output of the window with configurable characters and padding
+    ///
+    /// Formatting the window directly is the same as the default [`WindowDisplay`].
+    pub fn display(&self) -> WindowDisplay<'_, 'a> {
+        WindowDisplay {
+            window: self,
+            alive: '█',
+            dead: ' ',
+            pad: false,
+        }
+    }
+
+    /// The cells of the window as rows, where `true` at `grid[y][x]` is an alive cell at
+    /// `top_left + (x, y)`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    /// game.next_generation();
+    /// let grid = game.window(Rect::new(Pos2::new(0, -1), Pos2::new(3, 2))).to_grid();
+    /// assert_eq!(
+    ///     grid,
+    ///     [
+    ///         [false, true, false],
+    ///         [false, true, false],
+    ///         [false, true, false],
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_grid(&self) -> Vec<Vec<bool>> {
+        let (width, height) = (self.rect.width() as usize, self.rect.height() as usize);
+        let background = self.engine.background();
+        let mut grid = vec![vec![background; width]; height];
+        for &pos in self.iter() {
+            let rel = pos - self.rect.min;
+            grid[rel.y as usize][rel.x as usize] = !background;
+        }
+        grid
+    }
+
+    /// The cells of the window packed into a [`Bitmap`], where (0, 0) is the top-left corner
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_grid(&[
+    ///     [true, false, true],
+    ///     [false, true, false],
+    /// ]);
+    ///
+    /// // only partially overlapping the cells
+    /// let bitmap = game.window(Rect::new(Pos2::new(1, -1), Pos2::new(101, 1))).to_bitmap();
+    /// assert_eq!((bitmap.width, bitmap.height, bitmap.words_per_row()), (100, 2, 2));
+    /// assert_eq!(bitmap.row(1), [0b10, 0]);
+    /// assert!(bitmap.get(1, 1));
+    /// assert!(!bitmap.get(0, 1));
+    /// assert!(!bitmap.get(0, 0));
+    ///
+    /// // nothing alive in the window
+    /// let bitmap = game.window(Rect::new(Pos2::new(10, 10), Pos2::new(20, 20))).to_bitmap();
+    /// assert!(bitmap.rows.iter().all(|&word| word == 0));
+    ///
+    /// // an empty window
+    /// let bitmap = game.window(Rect::new(Pos2::new(0, 0), Pos2::new(0, 5))).to_bitmap();
+    /// assert!(bitmap.rows.is_empty());
+    /// ```
+    pub fn to_bitmap(&self) -> Bitmap {
+        let (width, height) = (self.rect.width(), self.rect.height());
+        let mut bitmap = Bitmap::new(width, height);
+        if width == 0 {
+            return bitmap;
+        }
+
+        let background = self.engine.background();
+        if background {
+            for y in 0..height {
+                for x in 0..width {
+                    bitmap.set(x, y, true);
+                }
+            }
+        }
+        let alive = &self.engine.alive;
+        let mut i = 0;
+        for y in 0..height {
+            let row = self.rect.min.y + y as i32;
+            // jump to the first cell of the row within the window
+            i += alive[i..].partition_point(|&p| p < Pos2::new(self.rect.min.x, row));
+            while let Some(&pos) = alive.get(i).filter(|p| p.y == row && p.x < self.rect.max.x) {
+                bitmap.set((pos.x - self.rect.min.x) as u32, y, !background);
+                i += 1;
+            }
+        }
+        bitmap
+    }
+
+    /// The number of alive cells in each `factor × factor` block of the window, for viewing a
+    /// large area at a smaller scale
+    ///
+    /// Blocks without any alive cells are left out. This is a single pass over the cells of the
+    /// window, one row of blocks at a time.
+    ///
+    /// ## Returns
+    /// [`InvariantError::ZeroFactor`] if `factor` is 0
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect};
+    ///
+    /// let game = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+    /// let window = game.window(Rect::new(Pos2::new(-1, -1), Pos2::new(4, 4)));
+    ///
+    /// // a factor of 1 is the same as the plain window, in block coordinates
+    /// let blocks = window.downsample(1).unwrap();
+    /// let cells = blocks.iter().map(|(block, _)| block + window.rect().min);
+    /// assert!(cells.eq(window.iter().copied()));
+    /// assert!(blocks.iter().all(|(_, count)| count == 1));
+    ///
+    /// // 5x5 doesn't divide evenly, so the right and bottom blocks are only 2 cells wide
+    /// let blocks = window.downsample(3).unwrap();
+    /// assert_eq!((blocks.width(), blocks.height()), (2, 2));
+    /// let counts = blocks.iter().map(|(block, n)| ((block.x, block.y), n));
+    /// assert_eq!(
+    ///     counts.collect::<Vec<_>>(),
+    ///     [((0, 0), 1), ((1, 0), 1), ((0, 1), 2), ((1, 1), 1)]
+    /// );
+    /// assert_eq!(window.downsample(2).unwrap().count(Pos2::new(2, 2)), 0);
+    /// let whole = window.downsample(8).unwrap();
+    /// assert_eq!(whole.iter().collect::<Vec<_>>(), [(Pos2::zero(), 5)]);
+    /// ```
+    pub fn downsample(&self, factor: u32) -> Result<DownsampledWindow, InvariantError> {
+        if factor == 0 {
+            return Err(InvariantError::ZeroFactor);
+        }
+        let min = self.rect.min;
+        let mut blocks = Vec::new();
+        // the block column of every alive cell in the current row of blocks
+        let mut row = Vec::new();
+        let mut block_y = None;
+        let mut flush = |row: &mut Vec<u32>, y: u32| {
+            row.sort_unstable();
+            for chunk in row.chunk_by(|a, b| a == b) {
+                blocks.push((Pos2::new(chunk[0] as i32, y as i32), chunk.len() as u32));
+            }
+            row.clear();
+        };
+        for &pos in self.iter() {
+            // the window is at most u32::MAX wide, so the offsets can't wrap around
+            let (x, y) = (
+                pos.x.wrapping_sub(min.x) as u32,
+                pos.y.wrapping_sub(min.y) as u32,
+            );
+            if block_y != Some(y / factor) {
+                if let Some(block_y) = block_y {
+                    flush(&mut row, block_y);
+                }
+                block_y = Some(y / factor);
+            }
+            row.push(x / factor);
+        }
+        if let Some(block_y) = block_y {
+            flush(&mut row, block_y);
+        }
+
+        let (width, height) = (
+            self.rect.width().div_ceil(factor),
+            self.rect.height().div_ceil(factor),
+        );
+        if self.engine.background() {
+            blocks = self.invert_blocks(factor, width, height, &blocks);
+        }
+        Ok(DownsampledWindow::new(factor, width, height, blocks))
+    }
+    /// The alive cells of every block, from the sorted counts of the cells differing from an
+    /// alive background
+    fn invert_blocks(
+        &self,
+        factor: u32,
+        width: u32,
+        height: u32,
+        differing: &[(Pos2, u32)],
+    ) -> Vec<(Pos2, u32)> {
+        let mut differing = differing.iter().peekable();
+        let mut blocks = Vec::new();
+        for y in 0..height {
+            // the blocks along the right and bottom edges can be cut off by the window
+            let rows = factor.min(self.rect.height() - y * factor);
+            for x in 0..width {
+                let block = Pos2::new(x as i32, y as i32);
+                let area = rows * factor.min(self.rect.width() - x * factor);
+                let dead = differing
+                    .next_if(|(pos, _)| *pos == block)
+                    .map_or(0, |&(_, n)| n);
+                if area > dead {
+                    blocks.push((block, area - dead));
+                }
+            }
+        }
+        blocks
+    }
+}
+
+impl<'a> core::fmt::Display for GameEngineWindow<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.display().fmt(f)
     }
 }
 
-impl<'a> std::fmt::Display for GameEngineWindow<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut last = self.tl - Pos2 { x: 1, y: 0 };
-        for alive in self.iter() {
+/// Configurable text output of a [`GameEngineWindow`], created by [`GameEngineWindow::display`]
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect};
+///
+/// let game = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+/// let window = game.window(Rect::new(Pos2::new(0, 0), Pos2::new(5, 4)));
+///
+/// // compact by default, stopping at the last alive cell
+/// assert_eq!(window.to_string(), " █\n  █\n███");
+/// // the full rectangle, so every frame has the same size
+/// let padded = window.display().alive_char('#').dead_char('.').pad_to_bounds(true);
+/// assert_eq!(padded.to_string(), ".#...\n..#..\n###..\n.....");
+/// ```
+#[derive(Clone, Copy)]
+pub struct WindowDisplay<'w, 'a> {
+    window: &'w GameEngineWindow<'a>,
+    alive: char,
+    dead: char,
+    pad: bool,
+}
+
+impl<'w, 'a> WindowDisplay<'w, 'a> {
+    /// The character for alive cells, `█` by default
+    pub fn alive_char(mut self, c: char) -> Self {
+        self.alive = c;
+        self
+    }
+    /// The character for dead cells, a space by default
+    pub fn dead_char(mut self, c: char) -> Self {
+        self.dead = c;
+        self
+    }
+    /// Whether to write every row and column of the window instead of stopping at the last alive
+    /// cell
+    pub fn pad_to_bounds(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    fn fmt_padded(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Rect { min: tl, max: br } = self.window.rect;
+        for (pos, alive) in self.window.iter_cells() {
+            if pos.x == tl.x && pos.y != tl.y {
+                writeln!(f)?;
+            }
+            write!(f, "{}", if alive { self.alive } else { self.dead })?;
+        }
+        // rows without any columns still need their line breaks
+        if tl.x >= br.x {
+            for _ in (tl.y..br.y).skip(1) {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+    fn fmt_compact(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let tl = self.window.rect.min;
+        let mut last = tl - Pos2 { x: 1, y: 0 };
+        for alive in self.window.iter() {
             // determine the number of lines to print
             let lines = alive.y - last.y;
-            // determine the number of padding spaces to print
+            // determine the number of padding cells to print
             let padding = match lines {
                 0 => alive.x - last.x - 1,
-                _ => alive.x - self.tl.x,
+                _ => alive.x - tl.x,
             };
-            write!(
-                f,
-                "{0:\n<1$}{0: <2$}█",
-                "", lines as usize, padding as usize
-            )?;
+            for _ in 0..lines {
+                writeln!(f)?;
+            }
+            for _ in 0..padding {
+                write!(f, "{}", self.dead)?;
+            }
+            write!(f, "{}", self.alive)?;
             last = *alive;
         }
         Ok(())
     }
 }
+
+impl core::fmt::Display for WindowDisplay<'_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // on an alive background every cell up to the end of the window is alive anyway
+        if self.pad || self.window.engine.background() {
+            self.fmt_padded(f)
+        } else {
+            self.fmt_compact(f)
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! The error type of the fallible library functions, for callers that don't care which part of
+//! the library failed
+//!
+//! Every module keeps its own precise error type, like [`DecodeError`] or [`InvariantError`],
+//! and each of them converts into [`Error`] so `?` works across modules.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{engine::{GameOfLife, InvariantError}, Error, Rule};
+//!
+//! fn start(rule: &str) -> cgolrs::Result<GameOfLife> {
+//!     let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+//!     game.set_rule(rule.parse()?)?;
+//!     Ok(game)
+//! }
+//! assert!(start("B36/S23").is_ok());
+//! assert!(matches!(start("B3/S9"), Err(Error::Rule(_))));
+//!
+//! // Generations rules with B0 don't parse, but they can still be built
+//! let mut game = start("B3/S23").unwrap();
+//! let seeds = Rule::generations(&[0, 2], &[], 3);
+//! assert_eq!(game.set_rule(seeds), Err(InvariantError::GenerationsB0));
+//! ```
+
+#[cfg(feature = "image")]
+use crate::enc::image::ImageError;
+use crate::{
+    enc::{DecodeError, EncodeError},
+    engine::InvariantError,
+    rule::ParseRuleError,
+};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// A [`Result`](core::result::Result) with [`Error`] as the default error
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Any error returned by the library
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A pattern couldn't be decoded
+    Decode(DecodeError),
+    /// A pattern couldn't be encoded
+    Encode(EncodeError),
+    /// The engine was asked for something it can't do
+    Invariant(InvariantError),
+    /// Reading or writing a file failed
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// A rule couldn't be parsed
+    Rule(ParseRuleError),
+    /// An image couldn't be imported as a pattern
+    #[cfg(feature = "image")]
+    Image(ImageError),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(e) => e.fmt(f),
+            Self::Encode(e) => e.fmt(f),
+            Self::Invariant(e) => e.fmt(f),
+            #[cfg(feature = "std")]
+            Self::Io(e) => e.fmt(f),
+            Self::Rule(e) => e.fmt(f),
+            #[cfg(feature = "image")]
+            Self::Image(e) => e.fmt(f),
+        }
+    }
+}
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Decode(e) => Some(e),
+            Self::Encode(e) => Some(e),
+            Self::Invariant(e) => Some(e),
+            #[cfg(feature = "std")]
+            Self::Io(e) => Some(e),
+            Self::Rule(e) => Some(e),
+            #[cfg(feature = "image")]
+            Self::Image(e) => Some(e),
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+impl From<EncodeError> for Error {
+    fn from(e: EncodeError) -> Self {
+        Self::Encode(e)
+    }
+}
+impl From<InvariantError> for Error {
+    fn from(e: InvariantError) -> Self {
+        Self::Invariant(e)
+    }
+}
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl From<ParseRuleError> for Error {
+    fn from(e: ParseRuleError) -> Self {
+        Self::Rule(e)
+    }
+}
+#[cfg(feature = "image")]
+impl From<ImageError> for Error {
+    fn from(e: ImageError) -> Self {
+        Self::Image(e)
+    }
+}
@@ -0,0 +1,206 @@
+//! A C interface to the engine and the RLE codec, for embedding the simulator in other languages
+//!
+//! This needs the `ffi` feature, and the library built as a shared one with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`. The header
+//! `include/cgolrs.h` is generated from this module with
+//! `cbindgen --config cbindgen.toml --output include/cgolrs.h`.
+//!
+//! A game is an opaque `cgol_t*` handle freed with [`cgol_free`]. The buffers and strings
+//! returned to the caller are owned by it and freed with [`cgol_cells_free`] and
+//! [`cgol_string_free`]. No function unwinds into the caller: a panic is caught and reported
+//! like any other error.
+
+use crate::{
+    enc::{Format, PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+};
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+/// A game of life, with its rule and its generation, called `cgol_t` in C
+pub struct Cgol {
+    game: GameOfLife,
+}
+
+/// The result of the functions that don't return a value
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgolStatus {
+    Ok = 0,
+    /// A pointer argument was null
+    NullPointer = 1,
+    /// The engine panicked, in which case the game may be in any valid state
+    Panic = 2,
+}
+
+/// Runs `f`, returning `error` instead if it panics
+fn guard<T>(error: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(error)
+}
+
+/// Decodes an RLE pattern, along with its rule, generation and the dying cells of Generations
+/// rules if it records them
+///
+/// ## Returns
+/// A new game freed with [`cgol_free`], or null if `rle` is null, not UTF-8, or has a rule the
+/// engine doesn't support
+///
+/// # Safety
+/// `rle` must be null or a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn cgol_new_from_rle(rle: *const c_char) -> *mut Cgol {
+    if rle.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: the caller passes a nul-terminated string
+    let Ok(rle) = unsafe { CStr::from_ptr(rle) }.to_str() else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || {
+        let Ok(pattern) = Format::Rle.decode(rle) else {
+            return ptr::null_mut();
+        };
+        let mut game = GameOfLife::from_alive(pattern.cells);
+        if game.set_rule(pattern.rule.unwrap_or_default()).is_err() {
+            return ptr::null_mut();
+        }
+        game.set_dying(pattern.dying);
+        game.set_generation(pattern.generation);
+        Box::into_raw(Box::new(Cgol { game }))
+    })
+}
+
+/// Frees a game, where null is ignored
+///
+/// # Safety
+/// `handle` must be null or a game that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn cgol_free(handle: *mut Cgol) {
+    if !handle.is_null() {
+        // SAFETY: the handle came from `Box::into_raw` and is only freed once
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Computes the next `n` generations
+///
+/// # Safety
+/// `handle` must be null or a valid game
+#[no_mangle]
+pub unsafe extern "C" fn cgol_step(handle: *mut Cgol, n: u64) -> CgolStatus {
+    // SAFETY: the caller passes a valid game
+    let Some(cgol) = (unsafe { handle.as_mut() }) else {
+        return CgolStatus::NullPointer;
+    };
+    guard(CgolStatus::Panic, || {
+        cgol.game.step_n(n);
+        CgolStatus::Ok
+    })
+}
+
+/// The number of alive cells, or -1 if `handle` is null
+///
+/// Like [`cgol_cells`], this counts the dead cells if the background is alive.
+///
+/// # Safety
+/// `handle` must be null or a valid game
+#[no_mangle]
+pub unsafe extern "C" fn cgol_population(handle: *const Cgol) -> i64 {
+    // SAFETY: the caller passes a valid game
+    match unsafe { handle.as_ref() } {
+        Some(cgol) => cgol.game.alive_count() as i64,
+        None => -1,
+    }
+}
+
+/// The alive cells, sorted by y and then x, as their x and y coordinates one after the other
+///
+/// `*out` is set to a buffer of `*len` coordinates, twice the number of cells, which is freed
+/// with [`cgol_cells_free`]. Without cells it's null with a length of 0. Like
+/// [`GameOfLife::alive`], these are the dead cells if the background is alive.
+///
+/// # Safety
+/// `handle` must be null or a valid game, and `out` and `len` null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn cgol_cells(
+    handle: *const Cgol,
+    out: *mut *mut i32,
+    len: *mut usize,
+) -> CgolStatus {
+    // SAFETY: the caller passes a valid game
+    let Some(cgol) = (unsafe { handle.as_ref() }) else {
+        return CgolStatus::NullPointer;
+    };
+    if out.is_null() || len.is_null() {
+        return CgolStatus::NullPointer;
+    }
+    let coords = guard(None, || {
+        let coords = cgol.game.alive().iter().flat_map(|pos| [pos.x, pos.y]);
+        Some(coords.collect::<Box<[i32]>>())
+    });
+    let Some(coords) = coords else {
+        return CgolStatus::Panic;
+    };
+    // SAFETY: the caller passes pointers valid for writes
+    unsafe {
+        *len = coords.len();
+        *out = match coords.is_empty() {
+            true => ptr::null_mut(),
+            false => Box::into_raw(coords).cast(),
+        };
+    }
+    CgolStatus::Ok
+}
+
+/// Frees the coordinates returned by [`cgol_cells`], where null is ignored
+///
+/// # Safety
+/// `cells` must be null or a buffer from [`cgol_cells`] that hasn't been freed yet, with the
+/// length it was returned with
+#[no_mangle]
+pub unsafe extern "C" fn cgol_cells_free(cells: *mut i32, len: usize) {
+    if !cells.is_null() {
+        // SAFETY: the buffer came from a boxed slice of `len` coordinates
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(cells, len)) });
+    }
+}
+
+/// Encodes the game as RLE, with its rule, generation and dying cells
+///
+/// ## Returns
+/// A nul-terminated string freed with [`cgol_string_free`], or null if `handle` is null
+///
+/// # Safety
+/// `handle` must be null or a valid game
+#[no_mangle]
+pub unsafe extern "C" fn cgol_to_rle(handle: *const Cgol) -> *mut c_char {
+    // SAFETY: the caller passes a valid game
+    let Some(cgol) = (unsafe { handle.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || {
+        let game = &cgol.game;
+        let rle = RunLengthEncoded::default()
+            .with_rule(&game.rule())
+            .set_generation(game.generation())
+            .set_dying(game.dying().to_vec())
+            .encode(&game.pattern_cells());
+        // the encoding never contains nul bytes
+        CString::new(rle).map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Frees a string returned by [`cgol_to_rle`], where null is ignored
+///
+/// # Safety
+/// `rle` must be null or a string from [`cgol_to_rle`] that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn cgol_string_free(rle: *mut c_char) {
+    if !rle.is_null() {
+        // SAFETY: the string came from `CString::into_raw`
+        drop(unsafe { CString::from_raw(rle) });
+    }
+}
@@ -0,0 +1,199 @@
+//! The starting grids of `--fill`, generated as sorted lists of alive cells
+//!
+//! Every fill but [`Fill::Random`] is deterministic, so runs from it can be reproduced.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{engine::GameOfLife, fill::Fill, Pos2};
+//!
+//! let fill = "cross".parse::<Fill>().unwrap();
+//! let game = GameOfLife::from_alive(fill.cells(3, 3));
+//! assert_eq!(game.alive(), [(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)].map(Pos2::from));
+//! ```
+
+use crate::{prelude::*, Pos2};
+use core::{fmt, str::FromStr};
+use rand::Rng;
+
+/// A way to fill the grid with alive cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    /// Every cell is alive with a chance of one half
+    Random,
+    /// Every other cell, like the squares of one color on a chessboard
+    Alternating,
+    /// Every cell
+    All,
+    /// No cells
+    Empty,
+    /// The cells on the edge of the grid
+    Border,
+    /// A chessboard of 2x2 blocks, starting with a block in the top-left corner
+    Checkerboard,
+    /// The disk inscribed in the grid
+    Circle,
+    /// The central row and column, which are two wide if the size is even
+    Cross,
+    /// The diagonal from the top-left corner
+    Diag,
+}
+impl Fill {
+    /// Every fill, in the order they're listed
+    pub const FILLS: &'static [Fill] = &[
+        Self::Random,
+        Self::Alternating,
+        Self::All,
+        Self::Empty,
+        Self::Border,
+        Self::Checkerboard,
+        Self::Circle,
+        Self::Cross,
+        Self::Diag,
+    ];
+
+    /// What the fill looks like, in a few words
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Random => "every cell alive with a chance of one half (default)",
+            Self::Alternating => "every other cell, like a chessboard",
+            Self::All => "every cell",
+            Self::Empty => "no cells",
+            Self::Border => "the edge of the grid",
+            Self::Checkerboard => "a chessboard of 2x2 blocks",
+            Self::Circle => "the disk inscribed in the grid",
+            Self::Cross => "the central rows and columns",
+            Self::Diag => "the diagonal from the top-left corner",
+        }
+    }
+
+    /// Whether the cell at `x, y` of a `width` by `height` grid is alive, where random cells
+    /// take a chance from `rng`
+    fn is_alive(self, x: i32, y: i32, width: i32, height: i32, rng: &mut impl Rng) -> bool {
+        match self {
+            Self::Random => rng.gen_bool(0.5),
+            Self::Alternating => (x + y) % 2 == 0,
+            Self::All => true,
+            Self::Empty => false,
+            Self::Border => x == 0 || y == 0 || x == width - 1 || y == height - 1,
+            Self::Checkerboard => (x / 2 + y / 2) % 2 == 0,
+            Self::Circle => {
+                // doubled distances to the center keep the math exact for even sizes
+                let dx = (2 * x + 1 - width) as i64;
+                let dy = (2 * y + 1 - height) as i64;
+                let diameter = width.min(height) as i64;
+                dx * dx + dy * dy <= diameter * diameter
+            }
+            Self::Cross => {
+                let central = |i: i32, size: i32| i == (size - 1) / 2 || i == size / 2;
+                central(x, width) || central(y, height)
+            }
+            Self::Diag => x == y,
+        }
+    }
+
+    /// The alive cells of a `width` by `height` grid with its top-left corner at the origin,
+    /// sorted by y and then x
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::fill::Fill;
+    ///
+    /// let populations = [
+    ///     (Fill::Alternating, 32, 8),
+    ///     (Fill::All, 64, 15),
+    ///     (Fill::Empty, 0, 0),
+    ///     (Fill::Border, 28, 12),
+    ///     (Fill::Checkerboard, 32, 8),
+    ///     (Fill::Circle, 52, 9),
+    ///     (Fill::Cross, 28, 7),
+    ///     (Fill::Diag, 8, 3),
+    /// ];
+    /// for (fill, square, wide) in populations {
+    ///     assert_eq!(fill.cells(8, 8).len(), square, "{} in 8x8", fill);
+    ///     assert_eq!(fill.cells(5, 3).len(), wide, "{} in 5x3", fill);
+    /// }
+    /// assert!(Fill::Random.cells(8, 8).len() <= 64);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cells(self, width: i32, height: i32) -> Vec<Pos2> {
+        self.cells_with_rng(width, height, &mut rand::thread_rng())
+    }
+    /// Like [`Fill::cells`], where [`Fill::Random`] takes its chances from `rng`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::fill::Fill;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let a = Fill::Random.cells_with_rng(8, 8, &mut StdRng::seed_from_u64(7));
+    /// let b = Fill::Random.cells_with_rng(8, 8, &mut StdRng::seed_from_u64(7));
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn cells_with_rng(self, width: i32, height: i32, rng: &mut impl Rng) -> Vec<Pos2> {
+        let mut cells = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if self.is_alive(x, y, width, height, rng) {
+                    cells.push(Pos2 { x, y });
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// The error returned when parsing an unknown [`Fill`] name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFillError {
+    input: String,
+}
+impl fmt::Display for ParseFillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown fill `{}`, expected one of", self.input)?;
+        for fill in Fill::FILLS {
+            write!(f, " `{}`", fill)?;
+        }
+        Ok(())
+    }
+}
+impl core::error::Error for ParseFillError {}
+
+/// Writes the name of the fill as accepted by [`Fill::from_str`]
+///
+/// # Example
+/// ```rust
+/// use cgolrs::fill::Fill;
+///
+/// for &fill in Fill::FILLS {
+///     assert_eq!(fill.to_string().parse(), Ok(fill));
+/// }
+/// ```
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Random => "random",
+            Self::Alternating => "alternating",
+            Self::All => "all",
+            Self::Empty => "empty",
+            Self::Border => "border",
+            Self::Checkerboard => "checkerboard",
+            Self::Circle => "circle",
+            Self::Cross => "cross",
+            Self::Diag => "diag",
+        };
+        f.pad(name)
+    }
+}
+impl FromStr for Fill {
+    type Err = ParseFillError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::FILLS
+            .iter()
+            .copied()
+            .find(|fill| fill.to_string() == s)
+            .ok_or_else(|| ParseFillError {
+                input: s.to_owned(),
+            })
+    }
+}
@@ -0,0 +1,520 @@
+//! `cgol-gui`, a window showing the simulation for patterns too big for the terminal
+//!
+//! It takes the options of `gol run` for the starting pattern, the rule, the threads and the
+//! output. The game runs on a thread of its own, which hands a bitmap of the view to the window
+//! over a channel, so a slow generation never freezes the window. Zoomed out, a bit of the bitmap
+//! stands for a block of cells, so the frames are never much bigger than the window.
+
+use std::{
+    io,
+    process::ExitCode,
+    sync::mpsc::{self, Receiver, SendError, SyncSender, TryRecvError, TrySendError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use cgolrs::{
+    enc,
+    engine::{Bitmap, GameEngineWindow, GameOfLife},
+    transform, Pos2, Rect,
+};
+use pixels::{Pixels, SurfaceTexture};
+use winit::{
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    event::{
+        ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode,
+        WindowEvent,
+    },
+    event_loop::{EventLoopBuilder, EventLoopProxy},
+    platform::run_return::EventLoopExtRunReturn,
+    window::WindowBuilder,
+};
+
+mod options;
+mod setup;
+
+/// The color of alive cells, as RGBA
+const ALIVE: [u8; 4] = [255, 255, 255, 255];
+/// The color of dead cells, as RGBA
+const DEAD: [u8; 4] = [0, 0, 0, 255];
+/// The closest the view zooms in, in pixels per cell
+const MAX_ZOOM: f64 = 64.0;
+/// The closest a pattern is zoomed in to fit the window, so small patterns aren't huge
+const MAX_FIT_ZOOM: f64 = 16.0;
+/// The farthest the view zooms out, in pixels per cell
+const MIN_ZOOM: f64 = 1.0 / 64.0;
+
+/// What the window asks of the simulation thread
+enum Command {
+    /// Pauses or resumes stepping
+    TogglePause,
+    /// Pauses, then computes one generation
+    Step,
+    /// Sends the cells within the rectangle from now on, a bit per block of `factor × factor`
+    /// cells
+    View { rect: Rect, factor: u32 },
+    /// Replaces the game with a pattern dropped on the window
+    Load(enc::Pattern),
+}
+
+/// A generation for the window to draw
+struct Frame {
+    generation: u64,
+    /// The number of alive cells, or `None` if infinitely many are on an alive background
+    population: Option<usize>,
+    paused: bool,
+    /// The view the frame was made for, a bit per block that has any alive cells
+    bitmap: Bitmap,
+    /// The cell at the top-left corner of the bitmap
+    origin: Pos2,
+    /// The number of cells along each side of a block
+    factor: u32,
+}
+
+/// The part of the grid shown in the window
+struct View {
+    /// The cell in the middle of the window, which can be between cells
+    center: (f64, f64),
+    /// The size of a cell in pixels, a power of two
+    zoom: f64,
+    /// The size of the window and its frame buffer in pixels
+    size: PhysicalSize<u32>,
+}
+
+impl View {
+    /// The cell under a pixel of the window
+    fn to_cell(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.center.0 + (x - self.size.width as f64 / 2.0) / self.zoom,
+            self.center.1 + (y - self.size.height as f64 / 2.0) / self.zoom,
+        )
+    }
+    /// The pixel of the window at the top-left corner of a cell
+    fn to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.center.0) * self.zoom + self.size.width as f64 / 2.0,
+            (y - self.center.1) * self.zoom + self.size.height as f64 / 2.0,
+        )
+    }
+    /// The number of cells along each side of the blocks that share a pixel, 1 unless zoomed out
+    fn factor(&self) -> u32 {
+        (1.0 / self.zoom).max(1.0) as u32
+    }
+    /// The cells within the window, including the ones cut off at its edges, widened to whole
+    /// blocks of [`View::factor`] cells
+    fn rect(&self) -> Rect {
+        let factor = self.factor() as f64;
+        let (min_x, min_y) = self.to_cell(0.0, 0.0);
+        let (max_x, max_y) = self.to_cell(self.size.width as f64, self.size.height as f64);
+        // blocks aligned to the grid don't flicker while panning, and casting saturates, so
+        // zooming out past the coordinate range can't overflow
+        let floor = |cell: f64| ((cell / factor).floor() * factor) as i32;
+        let ceil = |cell: f64| ((cell / factor).ceil() * factor) as i32;
+        Rect::new(
+            Pos2::new(floor(min_x), floor(min_y)),
+            Pos2::new(ceil(max_x), ceil(max_y)),
+        )
+    }
+    /// Centers the view on the cells, zoomed in as far as they fit, which are sorted
+    fn fit(&mut self, cells: &[Pos2]) {
+        let bounds = transform::bounding_box(cells);
+        self.center = (
+            (bounds.min.x as f64 + bounds.max.x as f64) / 2.0,
+            (bounds.min.y as f64 + bounds.max.y as f64) / 2.0,
+        );
+        let fit = f64::min(
+            self.size.width as f64 / bounds.width().max(1) as f64,
+            self.size.height as f64 / bounds.height().max(1) as f64,
+        );
+        self.zoom = 2f64
+            .powi(fit.log2().floor() as i32)
+            .clamp(MIN_ZOOM, MAX_FIT_ZOOM);
+    }
+    /// Zooms in or out by a power of two, keeping the cell under the pixel at `anchor` in place
+    fn zoom_by(&mut self, steps: i32, anchor: PhysicalPosition<f64>) {
+        let cell = self.to_cell(anchor.x, anchor.y);
+        self.zoom = (self.zoom * 2f64.powi(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.center = (
+            cell.0 - (anchor.x - self.size.width as f64 / 2.0) / self.zoom,
+            cell.1 - (anchor.y - self.size.height as f64 / 2.0) / self.zoom,
+        );
+    }
+    /// Paints a frame into the RGBA frame buffer, which is the size of the window
+    ///
+    /// Every set bit of the frame is a square of pixels, or a single pixel when zoomed out. The
+    /// frame may have been made for an earlier view, which is drawn where it is in this one.
+    fn draw(&self, buffer: &mut [u8], frame: Option<&Frame>) {
+        for pixel in buffer.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&DEAD);
+        }
+        let Some(frame) = frame else {
+            return;
+        };
+        let (width, height) = (self.size.width as i64, self.size.height as i64);
+        let factor = frame.factor as f64;
+        let size = (self.zoom * factor).max(1.0) as i64;
+        let bitmap = &frame.bitmap;
+        for by in 0..bitmap.height {
+            for (i, &word) in bitmap.row(by).iter().enumerate() {
+                let mut bits = word;
+                while bits != 0 {
+                    let bx = i * 64 + bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    let (x, y) = self.to_pixel(
+                        frame.origin.x as f64 + bx as f64 * factor,
+                        frame.origin.y as f64 + by as f64 * factor,
+                    );
+                    let (x, y) = (x.floor() as i64, y.floor() as i64);
+                    let (left, right) = (x.max(0), (x + size).min(width));
+                    if left >= right {
+                        continue;
+                    }
+                    for py in y.max(0)..(y + size).min(height) {
+                        let row = (py * width) as usize;
+                        let span = (row + left as usize) * 4..(row + right as usize) * 4;
+                        for pixel in buffer[span].chunks_exact_mut(4) {
+                            pixel.copy_from_slice(&ALIVE);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The bitmap of a frame, with a bit per `factor × factor` block of the window that has any alive
+/// cells
+fn frame_bitmap(window: GameEngineWindow<'_>, factor: u32) -> Bitmap {
+    if factor <= 1 {
+        return window.to_bitmap();
+    }
+    let blocks = window.downsample(factor).expect("the factor isn't 0");
+    let mut bitmap = Bitmap::new(blocks.width(), blocks.height());
+    for (block, _) in blocks.iter() {
+        bitmap.set(block.x as u32, block.y as u32, true);
+    }
+    bitmap
+}
+
+/// Runs the game until the window goes away, handing a frame to the window whenever the
+/// generation or the view changes, and returns the game and the metadata of its pattern
+///
+/// While running, frames the window hasn't taken yet are skipped rather than waited for, so the
+/// game isn't held back by drawing. `wake` tells the window that a frame is ready.
+fn simulate(
+    args: &options::RunArgs,
+    mut game: GameOfLife,
+    mut meta: enc::PatternMeta,
+    commands: Receiver<Command>,
+    frames: SyncSender<Frame>,
+    wake: EventLoopProxy<()>,
+) -> (GameOfLife, enc::PatternMeta) {
+    let gens = args.generations() as u64;
+    let mut last_generation = game.generation().saturating_add(gens);
+    let mut paused = false;
+    let mut view = (Rect::new(Pos2::zero(), Pos2::zero()), 1);
+    // whether the window is missing the current generation or view
+    let mut dirty = true;
+    loop {
+        let frame_start = Instant::now();
+        let running = !paused && game.generation() < last_generation;
+        // with nothing to compute or send, wait for the window to ask for something
+        let mut command = match running || dirty {
+            true => commands.try_recv(),
+            false => commands.recv().map_err(|_| TryRecvError::Disconnected),
+        };
+        loop {
+            match command {
+                Ok(Command::TogglePause) => paused = !paused,
+                Ok(Command::Step) => {
+                    paused = true;
+                    game.next_generation();
+                }
+                Ok(Command::View { rect, factor }) => view = (rect, factor),
+                Ok(Command::Load(pattern)) => {
+                    game = GameOfLife::from_unsorted(pattern.cells);
+                    game.set_generation(pattern.generation);
+                    let rule = setup::resolve_rule(args, pattern.rule);
+                    if let Err(e) = setup::configure(args, rule, &mut game) {
+                        eprintln!("error: {}", e);
+                    }
+                    game.set_dying(pattern.dying);
+                    meta = pattern.meta;
+                    last_generation = game.generation().saturating_add(gens);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return (game, meta),
+            }
+            dirty = true;
+            command = commands.try_recv();
+        }
+
+        if !paused && game.generation() < last_generation {
+            game.next_generation();
+            dirty = true;
+        }
+        if dirty {
+            let stopped = paused || game.generation() >= last_generation;
+            let (rect, factor) = view;
+            let frame = Frame {
+                generation: game.generation(),
+                population: (!game.background()).then(|| game.alive_count()),
+                paused: stopped,
+                bitmap: frame_bitmap(game.window(rect), factor),
+                origin: rect.min,
+                factor,
+            };
+            // the last frame before stopping has to get through, so it's waited for
+            let sent = match stopped {
+                true => frames
+                    .send(frame)
+                    .map_err(|SendError(frame)| TrySendError::Disconnected(frame)),
+                false => frames.try_send(frame),
+            };
+            match sent {
+                Ok(()) => {
+                    dirty = false;
+                    // the window may have closed since, which the next command tells
+                    let _ = wake.send_event(());
+                }
+                Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Disconnected(_)) => return (game, meta),
+            }
+        }
+
+        let sleep = match args.pacing() {
+            Some(options::Pacing::Sleep(time)) => Some(time),
+            Some(options::Pacing::Fps(fps)) => {
+                (Duration::from_secs(1) / fps).checked_sub(frame_start.elapsed())
+            }
+            None => None,
+        };
+        if let Some(time) = sleep.filter(|_| !paused) {
+            thread::sleep(time);
+        }
+    }
+}
+
+/// The title of the window for the latest frame
+fn title(name: Option<&str>, frame: &Frame) -> String {
+    let population = frame
+        .population
+        .map_or_else(|| "infinite".to_owned(), |population| population.to_string());
+    format!(
+        "{} - generation {}, population {}{}",
+        name.unwrap_or("cgol-gui"),
+        frame.generation,
+        population,
+        if frame.paused { " (paused)" } else { "" }
+    )
+}
+
+/// Reads a pattern dropped on the window, sorting its cells for the view to fit them
+fn read_dropped(args: &options::RunArgs, path: &std::path::Path) -> cgolrs::Result<enc::Pattern> {
+    let mut pattern = enc::decode_file(path, args.pattern().format())?;
+    pattern.cells.sort_unstable();
+    pattern.cells.dedup();
+    if pattern.meta.name.is_none() {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned());
+        pattern.meta.name = stem;
+    }
+    Ok(pattern)
+}
+
+/// Opens the window and runs the game until it's closed, then writes the `--output` file
+fn run(args: &options::RunArgs) -> Result<(), setup::CommandError> {
+    if setup::print_run_lists(args) {
+        return Ok(());
+    }
+    let pattern = setup::args_to_alive(&args.pattern())?;
+    let rule = setup::resolve_rule(args, pattern.rule);
+    let mut game = GameOfLife::from_unsorted(pattern.cells);
+    game.set_generation(pattern.generation);
+    setup::configure(args, rule, &mut game)?;
+    game.set_dying(pattern.dying);
+    let mut name = pattern.meta.name.clone();
+
+    let mut event_loop = EventLoopBuilder::new().build();
+    let window = WindowBuilder::new()
+        .with_title(name.as_deref().unwrap_or("cgol-gui"))
+        .with_inner_size(LogicalSize::new(1024.0, 768.0))
+        .build(&event_loop)
+        .map_err(io::Error::other)?;
+    let size = window.inner_size();
+    let surface = SurfaceTexture::new(size.width, size.height, &window);
+    let mut pixels = Pixels::new(size.width, size.height, surface).map_err(io::Error::other)?;
+    let mut view = View {
+        center: (0.0, 0.0),
+        zoom: 1.0,
+        size,
+    };
+    view.fit(game.alive());
+
+    let (commands, command_rx) = mpsc::channel();
+    let (frame_tx, frames) = mpsc::sync_channel(1);
+    let wake = event_loop.create_proxy();
+    let (game, meta) = thread::scope(|scope| -> cgolrs::Result<_> {
+        let meta = pattern.meta;
+        let simulation =
+            scope.spawn(move || simulate(args, game, meta, command_rx, frame_tx, wake));
+        let _ = commands.send(Command::View {
+            rect: view.rect(),
+            factor: view.factor(),
+        });
+
+        let mut frame = None;
+        let mut cursor = PhysicalPosition::new(0.0, 0.0);
+        let mut dragging = false;
+        let mut failed = None;
+        event_loop.run_return(|event, _, control_flow| {
+            control_flow.set_wait();
+            let view_before = (view.center, view.zoom, view.size);
+            match event {
+                Event::UserEvent(()) => {
+                    if let Some(latest) = frames.try_iter().last() {
+                        window.set_title(&title(name.as_deref(), &latest));
+                        frame = Some(latest);
+                        window.request_redraw();
+                    }
+                }
+                Event::RedrawRequested(_) => {
+                    view.draw(pixels.frame_mut(), frame.as_ref());
+                    if let Err(e) = pixels.render() {
+                        failed = Some(io::Error::other(e));
+                        control_flow.set_exit();
+                    }
+                }
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::CloseRequested => control_flow.set_exit(),
+                    WindowEvent::Resized(size) if size.width > 0 && size.height > 0 => {
+                        let resized = pixels
+                            .resize_surface(size.width, size.height)
+                            .and_then(|()| pixels.resize_buffer(size.width, size.height));
+                        if let Err(e) = resized {
+                            failed = Some(io::Error::other(e));
+                            control_flow.set_exit();
+                        }
+                        view.size = size;
+                    }
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    } => match key {
+                        VirtualKeyCode::Space => {
+                            let _ = commands.send(Command::TogglePause);
+                        }
+                        VirtualKeyCode::Period => {
+                            let _ = commands.send(Command::Step);
+                        }
+                        VirtualKeyCode::Escape => control_flow.set_exit(),
+                        _ => {}
+                    },
+                    WindowEvent::MouseInput {
+                        state,
+                        button: MouseButton::Left,
+                        ..
+                    } => dragging = state == ElementState::Pressed,
+                    WindowEvent::CursorMoved { position, .. } => {
+                        if dragging {
+                            view.center.0 -= (position.x - cursor.x) / view.zoom;
+                            view.center.1 -= (position.y - cursor.y) / view.zoom;
+                        }
+                        cursor = position;
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let steps = match delta {
+                            MouseScrollDelta::LineDelta(_, y) => y as f64,
+                            // a notch of a wheel is usually about 40 pixels
+                            MouseScrollDelta::PixelDelta(delta) => delta.y / 40.0,
+                        };
+                        if steps != 0.0 {
+                            view.zoom_by(steps.signum() as i32, cursor);
+                        }
+                    }
+                    WindowEvent::DroppedFile(path) => match read_dropped(args, &path) {
+                        Ok(pattern) => {
+                            view.fit(&pattern.cells);
+                            name = pattern.meta.name.clone();
+                            let _ = commands.send(Command::Load(pattern));
+                        }
+                        Err(e) => eprintln!("{}: {}", path.display(), e),
+                    },
+                    _ => {}
+                },
+                _ => {}
+            }
+            if (view.center, view.zoom, view.size) != view_before {
+                let _ = commands.send(Command::View {
+                    rect: view.rect(),
+                    factor: view.factor(),
+                });
+                window.request_redraw();
+            }
+        });
+
+        // hanging up both channels stops the simulation, even while it waits to send a frame
+        drop(frames);
+        drop(commands);
+        let finished = simulation.join().expect("simulation thread panicked");
+        match failed {
+            Some(e) => Err(e.into()),
+            None => Ok(finished),
+        }
+    })?;
+
+    if let Some(file_name) = args.output_file() {
+        setup::write_output(
+            args,
+            &file_name,
+            game.generation(),
+            &setup::cells_to_save(&game),
+            game.dying(),
+            &meta,
+            game.rule(),
+        )?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let env = std::env::args().skip(1).collect::<Vec<_>>();
+    let (parsed, help) = match env.split_first() {
+        Some((name, rest)) if name == "run" => (options::RunArgs::new(rest), "cgol-gui run --help"),
+        Some((name, _)) if options::COMMANDS.contains(&name.as_str()) => {
+            eprintln!("error: cgol-gui only runs patterns, use gol for the other commands");
+            return ExitCode::from(2);
+        }
+        _ => (options::RunArgs::new(&env), "cgol-gui --help"),
+    };
+    let args = match parsed {
+        Ok(Some(args)) => args,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}\nrun `{}` for the options", e, help);
+            return ExitCode::from(2);
+        }
+    };
+    // the window takes the place of the console, and HashLife can't show its generations
+    let unsupported = [
+        (args.console(), "console mode"),
+        (args.engine() == "hashlife", "--engine hashlife"),
+    ];
+    if let Some(&(_, what)) = unsupported.iter().find(|(given, _)| *given) {
+        eprintln!("error: cgol-gui doesn't support {}", what);
+        return ExitCode::from(2);
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(setup::exit_code(&e))
+        }
+    }
+}
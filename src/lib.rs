@@ -0,0 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod cursor;
+pub mod enc;
+pub mod engine;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fill;
+pub mod patterns;
+pub mod pos;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod rule;
+#[cfg(feature = "std")]
+pub mod soup;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "test-util")]
+pub mod testing;
+mod trace;
+pub mod transform;
+
+pub use error::{Error, Result};
+pub use pos::{Pos2, Rect};
+pub use rule::Rule;
+
+/// The allocating parts of the standard prelude, which `no_std` builds don't have
+mod prelude {
+    pub use alloc::{
+        borrow::ToOwned,
+        boxed::Box,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+}
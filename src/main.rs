@@ -1,72 +1,705 @@
-use std::{io, thread};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
+use cgolrs::{
+    enc, engine, patterns, soup,
+    stats::{self, Phase, Recorder},
+    transform, Pos2, Rect, Rule,
+};
+
+mod bench;
+mod commands;
 mod console;
-mod enc;
-mod engine;
 mod options;
-mod pos;
-mod stats;
-
-use pos::Pos2;
-use stats::Recorder;
-
-fn args_to_alive(args: &options::Args) -> Vec<Pos2> {
-    if let Some(file_name) = args.input_file() {
-        let decoder = enc::RunLengthEncoded::default();
-        let encoded_str = std::fs::read_to_string(file_name).unwrap();
-        return enc::PositionEncoder::decode(decoder, &encoded_str);
-    }
-
-    // setup the alive cells based on args
-    let (grid_w, grid_h) = args.grid_size();
-    let mut alive = Vec::new();
-    for y in 0..grid_h {
-        for x in 0..grid_w {
-            if args.fill_is_alive(x, y) {
-                alive.push(Pos2 {
-                    x: x as i32,
-                    y: y as i32,
-                });
+mod setup;
+
+use setup::{
+    args_to_alive, cells_to_save, configure, encode_file, exit_code, resolve_rule, write_output,
+    CommandError,
+};
+
+/// The center of the bounding box of the given cells
+fn center_of(cells: &[Pos2]) -> Pos2 {
+    let min_x = cells.iter().map(|p| p.x).min().unwrap_or_default();
+    let max_x = cells.iter().map(|p| p.x).max().unwrap_or_default();
+    // because the cells are sorted, the first and last cells have the lowest and highest y-value
+    let min_y = cells.first().map(|p| p.y).unwrap_or_default();
+    let max_y = cells.last().map(|p| p.y).unwrap_or_default();
+    Pos2 {
+        x: (min_x + max_x) / 2,
+        y: (min_y + max_y) / 2,
+    }
+}
+
+/// Writes `--autosave` files every so many generations, keeping the latest `--autosave-keep`
+struct Autosave {
+    every: u64,
+    template: String,
+    format: enc::Format,
+    keep: Option<usize>,
+    /// The files written so far, oldest first
+    saved: VecDeque<PathBuf>,
+    last_generation: u64,
+}
+impl Autosave {
+    /// The autosaves of a run starting at `generation`, if `--autosave` was given
+    fn new(args: &options::RunArgs, generation: u64) -> Option<Self> {
+        let template = args.autosave_path();
+        Some(Self {
+            every: args.autosave()?,
+            format: enc::Format::from_path(&template).unwrap_or(enc::Format::Rle),
+            template,
+            keep: args.autosave_keep(),
+            saved: VecDeque::new(),
+            last_generation: generation,
+        })
+    }
+
+    /// Whether `generation` should be saved and hasn't been yet
+    fn is_due(&self, generation: u64) -> bool {
+        generation.is_multiple_of(self.every) && generation != self.last_generation
+    }
+
+    /// Writes the game to the file for its generation, then removes the autosaves past the ones
+    /// to keep
+    ///
+    /// The file is written under a temporary name and renamed when it's complete, so an
+    /// interrupted run leaves the previous autosave intact.
+    fn save(&mut self, game: &engine::GameOfLife, meta: &enc::PatternMeta) -> cgolrs::Result<()> {
+        let generation = game.generation();
+        self.last_generation = generation;
+        let path = PathBuf::from(self.template.replace("{gen}", &generation.to_string()));
+        let Some(name) = path.file_name() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} isn't a file name", path.display()),
+            )
+            .into());
+        };
+        // the prefix keeps the extension, which decides whether the file is gzipped
+        let temp = path.with_file_name(format!(".tmp-{}", name.to_string_lossy()));
+        encode_file(
+            &temp,
+            self.format,
+            &cells_to_save(game),
+            game.dying(),
+            generation,
+            meta,
+            Some(game.rule()),
+        )?;
+        std::fs::rename(&temp, &path)?;
+
+        if self.saved.back() != Some(&path) {
+            self.saved.push_back(path);
+        }
+        while self.saved.len() > self.keep.unwrap_or(usize::MAX) {
+            let old = self.saved.pop_front().expect("more autosaves than kept");
+            match std::fs::remove_file(old) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
             }
         }
+        Ok(())
     }
-    alive
 }
 
-fn main() -> io::Result<()> {
-    let Some(args) = options::Args::from_env() else {
-        panic!("invalid arguments");
+/// The path of a snapshot of `generation` in the snapshot directory, stamped with the current time
+fn snapshot_path(args: &options::RunArgs, generation: u64, extension: &str) -> std::path::PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    std::path::Path::new(&args.snapshot_dir()).join(format!(
+        "snapshot-{}-{}.{}",
+        generation, timestamp, extension
+    ))
+}
+
+/// Computes the next generation, only within the console's selection if there is one
+fn step(game: &mut engine::GameOfLife, console: Option<&mut console::ConsoleRender>) {
+    let Some(console) = console else {
+        return game.next_generation();
+    };
+    let Some(region) = console.selection() else {
+        return game.next_generation();
     };
+    // the whole game steps instead when the rule can't be limited to the selection
+    if let Err(e) = game.next_generation_in(region) {
+        console.clear_selection();
+        console.flash(e.to_string());
+        game.next_generation();
+    }
+}
+
+/// Writes the current state to `snapshot-<generation>-<timestamp>.rle` in the snapshot directory
+fn save_snapshot(
+    args: &options::RunArgs,
+    game: &engine::GameOfLife,
+) -> io::Result<std::path::PathBuf> {
+    let gen = game.generation();
+    let path = snapshot_path(args, gen, "rle");
+
+    let encoder = enc::RunLengthEncoded::default()
+        .set_name(format!("cgol_sim snapshot of generation {}", gen))
+        .set_generation(gen);
+    encoder.encode_to(&game.pattern_cells(), std::fs::File::create(&path)?)?;
+    Ok(path)
+}
+
+/// Writes the visible part of the grid to `snapshot-<generation>-<timestamp>.png` in the snapshot
+/// directory, in the console's colors
+#[cfg(feature = "image")]
+fn save_image(
+    args: &options::RunArgs,
+    game: &engine::GameOfLife,
+    rect: Rect,
+) -> io::Result<std::path::PathBuf> {
+    let path = snapshot_path(args, game.generation(), "png");
+    game.window(rect)
+        .to_png(&path, args.scale(), engine::Theme::DARK)?;
+    Ok(path)
+}
+#[cfg(not(feature = "image"))]
+fn save_image(
+    _args: &options::RunArgs,
+    _game: &engine::GameOfLife,
+    _rect: Rect,
+) -> io::Result<std::path::PathBuf> {
+    Err(setup::png_unsupported())
+}
+
+/// Starts recording the run to the `--record` file with the first frame, if recording at all
+///
+/// The window defaults to the bounding box of the starting cells.
+#[cfg(feature = "record")]
+fn start_recording(
+    args: &options::RunArgs,
+    game: &engine::GameOfLife,
+) -> io::Result<Option<cgolrs::record::FrameRecorder<io::BufWriter<std::fs::File>>>> {
+    let Some(file_name) = args.record_file() else {
+        return Ok(None);
+    };
+    let rect = args
+        .record_window()
+        .unwrap_or_else(|| cgolrs::transform::bounding_box(game.alive()));
+    let mut recorder = cgolrs::record::FrameRecorder::create(file_name, rect, args.scale())?
+        .set_every(args.record_every());
+    recorder.record(game)?;
+    Ok(Some(recorder))
+}
+
+/// Catches Ctrl+C outside of console mode, returning the flag it sets
+///
+/// The first Ctrl+C lets the run finish its generation and end as if it reached `--gens`, so the
+/// output, stats and summary are still written. The second one exits right away with status 130.
+fn catch_interrupts() -> io::Result<Arc<AtomicBool>> {
+    use signal_hook::{consts::SIGINT, flag};
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    // registered first, so it only sees the flag set by an earlier signal
+    flag::register_conditional_shutdown(SIGINT, 130, Arc::clone(&interrupted))?;
+    flag::register(SIGINT, Arc::clone(&interrupted))?;
+    Ok(interrupted)
+}
+
+/// The recorder of the run's stats: periodic reports, and every generation written to each
+/// `--stats-out` file
+///
+/// It's shared with the hook of [`hook_stats`], which records every step of the game.
+fn open_stats(
+    args: &options::RunArgs,
+    game: &engine::GameOfLife,
+) -> io::Result<Arc<Mutex<stats::MultiRecorder>>> {
+    let (generation, alive) = (game.generation(), game.alive_count());
+    let mut recorders: Vec<Box<dyn Recorder + Send>> = vec![Box::new(stats::SimpleRecord::new(
+        generation,
+        alive,
+        args.report_interval(),
+    ))];
+    for (path, format) in args.stats_files() {
+        recorders.push(match format {
+            "json" => Box::new(stats::JsonRecord::create(path, generation, alive)?),
+            _ => Box::new(stats::CsvRecord::create(path)?),
+        });
+    }
+    Ok(Arc::new(Mutex::new(stats::MultiRecorder(recorders))))
+}
 
-    let alive = args_to_alive(&args);
+/// Records every generation of the game from now on in the stats
+fn hook_stats(game: &mut engine::GameOfLife, stats: &Arc<Mutex<stats::MultiRecorder>>) {
+    let stats = Arc::clone(stats);
+    game.on_generation(move |event| lock_stats(&stats).record(&event));
+}
+
+/// Locks the stats shared with the hook of the game
+fn lock_stats(stats: &Mutex<stats::MultiRecorder>) -> MutexGuard<'_, stats::MultiRecorder> {
+    stats.lock().expect("stats recorder poisoned")
+}
+
+/// The footer hint shown while running the `demo` subcommand
+fn demo_hint(pattern: &patterns::Pattern) -> String {
+    format!("{} | n next pattern, press ? for keys", pattern.name)
+}
+
+/// Prints the objects of the final state, one apgcode per line
+fn print_census(game: &engine::GameOfLife) {
+    let census = game.census();
+    println!("census: {} objects", census.objects());
+    for (code, count) in census.iter() {
+        println!("{:>8} x {}{}", count, code, speed_suffix(code));
+    }
+}
+
+/// The speed of a spaceship in a census, like ` (c/4 diagonal)` for its apgcode, and nothing
+/// for every other object
+fn speed_suffix(code: &str) -> String {
+    if !code.starts_with("xq") {
+        return String::new();
+    }
+    let mut game = engine::GameOfLife::from_alive(enc::apgcode::decode(code));
+    match game.analyze(MAX_ANALYZE_GENS) {
+        engine::Analysis::Spaceship { speed, .. } => format!(" ({})", speed),
+        _ => String::new(),
+    }
+}
+
+/// Runs the `soup` subcommand: many seeded random soups, run until they settle
+///
+/// Prints the census of all soups, and writes it to `soup-census.csv` in the snapshot directory
+/// along with the longest-lived and largest soups as RLE files, unless those soups are empty.
+fn run_soup(args: &commands::SoupArgs) -> Result<(), CommandError> {
+    let search = soup::SoupSearch::new(args.count(), args.seed())
+        .set_size(args.size())
+        .set_threads(args.threads());
+    let start = Instant::now();
+    let report = search.run();
+    println!(
+        "ran {} soups in {:.3}s, {} didn't settle",
+        report.soups,
+        start.elapsed().as_secs_f64(),
+        report.unsettled
+    );
+
+    let objects = report.census.objects();
+    println!("{:>8}  {:>9}  apgcode", "count", "frequency");
+    for (code, count) in report.census.iter() {
+        let frequency = count as f64 / objects as f64;
+        println!(
+            "{:>8}  {:>9.5}  {}{}",
+            count,
+            frequency,
+            code,
+            speed_suffix(code)
+        );
+    }
+
+    let dir = std::path::Path::new(&args.snapshot_dir()).to_owned();
+    let mut csv = io::BufWriter::new(std::fs::File::create(dir.join("soup-census.csv"))?);
+    writeln!(csv, "apgcode,count,frequency")?;
+    for (code, count) in report.census.iter() {
+        writeln!(csv, "{},{},{}", code, count, count as f64 / objects as f64)?;
+    }
+    csv.flush()?;
+
+    let outliers = [
+        ("longest", "longest-lived", report.longest_lived),
+        ("largest", "largest final population", report.largest),
+    ];
+    for (file, description, result) in outliers {
+        // with every soup empty, say at `--size 0`, there's nothing worth saving
+        let Some(result) = result.filter(|result| result.soup_population > 0) else {
+            continue;
+        };
+        let path = dir.join(format!("soup-{}.rle", file));
+        let encoder = enc::RunLengthEncoded::default()
+            .set_name(format!("soup {} of seed {}", result.index, args.seed()))
+            .with_comment(&format!(
+                "{}: settled after {} generations with {} cells",
+                description, result.generations, result.final_population
+            ));
+        encoder.encode_to(&search.soup(result.index), std::fs::File::create(&path)?)?;
+        println!("saved the {} soup to {}", description, path.display());
+    }
+    Ok(())
+}
+
+/// The most generations `info --analyze` steps a pattern looking for its period
+const MAX_ANALYZE_GENS: usize = 1 << 12;
+
+/// Runs the `info` subcommand: prints what a pattern file contains
+///
+/// Fails with the name of a file that can't be decoded.
+fn run_info(args: &commands::InfoArgs) -> Result<(), CommandError> {
+    let file_name = args.file();
+    let pattern = enc::decode_file(file_name, args.format())
+        .map_err(|error| CommandError::file(file_name, error))?;
+
+    println!("file: {}", file_name);
+    println!("format: {}", pattern.format);
+    if let Some(name) = &pattern.meta.name {
+        println!("name: {}", name);
+    }
+    if let Some(author) = &pattern.meta.author {
+        println!("author: {}", author);
+    }
+    for comment in &pattern.meta.comments {
+        println!("comment: {}", comment);
+    }
+    match pattern.rule {
+        Some(rule) => println!("rule: {}", rule),
+        None => println!("rule: {} (assumed)", cgolrs::Rule::default()),
+    }
+    if pattern.generation > 0 {
+        println!("generation: {}", pattern.generation);
+    }
+
+    let population = pattern.cells.len();
+    let bounds = transform::bounding_box(&pattern.cells);
+    println!("population: {}", population);
+    if !pattern.dying.is_empty() {
+        println!("dying: {}", pattern.dying.len());
+    }
+    if !bounds.is_empty() {
+        let max = bounds.max - Pos2::new(1, 1);
+        println!("bounding box: {} to {}", bounds.min, max);
+    }
+    println!("dimensions: {}x{}", bounds.width(), bounds.height());
+    if !bounds.is_empty() {
+        let density = population as f64 / bounds.area() as f64;
+        println!("density: {:.2}%", density * 100.0);
+    }
+
+    let mut game = engine::GameOfLife::from_alive(pattern.cells);
+    game.set_rule(pattern.rule.unwrap_or_default())?;
+    game.set_dying(pattern.dying);
+    if args.census() {
+        print_census(&game);
+    }
+    if args.analyze() {
+        let kind = match game.analyze(MAX_ANALYZE_GENS) {
+            engine::Analysis::Unresolved if !game.background() && game.alive_count() == 0 => {
+                "dies out".to_owned()
+            }
+            engine::Analysis::Unresolved => {
+                format!("no period within {} generations", MAX_ANALYZE_GENS)
+            }
+            analysis => analysis.to_string(),
+        };
+        println!("kind: {}", kind);
+    }
+    Ok(())
+}
+
+/// Runs the `convert` subcommand: decodes a pattern file, transforms it and encodes it again
+///
+/// The formats are taken from the file extensions unless `--format` overrides the output format.
+/// Fails with the name of a file that can't be decoded, and refuses to overwrite the input
+/// unless `--force` is given.
+fn run_convert(args: &commands::ConvertArgs) -> Result<(), CommandError> {
+    let (input, output) = (args.input(), args.output());
+    let same_file = std::fs::canonicalize(input)
+        .ok()
+        .zip(std::fs::canonicalize(output).ok())
+        .is_some_and(|(input, output)| input == output);
+    if same_file && !args.force() {
+        let message = format!("refusing to overwrite {}, use --force", input);
+        return Err(CommandError::Failed(message));
+    }
+
+    let pattern =
+        enc::decode_file(input, None).map_err(|error| CommandError::file(input, error))?;
+    // the engine moves the dying cells of Generations rules along with the alive ones
+    let mut game = engine::GameOfLife::from_unsorted(pattern.cells);
+    game.set_rule(pattern.rule.unwrap_or_default())?;
+    game.set_dying(pattern.dying);
+    for _ in 0..args.rotate() / 90 {
+        game.rotate_cw();
+    }
+    if args.flip_x() {
+        game.flip_x();
+    }
+    if args.recenter() {
+        game.recenter();
+    }
+    let cells = game.alive();
+
+    let format = args
+        .format()
+        .or_else(|| enc::Format::from_path(output))
+        .ok_or_else(|| {
+            CommandError::Failed(format!("unknown format of {}, use --format", output))
+        })?;
+    encode_file(
+        output,
+        format,
+        cells,
+        game.dying(),
+        pattern.generation,
+        &pattern.meta,
+        pattern.rule,
+    )?;
+    println!(
+        "converted {} ({}) to {} ({}), {} cells",
+        input,
+        pattern.format,
+        output,
+        format,
+        cells.len()
+    );
+    Ok(())
+}
+
+/// Advances the pattern with the HashLife engine in one go, since it's only fast over long spans
+fn run_hashlife(
+    args: &options::RunArgs,
+    alive: Vec<Pos2>,
+    meta: &enc::PatternMeta,
+) -> Result<(), CommandError> {
+    // console mode and a missing --gens are rejected by Command::new
+    let gens = args.generations();
+
+    let mut life = engine::HashLife::from_alive(&alive);
+    let start = Instant::now();
+    life.advance(gens as u64);
+    println!(
+        "advanced {} generations in {:.3}s, alive: {}",
+        life.generation(),
+        start.elapsed().as_secs_f64(),
+        life.population()
+    );
+
+    let alive = life.to_alive();
+    if args.census() {
+        print_census(&engine::GameOfLife::from_alive(alive.clone()));
+    }
+    if let Some(file_name) = args.output_file() {
+        write_output(
+            args,
+            &file_name,
+            life.generation(),
+            &alive,
+            &[],
+            meta,
+            Rule::CONWAY,
+        )?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let (command, help) = commands::Command::from_env();
+    let command = match command {
+        Ok(Some(command)) => command,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}\nrun `{}` for the options", e, help);
+            return ExitCode::from(2);
+        }
+    };
+    #[cfg(feature = "tracing")]
+    if let Some(level) = command.log_level() {
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(io::stderr)
+            .init();
+    }
+    let result = match command {
+        commands::Command::Run(args) => run(&args),
+        commands::Command::Convert(args) => run_convert(&args),
+        commands::Command::Info(args) => run_info(&args),
+        commands::Command::Soup(args) => run_soup(&args),
+        commands::Command::Bench(args) => bench::run(&args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+/// Runs the `run` subcommand, the default: simulates the starting pattern, in console mode or
+/// reporting stats, until `--gens` or `--duration` is reached
+fn run(args: &options::RunArgs) -> Result<(), CommandError> {
+    if setup::print_run_lists(args) {
+        return Ok(());
+    }
+
+    let mut demo = args.demo().then(patterns::DemoCycle::new);
+    let (alive, dying, generation, meta, rule) = match demo {
+        Some(ref demo) => (
+            demo.current().cells(),
+            Vec::new(),
+            0,
+            enc::PatternMeta::default(),
+            None,
+        ),
+        None => {
+            let pattern = args_to_alive(&args.pattern())?;
+            (
+                pattern.cells,
+                pattern.dying,
+                pattern.generation,
+                pattern.meta,
+                pattern.rule,
+            )
+        }
+    };
+    let rule = resolve_rule(args, rule);
+    if let Some(ref name) = meta.name {
+        println!("pattern: {}", name);
+    }
     println!("alive: {}", alive.len());
+    println!("rule: {}", rule);
+    if args.engine() == "hashlife" {
+        if rule != Rule::CONWAY {
+            eprintln!(
+                "warning: HashLife only runs {}, ignoring {}",
+                Rule::CONWAY,
+                rule
+            );
+        }
+        return run_hashlife(args, alive, &meta);
+    }
 
     // setup the engine and reporting metrics
-    let mut game = engine::GameOfLife::from_alive(alive);
     let mut console = if args.console() {
         Some(console::ConsoleRender::new()?)
     } else {
         None
     };
-    let sleep = args.sleep();
+    if let (Some(ref mut console), Some(gradient)) = (&mut console, args.age_gradient()) {
+        console.set_age_gradient(gradient);
+    }
+    if let (Some(ref mut console), Some(ref name)) = (&mut console, &meta.name) {
+        console.set_hint(name.as_str());
+    }
+    if let (Some(ref mut console), Some(ref demo)) = (&mut console, &demo) {
+        console.center_on(center_of(&alive))?;
+        console.set_hint(demo_hint(demo.current()));
+    }
+    // input files can't be trusted to be sorted, so re-establish the engine's invariant
+    let mut game = engine::GameOfLife::from_unsorted(alive);
+    game.set_generation(generation);
+    configure(args, rule, &mut game)?;
+    // the rule decides which of the dying states are kept
+    game.set_dying(dying);
+    let mut cull_center = center_of(game.alive());
+    let mut culled_at = game.generation();
+    let pacing = args.pacing();
+    let last_generation = generation.saturating_add(args.generations() as u64);
+    let deadline = args.duration().map(|duration| Instant::now() + duration);
+    // console mode reads Ctrl+C as a key press instead
+    let interrupted = if args.console() {
+        Arc::new(AtomicBool::new(false))
+    } else {
+        catch_interrupts()?
+    };
 
-    // let mut stats = stats::CsvRecord::new(game.alive_count());
-    let mut stats = stats::CsvRecord::new(game.alive_count());
-    'generations: for _ in 0..args.generations() {
+    #[cfg(feature = "record")]
+    let mut recorder = start_recording(args, &game)?;
+
+    let mut autosave = Autosave::new(args, game.generation());
+    let stats = open_stats(args, &game)?;
+    lock_stats(&stats).set_phase_timing(!args.quiet());
+    hook_stats(&mut game, &stats);
+    let mut paused = args.edit();
+    if let (Some(ref mut console), true) = (&mut console, paused) {
+        console.begin_edit()?;
+    }
+    'generations: while game.generation() < last_generation {
+        let frame_start = Instant::now();
+        // the frame's timestamp doubles as the deadline check, so there's one clock read per frame
+        if deadline.is_some_and(|deadline| frame_start >= deadline) {
+            break;
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            eprintln!("interrupted, finishing the run");
+            break;
+        }
         // render the console if in console mode
         if let Some(ref mut console) = console {
             while let Some(cmd) = console.poll_events()? {
                 match cmd {
                     console::ConsoleCommand::Exit => break 'generations,
-                    _ => {}
+                    console::ConsoleCommand::TogglePause => paused = !paused,
+                    console::ConsoleCommand::NextPattern => {
+                        let Some(ref mut demo) = demo else {
+                            continue;
+                        };
+                        let pattern = demo.advance();
+                        let alive = pattern.cells();
+                        console.center_on(center_of(&alive))?;
+                        console.set_hint(demo_hint(pattern));
+                        game = engine::GameOfLife::from_alive(alive);
+                        configure(args, rule, &mut game)?;
+                        hook_stats(&mut game, &stats);
+                        cull_center = center_of(game.alive());
+                    }
+                    console::ConsoleCommand::Edit if paused => console.begin_edit()?,
+                    console::ConsoleCommand::Resume => {
+                        console.end_edit();
+                        paused = false;
+                    }
+                    console::ConsoleCommand::ToggleCell(pos) if paused => {
+                        game.toggle_cell(pos);
+                    }
+                    console::ConsoleCommand::SetCell(pos, alive) => game.set_cell(pos, alive),
+                    console::ConsoleCommand::SaveSnapshot => match save_snapshot(args, &game) {
+                        Ok(path) => console.flash(format!("saved {}", path.display())),
+                        Err(e) => console.flash(format!("snapshot failed: {}", e)),
+                    },
+                    console::ConsoleCommand::SaveImage(rect) => {
+                        match save_image(args, &game, rect) {
+                            Ok(path) => console.flash(format!("saved {}", path.display())),
+                            Err(e) => console.flash(format!("image failed: {}", e)),
+                        }
+                    }
+                    console::ConsoleCommand::Rewind => {
+                        paused = true;
+                        if !game.rewind(1) {
+                            console.flash(format!(
+                                "no history before generation {}",
+                                game.generation()
+                            ));
+                        }
+                    }
+                    console::ConsoleCommand::StepForward => {
+                        paused = true;
+                        stats::timed_shared(&stats, Phase::Compute, || {
+                            step(&mut game, Some(console))
+                        });
+                        #[cfg(feature = "record")]
+                        if let Some(ref mut recorder) = recorder {
+                            recorder.record(&game)?;
+                        }
+                    }
+                    console::ConsoleCommand::Edit
+                    | console::ConsoleCommand::ToggleCell(_)
+                    | console::ConsoleCommand::Handled => {}
                 }
             }
-            console.render(&game)?;
+            stats::timed_shared(&stats, Phase::Render, || console.render(&game))?;
         }
 
-        // report metrics every 500ms or always if in console mode
-        if stats.has_report(console.is_some()) {
-            let report = stats.report();
+        // report metrics every interval or always if in console mode, which --quiet doesn't hide
+        let report = {
+            let mut stats = lock_stats(&stats);
+            let shown = console.is_some() || !args.quiet();
+            (shown && stats.has_report(console.is_some())).then(|| stats.report())
+        };
+        if let Some(report) = report {
             if let Some(ref mut console) = console {
                 console.set_report(report);
             } else {
@@ -75,18 +708,78 @@ fn main() -> io::Result<()> {
         }
 
         // compute the next generation
-        game.next_generation();
-        stats.record(game.alive_count());
+        if !paused {
+            stats::timed_shared(&stats, Phase::Compute, || step(&mut game, console.as_mut()));
+            #[cfg(feature = "record")]
+            if let Some(ref mut recorder) = recorder {
+                recorder.record(&game)?;
+            }
+        }
+        // drop the debris far away from the starting pattern, once per culling generation
+        if let Some(radius) = args.cull_radius() {
+            let generation = game.generation();
+            if generation.is_multiple_of(args.cull_every()) && generation != culled_at {
+                culled_at = generation;
+                let radius = i32::try_from(radius).unwrap_or(i32::MAX);
+                stats::timed_shared(&stats, Phase::Compute, || {
+                    game.cull_beyond_radius(cull_center, radius)
+                });
+            }
+        }
+        if let Some(ref mut autosave) = autosave {
+            if autosave.is_due(game.generation()) {
+                let saved =
+                    stats::timed_shared(&stats, Phase::Save, || autosave.save(&game, &meta));
+                if let Err(e) = saved {
+                    let message = format!("autosave failed: {}", e);
+                    match console {
+                        Some(ref mut console) => console.flash(message),
+                        None => eprintln!("{}", message),
+                    }
+                }
+            }
+        }
+        let sleep = match pacing {
+            Some(options::Pacing::Sleep(time)) => Some(time),
+            // only sleep for what's left of the frame, and not at all when over budget
+            Some(options::Pacing::Fps(fps)) => {
+                (Duration::from_secs(1) / fps).checked_sub(frame_start.elapsed())
+            }
+            None => None,
+        };
         if let Some(time) = sleep {
-            thread::sleep(time);
+            stats::timed_shared(&stats, Phase::Idle, || thread::sleep(time));
         }
     }
+    let interactive = console.is_some();
     std::mem::drop(console);
+    {
+        let mut stats = lock_stats(&stats);
+        stats.save()?;
+        if !interactive {
+            println!("{}", stats.summary());
+        }
+    }
+    if args.census() {
+        print_census(&game);
+    }
+    #[cfg(feature = "record")]
+    if let Some(recorder) = recorder {
+        let frames = recorder.frames();
+        recorder.finish()?.flush()?;
+        println!("recorded {} frames", frames);
+    }
 
     if let Some(file_name) = args.output_file() {
-        let encoder = enc::RunLengthEncoded::default().set_name("cgol_sim generated pattern");
-        let encoded_game = enc::PositionEncoder::encode(encoder, &game.take());
-        std::fs::write(file_name, encoded_game).expect("write encoded game to file");
+        write_output(
+            args,
+            &file_name,
+            game.generation(),
+            &cells_to_save(&game),
+            game.dying(),
+            &meta,
+            game.rule(),
+        )?;
     }
 
     Ok(())
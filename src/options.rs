@@ -1,94 +1,814 @@
-use std::time::Duration;
+use cgolrs::Rule;
+use std::{fmt, str::FromStr, time::Duration};
 
-pub struct Args {
+/// The names of the subcommands of `gol`, which are only recognized as the first argument
+pub const COMMANDS: &[&str] = &["run", "convert", "info", "soup", "bench"];
+
+/// The error returned for invalid command line arguments
+#[derive(Debug)]
+pub enum ArgsError {
+    /// An unknown flag, or a flag missing its value
+    Parse(getopts::Fail),
+    /// A flag with a value it doesn't accept
+    InvalidValue {
+        flag: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    /// Two arguments that can't be used together
+    Conflict {
+        flag: &'static str,
+        other: &'static str,
+    },
+    /// An argument that's required by a command or another flag
+    Missing {
+        argument: &'static str,
+        required_by: &'static str,
+    },
+    /// A `--pattern` name that isn't built in, with the names that look like it
+    UnknownPattern {
+        name: String,
+        similar: Vec<&'static str>,
+    },
+    /// A free argument the command doesn't take
+    Unexpected(String),
+}
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(fail) => write!(f, "{}", fail),
+            Self::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value `{}` for --{}, expected {}",
+                value, flag, expected
+            ),
+            Self::Conflict { flag, other } => {
+                write!(f, "{} can't be used together with {}", flag, other)
+            }
+            Self::Missing {
+                argument,
+                required_by,
+            } => write!(f, "{} requires {}", required_by, argument),
+            Self::UnknownPattern { name, similar } => {
+                write!(f, "unknown pattern `{}`", name)?;
+                if !similar.is_empty() {
+                    write!(f, ", did you mean {}?", similar.join(" or "))?;
+                }
+                write!(f, " (`--pattern list` shows them all)")
+            }
+            Self::Unexpected(argument) => {
+                write!(f, "unexpected argument `{}`", argument)?;
+                if COMMANDS.contains(&argument.as_str()) {
+                    write!(f, ", commands go before the options")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+impl std::error::Error for ArgsError {}
+
+/// Parses the value of a flag, see [`Flags::check`]
+pub fn parse_value<T: FromStr>(
+    flag: &'static str,
+    value: &str,
+    expected: &'static str,
+) -> Result<T, ArgsError> {
+    value.trim().parse().map_err(|_| ArgsError::InvalidValue {
+        flag,
+        value: value.to_owned(),
+        expected,
+    })
+}
+
+/// Parses a rule by its preset name, or else in the `B3/S23` notation
+fn parse_rule(rule: &str) -> Option<Rule> {
+    Rule::from_name(rule).or_else(|| rule.parse().ok())
+}
+
+/// Parses an age gradient like `0=white,4=red` into `(max_age, color)` pairs in ascending order
+pub fn parse_age_gradient(list: &str) -> Result<Vec<(u32, crossterm::style::Color)>, ArgsError> {
+    let invalid = || ArgsError::InvalidValue {
+        flag: "age-gradient",
+        value: list.to_owned(),
+        expected: "a list of AGE=COLOR",
+    };
+    let mut gradient = list
+        .split(',')
+        .map(|entry| {
+            let (age, color) = entry.split_once('=').ok_or_else(invalid)?;
+            let age = age.trim().parse().map_err(|_| invalid())?;
+            let color = crossterm::style::Color::try_from(color.trim()).map_err(|_| invalid())?;
+            Ok((age, color))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    gradient.sort_by_key(|&(age, _)| age);
+    Ok(gradient)
+}
+
+/// Parses an area given as `x,y,width,height`, which can't be empty
+pub fn parse_window(window: &str) -> Result<cgolrs::Rect, ArgsError> {
+    let invalid = || ArgsError::InvalidValue {
+        flag: "record-window",
+        value: window.to_owned(),
+        expected: "X,Y,W,H with a positive width and height",
+    };
+    let parts = window
+        .split(',')
+        .map(|part| part.trim().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let [x, y, w, h] = parts[..] else {
+        return Err(invalid());
+    };
+    let (Ok(x), Ok(y), Ok(w), Ok(h)) = (
+        i32::try_from(x),
+        i32::try_from(y),
+        u32::try_from(w),
+        u32::try_from(h),
+    ) else {
+        return Err(invalid());
+    };
+    if w == 0 || h == 0 {
+        return Err(invalid());
+    }
+    Ok(cgolrs::Rect::from_size(cgolrs::Pos2::new(x, y), w, h))
+}
+
+/// Parses an input file with an optional `@x,y` offset, like `eater.rle@40,20`
+///
+/// An `@` suffix without a comma is part of the file name.
+fn parse_input(input: &str) -> Result<(String, cgolrs::Pos2), ArgsError> {
+    let Some((file, offset)) = input
+        .rsplit_once('@')
+        .filter(|(_, offset)| offset.contains(','))
+    else {
+        return Ok((input.to_owned(), cgolrs::Pos2::zero()));
+    };
+    let offset = offset.parse().map_err(|_| ArgsError::InvalidValue {
+        flag: "input",
+        value: input.to_owned(),
+        expected: "FILE or FILE@X,Y",
+    })?;
+    Ok((file.to_owned(), offset))
+}
+
+/// Parses a list of built-in patterns with optional offsets, like `glider@0,0,lwss@30,10`
+///
+/// Since the offsets contain commas too, an `@` takes the part after the next comma as its y.
+fn parse_patterns(
+    list: &str,
+) -> Result<Vec<(&'static cgolrs::patterns::Pattern, cgolrs::Pos2)>, ArgsError> {
+    let invalid = || ArgsError::InvalidValue {
+        flag: "pattern",
+        value: list.to_owned(),
+        expected: "NAME or NAME@X,Y separated by commas",
+    };
+    let mut parts = list.split(',');
+    let mut patterns = Vec::new();
+    while let Some(part) = parts.next() {
+        let (name, offset) = match part.split_once('@') {
+            Some((name, x)) => {
+                let x = x.trim().parse().map_err(|_| invalid())?;
+                let y = parts.next().ok_or_else(invalid)?;
+                let y = y.trim().parse().map_err(|_| invalid())?;
+                (name.trim(), cgolrs::Pos2::new(x, y))
+            }
+            None => (part.trim(), cgolrs::Pos2::zero()),
+        };
+        let pattern = cgolrs::patterns::find(name).ok_or_else(|| ArgsError::UnknownPattern {
+            name: name.to_owned(),
+            similar: cgolrs::patterns::similar(name),
+        })?;
+        patterns.push((pattern, offset));
+    }
+    Ok(patterns)
+}
+
+/// How the main loop waits between generations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    /// Sleeps a fixed time after every generation
+    Sleep(Duration),
+    /// Sleeps what's left of each frame to run a number of generations per second
+    Fps(u32),
+}
+
+/// The parsed flags of a command, with the helpers to check and read their values
+pub struct Flags {
     matches: getopts::Matches,
 }
+impl std::ops::Deref for Flags {
+    type Target = getopts::Matches;
 
-impl Args {
-    fn new<T: AsRef<str>>(args: &[T]) -> Option<Self> {
-        let mut opts = getopts::Options::new();
+    fn deref(&self) -> &Self::Target {
+        &self.matches
+    }
+}
+impl Flags {
+    /// Parses `args` with the options of a command, or prints its help and returns `None`
+    pub fn parse<T: AsRef<str>>(
+        mut opts: getopts::Options,
+        args: &[T],
+        usage: &str,
+    ) -> Result<Option<Self>, ArgsError> {
         opts.optflag("", "help", "print this help menu");
-        opts.optflag("c", "console", "run in console mode");
-        opts.optopt("o", "output", "output file", "FILE");
-        opts.optopt("i", "input", "input file", "FILE");
-        opts.optopt("w", "width", "set grid width", "WIDTH");
-        opts.optopt("h", "height", "set grid height", "HEIGHT");
-        opts.optopt("f", "fill", "set fill type", "TYPE");
+        #[cfg(feature = "tracing")]
         opts.optopt(
-            "s",
-            "sleep",
-            "the amount of time to sleep between generations",
-            "MILLIS",
+            "",
+            "log-level",
+            "log the spans and events of the library to stderr: error, warn, info, debug or trace",
+            "LEVEL",
         );
-        opts.optopt("g", "gens", "max number of generations", "COUNT");
-
-        let matches = opts.parse(args.iter().map(T::as_ref)).unwrap();
+        let matches = opts
+            .parse(args.iter().map(T::as_ref))
+            .map_err(ArgsError::Parse)?;
         if matches.opt_present("help") {
-            println!("{}", opts.usage("usage: gol [options] [FILE]"));
-            None
-        } else {
-            Some(Self { matches })
+            println!("{}", opts.usage(usage));
+            return Ok(None);
         }
+        let flags = Self { matches };
+        #[cfg(feature = "tracing")]
+        flags.check::<tracing::Level>("log-level", "error, warn, info, debug or trace")?;
+        Ok(Some(flags))
     }
-    pub fn from_env() -> Option<Self> {
-        let env = std::env::args().collect::<Vec<_>>();
-        Self::new(&env[1..])
+    /// Rejects free arguments past the first `expected` ones
+    pub fn check_free(&self, expected: usize) -> Result<(), ArgsError> {
+        match self.free.get(expected) {
+            Some(argument) => Err(ArgsError::Unexpected(argument.clone())),
+            None => Ok(()),
+        }
     }
-
-    fn width(&self) -> Option<i32> {
-        self.matches.opt_get("width").unwrap()
+    /// Parses the value of a flag, if it was given
+    pub fn check<T: FromStr>(
+        &self,
+        flag: &'static str,
+        expected: &'static str,
+    ) -> Result<Option<T>, ArgsError> {
+        self.opt_str(flag)
+            .map(|value| parse_value(flag, &value, expected))
+            .transpose()
     }
-    fn height(&self) -> Option<i32> {
-        self.matches.opt_get("height").unwrap()
+    /// Parses the value of a flag that has to be a positive count, if it was given
+    pub fn check_positive<T: FromStr + Default + PartialEq>(
+        &self,
+        flag: &'static str,
+    ) -> Result<Option<T>, ArgsError> {
+        match self.check::<T>(flag, COUNT)? {
+            Some(value) if value == T::default() => Err(self.invalid(flag, COUNT)),
+            value => Ok(value),
+        }
     }
-    fn fill(&self) -> Option<String> {
-        self.matches.opt_str("fill")
+    /// The error for the value given to a flag
+    pub fn invalid(&self, flag: &'static str, expected: &'static str) -> ArgsError {
+        ArgsError::InvalidValue {
+            flag,
+            value: self.opt_str(flag).unwrap_or_default(),
+            expected,
+        }
     }
-
-    pub fn console(&self) -> bool {
-        self.matches.opt_present("console")
+    /// The parsed value of a flag, which was checked when the command was parsed
+    pub fn value<T: FromStr>(&self, flag: &str) -> Option<T> {
+        self.opt_str(flag).map(|value| match value.trim().parse() {
+            Ok(value) => value,
+            Err(_) => unreachable!("--{} is validated by Command::new", flag),
+        })
     }
-    pub fn generations(&self) -> usize {
-        self.matches.opt_get("gens").unwrap().unwrap_or(usize::MAX) // kinda hacky way of saying "infinity"
+    /// The pattern file format, if given explicitly
+    pub fn format(&self) -> Option<cgolrs::enc::Format> {
+        let format = self.opt_str("format")?;
+        Some(
+            format
+                .parse()
+                .expect("the format is validated by Command::new"),
+        )
     }
-    pub fn sleep(&self) -> Option<Duration> {
-        match self.matches.opt_get("sleep").unwrap() {
-            Some(millis) => Some(Duration::from_millis(millis)),
-            None if self.console() => Some(Duration::from_millis(100)),
-            None => None,
+    pub fn check_format(&self) -> Result<(), ArgsError> {
+        if let Some(format) = self.opt_str("format") {
+            if format.parse::<cgolrs::enc::Format>().is_err() {
+                let expected = "rle, life105, life106, plaintext or bin";
+                return Err(self.invalid("format", expected));
+            }
         }
+        Ok(())
     }
+}
+
+const NUMBER: &str = "a number";
+pub const COUNT: &str = "a positive whole number";
 
+/// Adds the options that pick the starting pattern, shared by `run` and `bench`
+pub fn pattern_options(opts: &mut getopts::Options) {
+    opts.optmulti(
+        "i",
+        "input",
+        "input file, can be repeated with offsets to combine patterns",
+        "FILE[@X,Y]",
+    );
+    opts.optopt(
+        "p",
+        "pattern",
+        "start from built-in patterns, e.g. glider@0,0,lwss@30,10, or `list` to show them",
+        "LIST",
+    );
+    opts.optopt(
+        "",
+        "offset",
+        "move the whole starting pattern, after any per-file offsets",
+        "X,Y",
+    );
+    opts.optopt(
+        "",
+        "format",
+        "pattern file format: rle, life105, life106, plaintext or bin (detected for input)",
+        "NAME",
+    );
+    opts.optopt(
+        "",
+        "threshold",
+        "brightness below which pixels of PNG input are alive (default 128)",
+        "0-255",
+    );
+    opts.optopt("w", "width", "set grid width", "WIDTH");
+    opts.optopt("h", "height", "set grid height", "HEIGHT");
+    opts.optopt(
+        "f",
+        "fill",
+        "set fill type, e.g. random (default), border or circle, or `list` to show them",
+        "TYPE",
+    );
+}
+
+/// The options that pick the starting pattern, shared by `run` and `bench`
+pub struct PatternArgs<'a> {
+    pub flags: &'a Flags,
+    /// Whether the default grid is the size of the terminal
+    pub console: bool,
+}
+
+impl PatternArgs<'_> {
+    pub fn validate(&self) -> Result<(), ArgsError> {
+        self.flags.check::<i32>("width", NUMBER)?;
+        self.flags.check::<i32>("height", NUMBER)?;
+        self.flags
+            .check::<u8>("threshold", "a brightness from 0 to 255")?;
+        self.flags.check_format()?;
+        if let Some(fill) = self.flags.opt_str("fill").filter(|fill| fill != "list") {
+            if fill.parse::<cgolrs::fill::Fill>().is_err() {
+                return Err(self.flags.invalid("fill", "a fill type from `--fill list`"));
+            }
+        }
+        for input in self.flags.opt_strs("input") {
+            parse_input(&input)?;
+        }
+        self.flags.check::<cgolrs::Pos2>("offset", "X,Y")?;
+        if let Some(list) = self.flags.opt_str("pattern").filter(|list| list != "list") {
+            parse_patterns(&list)?;
+        }
+        for (source, flag) in [("input", "--input"), ("pattern", "--pattern")] {
+            if !self.flags.opt_present(source) {
+                continue;
+            }
+            for (name, other) in [
+                ("fill", "--fill"),
+                ("width", "--width"),
+                ("height", "--height"),
+            ] {
+                if self.flags.opt_present(name) {
+                    return Err(ArgsError::Conflict { flag, other });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The fill of the starting grid, random by default
+    pub fn fill(&self) -> cgolrs::fill::Fill {
+        match self.flags.opt_str("fill") {
+            Some(fill) => fill
+                .parse()
+                .expect("the fill type is validated by Command::new"),
+            None => cgolrs::fill::Fill::Random,
+        }
+    }
+    /// Whether `--fill list` asked for the fill types
+    pub fn list_fills(&self) -> bool {
+        self.flags
+            .opt_str("fill")
+            .is_some_and(|fill| fill == "list")
+    }
+    /// The size of the filled grid, which is the terminal's in console mode and 500x500 otherwise
     pub fn grid_size(&self) -> (i32, i32) {
-        let default = if self.console() {
-            let (cols, rows) = crossterm::terminal::size().unwrap();
+        let default = if self.console {
+            // without a terminal to measure, console mode fails to start later with a clearer
+            // error than this one
+            let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
             (cols as i32, rows as i32)
         } else {
             (500, 500)
         };
 
         (
-            self.width().unwrap_or(default.0),
-            self.height().unwrap_or(default.1),
+            self.flags.value("width").unwrap_or(default.0),
+            self.flags.value("height").unwrap_or(default.1),
         )
     }
-    pub fn fill_is_alive(&self, x: i32, y: i32) -> bool {
-        use rand::prelude::*;
+    /// Whether `--pattern list` asked for the names of the built-in patterns
+    pub fn list_patterns(&self) -> bool {
+        self.flags
+            .opt_str("pattern")
+            .is_some_and(|list| list == "list")
+    }
+    /// The built-in patterns to start from and their offsets, in the order they were given
+    pub fn patterns(&self) -> Vec<(&'static cgolrs::patterns::Pattern, cgolrs::Pos2)> {
+        match self.flags.opt_str("pattern") {
+            Some(list) if list != "list" => {
+                parse_patterns(&list).expect("the patterns are validated by Command::new")
+            }
+            _ => Vec::new(),
+        }
+    }
+    /// The offset of the whole starting pattern
+    pub fn offset(&self) -> cgolrs::Pos2 {
+        self.flags.value("offset").unwrap_or_default()
+    }
+    /// The input files and the offsets their cells are moved by, in the order they were given
+    pub fn input_files(&self) -> Vec<(String, cgolrs::Pos2)> {
+        self.flags
+            .opt_strs("input")
+            .iter()
+            .map(|input| parse_input(input).expect("the inputs are validated by Command::new"))
+            .collect()
+    }
+    /// The pattern file format, if given explicitly
+    pub fn format(&self) -> Option<cgolrs::enc::Format> {
+        self.flags.format()
+    }
+    /// The brightness below which pixels of PNG input are alive
+    #[cfg(feature = "image")]
+    pub fn threshold(&self) -> u8 {
+        self.flags.value("threshold").unwrap_or(128)
+    }
+}
+
+/// The options of `gol run`, which simulates a pattern
+pub struct RunArgs {
+    pub flags: Flags,
+}
+
+impl RunArgs {
+    pub fn new<T: AsRef<str>>(args: &[T]) -> Result<Option<Self>, ArgsError> {
+        let mut opts = getopts::Options::new();
+        opts.optflag("c", "console", "run in console mode");
+        opts.optflag(
+            "e",
+            "edit",
+            "start paused in the pattern editor (implies console mode)",
+        );
+        opts.optflag("", "ages", "color cells by their age in console mode");
+        opts.optflag(
+            "",
+            "immigration",
+            "give cells one of two colors, which births take from most of their parents",
+        );
+        opts.optflag(
+            "",
+            "census",
+            "print the objects of the final state by their apgcode",
+        );
+        opts.optopt(
+            "",
+            "age-gradient",
+            "colors by maximum age, e.g. 0=white,4=red,64=dark_blue",
+            "LIST",
+        );
+        opts.optopt("o", "output", "output file", "FILE");
+        pattern_options(&mut opts);
+        opts.optopt(
+            "r",
+            "rule",
+            "the rule, a name like highlife or seeds, B3/S23 or B2-a/S12 notation or a MAP, or \
+             `list` to show the names (default the input's rule, or life)",
+            "RULE",
+        );
+        opts.optopt(
+            "",
+            "autosave",
+            "save the pattern every COUNT generations while running",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "autosave-path",
+            "the autosave file name, where {gen} is the generation (default autosave-{gen}.rle)",
+            "TEMPLATE",
+        );
+        opts.optopt(
+            "",
+            "autosave-keep",
+            "the number of latest autosaves to keep (default all)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "cull-radius",
+            "drop the cells more than RADIUS cells from the center of the starting pattern, which \
+             changes the simulation but keeps guns and puffers bounded",
+            "RADIUS",
+        );
+        opts.optopt(
+            "",
+            "cull-every",
+            "the number of generations between culls (default 100)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "scale",
+            "pixels per cell in PNG output and recordings (default 4)",
+            "PIXELS",
+        );
+        opts.optopt("", "record", "record the run to an animated GIF", "FILE");
+        opts.optopt(
+            "",
+            "record-every",
+            "the number of generations between recorded frames (default 1)",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "record-window",
+            "the recorded area (default the starting bounding box)",
+            "X,Y,W,H",
+        );
+        opts.optopt(
+            "",
+            "snapshot-dir",
+            "directory for snapshots taken in console mode",
+            "DIR",
+        );
+        opts.optopt(
+            "s",
+            "sleep",
+            "the amount of time to sleep between generations",
+            "MILLIS",
+        );
+        opts.optopt(
+            "",
+            "fps",
+            "the target generations per second, sleeping only what's left of each (default 10 \
+             in console mode)",
+            "RATE",
+        );
+        opts.optopt("g", "gens", "max number of generations", "COUNT");
+        opts.optopt(
+            "",
+            "duration",
+            "stop after this much wall time, or at --gens if that comes first",
+            "SECS",
+        );
+        opts.optopt(
+            "",
+            "report-interval",
+            "the time between stats reports, 0 for every generation (default 500)",
+            "MILLIS",
+        );
+        opts.optopt(
+            "",
+            "stats-format",
+            "the format of the per-generation stats: csv (default) or json",
+            "NAME",
+        );
+        opts.optmulti(
+            "",
+            "stats-out",
+            "a stats file, can be repeated (default perf.csv or perf.ndjson)",
+            "FILE",
+        );
+        opts.optflag(
+            "q",
+            "quiet",
+            "only print a summary instead of periodic stats reports",
+        );
+        opts.optopt(
+            "t",
+            "threads",
+            "the number of threads used to compute each generation",
+            "COUNT",
+        );
+        opts.optopt(
+            "",
+            "engine",
+            "simulation engine: scan (default) or hashlife, which requires --gens and no console",
+            "NAME",
+        );
+
+        let usage = concat!(
+            "usage: gol [run] [options]\n",
+            "       gol [run] demo [options]\n",
+            "       gol convert IN OUT [options]\n",
+            "       gol info FILE [options]\n",
+            "       gol soup [options]\n",
+            "       gol bench [options]\n\n",
+            "Each command has its own options, e.g. `gol soup --help`. Without a command, the\n",
+            "options are those of `gol run`, which runs a pattern. `demo` cycles through\n",
+            "curated patterns in console mode.",
+        );
+        let Some(flags) = Flags::parse(opts, args, usage)? else {
+            return Ok(None);
+        };
+        let args = Self { flags };
+        args.validate()?;
+        Ok(Some(args))
+    }
 
-        let f = self.fill();
-        match f.as_deref().unwrap_or("random") {
-            "random" => rand::thread_rng().gen_bool(0.5),
-            "alternating" => (x + y) % 2 == 0,
-            "all" => true,
-            "empty" => false,
-            _ => panic!("invalid fill type"),
+    /// Checks every value and combination of flags up front, so the accessors can't fail
+    fn validate(&self) -> Result<(), ArgsError> {
+        let flags = &self.flags;
+        if self.demo() {
+            flags.check_free(1)?;
+        } else {
+            flags.check_free(0)?;
+        }
+        self.pattern().validate()?;
+        flags.check::<u64>("sleep", COUNT)?;
+        flags.check_positive::<u32>("fps")?;
+        if flags.opt_present("fps") && flags.opt_present("sleep") {
+            return Err(ArgsError::Conflict {
+                flag: "--fps",
+                other: "--sleep",
+            });
+        }
+        flags.check::<usize>("gens", COUNT)?;
+        const SECONDS: &str = "a positive number of seconds";
+        if let Some(secs) = flags.check::<f64>("duration", SECONDS)? {
+            if Duration::try_from_secs_f64(secs).map_or(true, |time| time.is_zero()) {
+                return Err(flags.invalid("duration", SECONDS));
+            }
+        }
+        flags.check::<u64>("report-interval", COUNT)?;
+        flags.check_positive::<u32>("scale")?;
+        flags.check_positive::<u64>("record-every")?;
+        flags.check_positive::<usize>("threads")?;
+        flags.check_positive::<u64>("autosave")?;
+        flags.check_positive::<usize>("autosave-keep")?;
+        if !flags.opt_present("autosave") {
+            let options = [
+                ("autosave-path", "--autosave-path"),
+                ("autosave-keep", "--autosave-keep"),
+            ];
+            if let Some(&(_, flag)) = options.iter().find(|(name, _)| flags.opt_present(name)) {
+                return Err(ArgsError::Missing {
+                    argument: "--autosave",
+                    required_by: flag,
+                });
+            }
+        }
+        flags.check::<u32>("cull-radius", COUNT)?;
+        flags.check_positive::<u64>("cull-every")?;
+        if flags.opt_present("cull-every") && !flags.opt_present("cull-radius") {
+            return Err(ArgsError::Missing {
+                argument: "--cull-radius",
+                required_by: "--cull-every",
+            });
+        }
+        if let Some(rule) = flags.opt_str("rule").filter(|rule| rule != "list") {
+            if parse_rule(&rule).is_none() {
+                let expected = "a name from `--rule list`, B/S notation or a MAP";
+                return Err(flags.invalid("rule", expected));
+            }
+        }
+        if let Some(list) = flags.opt_str("age-gradient") {
+            parse_age_gradient(&list)?;
+        }
+        if let Some(window) = flags.opt_str("record-window") {
+            parse_window(&window)?;
+        }
+        // the demo command brings its own patterns
+        for (name, flag) in [("pattern", "--pattern"), ("offset", "--offset")] {
+            if self.demo() && flags.opt_present(name) {
+                return Err(ArgsError::Conflict {
+                    flag,
+                    other: "the demo command",
+                });
+            }
+        }
+        if let Some(format) = flags.opt_str("stats-format") {
+            if format != "csv" && format != "json" {
+                return Err(flags.invalid("stats-format", "csv or json"));
+            }
+        }
+        match flags.opt_str("engine").as_deref() {
+            None | Some("scan") => {}
+            Some("hashlife") => {
+                let console = [
+                    (flags.opt_present("console"), "--console"),
+                    (self.edit(), "--edit"),
+                    (self.demo(), "the demo command"),
+                    (flags.opt_present("duration"), "--duration"),
+                    (flags.opt_present("autosave"), "--autosave"),
+                    (flags.opt_present("cull-radius"), "--cull-radius"),
+                    (
+                        self.rule().is_some_and(|rule| rule != Rule::CONWAY),
+                        "--rule",
+                    ),
+                ];
+                if let Some(&(_, other)) = console.iter().find(|(given, _)| *given) {
+                    return Err(ArgsError::Conflict {
+                        flag: "--engine hashlife",
+                        other,
+                    });
+                }
+                if !flags.opt_present("gens") {
+                    return Err(ArgsError::Missing {
+                        argument: "--gens",
+                        required_by: "--engine hashlife",
+                    });
+                }
+                if self.generations() as u64 > cgolrs::engine::HashLife::MAX_GENERATION {
+                    return Err(flags.invalid("gens", "at most 2^58 with --engine hashlife"));
+                }
+            }
+            Some(_) => return Err(flags.invalid("engine", "scan or hashlife")),
         }
+        Ok(())
     }
 
+    /// The options that pick the starting pattern
+    pub fn pattern(&self) -> PatternArgs<'_> {
+        PatternArgs {
+            flags: &self.flags,
+            console: self.console(),
+        }
+    }
+    /// Whether `demo` was given, which cycles through curated patterns
+    pub fn demo(&self) -> bool {
+        self.flags.free.first().is_some_and(|cmd| cmd == "demo")
+    }
+    pub fn edit(&self) -> bool {
+        self.flags.opt_present("edit")
+    }
+    pub fn ages(&self) -> bool {
+        self.flags.opt_present("ages") || self.flags.opt_present("age-gradient")
+    }
+    pub fn immigration(&self) -> bool {
+        self.flags.opt_present("immigration")
+    }
+    /// The rule given with `--rule`, if any
+    pub fn rule(&self) -> Option<Rule> {
+        let rule = self.flags.opt_str("rule").filter(|rule| rule != "list")?;
+        Some(parse_rule(&rule).expect("the rule is validated by Command::new"))
+    }
+    /// Whether `--rule list` asked for the names of the rules
+    pub fn list_rules(&self) -> bool {
+        self.flags
+            .opt_str("rule")
+            .is_some_and(|rule| rule == "list")
+    }
+    pub fn console(&self) -> bool {
+        self.demo() || self.edit() || self.flags.opt_present("console")
+    }
+    pub fn engine(&self) -> String {
+        let engine = self
+            .flags
+            .opt_str("engine")
+            .unwrap_or_else(|| "scan".to_owned());
+        debug_assert!(engine == "scan" || engine == "hashlife");
+        engine
+    }
+    pub fn threads(&self) -> usize {
+        self.flags.value("threads").unwrap_or(1)
+    }
+    pub fn generations(&self) -> usize {
+        self.flags.value("gens").unwrap_or(usize::MAX) // kinda hacky way of saying "infinity"
+    }
+    /// How the main loop waits between generations, if at all
+    pub fn pacing(&self) -> Option<Pacing> {
+        if let Some(millis) = self.flags.value("sleep") {
+            return Some(Pacing::Sleep(Duration::from_millis(millis)));
+        }
+        match self.flags.value("fps") {
+            Some(fps) => Some(Pacing::Fps(fps)),
+            None if self.console() => Some(Pacing::Fps(10)),
+            None => None,
+        }
+    }
     pub fn output_file(&self) -> Option<String> {
-        self.matches.opt_str("output")
+        self.flags.opt_str("output")
+    }
+    /// The pattern file format, if given explicitly
+    pub fn format(&self) -> Option<cgolrs::enc::Format> {
+        self.flags.format()
     }
-    pub fn input_file(&self) -> Option<String> {
-        self.matches.opt_str("input")
+    /// The size of a cell in pixels in PNG output and recordings
+    #[cfg(any(feature = "image", feature = "record"))]
+    // the window doesn't record, so it only reads the scale for PNG output
+    #[cfg_attr(not(feature = "image"), allow(dead_code))]
+    pub fn scale(&self) -> u32 {
+        self.flags.value("scale").unwrap_or(4)
     }
 }
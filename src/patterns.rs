@@ -0,0 +1,160 @@
+use crate::{
+    enc::{self, PositionEncoder},
+    pos::Pos2,
+    prelude::*,
+    transform,
+};
+
+/// A well-known pattern that ships with the binary
+pub struct Pattern {
+    pub name: &'static str,
+    /// What the pattern is known for, in a few words
+    pub description: &'static str,
+    /// The cell sequence of the pattern in RLE format (without the header line)
+    rle: &'static str,
+}
+impl Pattern {
+    /// Decodes the pattern into its alive cells, anchored at the origin
+    pub fn cells(&self) -> Vec<Pos2> {
+        enc::RunLengthEncoded::default().decode(self.rle)
+    }
+    /// The width and height of the pattern's bounding box
+    pub fn size(&self) -> (u32, u32) {
+        let bounds = transform::bounding_box(&self.cells());
+        (bounds.width(), bounds.height())
+    }
+}
+
+pub const PATTERNS: &[Pattern] = &[
+    Pattern {
+        name: "glider",
+        description: "the smallest spaceship, moves diagonally every 4 generations",
+        rle: "bo$2bo$3o!",
+    },
+    Pattern {
+        name: "lwss",
+        description: "the lightweight spaceship, moves sideways every 4 generations",
+        rle: "bo2bo$o4b$o3bo$4o!",
+    },
+    Pattern {
+        name: "blinker",
+        description: "the smallest oscillator, with period 2",
+        rle: "3o!",
+    },
+    Pattern {
+        name: "pulsar",
+        description: "an oscillator with period 3",
+        rle: "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    Pattern {
+        name: "r-pentomino",
+        description: "a methuselah that settles after 1103 generations",
+        rle: "b2o$2o$bo!",
+    },
+    Pattern {
+        name: "acorn",
+        description: "a methuselah that settles after 5206 generations",
+        rle: "bo$3bo$2o2b3o!",
+    },
+    Pattern {
+        name: "diehard",
+        description: "dies out after 130 generations",
+        rle: "6bo$2o$bo3b3o!",
+    },
+    Pattern {
+        name: "gosper-glider-gun",
+        description: "the first known gun, firing a glider every 30 generations",
+        rle: "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+];
+
+/// Finds a built-in pattern by its name
+pub fn find(name: &str) -> Option<&'static Pattern> {
+    PATTERNS.iter().find(|p| p.name == name)
+}
+
+/// The names of the built-in patterns that look like `name`, for suggestions after a typo
+///
+/// A name is similar if it contains `name`, or it's at most a third of its letters off.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::patterns;
+///
+/// assert_eq!(patterns::similar("gilder"), ["glider"]);
+/// assert_eq!(patterns::similar("glider-gun"), ["gosper-glider-gun"]);
+/// assert!(patterns::similar("spaceship").is_empty());
+/// ```
+pub fn similar(name: &str) -> Vec<&'static str> {
+    PATTERNS
+        .iter()
+        .map(|p| p.name)
+        .filter(|known| known.contains(name) || edit_distance(known, name) <= name.len() / 3)
+        .collect()
+}
+
+/// The number of single letter insertions, deletions or substitutions between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    // the distances of a's prefix so far to every prefix of b
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Cycles through a curated selection of patterns for the `demo` subcommand
+///
+/// # Example
+/// ```rust
+/// use cgolrs::patterns::DemoCycle;
+///
+/// let mut demo = DemoCycle::new();
+/// assert_eq!(demo.current().name, DemoCycle::ORDER[0]);
+/// for &name in &DemoCycle::ORDER[1..] {
+///     assert_eq!(demo.advance().name, name);
+/// }
+/// // the last pattern is followed by the first one again
+/// assert_eq!(demo.advance().name, DemoCycle::ORDER[0]);
+/// assert_eq!(demo.advance().name, DemoCycle::ORDER[1]);
+/// ```
+pub struct DemoCycle {
+    idx: usize,
+}
+impl DemoCycle {
+    /// The names of the curated patterns, in the order they are shown
+    pub const ORDER: &'static [&'static str] = &[
+        "gosper-glider-gun",
+        "r-pentomino",
+        "acorn",
+        "pulsar",
+        "lwss",
+        "diehard",
+    ];
+
+    pub fn new() -> Self {
+        Self { idx: 0 }
+    }
+
+    pub fn current(&self) -> &'static Pattern {
+        find(Self::ORDER[self.idx]).expect("demo pattern is built-in")
+    }
+
+    /// Moves on to the next curated pattern, wrapping around after the last one
+    pub fn advance(&mut self) -> &'static Pattern {
+        self.idx = (self.idx + 1) % Self::ORDER.len();
+        self.current()
+    }
+}
+impl Default for DemoCycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
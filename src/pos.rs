@@ -1,14 +1,33 @@
-use std::{
+use crate::prelude::*;
+use core::{
     cmp::Ordering,
-    ops::{Add, Neg, Sub},
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Pos2 {
     pub x: i32,
     pub y: i32,
 }
 impl Pos2 {
+    /// Creates a new position
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Pos2;
+    ///
+    /// const ORIGIN: Pos2 = Pos2::new(0, 0);
+    /// assert_eq!(ORIGIN, Pos2::zero());
+    /// assert_eq!(Pos2::new(-3, 4), Pos2 { x: -3, y: 4 });
+    /// ```
+    #[inline]
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
     #[inline]
     pub fn zero() -> Self {
         Self { x: 0, y: 0 }
@@ -17,7 +36,205 @@ impl Pos2 {
     pub fn one() -> Self {
         Self { x: 1, y: 1 }
     }
+
+    /// Adds two positions, returning [`None`] if either coordinate overflows
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Pos2;
+    ///
+    /// assert_eq!(Pos2::new(1, 2).checked_add(Pos2::new(3, 4)), Some(Pos2::new(4, 6)));
+    /// assert_eq!(Pos2::new(i32::MAX, 0).checked_add(Pos2::new(1, 0)), None);
+    /// ```
+    #[inline]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(rhs.x)?,
+            y: self.y.checked_add(rhs.y)?,
+        })
+    }
+    /// Subtracts two positions, returning [`None`] if either coordinate overflows
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Pos2;
+    ///
+    /// assert_eq!(Pos2::new(4, 6).checked_sub(Pos2::new(3, 4)), Some(Pos2::new(1, 2)));
+    /// assert_eq!(Pos2::new(i32::MIN, 0).checked_sub(Pos2::new(1, 0)), None);
+    /// ```
+    #[inline]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_sub(rhs.x)?,
+            y: self.y.checked_sub(rhs.y)?,
+        })
+    }
+    /// Adds two positions, clamping each coordinate at the bounds of [`i32`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Pos2;
+    ///
+    /// let edge = Pos2::new(i32::MAX - 1, i32::MIN + 1);
+    /// assert_eq!(edge.saturating_add(Pos2::new(5, -5)), Pos2::new(i32::MAX, i32::MIN));
+    /// ```
+    #[inline]
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x.saturating_add(rhs.x),
+            y: self.y.saturating_add(rhs.y),
+        }
+    }
+    /// Adds two positions, wrapping each coordinate around at the bounds of [`i32`]
+    ///
+    /// Note that wrapping does not preserve the ordering of positions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Pos2;
+    ///
+    /// let edge = Pos2::new(i32::MAX, 0);
+    /// assert_eq!(edge.wrapping_add(Pos2::new(1, 0)), Pos2::new(i32::MIN, 0));
+    /// ```
+    #[inline]
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x.wrapping_add(rhs.x),
+            y: self.y.wrapping_add(rhs.y),
+        }
+    }
+
+    /// The 8 surrounding positions (Moore neighborhood), in row-major order
+    ///
+    /// # Example
+    /// Counting the live neighbors of a cell:
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// // a horizontal blinker
+    /// let game = GameOfLife::from_alive(vec![
+    ///     Pos2 { x: 0, y: 1 },
+    ///     Pos2 { x: 1, y: 1 },
+    ///     Pos2 { x: 2, y: 1 },
+    /// ]);
+    /// let above_center = Pos2 { x: 1, y: 0 };
+    /// let alive = above_center.neighbors().filter(|&p| game.is_alive(p)).count();
+    /// assert_eq!(alive, 3);
+    /// ```
+    #[inline]
+    pub fn neighbors(&self) -> impl ExactSizeIterator<Item = Pos2> {
+        let center = *self;
+        MOORE_OFFSETS.iter().map(move |&offset| center + offset)
+    }
+    /// The 4 orthogonally adjacent positions (von Neumann neighborhood)
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_alive(vec![Pos2 { x: 0, y: 0 }, Pos2 { x: 1, y: 1 }]);
+    /// let alive = Pos2 { x: 1, y: 0 }
+    ///     .von_neumann_neighbors()
+    ///     .filter(|&p| game.is_alive(p))
+    ///     .count();
+    /// assert_eq!(alive, 2);
+    /// ```
+    #[inline]
+    pub fn von_neumann_neighbors(&self) -> impl ExactSizeIterator<Item = Pos2> {
+        let center = *self;
+        VON_NEUMANN_OFFSETS
+            .iter()
+            .map(move |&offset| center + offset)
+    }
+    /// All positions within a Chebyshev distance of `radius`, excluding the center itself
+    ///
+    /// The positions are yielded in row-major order, so `neighborhood(1)` is the same as
+    /// [`Pos2::neighbors`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::{engine::GameOfLife, Pos2};
+    ///
+    /// let game = GameOfLife::from_alive(vec![Pos2 { x: -2, y: -2 }, Pos2 { x: 1, y: 0 }]);
+    /// let center = Pos2::zero();
+    /// assert_eq!(center.neighborhood(2).len(), 24);
+    /// assert_eq!(center.neighborhood(1).filter(|&p| game.is_alive(p)).count(), 1);
+    /// assert_eq!(center.neighborhood(2).filter(|&p| game.is_alive(p)).count(), 2);
+    /// ```
+    #[inline]
+    pub fn neighborhood(&self, radius: u32) -> Neighborhood {
+        Neighborhood::new(*self, radius)
+    }
+}
+
+const MOORE_OFFSETS: [Pos2; 8] = [
+    Pos2 { x: -1, y: -1 },
+    Pos2 { x: 0, y: -1 },
+    Pos2 { x: 1, y: -1 },
+    Pos2 { x: -1, y: 0 },
+    Pos2 { x: 1, y: 0 },
+    Pos2 { x: -1, y: 1 },
+    Pos2 { x: 0, y: 1 },
+    Pos2 { x: 1, y: 1 },
+];
+const VON_NEUMANN_OFFSETS: [Pos2; 4] = [
+    Pos2 { x: 0, y: -1 },
+    Pos2 { x: -1, y: 0 },
+    Pos2 { x: 1, y: 0 },
+    Pos2 { x: 0, y: 1 },
+];
+
+/// An iterator over the positions surrounding a center, see [`Pos2::neighborhood`]
+#[derive(Debug, Clone)]
+pub struct Neighborhood {
+    center: Pos2,
+    radius: i32,
+    /// The offset from the center of the next position to yield
+    offset: Pos2,
+    remaining: usize,
 }
+impl Neighborhood {
+    fn new(center: Pos2, radius: u32) -> Self {
+        let radius = radius as i32;
+        let side = 2 * radius as usize + 1;
+        Self {
+            center,
+            radius,
+            offset: Pos2 {
+                x: -radius,
+                y: -radius,
+            },
+            remaining: side * side - 1,
+        }
+    }
+}
+impl Iterator for Neighborhood {
+    type Item = Pos2;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let offset = self.offset;
+            self.offset.x += 1;
+            if self.offset.x > self.radius {
+                self.offset.x = -self.radius;
+                self.offset.y += 1;
+            }
+
+            // the center isn't part of its own neighborhood
+            if offset != Pos2::zero() {
+                self.remaining -= 1;
+                return Some(self.center + offset);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl ExactSizeIterator for Neighborhood {}
 impl Default for Pos2 {
     #[inline]
     fn default() -> Self {
@@ -71,3 +288,254 @@ impl Sub for Pos2 {
         }
     }
 }
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// let mut pos = Pos2::new(1, 2);
+/// pos += Pos2::new(3, -4);
+/// assert_eq!(pos, Pos2::new(4, -2));
+/// ```
+impl AddAssign for Pos2 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// let mut pos = Pos2::new(1, 2);
+/// pos -= Pos2::new(3, -4);
+/// assert_eq!(pos, Pos2::new(-2, 6));
+/// ```
+impl SubAssign for Pos2 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+/// Scales both coordinates
+///
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// assert_eq!(Pos2::new(3, -4) * 2, Pos2::new(6, -8));
+/// ```
+impl Mul<i32> for Pos2 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+/// Divides both coordinates, rounding towards zero like integer division
+///
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// assert_eq!(Pos2::new(7, -8) / 2, Pos2::new(3, -4));
+/// ```
+impl Div<i32> for Pos2 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: i32) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// let pos: Pos2 = (3, -4).into();
+/// assert_eq!(pos, Pos2::new(3, -4));
+/// let (x, y) = pos.into();
+/// assert_eq!((x, y), (3, -4));
+/// ```
+impl From<(i32, i32)> for Pos2 {
+    #[inline]
+    fn from((x, y): (i32, i32)) -> Self {
+        Self { x, y }
+    }
+}
+impl From<Pos2> for (i32, i32) {
+    #[inline]
+    fn from(pos: Pos2) -> Self {
+        (pos.x, pos.y)
+    }
+}
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// assert_eq!(Pos2::from([3, -4]), Pos2::new(3, -4));
+/// ```
+impl From<[i32; 2]> for Pos2 {
+    #[inline]
+    fn from([x, y]: [i32; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Formats the position as `(x, y)`
+///
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// assert_eq!(Pos2::new(3, -4).to_string(), "(3, -4)");
+/// ```
+impl fmt::Display for Pos2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// The error returned when parsing a [`Pos2`] from a string fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePosError {
+    input: String,
+}
+impl fmt::Display for ParsePosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid position `{}`, expected `x,y`", self.input)
+    }
+}
+impl core::error::Error for ParsePosError {}
+
+/// Parses a position from `x,y`, allowing whitespace around either coordinate
+///
+/// ```rust
+/// use cgolrs::Pos2;
+///
+/// assert_eq!("3,-4".parse(), Ok(Pos2::new(3, -4)));
+/// assert_eq!(" -12 , 7 ".parse(), Ok(Pos2::new(-12, 7)));
+/// assert!("3".parse::<Pos2>().is_err());
+/// assert!("3,4,5".parse::<Pos2>().is_err());
+/// assert!("x,y".parse::<Pos2>().is_err());
+///
+/// // round-trip through the plain coordinates
+/// let pos = Pos2::new(-2147483648, 2147483647);
+/// assert_eq!(format!("{},{}", pos.x, pos.y).parse(), Ok(pos));
+/// ```
+impl FromStr for Pos2 {
+    type Err = ParsePosError;
+
+    /// Parses a position in the form of `x,y`, ignoring surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParsePosError {
+            input: s.to_owned(),
+        };
+        let (x, y) = s.split_once(',').ok_or_else(err)?;
+        Ok(Self {
+            x: x.trim().parse().map_err(|_| err())?,
+            y: y.trim().parse().map_err(|_| err())?,
+        })
+    }
+}
+
+/// A rectangle of positions, from `min` (inclusive) to `max` (exclusive) on both axes
+///
+/// Because the rectangle is half-open, `max - min` is its size and adjacent rectangles share no
+/// positions. A rectangle where `max` isn't past `min` on either axis is empty.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{Pos2, Rect};
+///
+/// let rect = Rect::from_size(Pos2::new(-1, -1), 3, 2);
+/// assert_eq!(rect.max, Pos2::new(2, 1));
+/// assert_eq!((rect.width(), rect.height(), rect.area()), (3, 2, 6));
+/// assert!(rect.contains(Pos2::new(-1, 0)));
+/// assert!(!rect.contains(Pos2::new(2, 0)));
+///
+/// let other = Rect::new(Pos2::new(1, 0), Pos2::new(5, 5));
+/// assert_eq!(rect.intersect(other), Rect::new(Pos2::new(1, 0), Pos2::new(2, 1)));
+/// assert_eq!(rect.union(other), Rect::new(Pos2::new(-1, -1), Pos2::new(5, 5)));
+/// assert!(rect.intersect(Rect::from_size(Pos2::new(10, 10), 1, 1)).is_empty());
+///
+/// let positions = Rect::from_size(Pos2::zero(), 2, 2).iter().collect::<Vec<_>>();
+/// assert_eq!(positions, [(0, 0), (1, 0), (0, 1), (1, 1)].map(Pos2::from));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub min: Pos2,
+    pub max: Pos2,
+}
+impl Rect {
+    /// Creates a rectangle from its top-left corner (inclusive) and bottom-right corner
+    /// (exclusive)
+    #[inline]
+    pub const fn new(min: Pos2, max: Pos2) -> Self {
+        Self { min, max }
+    }
+    /// Creates a rectangle of `width × height` positions starting at `origin`
+    ///
+    /// The size is clamped so the rectangle doesn't overflow [`i32`].
+    #[inline]
+    pub fn from_size(origin: Pos2, width: u32, height: u32) -> Self {
+        let size = Pos2::new(
+            width.min(i32::MAX as u32) as i32,
+            height.min(i32::MAX as u32) as i32,
+        );
+        Self::new(origin, origin.saturating_add(size))
+    }
+
+    /// The number of columns, which is 0 for empty rectangles
+    #[inline]
+    pub fn width(&self) -> u32 {
+        (self.max.x as i64 - self.min.x as i64).max(0) as u32
+    }
+    /// The number of rows, which is 0 for empty rectangles
+    #[inline]
+    pub fn height(&self) -> u32 {
+        (self.max.y as i64 - self.min.y as i64).max(0) as u32
+    }
+    /// The number of positions in the rectangle
+    #[inline]
+    pub fn area(&self) -> u64 {
+        self.width() as u64 * self.height() as u64
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.area() == 0
+    }
+
+    /// Whether `pos` is within the rectangle
+    #[inline]
+    pub fn contains(&self, pos: Pos2) -> bool {
+        (self.min.x..self.max.x).contains(&pos.x) && (self.min.y..self.max.y).contains(&pos.y)
+    }
+    /// The positions in both rectangles, which may be empty
+    pub fn intersect(&self, other: Rect) -> Rect {
+        Rect {
+            min: Pos2::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+            max: Pos2::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y)),
+        }
+    }
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: Rect) -> Rect {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Rect {
+            min: Pos2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Pos2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Every position in the rectangle in row-major order, the same order cells are sorted in
+    pub fn iter(&self) -> impl Iterator<Item = Pos2> {
+        let (min, max) = (self.min, self.max);
+        (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| Pos2::new(x, y)))
+    }
+}
@@ -0,0 +1,163 @@
+//! Animated GIF capture of a run
+//!
+//! This needs the `record` feature. Frames are written as they're captured, so recording a long
+//! run only ever holds one frame in memory.
+
+use crate::{engine::GameOfLife, Rect};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// The palette of every frame: black for dead cells and white for alive ones, like the console
+const PALETTE: [u8; 6] = [0, 0, 0, 255, 255, 255];
+
+/// Records a window of a run to an animated GIF, one frame every few generations
+///
+/// The GIF is finished by [`FrameRecorder::finish`], or when the recorder is dropped, which
+/// ignores any errors.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{engine::GameOfLife, patterns, record::FrameRecorder, Pos2, Rect};
+///
+/// let mut game = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+/// let rect = Rect::new(Pos2::new(-1, -1), Pos2::new(7, 7));
+/// let mut recorder = FrameRecorder::new(Vec::new(), rect, 4).unwrap().set_every(2);
+/// for _ in 0..8 {
+///     recorder.record(&game).unwrap();
+///     game.next_generation();
+/// }
+/// assert_eq!(recorder.frames(), 4);
+/// let gif = recorder.finish().unwrap();
+/// assert!(gif.starts_with(b"GIF89a"));
+///
+/// // GIF frames are at most 65535 pixels wide
+/// let wide = Rect::new(Pos2::new(0, 0), Pos2::new(20_000, 10));
+/// assert!(FrameRecorder::new(Vec::new(), wide, 4).is_err());
+/// let zero = FrameRecorder::new(Vec::new(), rect, 0).err().unwrap();
+/// assert_eq!(zero.to_string(), "cells can't be drawn at scale 0");
+/// ```
+pub struct FrameRecorder<W: Write> {
+    encoder: gif::Encoder<W>,
+    rect: Rect,
+    scale: u32,
+    every: u64,
+    delay: u16,
+    frames: usize,
+    /// The palette indices of the current frame, reused between frames
+    buffer: Vec<u8>,
+}
+impl FrameRecorder<BufWriter<File>> {
+    /// Creates a recorder writing to the file at `path`, see [`FrameRecorder::new`]
+    pub fn create(path: impl AsRef<Path>, rect: Rect, scale: u32) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), rect, scale)
+    }
+}
+impl<W: Write> FrameRecorder<W> {
+    /// Creates a recorder of the cells in `rect`, drawing each cell as a `scale × scale` block of
+    /// pixels
+    ///
+    /// ## Returns
+    /// An [`io::ErrorKind::InvalidInput`] error if the window is empty, `scale` is 0, or the
+    /// frames would be wider or taller than the 65535 pixels a GIF allows
+    pub fn new(w: W, rect: Rect, scale: u32) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+        if rect.is_empty() {
+            return Err(invalid("nothing to record in an empty window".to_owned()));
+        }
+        if scale == 0 {
+            return Err(invalid("cells can't be drawn at scale 0".to_owned()));
+        }
+        let size = |cells: u32| {
+            cells
+                .checked_mul(scale)
+                .and_then(|pixels| u16::try_from(pixels).ok())
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "a {}x{} window at scale {} is too large for a GIF",
+                        rect.width(),
+                        rect.height(),
+                        scale
+                    ))
+                })
+        };
+        let (width, height) = (size(rect.width())?, size(rect.height())?);
+
+        let mut encoder =
+            gif::Encoder::new(w, width, height, &PALETTE).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            encoder,
+            rect,
+            scale,
+            every: 1,
+            delay: 10,
+            frames: 0,
+            buffer: Vec::with_capacity(width as usize * height as usize),
+        })
+    }
+    /// Only records generations that are a multiple of `every`, 1 by default
+    ///
+    /// An `every` of 0 records every generation, like 1.
+    pub fn set_every(mut self, every: u64) -> Self {
+        self.every = every.max(1);
+        self
+    }
+    /// How long each frame is shown in hundredths of a second, 10 by default
+    pub fn set_delay(mut self, delay: u16) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The number of frames recorded so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Appends a frame of the current generation, if it's one of the recorded generations
+    ///
+    /// ## Returns
+    /// Whether a frame was recorded
+    pub fn record(&mut self, game: &GameOfLife) -> io::Result<bool> {
+        if !game.generation().is_multiple_of(self.every) {
+            return Ok(false);
+        }
+
+        // draw one row of pixels per row of cells and repeat it `scale` times
+        let bitmap = game.window(self.rect).to_bitmap();
+        let scale = self.scale as usize;
+        let row_len = bitmap.width as usize * scale;
+        self.buffer.clear();
+        for y in 0..bitmap.height {
+            let start = self.buffer.len();
+            for x in 0..bitmap.width {
+                let index = bitmap.get(x, y) as u8;
+                self.buffer.extend(std::iter::repeat_n(index, scale));
+            }
+            for _ in 1..scale {
+                self.buffer.extend_from_within(start..start + row_len);
+            }
+        }
+
+        let frame = gif::Frame {
+            width: row_len as u16,
+            height: (bitmap.height as usize * scale) as u16,
+            buffer: Cow::Borrowed(&self.buffer),
+            delay: self.delay,
+            ..Default::default()
+        };
+        self.encoder.write_frame(&frame).map_err(io::Error::other)?;
+        self.frames += 1;
+        Ok(true)
+    }
+
+    /// Finishes the GIF and returns the writer
+    pub fn finish(self) -> io::Result<W> {
+        self.encoder.into_inner()
+    }
+}
@@ -0,0 +1,580 @@
+use crate::prelude::*;
+use core::{fmt, str::FromStr};
+
+/// A Life-like rule, which decides the next state of a cell from its own state and its 8
+/// neighbors
+///
+/// Most rules are outer-totalistic, a set of neighbor counts that give birth to a dead cell and a
+/// set that let an alive cell survive, written `B3/S23` for Conway's Game of Life. Isotropic
+/// non-totalistic rules also tell the arrangements of the neighbors apart with the letters of
+/// Hensel notation, like `B2-a/S12`, and any other rule is written as the `MAP` of its
+/// neighborhood table. Well-known rules also have names, see [`Rule::PRESETS`]. The scanning
+/// engine simulates any rule, HashLife only [`Rule::CONWAY`].
+///
+/// Rules with `B0` bring the infinite dead background to life, so the scanning engine stores the
+/// cells that differ from the background and steps them with [`Rule::for_background`].
+///
+/// Generations rules like Brian's Brain (`B2/S/C3`) have more than the two states. Alive cells
+/// that don't survive pass through the dying states 2, 3 and so on before they're dead, and only
+/// the alive cells (state 1) count as neighbors or can survive. Dying cells can't be born again
+/// until they're dead. Generations rules with `B0` aren't supported.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::Rule;
+///
+/// let highlife: Rule = "B36/S23".parse().unwrap();
+/// assert!(highlife.is_born(6));
+/// assert!(highlife.survives(2) && !highlife.survives(6));
+/// assert_eq!(highlife.to_string(), "B36/S23");
+///
+/// // the older survival/birth notation
+/// assert_eq!("23/3".parse(), Ok(Rule::CONWAY));
+/// assert!("B9/S23".parse::<Rule>().is_err());
+///
+/// // Hensel notation, where `2-a` is every arrangement of 2 neighbors but the adjacent ones
+/// let rule: Rule = "B2-a/S12".parse().unwrap();
+/// assert_eq!(rule.to_string(), "B2-a/S12");
+/// assert_eq!("B2cekin/S12".parse(), Ok(rule));
+/// assert_eq!("B2cekain/S12".parse(), Ok(Rule::new(&[2], &[1, 2])));
+/// assert!("B2x/S12".parse::<Rule>().is_err());
+///
+/// // Generations rules, in both notations
+/// let brain: Rule = "/2/3".parse().unwrap();
+/// assert_eq!(brain.states(), 3);
+/// assert_eq!(brain.to_string(), "B2/S/C3");
+/// assert_eq!("b2/s/c3".parse(), Ok(brain));
+/// assert!("B2/S/C1".parse::<Rule>().is_err());
+/// assert!("B02/S/C3".parse::<Rule>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Rule {
+    /// Bit `i` is set when the center of the neighborhood `i` is alive in the next generation,
+    /// in the layout of [`Rule::build_table`]
+    table: [u64; 8],
+    /// The number of states including dead and alive, which is 2 unless it's a Generations rule
+    states: u8,
+}
+
+/// The number of 3x3 neighborhoods
+const NEIGHBORHOODS: usize = 1 << 9;
+/// The bit of the center cell in a neighborhood
+const CENTER: usize = 1 << 4;
+/// Every neighbor of the center cell
+const ALL_NEIGHBORS: usize = (NEIGHBORHOODS - 1) & !CENTER;
+
+/// The letters of Hensel notation for 1 to 4 neighbors with one of the arrangements each stands
+/// for, where bit `y * 3 + x` is the neighbor in column `x` and row `y`
+///
+/// A letter stands for all rotations and reflections of its arrangement. The letters for 5 to 7
+/// neighbors stand for the opposite arrangements of the same letters for 3 to 1.
+const HENSEL: [&[(char, usize)]; 4] = [
+    &[('c', 1), ('e', 2)],
+    &[
+        ('c', 5),
+        ('e', 10),
+        ('k', 33),
+        ('a', 3),
+        ('i', 40),
+        ('n', 68),
+    ],
+    &[
+        ('c', 69),
+        ('e', 42),
+        ('k', 98),
+        ('a', 11),
+        ('i', 7),
+        ('n', 13),
+        ('y', 97),
+        ('q', 70),
+        ('j', 14),
+        ('r', 41),
+    ],
+    &[
+        ('c', 325),
+        ('e', 170),
+        ('k', 99),
+        ('a', 15),
+        ('i', 45),
+        ('n', 71),
+        ('y', 101),
+        ('q', 102),
+        ('j', 106),
+        ('r', 43),
+        ('t', 105),
+        ('w', 78),
+        ('z', 108),
+    ],
+];
+
+/// The characters of the base64 strings of `MAP` rules
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// The length of the base64 string of a `MAP` rule, 512 bits padded to a multiple of 6
+const MAP_LEN: usize = NEIGHBORHOODS.div_ceil(6);
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`
+    pub const CONWAY: Rule = Rule::new(&[3], &[2, 3]);
+
+    /// Well-known rules by name, in the order they're listed
+    pub const PRESETS: &'static [(&'static str, Rule)] = &[
+        ("life", Self::CONWAY),
+        ("highlife", Rule::new(&[3, 6], &[2, 3])),
+        ("seeds", Rule::new(&[2], &[])),
+        ("day-and-night", Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8])),
+        ("diamoeba", Rule::new(&[3, 5, 6, 7, 8], &[5, 6, 7, 8])),
+        ("morley", Rule::new(&[3, 6, 8], &[2, 4, 5])),
+        ("2x2", Rule::new(&[3, 6], &[1, 2, 5])),
+        ("34-life", Rule::new(&[3, 4], &[3, 4])),
+        ("anneal", Rule::new(&[4, 6, 7, 8], &[3, 5, 6, 7, 8])),
+        ("coral", Rule::new(&[3], &[4, 5, 6, 7, 8])),
+        ("gnarl", Rule::new(&[1], &[1])),
+        (
+            "life-without-death",
+            Rule::new(&[3], &[0, 1, 2, 3, 4, 5, 6, 7, 8]),
+        ),
+        ("long-life", Rule::new(&[3, 4, 5], &[5])),
+        ("maze", Rule::new(&[3], &[1, 2, 3, 4, 5])),
+        ("replicator", Rule::new(&[1, 3, 5, 7], &[1, 3, 5, 7])),
+        ("brians-brain", Rule::generations(&[2], &[], 3)),
+        ("star-wars", Rule::generations(&[2], &[3, 4, 5], 4)),
+    ];
+
+    /// The preset with the name, in any case
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Rule;
+    ///
+    /// assert_eq!(Rule::from_name("Life"), Some(Rule::CONWAY));
+    /// assert_eq!(Rule::from_name("highlife"), "B36/S23".parse().ok());
+    /// assert_eq!(Rule::from_name("seeds").unwrap().to_string(), "B2/S");
+    /// assert_eq!(Rule::from_name("B3/S23"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Rule> {
+        let name = name.trim();
+        Self::PRESETS
+            .iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .map(|&(_, rule)| rule)
+    }
+    /// The name of the preset with this rule, if it's one of [`Rule::PRESETS`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Rule;
+    ///
+    /// assert_eq!(Rule::CONWAY.name(), Some("life"));
+    /// assert_eq!("B2/S".parse::<Rule>().unwrap().name(), Some("seeds"));
+    /// assert_eq!("B2/S1".parse::<Rule>().unwrap().name(), None);
+    /// ```
+    pub fn name(&self) -> Option<&'static str> {
+        Self::PRESETS
+            .iter()
+            .find(|(_, rule)| rule == self)
+            .map(|&(name, _)| name)
+    }
+
+    /// Creates a rule from the neighbor counts for birth and survival
+    ///
+    /// # Panics
+    /// If a count is more than 8
+    pub const fn new(birth: &[u8], survival: &[u8]) -> Self {
+        Self::generations(birth, survival, 2)
+    }
+    /// Creates a Generations rule with `states` states, counting dead and alive
+    ///
+    /// # Panics
+    /// If a count is more than 8, or there are less than 2 states
+    pub const fn generations(birth: &[u8], survival: &[u8], states: u8) -> Self {
+        assert!(states >= 2, "a rule has at least the dead and alive states");
+        let (birth, survival) = (Self::mask(birth), Self::mask(survival));
+        let mut table = [0; 8];
+        let mut i = 0;
+        while i < NEIGHBORHOODS {
+            let neighbors = (i & !CENTER).count_ones();
+            let counts = if i & CENTER != 0 { survival } else { birth };
+            if counts & 1 << neighbors != 0 {
+                table[i / 64] |= 1 << (i % 64);
+            }
+            i += 1;
+        }
+        Self { table, states }
+    }
+    const fn mask(counts: &[u8]) -> u16 {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < counts.len() {
+            assert!(counts[i] <= 8, "a cell has at most 8 neighbors");
+            mask |= 1 << counts[i];
+            i += 1;
+        }
+        mask
+    }
+    /// The rule with the next state of the center of each neighborhood from `next`
+    fn from_fn(states: u8, next: impl Fn(usize) -> bool) -> Self {
+        let mut table = [0; 8];
+        for i in (0..NEIGHBORHOODS).filter(|&i| next(i)) {
+            table[i / 64] |= 1 << (i % 64);
+        }
+        Self { table, states }
+    }
+
+    /// Whether the center of the neighborhood is alive in the next generation
+    #[inline]
+    const fn next(&self, neighborhood: usize) -> bool {
+        self.table[neighborhood / 64] & 1 << (neighborhood % 64) != 0
+    }
+    /// Whether a dead cell with `neighbors` alive neighbors is born, in at least one of their
+    /// arrangements for non-totalistic rules
+    pub fn is_born(&self, neighbors: u32) -> bool {
+        arrangements(neighbors).any(|i| self.next(i))
+    }
+    /// Whether an alive cell with `neighbors` alive neighbors survives, in at least one of their
+    /// arrangements for non-totalistic rules
+    pub fn survives(&self, neighbors: u32) -> bool {
+        arrangements(neighbors).any(|i| self.next(i | CENTER))
+    }
+    /// The number of states including dead and alive, more than 2 for Generations rules
+    #[inline]
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The next state of the center cell of every 3x3 neighborhood, for the lookup tables of the
+    /// engine
+    ///
+    /// The table is indexed by a 9-bit number, where each row of the neighborhood is 3 bits with
+    /// the top row in the lowest bits and the left cell of a row in its highest bit. The center
+    /// cell is the middle-most bit, `1 << 4`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Rule;
+    ///
+    /// let table = Rule::CONWAY.build_table();
+    /// // a dead cell below a row of 3 alive cells is born
+    /// assert!(table[0b000_000_111]);
+    /// // while an alive cell with a single neighbor dies
+    /// assert!(!table[0b000_010_001]);
+    /// ```
+    pub const fn build_table(&self) -> [bool; NEIGHBORHOODS] {
+        let mut table = [false; NEIGHBORHOODS];
+        let mut i = 0;
+        while i < NEIGHBORHOODS {
+            table[i] = self.next(i);
+            i += 1;
+        }
+        table
+    }
+
+    /// Whether the infinite background is alive in the generation after one where it's
+    /// `background`
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Rule;
+    ///
+    /// // without B0 the background stays dead
+    /// assert!(!Rule::CONWAY.next_background(false));
+    /// // with B0 but not S8 it flips every generation
+    /// let flipping: Rule = "B0123478/S3467".parse().unwrap();
+    /// assert!(flipping.next_background(false) && !flipping.next_background(true));
+    /// // with B0 and S8 it stays alive once born
+    /// let antilife: Rule = "B0123478/S01234678".parse().unwrap();
+    /// assert!(antilife.next_background(false) && antilife.next_background(true));
+    /// ```
+    pub fn next_background(self, background: bool) -> bool {
+        match background {
+            true => self.next(NEIGHBORHOODS - 1),
+            false => self.next(0),
+        }
+    }
+    /// The rule that steps the cells differing from a `background` that's dead or alive to the
+    /// cells differing from the next background
+    ///
+    /// On a dead background that stays dead this is the rule itself. The returned rule never has
+    /// `B0`, so the cells stay finite.
+    ///
+    /// # Example
+    /// ```rust
+    /// use cgolrs::Rule;
+    ///
+    /// assert_eq!(Rule::CONWAY.for_background(false), Rule::CONWAY);
+    /// // AntiLife is Life with the states swapped, so the dead cells on its alive background
+    /// // follow Life
+    /// let antilife: Rule = "B0123478/S01234678".parse().unwrap();
+    /// assert_eq!(antilife.for_background(true), Rule::CONWAY);
+    /// assert!(!antilife.for_background(false).is_born(0));
+    /// ```
+    pub fn for_background(self, background: bool) -> Rule {
+        // on an alive background the stored cells are the dead ones, so the actual neighborhood
+        // is the opposite, and on an alive next background the cells to store are the ones that
+        // end up dead
+        let flip = if background { NEIGHBORHOODS - 1 } else { 0 };
+        let next_background = self.next_background(background);
+        Self::from_fn(self.states, |i| self.next(i ^ flip) != next_background)
+    }
+
+    /// Whether the rule treats every rotation and reflection of a neighborhood the same, so it
+    /// can be written in Hensel notation
+    fn is_isotropic(&self) -> bool {
+        (0..NEIGHBORHOODS).all(|i| symmetries(i).all(|j| self.next(i) == self.next(j)))
+    }
+    /// Writes the birth or survival part of Hensel notation, with the shorter of the letters that
+    /// are in the rule and the ones that aren't for each count
+    fn write_counts(&self, f: &mut fmt::Formatter<'_>, center: usize) -> fmt::Result {
+        for neighbors in 0..=8 {
+            let classes = hensel_classes(neighbors);
+            let (included, excluded): (Vec<_>, Vec<_>) = classes
+                .iter()
+                .partition(|&&(_, arrangement)| self.next(arrangement | center));
+            let letters = |classes: Vec<&(Option<char>, usize)>| {
+                classes
+                    .into_iter()
+                    .filter_map(|&(letter, _)| letter)
+                    .collect::<String>()
+            };
+            match (included.len(), excluded.len()) {
+                (0, _) => {}
+                (_, 0) => write!(f, "{}", neighbors)?,
+                (i, e) if e < i => write!(f, "{}-{}", neighbors, letters(excluded))?,
+                _ => write!(f, "{}{}", neighbors, letters(included))?,
+            }
+        }
+        Ok(())
+    }
+    /// The base64 string of the `MAP` notation, with a bit for every neighborhood in the order
+    /// where the top left cell is the highest bit
+    fn map_string(&self) -> String {
+        let bit = |m: usize| m < NEIGHBORHOODS && self.next(flip_rows(m));
+        (0..MAP_LEN)
+            .map(|c| {
+                let index = (0..6).fold(0, |index, b| index << 1 | bit(c * 6 + b) as usize);
+                char::from(BASE64[index])
+            })
+            .collect()
+    }
+    /// Parses the base64 string of the `MAP` notation
+    fn from_map_string(map: &str, states: u8) -> Option<Self> {
+        let map = map.as_bytes();
+        if map.len() != MAP_LEN {
+            return None;
+        }
+        let mut bits = [false; MAP_LEN * 6];
+        for (c, &byte) in map.iter().enumerate() {
+            let index = BASE64.iter().position(|&b| b == byte)?;
+            for b in 0..6 {
+                bits[c * 6 + b] = index & 1 << (5 - b) != 0;
+            }
+        }
+        Some(Self::from_fn(states, |i| bits[flip_rows(i)]))
+    }
+}
+impl Default for Rule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+/// The neighborhoods with a dead center and `neighbors` alive neighbors
+fn arrangements(neighbors: u32) -> impl Iterator<Item = usize> {
+    (0..NEIGHBORHOODS).filter(move |&i| i & CENTER == 0 && i.count_ones() == neighbors)
+}
+
+/// Swaps the top and bottom rows of a neighborhood, which turns the layout of
+/// [`Rule::build_table`] into the one of the `MAP` notation and back
+fn flip_rows(neighborhood: usize) -> usize {
+    (neighborhood & 0b111) << 6 | neighborhood & 0b111_000 | neighborhood >> 6
+}
+
+/// The 8 rotations and reflections of a neighborhood in the layout of [`Rule::build_table`]
+fn symmetries(neighborhood: usize) -> impl Iterator<Item = usize> {
+    // the cells as (x, y), where the left cell of a row is its highest bit
+    let cells = (0..9)
+        .filter(|b| neighborhood & 1 << b != 0)
+        .map(|b| (2 - b % 3, b / 3))
+        .collect::<Vec<_>>();
+    (0..8).map(move |symmetry| {
+        cells
+            .iter()
+            .map(|&(x, y)| {
+                let (x, y) = if symmetry >= 4 { (2 - x, y) } else { (x, y) };
+                // rotate 90° clockwise as many times as needed
+                let (x, y) = (0..symmetry % 4).fold((x, y), |(x, y), _| (2 - y, x));
+                1 << (y * 3 + 2 - x)
+            })
+            .sum()
+    })
+}
+
+/// The Hensel letters for a neighbor count with one of their arrangements in the layout of
+/// [`Rule::build_table`], or a single arrangement without a letter for 0 and 8 neighbors
+fn hensel_classes(neighbors: u32) -> Vec<(Option<char>, usize)> {
+    // turn the `y * 3 + x` layout of HENSEL around within each row
+    let layout = |cells: usize| {
+        (0..9)
+            .filter(|b| cells & 1 << b != 0)
+            .map(|b| 1 << (b / 3 * 3 + 2 - b % 3))
+            .sum()
+    };
+    match neighbors {
+        0 => vec![(None, 0)],
+        1..=4 => HENSEL[neighbors as usize - 1]
+            .iter()
+            .map(|&(letter, cells)| (Some(letter), layout(cells)))
+            .collect(),
+        5..=7 => HENSEL[7 - neighbors as usize]
+            .iter()
+            .map(|&(letter, cells)| (Some(letter), layout(cells ^ ALL_NEIGHBORS)))
+            .collect(),
+        _ => vec![(None, ALL_NEIGHBORS)],
+    }
+}
+
+/// Parses the birth or survival part of Hensel notation, e.g. `2-a3`, setting the neighborhoods
+/// it covers with the `center` bit
+///
+/// Each count stands for all arrangements of that many neighbors, unless it's followed by the
+/// letters of the arrangements, or by `-` and the letters of the arrangements to leave out.
+fn parse_counts(counts: &str, center: usize, next: &mut [bool; NEIGHBORHOODS]) -> Option<()> {
+    let mut chars = counts.chars().peekable();
+    while let Some(c) = chars.next() {
+        let neighbors = c.to_digit(10).filter(|&n| n <= 8)?;
+        let exclude = chars.next_if_eq(&'-').is_some();
+        let mut letters = Vec::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            letters.push(letter.to_ascii_lowercase());
+        }
+        let classes = hensel_classes(neighbors);
+        let known = |letter| classes.iter().any(|&(l, _)| l == Some(letter));
+        if (exclude && letters.is_empty()) || !letters.iter().all(|&l| known(l)) {
+            return None;
+        }
+
+        for (letter, arrangement) in classes {
+            let listed = letter.is_some_and(|letter| letters.contains(&letter));
+            if letters.is_empty() || listed != exclude {
+                for i in symmetries(arrangement) {
+                    next[i | center] = true;
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_isotropic() {
+            write!(f, "B")?;
+            self.write_counts(f, 0)?;
+            write!(f, "/S")?;
+            self.write_counts(f, CENTER)?;
+        } else {
+            write!(f, "MAP{}", self.map_string())?;
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when parsing a [`Rule`] from a string fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRuleError {
+    input: String,
+}
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid rule `{}`, expected `B3/S23`, `B2-a/S12`, `B2/S/C3` or a `MAP`",
+            self.input
+        )
+    }
+}
+impl core::error::Error for ParseRuleError {}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// Parses a rule in the `B3/S23` notation (in any case and order) with optional Hensel
+    /// letters, the older `23/3` survival/birth notation, or the `MAP` notation, each with the
+    /// states of a Generations rule at the end like `B2/S/C3` or `/2/3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseRuleError {
+            input: s.to_owned(),
+        };
+        let parse_states = |states: Option<&str>| match states {
+            Some(states) => states
+                .trim_start_matches(['C', 'c'])
+                .parse()
+                .ok()
+                .filter(|&n| n >= 2)
+                .ok_or_else(err),
+            None => Ok(2),
+        };
+
+        let rule = match s.trim().strip_prefix("MAP") {
+            // base64 has slashes of its own, so the states come after the fixed length
+            Some(map) => {
+                let (map, states) = map.split_at_checked(MAP_LEN).ok_or_else(err)?;
+                let states = match states.trim_start_matches('=') {
+                    "" => None,
+                    states => Some(states.strip_prefix('/').ok_or_else(err)?),
+                };
+                Self::from_map_string(map, parse_states(states)?).ok_or_else(err)?
+            }
+            None => {
+                let mut parts = s.trim().split('/');
+                let (Some(first), Some(second), states, None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(err());
+                };
+                let tagged = |part: &str| {
+                    let mut chars = part.chars();
+                    let tag = chars.next()?.to_ascii_uppercase();
+                    matches!(tag, 'B' | 'S').then(|| (tag, chars.as_str().to_owned()))
+                };
+                let (birth, survival) = match (tagged(first), tagged(second)) {
+                    (Some(('B', birth)), Some(('S', survival)))
+                    | (Some(('S', survival)), Some(('B', birth))) => (birth, survival),
+                    (None, None) => (second.to_owned(), first.to_owned()),
+                    _ => return Err(err()),
+                };
+                let mut next = [false; NEIGHBORHOODS];
+                parse_counts(&birth, 0, &mut next).ok_or_else(err)?;
+                parse_counts(&survival, CENTER, &mut next).ok_or_else(err)?;
+                Self::from_fn(parse_states(states)?, |i| next[i])
+            }
+        };
+        // dying cells can't be told apart from an alive background
+        if rule.states > 2 && rule.is_born(0) {
+            return Err(err());
+        }
+        Ok(rule)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Rule> for String {
+    fn from(rule: Rule) -> Self {
+        rule.to_string()
+    }
+}
+#[cfg(feature = "serde")]
+impl TryFrom<String> for Rule {
+    type Error = ParseRuleError;
+
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        rule.parse()
+    }
+}
@@ -0,0 +1,401 @@
+//! Reading the starting pattern from the options, configuring the game and writing the output,
+//! shared by the command line and the GUI
+
+#[cfg(not(feature = "image"))]
+use std::io;
+use std::{fmt, io::Write, path::Path};
+
+use cgolrs::{enc, engine, patterns, transform, Pos2, Rule};
+
+use crate::options;
+
+/// The number of checkpoints kept for rewinding in console mode
+const HISTORY_CAPACITY: usize = 64;
+/// The number of generations between checkpoints, so console mode can rewind about 2000
+const HISTORY_INTERVAL: u64 = 32;
+
+/// Prints the names of the built-in patterns with their size and description
+fn print_patterns() {
+    for pattern in patterns::PATTERNS {
+        let (width, height) = pattern.size();
+        let size = format!("{}x{}", width, height);
+        println!("{:<20}{:>7}  {}", pattern.name, size, pattern.description);
+    }
+}
+
+/// Prints the lists asked for by `--pattern list` or `--fill list`, returning whether there was one
+pub fn print_lists(pattern: &options::PatternArgs) -> bool {
+    if pattern.list_patterns() {
+        print_patterns();
+    } else if pattern.list_fills() {
+        for &fill in cgolrs::fill::Fill::FILLS {
+            println!("{:<14}{}", fill, fill.description());
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Prints the lists asked for by `--pattern list`, `--fill list` or `--rule list`, returning
+/// whether there was one
+pub fn print_run_lists(args: &options::RunArgs) -> bool {
+    if print_lists(&args.pattern()) {
+        return true;
+    }
+    if !args.list_rules() {
+        return false;
+    }
+    for &(name, rule) in Rule::PRESETS {
+        println!("{:<20}{}", name, rule);
+    }
+    true
+}
+
+/// The starting pattern, which is only past generation 0 when resuming from a saved pattern
+///
+/// The input files and then the built-in `--pattern`s are moved by their offsets and combined
+/// into one pattern, which keeps the metadata, generation and rule of the first one. Cells that
+/// are alive in more than one input are reported as a warning. Without inputs, the grid is
+/// filled by `--fill`. Either way, the cells are moved by `--offset` last.
+///
+/// ## Returns
+/// The error of the first input file or image that can't be read, with its name.
+pub fn args_to_alive(args: &options::PatternArgs) -> Result<enc::Pattern, CommandError> {
+    let mut pattern = starting_pattern(args)?;
+    // translating keeps the cells sorted, even with negative offsets
+    pattern.cells = transform::translate(&pattern.cells, args.offset());
+    pattern.dying = translate_dying(&pattern.dying, args.offset());
+    Ok(pattern)
+}
+
+/// Moves the dying cells of a Generations pattern by `offset`, like [`transform::translate`]
+fn translate_dying(dying: &[(Pos2, u8)], offset: Pos2) -> Vec<(Pos2, u8)> {
+    dying
+        .iter()
+        .filter_map(|&(pos, state)| Some((pos.checked_add(offset)?, state)))
+        .collect()
+}
+
+/// The starting pattern of [`args_to_alive`] before it's moved by `--offset`
+fn starting_pattern(args: &options::PatternArgs) -> Result<enc::Pattern, CommandError> {
+    let files = args
+        .input_files()
+        .into_iter()
+        .map(|(file_name, offset)| read_input(args, &file_name, offset))
+        .collect::<Result<Vec<_>, _>>()?;
+    let builtins = args
+        .patterns()
+        .into_iter()
+        .map(|(pattern, offset)| builtin_pattern(pattern, offset));
+    let mut inputs = files.into_iter().chain(builtins);
+    if let Some(mut pattern) = inputs.next() {
+        let mut overlap = 0;
+        for input in inputs {
+            let cells = input.cells;
+            let (combined, overlapping) = transform::union(&pattern.cells, &cells);
+            pattern.cells = combined;
+            pattern.dying.extend(input.dying);
+            overlap += overlapping;
+        }
+        if overlap > 0 {
+            eprintln!(
+                "warning: {} cells are alive in more than one input",
+                overlap
+            );
+        }
+        return Ok(pattern);
+    }
+
+    // setup the alive cells based on args
+    let (grid_w, grid_h) = args.grid_size();
+    Ok(enc::Pattern {
+        format: enc::Format::Rle,
+        cells: args.fill().cells(grid_w, grid_h),
+        dying: Vec::new(),
+        meta: enc::PatternMeta::default(),
+        generation: 0,
+        rule: None,
+    })
+}
+
+/// The pattern of an input file moved by `offset`, with its cells sorted and deduplicated
+///
+/// Input files are decoded in the format given by `--format`, or else the detected format with
+/// the file extension as a fallback, and gzipped files are decompressed. PNG images are read
+/// with the dark pixels alive.
+fn read_input(
+    args: &options::PatternArgs,
+    file_name: &str,
+    offset: Pos2,
+) -> Result<enc::Pattern, CommandError> {
+    let mut pattern = if file_name.ends_with(".png") {
+        image_to_alive(args, file_name).map_err(|error| CommandError::file(file_name, error))?
+    } else {
+        enc::decode_file(file_name, args.format()).map_err(|error| {
+            let hint = matches!(
+                error,
+                cgolrs::Error::Decode(enc::DecodeError::UnknownFormat)
+            )
+            .then_some(", use --format for unknown formats");
+            CommandError::File {
+                file_name: file_name.to_owned(),
+                error,
+                hint: hint.unwrap_or_default(),
+            }
+        })?
+    };
+    // input files can't be trusted to be sorted, and merging them relies on it
+    pattern.cells = transform::translate(&pattern.cells, offset);
+    pattern.dying = translate_dying(&pattern.dying, offset);
+    pattern.cells.sort_unstable();
+    pattern.cells.dedup();
+    Ok(pattern)
+}
+
+/// A built-in pattern moved by `offset`, named after the pattern
+fn builtin_pattern(pattern: &patterns::Pattern, offset: Pos2) -> enc::Pattern {
+    let mut cells = transform::translate(&pattern.cells(), offset);
+    cells.sort_unstable();
+    enc::Pattern {
+        format: enc::Format::Rle,
+        cells,
+        dying: Vec::new(),
+        meta: enc::PatternMeta {
+            name: Some(pattern.name.to_owned()),
+            ..Default::default()
+        },
+        generation: 0,
+        rule: None,
+    }
+}
+
+/// The starting pattern from the dark pixels of an image, named after the file
+#[cfg(feature = "image")]
+fn image_to_alive(args: &options::PatternArgs, file_name: &str) -> cgolrs::Result<enc::Pattern> {
+    let cells = enc::image::decode_image(file_name, args.threshold())?;
+    let name = std::path::Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned());
+    Ok(enc::Pattern {
+        format: enc::Format::Rle,
+        cells,
+        dying: Vec::new(),
+        meta: enc::PatternMeta {
+            name,
+            ..Default::default()
+        },
+        generation: 0,
+        rule: None,
+    })
+}
+
+#[cfg(not(feature = "image"))]
+fn image_to_alive(_args: &options::PatternArgs, _file_name: &str) -> cgolrs::Result<enc::Pattern> {
+    let unsupported = io::Error::new(
+        io::ErrorKind::Unsupported,
+        "PNG input requires the `image` feature",
+    );
+    Err(unsupported.into())
+}
+
+/// Writes the final cells to the `--output` file in the format chosen by `--format`, RLE by
+/// default
+///
+/// RLE output keeps the name and comments of the input pattern and records the rule in its
+/// header, and names ending in `.gz` are compressed. Names ending in `.png` are saved as an image
+/// of the bounding box instead.
+pub fn write_output(
+    args: &options::RunArgs,
+    file_name: &str,
+    generation: u64,
+    alive: &[Pos2],
+    dying: &[(Pos2, u8)],
+    meta: &enc::PatternMeta,
+    rule: Rule,
+) -> cgolrs::Result<()> {
+    if file_name.ends_with(".png") {
+        #[cfg(feature = "image")]
+        return Ok(
+            engine::GameOfLife::from_alive(alive.to_vec()).save_png(file_name, args.scale())?
+        );
+        #[cfg(not(feature = "image"))]
+        return Err(png_unsupported().into());
+    }
+
+    let format = args.format().unwrap_or(enc::Format::Rle);
+    encode_file(
+        file_name,
+        format,
+        alive,
+        dying,
+        generation,
+        meta,
+        Some(rule),
+    )
+}
+
+/// The cells of the game to write to a pattern file, warning when an alive background is cut off
+pub fn cells_to_save(game: &engine::GameOfLife) -> std::borrow::Cow<'_, [Pos2]> {
+    if game.background() {
+        eprintln!(
+            "warning: generation {} has an alive background, only the bounding box of its dead \
+             cells is saved",
+            game.generation()
+        );
+    }
+    game.pattern_cells()
+}
+
+/// Writes a pattern to a file in the format, keeping as much of the metadata as it can store
+///
+/// Names ending in `.gz` are compressed. Only RLE stores the dying cells of a Generations rule.
+pub fn encode_file(
+    file_name: impl AsRef<Path>,
+    format: enc::Format,
+    alive: &[Pos2],
+    dying: &[(Pos2, u8)],
+    generation: u64,
+    meta: &enc::PatternMeta,
+    rule: Option<Rule>,
+) -> cgolrs::Result<()> {
+    let mut file = enc::create_file(file_name)?;
+    match format {
+        enc::Format::Rle => {
+            let mut meta = meta.clone();
+            meta.name
+                .get_or_insert_with(|| "cgol_sim generated pattern".to_owned());
+            let mut encoder = enc::RunLengthEncoded::default()
+                .set_meta(meta)
+                .set_generation(generation)
+                .set_dying(dying.to_vec());
+            if let Some(rule) = &rule {
+                encoder = encoder.with_rule(rule);
+            }
+            encoder.encode_to(alive, &mut file)
+        }
+        enc::Format::Life105 => {
+            let description = match (&meta.name, meta.comments.is_empty()) {
+                (_, false) => meta.comments.join(" "),
+                (Some(name), true) => name.clone(),
+                (None, true) => format!("cgol_sim generated pattern, generation {}", generation),
+            };
+            let encoder = enc::Life105::default().set_description(description);
+            file.write_all(enc::PositionEncoder::encode(encoder, alive).as_bytes())
+        }
+        enc::Format::Life106 => {
+            file.write_all(enc::PositionEncoder::encode(enc::Life106, alive).as_bytes())
+        }
+        enc::Format::Plaintext => {
+            let name = meta.name.as_deref().unwrap_or("cgol_sim generated pattern");
+            let encoder = enc::Plaintext::default().set_name(name);
+            file.write_all(enc::PositionEncoder::encode(encoder, alive).as_bytes())
+        }
+        enc::Format::Binary => {
+            let encoder = enc::Binary::default()
+                .set_generation(generation)
+                .with_rule(&rule.unwrap_or_default());
+            encoder.encode_to(alive, &mut file)
+        }
+    }?;
+    Ok(file.flush()?)
+}
+
+/// The error for PNG export in builds without the `image` feature
+#[cfg(not(feature = "image"))]
+pub fn png_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "PNG export requires the `image` feature",
+    )
+}
+
+/// The rule of the run: `--rule` if given, or else the rule of the starting pattern, or else
+/// Conway's Life
+pub fn resolve_rule(args: &options::RunArgs, pattern_rule: Option<Rule>) -> Rule {
+    args.rule().or(pattern_rule).unwrap_or_default()
+}
+
+/// Applies the options that apply to every game, including the ones switched to in demo mode
+pub fn configure(
+    args: &options::RunArgs,
+    rule: Rule,
+    game: &mut engine::GameOfLife,
+) -> cgolrs::Result<()> {
+    game.set_rule(rule)?;
+    game.set_threads(args.threads());
+    game.track_ages(args.ages());
+    game.track_colors(args.immigration());
+    if args.console() {
+        game.enable_history(HISTORY_CAPACITY, HISTORY_INTERVAL);
+    }
+    Ok(())
+}
+
+/// The error of a command, which `main` prints before exiting with its [`exit_code`]
+#[derive(Debug)]
+pub enum CommandError {
+    /// An error that isn't about one file in particular
+    Other(cgolrs::Error),
+    /// An error reading a file, followed by a hint on how to fix it
+    File {
+        file_name: String,
+        error: cgolrs::Error,
+        hint: &'static str,
+    },
+    /// A command that can't go on, like `convert` refusing to overwrite its input
+    // the GUI only runs patterns, which can't fail this way
+    #[cfg_attr(feature = "gui", allow(dead_code))]
+    Failed(String),
+}
+impl CommandError {
+    /// An error reading `file_name`, with no hint
+    pub fn file(file_name: &str, error: cgolrs::Error) -> Self {
+        Self::File {
+            file_name: file_name.to_owned(),
+            error,
+            hint: "",
+        }
+    }
+}
+impl<E: Into<cgolrs::Error>> From<E> for CommandError {
+    fn from(error: E) -> Self {
+        Self::Other(error.into())
+    }
+}
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Other(error) => error.fmt(f),
+            Self::File {
+                file_name,
+                error,
+                hint,
+            } => write!(f, "{}: {}{}", file_name, error, hint),
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+/// The exit status of a failed command, after the BSD `sysexits.h` codes
+///
+/// Invalid arguments exit with 2 before any command runs.
+pub fn exit_code(error: &CommandError) -> u8 {
+    let error = match error {
+        CommandError::Other(error) | CommandError::File { error, .. } => error,
+        CommandError::Failed(_) => return 1,
+    };
+    match error {
+        // EX_DATAERR, the input was wrong
+        cgolrs::Error::Decode(_) | cgolrs::Error::Rule(_) | cgolrs::Error::Invariant(_) => 65,
+        // EX_CANTCREAT, the output can't be written in the format asked for
+        cgolrs::Error::Encode(_) => 73,
+        // EX_IOERR
+        cgolrs::Error::Io(_) => 74,
+        #[cfg(feature = "image")]
+        cgolrs::Error::Image(enc::image::ImageError::Decode(image::ImageError::IoError(_))) => 74,
+        #[cfg(feature = "image")]
+        cgolrs::Error::Image(_) => 65,
+        _ => 1,
+    }
+}
@@ -0,0 +1,216 @@
+//! Searching many random soups and aggregating what they settle into
+//!
+//! Every soup is generated from the seed and its index, so any soup of a search can be
+//! regenerated with [`SoupSearch::soup`], and the results don't depend on the number of threads.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::soup::SoupSearch;
+//!
+//! let search = SoupSearch::new(8, 42).set_threads(2);
+//! let report = search.run();
+//! assert_eq!(report.soups, 8);
+//! assert!(report.census.count("xs4_33") > 0);
+//! assert_eq!(report, SoupSearch::new(8, 42).run());
+//!
+//! // the outliers can be regenerated for a closer look
+//! let longest = report.longest_lived.unwrap();
+//! assert_eq!(search.soup(longest.index).len(), longest.soup_population);
+//! let (rerun, _) = search.run_soup(longest.index);
+//! assert_eq!(rerun, longest);
+//! ```
+
+use crate::{
+    engine::{Census, GameOfLife, InvariantError},
+    Pos2, Rect,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+/// The number of generations the population must repeat over for a soup to count as settled
+const STABLE_WINDOW: usize = 180;
+/// The longest period of the population that counts as settled
+const MAX_STABLE_PERIOD: usize = 60;
+
+/// The outcome of running one soup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoupResult {
+    /// The index of the soup, see [`SoupSearch::soup`]
+    pub index: u64,
+    /// The number of alive cells of the soup before running it
+    pub soup_population: usize,
+    /// The number of generations until the soup settled, or the limit if it didn't
+    pub generations: u64,
+    /// The number of alive cells once it settled
+    pub final_population: usize,
+    /// Whether the soup settled within the generation limit
+    pub settled: bool,
+}
+
+/// The aggregated results of a [`SoupSearch`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoupReport {
+    /// The number of soups that were run
+    pub soups: usize,
+    /// The number of soups that didn't settle within the generation limit, which aren't part of
+    /// the census
+    pub unsettled: usize,
+    /// The objects of every settled soup
+    pub census: Census,
+    /// The soup that took the most generations to settle
+    pub longest_lived: Option<SoupResult>,
+    /// The settled soup with the most alive cells
+    pub largest: Option<SoupResult>,
+}
+impl SoupReport {
+    /// Adds the outcome of one soup
+    fn add(&mut self, result: SoupResult, census: &Census) {
+        self.soups += 1;
+        if !result.settled {
+            self.unsettled += 1;
+            return;
+        }
+        self.census.merge(census);
+        // ties go to the lowest index, so the report doesn't depend on the order of the soups
+        let better = |best: &Option<SoupResult>, key: fn(&SoupResult) -> u64| {
+            best.is_none_or(|best| {
+                (key(&result), std::cmp::Reverse(result.index))
+                    > (key(&best), std::cmp::Reverse(best.index))
+            })
+        };
+        if better(&self.longest_lived, |r| r.generations) {
+            self.longest_lived = Some(result);
+        }
+        if better(&self.largest, |r| r.final_population as u64) {
+            self.largest = Some(result);
+        }
+    }
+}
+
+/// A batch of seeded random soups, run until they settle and aggregated into a census
+pub struct SoupSearch {
+    count: u64,
+    seed: u64,
+    size: u32,
+    density: f64,
+    max_gens: u64,
+    threads: usize,
+}
+impl SoupSearch {
+    /// Creates a search of `count` soups from `seed`, each 16×16 cells at half density
+    pub fn new(count: u64, seed: u64) -> Self {
+        Self {
+            count,
+            seed,
+            size: 16,
+            density: 0.5,
+            max_gens: 20_000,
+            threads: 1,
+        }
+    }
+    /// The width and height of each soup
+    pub fn set_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+    /// The chance of each cell of a soup being alive
+    ///
+    /// ## Returns
+    /// [`InvariantError::Density`] if `density` isn't between 0 and 1
+    pub fn set_density(mut self, density: f64) -> Result<Self, InvariantError> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err(InvariantError::Density);
+        }
+        self.density = density;
+        Ok(self)
+    }
+    /// The most generations a soup is run for before giving up on it settling
+    pub fn set_max_gens(mut self, max_gens: u64) -> Self {
+        self.max_gens = max_gens;
+        self
+    }
+    /// The number of threads running soups, each one soup at a time
+    pub fn set_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// The starting cells of the soup with the index, with its top-left corner at the origin
+    pub fn soup(&self, index: u64) -> Vec<Pos2> {
+        let seed = self.seed ^ index.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let rect = Rect::from_size(Pos2::zero(), self.size, self.size);
+        GameOfLife::random(rect, self.density, seed)
+            .expect("the density is checked by set_density")
+            .alive()
+            .to_vec()
+    }
+
+    /// Runs one soup until its population repeats, see [`SoupSearch::soup`]
+    pub fn run_soup(&self, index: u64) -> (SoupResult, Census) {
+        let soup = self.soup(index);
+        let soup_population = soup.len();
+        let mut game = GameOfLife::from_alive(soup);
+        let mut populations = vec![game.alive_count()];
+        let mut settled = false;
+        while game.generation() < self.max_gens {
+            game.next_generation();
+            populations.push(game.alive_count());
+            if populations.len().is_multiple_of(30) && is_settled(&populations) {
+                settled = true;
+                break;
+            }
+        }
+
+        let census = if settled {
+            game.census()
+        } else {
+            Census::default()
+        };
+        let result = SoupResult {
+            index,
+            soup_population,
+            generations: game.generation(),
+            final_population: game.alive_count(),
+            settled,
+        };
+        (result, census)
+    }
+
+    /// Runs every soup, spread across the threads
+    pub fn run(&self) -> SoupReport {
+        let next = AtomicUsize::new(0);
+        let report = Mutex::new(SoupReport::default());
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed) as u64;
+                    if index >= self.count {
+                        break;
+                    }
+                    let (result, census) = self.run_soup(index);
+                    report
+                        .lock()
+                        .expect("no thread panics while holding the report")
+                        .add(result, &census);
+                });
+            }
+        });
+        report
+            .into_inner()
+            .expect("no thread panics while holding the report")
+    }
+}
+
+/// Whether the populations of the last generations repeat with a short period
+///
+/// Escaping gliders and other spaceships keep the population periodic too, so they don't stop a
+/// soup from settling.
+fn is_settled(populations: &[usize]) -> bool {
+    let Some(window) = populations.len().checked_sub(STABLE_WINDOW) else {
+        return false;
+    };
+    let recent = &populations[window..];
+    (1..=MAX_STABLE_PERIOD).any(|period| recent.windows(period + 1).all(|w| w[0] == w[period]))
+}
@@ -1,103 +1,621 @@
-use std::time::Instant;
+//! Recording the stats of a run, like the speed and population of every generation
+//!
+//! A [`Recorder`] is told about every step with [`Recorder::record`], usually from a hook of the
+//! game. [`SimpleRecord`] prints periodic reports, [`CsvRecord`] and [`JsonRecord`] stream every
+//! generation to a file, and [`MultiRecorder`] combines them.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{engine::GameOfLife, patterns, stats::{self, Phase, Recorder}};
+//! use std::{sync::{Arc, Mutex}, time::Duration};
+//!
+//! let mut game = GameOfLife::from_alive(patterns::find("glider").unwrap().cells());
+//! let recorder = stats::SimpleRecord::new(game.generation(), 5, Duration::ZERO);
+//! let recorder = Arc::new(Mutex::new(recorder));
+//! recorder.lock().unwrap().set_phase_timing(true);
+//! let hook_recorder = Arc::clone(&recorder);
+//! game.on_generation(move |event| hook_recorder.lock().unwrap().record(&event));
+//! for _ in 0..4 {
+//!     stats::timed_shared(&recorder, Phase::Compute, || game.next_generation());
+//! }
+//! let mut recorder = recorder.lock().unwrap();
+//! assert!(recorder.has_report(false));
+//! assert!(recorder.report().ends_with("gens:4, alive:5 | compute:100% render:0% idle:0%"));
+//! ```
 
+use crate::engine::GenerationEvent;
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The parts of a generation that are timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Computing the next generation
+    Compute,
+    /// Drawing the console
+    Render,
+    /// Sleeping between generations
+    Idle,
+    /// Writing autosaves
+    Save,
+}
+
+/// The time spent in each [`Phase`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseTimes([Duration; 4]);
+impl PhaseTimes {
+    pub fn add(&mut self, phase: Phase, time: Duration) {
+        self.0[phase as usize] += time;
+    }
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.0[phase as usize]
+    }
+    /// The share of each phase of the total time in percent, or `None` if nothing was timed
+    fn percentages(&self) -> Option<[f64; 4]> {
+        let total = self.0.iter().sum::<Duration>().as_secs_f64();
+        (total > 0.0).then(|| self.0.map(|time| time.as_secs_f64() / total * 100.0))
+    }
+}
+
+/// Runs `f` and adds the time it took to the phase, or just runs it if phase timing is off
+#[inline]
+pub fn timed<R: Recorder + ?Sized, T>(recorder: &mut R, phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !recorder.times_phases() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    recorder.add_phase(phase, start.elapsed());
+    result
+}
+/// Like [`timed`] for a recorder shared with a hook of the game, which is only locked around `f`
+/// so the hook can record the steps `f` computes
+pub fn timed_shared<R: Recorder + ?Sized, T>(
+    recorder: &Mutex<R>,
+    phase: Phase,
+    f: impl FnOnce() -> T,
+) -> T {
+    let lock = || recorder.lock().expect("stats recorder poisoned");
+    if !lock().times_phases() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    lock().add_phase(phase, start.elapsed());
+    result
+}
+
+/// Collects the stats of a run, usually as a `Box<dyn Recorder>`
+///
+/// Only [`Recorder::record`] is required, recorders that don't time phases, print reports or
+/// write files can leave the rest as is.
 pub trait Recorder {
-    type Str: AsRef<str>;
+    /// Records a generation right after its step
+    fn record(&mut self, event: &GenerationEvent<'_>);
 
-    fn record(&mut self, alive: usize);
+    /// Enables or disables timing the phases, which is checked before every timed phase
+    fn set_phase_timing(&mut self, _enabled: bool) {}
+    fn times_phases(&self) -> bool {
+        false
+    }
+    /// Adds time spent in a phase since the last record
+    fn add_phase(&mut self, _phase: Phase, _time: Duration) {}
+
+    /// Whether a periodic report is due, which is always the case in `interactive` mode
+    fn has_report(&self, _interactive: bool) -> bool {
+        false
+    }
+    /// The stats since the last report, or an empty string for recorders that don't report
+    fn report(&mut self) -> String {
+        String::new()
+    }
+    /// The stats of the whole run, printed once it's over
+    fn summary(&self) -> String {
+        String::new()
+    }
+    /// Writes everything recorded so far to the recorder's file, if it has one
+    fn save(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
 
-    fn has_report(&self, interactive: bool) -> bool;
-    fn report(&mut self) -> Self::Str;
+/// A source of timestamps, so tests can control the time [`SimpleRecord`] sees
+pub trait Clock {
+    /// The time since a fixed starting point
+    fn now(&self) -> Duration;
 }
 
-pub struct SimpleRecord {
-    gens: usize,
-    alive: usize,
+/// The real time since the clock was created
+pub struct MonotonicClock(Instant);
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+impl Clock for MonotonicClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Reports the speed and population of the run every interval
+///
+/// Besides the speed since the last report, the reports show an exponentially weighted moving
+/// average of the speed, which doesn't jump around with every hiccup, and the peak speed.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{engine::GameOfLife, stats::{Clock, Recorder, SimpleRecord}};
+/// use std::{cell::Cell, rc::Rc, time::Duration};
+///
+/// struct FakeClock(Rc<Cell<Duration>>);
+/// impl Clock for FakeClock {
+///     fn now(&self) -> Duration {
+///         self.0.get()
+///     }
+/// }
+///
+/// let time = Rc::new(Cell::new(Duration::ZERO));
+/// let clock = FakeClock(time.clone());
+/// let mut record = SimpleRecord::with_clock(0, 3, Duration::from_secs(1), clock).set_alpha(0.5);
+/// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+/// let mut run = |gens: u32, secs: u64| {
+///     for _ in 0..gens {
+///         game.step_with(|event| record.record(&event));
+///     }
+///     time.set(time.get() + Duration::from_secs(secs));
+///     assert!(record.has_report(false));
+///     record.report()
+/// };
+/// assert!(run(10, 1).starts_with("10.00gen/s (avg 10.00, peak 10.00) gens:10"));
+/// assert!(run(40, 2).starts_with("20.00gen/s (avg 15.00, peak 20.00) gens:50"));
+/// assert!(run(5, 1).starts_with("5.00gen/s (avg 10.00, peak 20.00) gens:55"));
+/// ```
+pub struct SimpleRecord<C: Clock = MonotonicClock> {
+    clock: C,
+    generation: u64,
+    /// The number of alive cells, or `None` if infinitely many are
+    alive: Option<usize>,
     gens_in_report: usize,
-    last_report: Instant,
+    last_report: Duration,
+    /// The time between periodic reports, where zero reports every generation
+    interval: Duration,
+    start: Duration,
+    start_generation: u64,
+    /// The weight of the latest speed in the moving average
+    alpha: f64,
+    /// The moving average of the speed, once there was a report
+    average: Option<f64>,
+    peak: f64,
+    phase_timing: bool,
+    /// The phase times since the last report
+    phases: PhaseTimes,
 }
 impl SimpleRecord {
-    pub fn new(alive: usize) -> Self {
+    pub fn new(generation: u64, alive: usize, interval: Duration) -> Self {
+        Self::with_clock(generation, alive, interval, MonotonicClock::default())
+    }
+}
+impl<C: Clock> SimpleRecord<C> {
+    /// Creates a record that takes its timestamps from `clock`
+    pub fn with_clock(generation: u64, alive: usize, interval: Duration, clock: C) -> Self {
+        let now = clock.now();
         Self {
-            gens: 0,
-            alive,
+            clock,
+            generation,
+            alive: Some(alive),
             gens_in_report: 0,
-            last_report: Instant::now(),
+            last_report: now,
+            interval,
+            start: now,
+            start_generation: generation,
+            alpha: 0.3,
+            average: None,
+            peak: 0.0,
+            phase_timing: false,
+            phases: PhaseTimes::default(),
         }
     }
+    /// The weight of the latest report in the moving average of the speed, from 0 to 1 (default
+    /// 0.3), where higher values follow changes faster
+    ///
+    /// # Panics
+    /// If alpha isn't within `0.0..=1.0`
+    pub fn set_alpha(mut self, alpha: f64) -> Self {
+        assert!((0.0..=1.0).contains(&alpha), "alpha must be within 0 and 1");
+        self.alpha = alpha;
+        self
+    }
 }
-impl Recorder for SimpleRecord {
-    type Str = String;
-
-    fn record(&mut self, alive: usize) {
-        self.gens += 1;
+impl<C: Clock> Recorder for SimpleRecord<C> {
+    fn record(&mut self, event: &GenerationEvent<'_>) {
+        self.generation = event.generation;
         self.gens_in_report += 1;
-        self.alive = alive;
+        self.alive = event.alive_count();
+    }
+
+    fn set_phase_timing(&mut self, enabled: bool) {
+        self.phase_timing = enabled;
+    }
+    #[inline]
+    fn times_phases(&self) -> bool {
+        self.phase_timing
+    }
+    fn add_phase(&mut self, phase: Phase, time: Duration) {
+        self.phases.add(phase, time);
     }
 
     fn has_report(&self, interactive: bool) -> bool {
-        interactive || self.last_report.elapsed().as_millis() >= 500
+        interactive || self.clock.now().saturating_sub(self.last_report) >= self.interval
     }
-    fn report(&mut self) -> Self::Str {
-        let gens_per_sec = self.gens_in_report as f64 / self.last_report.elapsed().as_secs_f64();
+    fn report(&mut self) -> String {
+        let now = self.clock.now();
+        let secs = now.saturating_sub(self.last_report).as_secs_f64();
+        let gens_per_sec = if secs > 0.0 {
+            self.gens_in_report as f64 / secs
+        } else {
+            0.0
+        };
+        let average = match self.average {
+            Some(average) => self.alpha * gens_per_sec + (1.0 - self.alpha) * average,
+            None => gens_per_sec,
+        };
+        self.average = Some(average);
+        self.peak = self.peak.max(gens_per_sec);
+        let phases = std::mem::take(&mut self.phases);
         // reset stats for next report
-        self.last_report = Instant::now();
+        self.last_report = now;
         self.gens_in_report = 0;
 
+        let mut report = format!(
+            "{:.02}gen/s (avg {:.02}, peak {:.02}) gens:{}, alive:{}",
+            gens_per_sec,
+            average,
+            self.peak,
+            self.generation,
+            alive_text(self.alive)
+        );
+        if let Some([compute, render, idle, save]) = phases.percentages() {
+            report += &format!(
+                " | compute:{:.0}% render:{:.0}% idle:{:.0}%",
+                compute, render, idle
+            );
+            // most runs don't autosave, so they don't get a column of zeros
+            if !phases.get(Phase::Save).is_zero() {
+                report += &format!(" save:{:.0}%", save);
+            }
+        }
+        report
+    }
+    fn summary(&self) -> String {
+        let gens = self.generation - self.start_generation;
+        let secs = self.clock.now().saturating_sub(self.start).as_secs_f64();
+        // the peak is only measured by the reports, which --quiet runs never make
+        let peak = match self.average {
+            Some(_) => format!(", peak {:.02}", self.peak),
+            None => String::new(),
+        };
         format!(
-            "{:.02}gen/s gens:{}, alive:{}",
-            gens_per_sec, self.gens, self.alive
+            "ran {} generations in {:.3}s ({:.02}gen/s{}), gens:{}, alive:{}",
+            gens,
+            secs,
+            gens as f64 / secs,
+            peak,
+            self.generation,
+            alive_text(self.alive)
         )
     }
 }
 
+/// The number of alive cells in reports, which is infinite on an alive background
+fn alive_text(alive: Option<usize>) -> String {
+    alive.map_or_else(|| "infinite".to_owned(), |alive| alive.to_string())
+}
+
+/// The longest time recorded stats stay in memory before they're flushed to the file
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Streams a CSV line per generation to a file as the run goes, see [`CsvRecord::create`]
 pub struct CsvRecord {
-    inner: SimpleRecord,
-    gens: usize,
-    data: Vec<(u128, usize)>,
+    file: io::BufWriter<fs::File>,
     last: Instant,
+    last_flush: Instant,
+    phase_timing: bool,
+    /// The phase times since the last record
+    phases: PhaseTimes,
+    /// The first error writing the file, which stops the recording until [`Recorder::save`]
+    /// returns it
+    error: Option<io::Error>,
 }
 impl CsvRecord {
-    pub fn new(alive: usize) -> Self {
-        Self {
-            inner: SimpleRecord::new(alive),
-            gens: 0,
-            data: Vec::new(),
+    /// Creates a recorder writing `gen,delta_t,alive,compute_us,render_us,idle_us,save_us` lines
+    /// to `path`, starting with the header
+    ///
+    /// The phase columns are empty unless phase timing is enabled, and `alive` is empty while
+    /// infinitely many cells are alive on an alive background. The lines are flushed every
+    /// second and when the recorder is dropped, so a run that's killed loses at most the last
+    /// second. Recording can't fail, so if writing the file does, nothing more is written and
+    /// the next save returns the error.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(
+            file,
+            "gen,delta_t,alive,compute_us,render_us,idle_us,save_us"
+        )?;
+        Ok(Self {
+            file,
             last: Instant::now(),
-        }
+            last_flush: Instant::now(),
+            phase_timing: false,
+            phases: PhaseTimes::default(),
+            error: None,
+        })
     }
 }
 impl Recorder for CsvRecord {
-    type Str = String; // doesnt matter in this case
-
-    fn record(&mut self, alive: usize) {
+    fn record(&mut self, event: &GenerationEvent<'_>) {
+        if self.error.is_some() {
+            return;
+        }
         let delta = self.last.elapsed().as_micros();
         self.last = Instant::now();
-        self.gens += 1;
 
-        self.data.push((delta, alive));
-        self.inner.record(alive);
+        let phases = std::mem::take(&mut self.phases);
+        let phase_columns = if self.phase_timing {
+            let micros = |phase| phases.get(phase).as_micros();
+            let (compute, render, idle) = (Phase::Compute, Phase::Render, Phase::Idle);
+            format!(
+                "{},{},{},{}",
+                micros(compute),
+                micros(render),
+                micros(idle),
+                micros(Phase::Save)
+            )
+        } else {
+            ",,,".to_owned()
+        };
+        // infinitely many cells are alive on an alive background
+        let alive = event
+            .alive_count()
+            .map_or(String::new(), |alive| alive.to_string());
+        let written = writeln!(
+            self.file,
+            "{},{},{},{}",
+            event.generation, delta, alive, phase_columns
+        );
+        let flushed = match written {
+            Ok(()) if self.last_flush.elapsed() >= FLUSH_INTERVAL => self.save(),
+            written => written,
+        };
+        self.error = flushed.err();
     }
 
-    // never has a console report
-    fn has_report(&self, interactive: bool) -> bool {
-        self.inner.has_report(interactive)
+    fn set_phase_timing(&mut self, enabled: bool) {
+        self.phase_timing = enabled;
+    }
+    #[inline]
+    fn times_phases(&self) -> bool {
+        self.phase_timing
     }
-    fn report(&mut self) -> Self::Str {
-        self.inner.report()
+    fn add_phase(&mut self, phase: Phase, time: Duration) {
+        self.phases.add(phase, time);
+    }
+
+    /// Writes the buffered lines to the file, or returns the error that stopped the recording
+    fn save(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.last_flush = Instant::now();
+        self.file.flush()
     }
 }
 impl Drop for CsvRecord {
     fn drop(&mut self) {
-        use std::{fs, io::{self, Write}};
+        // errors are reported by an explicit save, and would abort if this drop is unwinding
+        let _ = self.save();
+    }
+}
 
-        let file = fs::File::create("perf.csv").expect("create perf.csv");
-        let mut file = io::BufWriter::new(file);
+/// Streams one JSON object per generation to a file as the run goes, see [`JsonRecord::create`]
+pub struct JsonRecord {
+    file: io::LineWriter<fs::File>,
+    last: Instant,
+    start: Instant,
+    start_generation: u64,
+    generation: u64,
+    /// The number of alive cells, or `None` if infinitely many are
+    alive: Option<usize>,
+    births: u64,
+    deaths: u64,
+    /// The first error writing the file, which stops the recording until [`Recorder::save`]
+    /// returns it
+    error: Option<io::Error>,
+}
+impl JsonRecord {
+    /// Creates a recorder writing NDJSON to `path`, for a run starting at `generation` with
+    /// `alive` cells
+    ///
+    /// Every generation is a line like `{"gen":1,"delta_us":12,"alive":6,"births":2,"deaths":1}`
+    /// and the run ends with a summary line like `{"summary":true,"gens":1,...}`, where `alive` is
+    /// `null` while infinitely many cells are alive on an alive background. Like
+    /// [`CsvRecord`], it stops writing at the first error, which the next save returns.
+    pub fn create(path: impl AsRef<Path>, generation: u64, alive: usize) -> io::Result<Self> {
+        Ok(Self {
+            file: io::LineWriter::new(fs::File::create(path)?),
+            last: Instant::now(),
+            start: Instant::now(),
+            start_generation: generation,
+            generation,
+            alive: Some(alive),
+            births: 0,
+            deaths: 0,
+            error: None,
+        })
+    }
+}
+impl Recorder for JsonRecord {
+    fn record(&mut self, event: &GenerationEvent<'_>) {
+        let delta = self.last.elapsed().as_micros();
+        self.last = Instant::now();
+        self.generation = event.generation;
+        self.alive = event.alive_count();
+
+        let (births, deaths) = event.changes();
+        self.births += births as u64;
+        self.deaths += deaths as u64;
+        if self.error.is_some() {
+            return;
+        }
+        let written = writeln!(
+            self.file,
+            r#"{{"gen":{},"delta_us":{},"alive":{},"births":{},"deaths":{}}}"#,
+            self.generation,
+            delta,
+            alive_json(self.alive),
+            births,
+            deaths
+        );
+        self.error = written.err();
+    }
 
-        file.write_all(b"gen,delta_t,alive").unwrap();
-        for (i, (delta, alive)) in self.data.iter().enumerate() {
-            let line = format!("{},{},{}\n", i, delta, alive);
-            file.write_all(line.as_bytes()).unwrap();
+    /// Flushes the last line, or returns the error that stopped the recording
+    fn save(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
         }
-        file.flush().unwrap();
+        self.file.flush()
+    }
+}
+impl Drop for JsonRecord {
+    fn drop(&mut self) {
+        // errors are reported by an explicit save, and would abort if this drop is unwinding
+        let _ = writeln!(
+            self.file,
+            concat!(
+                r#"{{"summary":true,"gens":{},"elapsed_us":{},"generation":{},"alive":{},"#,
+                r#""births":{},"deaths":{}}}"#
+            ),
+            self.generation - self.start_generation,
+            self.start.elapsed().as_micros(),
+            self.generation,
+            alive_json(self.alive),
+            self.births,
+            self.deaths
+        );
     }
 }
+
+/// The number of alive cells as JSON, which is `null` on an alive background
+fn alive_json(alive: Option<usize>) -> String {
+    alive.map_or_else(|| "null".to_owned(), |alive| alive.to_string())
+}
+
+/// Passes everything on to several recorders, like console reports and a CSV file at once
+///
+/// Reports and summaries are the non-empty ones of the children, joined by ` | `.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{engine::{GameOfLife, GenerationEvent}, stats::{MultiRecorder, Recorder}};
+/// use std::sync::{Arc, Mutex};
+///
+/// /// Logs every call it gets
+/// struct Mock(&'static str, Arc<Mutex<Vec<String>>>);
+/// impl Recorder for Mock {
+///     fn record(&mut self, event: &GenerationEvent<'_>) {
+///         self.1.lock().unwrap().push(format!("{} record {}", self.0, event.generation));
+///     }
+///     fn has_report(&self, _interactive: bool) -> bool {
+///         self.1.lock().unwrap().push(format!("{} has_report", self.0));
+///         self.0 == "b"
+///     }
+///     fn report(&mut self) -> String {
+///         self.1.lock().unwrap().push(format!("{} report", self.0));
+///         self.0.to_owned()
+///     }
+///     fn save(&mut self) -> std::io::Result<()> {
+///         self.1.lock().unwrap().push(format!("{} save", self.0));
+///         Ok(())
+///     }
+/// }
+///
+/// let log = Arc::new(Mutex::new(Vec::new()));
+/// let mut multi = MultiRecorder(vec![
+///     Box::new(Mock("a", log.clone())),
+///     Box::new(Mock("b", log.clone())),
+/// ]);
+/// let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+/// game.step_with(|event| multi.record(&event));
+/// assert!(multi.has_report(false));
+/// assert_eq!(multi.report(), "a | b");
+/// multi.save().unwrap();
+/// assert_eq!(
+///     *log.lock().unwrap(),
+///     [
+///         "a record 1",
+///         "b record 1",
+///         "a has_report",
+///         "b has_report",
+///         "a report",
+///         "b report",
+///         "a save",
+///         "b save",
+///     ]
+/// );
+/// ```
+pub struct MultiRecorder(pub Vec<Box<dyn Recorder + Send>>);
+impl Recorder for MultiRecorder {
+    fn record(&mut self, event: &GenerationEvent<'_>) {
+        for recorder in &mut self.0 {
+            recorder.record(event);
+        }
+    }
+
+    fn set_phase_timing(&mut self, enabled: bool) {
+        for recorder in &mut self.0 {
+            recorder.set_phase_timing(enabled);
+        }
+    }
+    fn times_phases(&self) -> bool {
+        self.0.iter().any(|recorder| recorder.times_phases())
+    }
+    fn add_phase(&mut self, phase: Phase, time: Duration) {
+        for recorder in &mut self.0 {
+            recorder.add_phase(phase, time);
+        }
+    }
+
+    fn has_report(&self, interactive: bool) -> bool {
+        self.0
+            .iter()
+            .any(|recorder| recorder.has_report(interactive))
+    }
+    fn report(&mut self) -> String {
+        join(self.0.iter_mut().map(|recorder| recorder.report()))
+    }
+    fn summary(&self) -> String {
+        join(self.0.iter().map(|recorder| recorder.summary()))
+    }
+    /// Saves every recorder, even if saving an earlier one failed
+    fn save(&mut self) -> io::Result<()> {
+        self.0
+            .iter_mut()
+            .map(|recorder| recorder.save())
+            .fold(Ok(()), Result::and)
+    }
+}
+
+/// Joins the non-empty strings with ` | `
+fn join(strings: impl Iterator<Item = String>) -> String {
+    strings
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
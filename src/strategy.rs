@@ -0,0 +1,40 @@
+//! [`proptest`] strategies for cells and games that keep the invariants of the engine, so
+//! property tests don't need their own generators of sorted cells
+//!
+//! The cells are drawn from a [`Rect`], which has to be non-empty and within
+//! [`COORD_RANGE`](crate::engine::COORD_RANGE). A small area keeps the cells close enough to
+//! interact.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{strategy, Pos2, Rect};
+//! use proptest::{strategy::{Strategy, ValueTree}, test_runner::TestRunner};
+//!
+//! let area = Rect::from_size(Pos2::new(-8, -8), 16, 16);
+//! let mut runner = TestRunner::deterministic();
+//! for _ in 0..100 {
+//!     let cells = strategy::cells(area, 64).new_tree(&mut runner).unwrap().current();
+//!     assert!(cells.len() <= 64);
+//!     assert!(cells.windows(2).all(|w| w[0] < w[1]));
+//!     assert!(cells.iter().all(|&pos| area.contains(pos)));
+//! }
+//! ```
+
+use crate::{engine::GameOfLife, Pos2, Rect};
+use proptest::{collection, strategy::Strategy};
+
+/// A position within `area`
+pub fn pos_in(area: Rect) -> impl Strategy<Value = Pos2> {
+    (area.min.x..area.max.x, area.min.y..area.max.y).prop_map(Pos2::from)
+}
+
+/// Up to `max_cells` distinct cells within `area`, sorted by y and then x
+pub fn cells(area: Rect, max_cells: usize) -> impl Strategy<Value = Vec<Pos2>> {
+    // a set is sorted by the order of `Pos2`, which is the order of the engine
+    collection::btree_set(pos_in(area), 0..=max_cells).prop_map(|cells| cells.into_iter().collect())
+}
+
+/// A game of Conway's Life with up to `max_cells` alive cells within `area`
+pub fn game(area: Rect, max_cells: usize) -> impl Strategy<Value = GameOfLife> {
+    cells(area, max_cells).prop_map(GameOfLife::from_alive)
+}
@@ -0,0 +1,218 @@
+//! Helpers for checking that engines evolve patterns the same way
+//!
+//! Every engine and every step mode (threads, incremental steps, reused buffers) has to produce
+//! exactly the generations of the plain single-threaded engine. [`assert_same_evolution`] steps
+//! two engines side by side and panics at the first generation where they differ,
+//! [`FIXTURES`] are a dozen canonical patterns to run them on, and [`Reference`] is a naive dense
+//! implementation of Conway's Life to serve as ground truth.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{engine::GameOfLife, testing};
+//!
+//! for fixture in testing::FIXTURES {
+//!     let mut game = GameOfLife::from_alive(fixture.cells());
+//!     let mut reference = testing::Reference::from_alive(&fixture.cells());
+//!     testing::assert_same_evolution(&mut game, &mut reference, 30);
+//! }
+//! ```
+
+use crate::{enc::RunLengthEncoded, engine::Engine, transform, Pos2, Rect};
+
+/// A canonical pattern, stored as an RLE file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixture {
+    /// The name of the pattern, which is also the name of its file
+    pub name: &'static str,
+    /// The RLE file of the pattern, with a `#N` line and a header
+    pub rle: &'static str,
+}
+
+impl Fixture {
+    /// The alive cells of the pattern, sorted by y and then x
+    ///
+    /// # Panics
+    /// If the RLE file doesn't decode strictly, which is checked by the tests.
+    pub fn cells(&self) -> Vec<Pos2> {
+        RunLengthEncoded::default()
+            .try_decode(self.rle)
+            .unwrap_or_else(|err| panic!("fixture {} doesn't decode: {}", self.name, err))
+    }
+}
+
+macro_rules! fixtures {
+    ($($name:literal),* $(,)?) => {
+        [$(Fixture {
+            name: $name,
+            rle: include_str!(concat!("fixtures/", $name, ".rle")),
+        }),*]
+    };
+}
+
+/// Still lifes, oscillators, spaceships, a methuselah and a gun
+pub const FIXTURES: [Fixture; 12] = fixtures![
+    "block",
+    "beehive",
+    "blinker",
+    "toad",
+    "beacon",
+    "pulsar",
+    "pentadecathlon",
+    "glider",
+    "lwss",
+    "copperhead",
+    "r-pentomino",
+    "gosper-glider-gun",
+];
+
+/// The fixture named `name`, if there is one
+pub fn fixture(name: &str) -> Option<Fixture> {
+    FIXTURES.into_iter().find(|fixture| fixture.name == name)
+}
+
+/// Steps `a` and `b` for `gens` generations, checking that they have the same alive cells before
+/// the first step and after every step
+///
+/// # Panics
+/// At the first generation where the engines differ, naming the first cell that's alive in only
+/// one of them.
+pub fn assert_same_evolution(a: &mut impl Engine, b: &mut impl Engine, gens: usize) {
+    for generation in 0..=gens {
+        if generation > 0 {
+            a.next_generation();
+            b.next_generation();
+        }
+        let mut cells_a = a.iter();
+        let mut cells_b = b.iter();
+        loop {
+            match (cells_a.next(), cells_b.next()) {
+                (None, None) => break,
+                (x, y) if x == y => {}
+                // the cell that comes first is the one the other engine is missing
+                (Some(x), y) if y.is_none_or(|y| x < y) => panic!(
+                    "engines differ at generation {}: {} is only alive in the first",
+                    generation, x
+                ),
+                (_, y) => panic!(
+                    "engines differ at generation {}: {} is only alive in the second",
+                    generation,
+                    y.unwrap()
+                ),
+            }
+        }
+    }
+}
+
+/// Computes the next generation of `grid` by counting the neighbors of every cell, the plainest
+/// possible implementation of Conway's Life
+///
+/// The cells are indexed as `grid[y][x]`, every row has the same length, and everything outside
+/// of the grid is dead, so patterns reaching the edge lose the cells that would be born past it.
+pub fn step_reference(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let height = grid.len() as isize;
+    let alive = |x: isize, y: isize| {
+        y >= 0 && y < height && x >= 0 && grid[y as usize].get(x as usize) == Some(&true)
+    };
+    grid.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let y = y as isize;
+            (0..row.len() as isize)
+                .map(|x| {
+                    let mut neighbors = 0;
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            if (dx, dy) != (0, 0) && alive(x + dx, y + dy) {
+                                neighbors += 1;
+                            }
+                        }
+                    }
+                    neighbors == 3 || (neighbors == 2 && alive(x, y))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// An unbounded [`Engine`] built on [`step_reference`]
+///
+/// The grid is the bounding box of the alive cells, padded by a row and a column on every side
+/// before each step so nothing is lost at the edge. It's slow, but simple enough to trust.
+#[derive(Debug, Clone, Default)]
+pub struct Reference {
+    /// The position of `grid[0][0]`
+    origin: Pos2,
+    grid: Vec<Vec<bool>>,
+}
+
+impl Reference {
+    /// Creates the engine from alive cells, sorted by y and then x
+    pub fn from_alive(alive: &[Pos2]) -> Self {
+        let bounds = transform::bounding_box(alive);
+        let mut grid = vec![vec![false; bounds.width() as usize]; bounds.height() as usize];
+        for pos in alive {
+            grid[(pos.y - bounds.min.y) as usize][(pos.x - bounds.min.x) as usize] = true;
+        }
+        Self {
+            origin: bounds.min,
+            grid,
+        }
+    }
+    /// The alive cells within the grid
+    fn cells(&self) -> impl Iterator<Item = Pos2> + '_ {
+        self.grid.iter().enumerate().flat_map(move |(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &alive)| alive)
+                .map(move |(x, _)| Pos2::new(self.origin.x + x as i32, self.origin.y + y as i32))
+        })
+    }
+}
+
+impl Engine for Reference {
+    fn next_generation(&mut self) {
+        let width = self.grid.first().map_or(0, Vec::len) + 2;
+        let mut padded = vec![vec![false; width]];
+        for row in &self.grid {
+            let mut padded_row = Vec::with_capacity(width);
+            padded_row.push(false);
+            padded_row.extend_from_slice(row);
+            padded_row.push(false);
+            padded.push(padded_row);
+        }
+        padded.push(vec![false; width]);
+        let origin = Pos2::new(self.origin.x - 1, self.origin.y - 1);
+        let next = Self {
+            origin,
+            grid: step_reference(&padded),
+        };
+        // shrink back to the bounding box, so the grid doesn't grow with every generation
+        *self = Self::from_alive(&next.cells().collect::<Vec<_>>());
+    }
+    fn alive_count(&self) -> usize {
+        self.grid.iter().flatten().filter(|&&alive| alive).count()
+    }
+    fn is_alive(&self, pos: Pos2) -> bool {
+        let (x, y) = (pos.x - self.origin.x, pos.y - self.origin.y);
+        x >= 0
+            && y >= 0
+            && self
+                .grid
+                .get(y as usize)
+                .and_then(|row| row.get(x as usize))
+                == Some(&true)
+    }
+    fn insert(&mut self, pos: Pos2) {
+        let mut cells = self.cells().collect::<Vec<_>>();
+        if let Err(i) = cells.binary_search(&pos) {
+            cells.insert(i, pos);
+        }
+        *self = Self::from_alive(&cells);
+    }
+    fn iter(&self) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Box::new(self.cells())
+    }
+    fn window(&self, rect: Rect) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Box::new(self.cells().filter(move |&pos| rect.contains(pos)))
+    }
+}
@@ -0,0 +1,118 @@
+//! The spans and events of the `tracing` feature
+//!
+//! Without the feature, every function here is empty and inlined away, so the engine and the
+//! codecs call them without `cfg`s of their own.
+
+use crate::{enc::Format, Pos2};
+#[cfg(feature = "std")]
+use std::io::Write;
+
+/// The span around a step of [`GameOfLife`](crate::engine::GameOfLife), created by [`step`]
+pub(crate) struct Step {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    start: std::time::Instant,
+}
+
+/// Enters the span of the step from `generation` on `threads` threads
+#[inline]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn step(generation: u64, threads: usize) -> Step {
+    Step {
+        #[cfg(feature = "tracing")]
+        span: tracing::debug_span!(
+            target: "cgolrs::engine",
+            "next_generation",
+            generation,
+            threads,
+            population = tracing::field::Empty,
+            births = tracing::field::Empty,
+            deaths = tracing::field::Empty,
+            duration_us = tracing::field::Empty,
+        )
+        .entered(),
+        #[cfg(feature = "tracing")]
+        start: std::time::Instant::now(),
+    }
+}
+impl Step {
+    /// Records the population of the `next` generation, its births and deaths, and how long the
+    /// step took, then leaves the span
+    ///
+    /// The births and deaths take a pass over both generations, so `changes` is only called if
+    /// the span is enabled.
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish(self, next: &[Pos2], changes: impl FnOnce() -> (usize, usize)) {
+        #[cfg(feature = "tracing")]
+        if !self.span.is_disabled() {
+            let (births, deaths) = changes();
+            self.span.record("population", next.len());
+            self.span.record("births", births);
+            self.span.record("deaths", deaths);
+            self.span
+                .record("duration_us", self.start.elapsed().as_micros() as u64);
+            tracing::trace!(target: "cgolrs::engine", "stepped");
+        }
+    }
+}
+
+/// Logs that `cells` cells were decoded from `bytes` bytes in `format`
+#[inline]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn decoded(format: Format, bytes: usize, cells: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "cgolrs::enc", %format, bytes, cells, "decoded");
+}
+
+/// Logs that `cells` cells were encoded into `bytes` bytes in `format`
+#[inline]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn encoded(format: Format, cells: usize, bytes: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "cgolrs::enc", %format, cells, bytes, "encoded");
+}
+
+/// Counts the bytes written to `w`, which are logged by [`encoded`] once it's dropped
+#[cfg(feature = "tracing")]
+pub(crate) fn counted<W: Write>(w: W, format: Format, cells: usize) -> Counted<W> {
+    Counted {
+        inner: w,
+        format,
+        cells,
+        bytes: 0,
+    }
+}
+/// Without `tracing` the writer is passed through as is
+#[cfg(all(feature = "std", not(feature = "tracing")))]
+#[inline]
+pub(crate) fn counted<W: Write>(w: W, _: Format, _: usize) -> W {
+    w
+}
+
+/// A writer counting the bytes of an encoding, see [`counted`]
+#[cfg(feature = "tracing")]
+pub(crate) struct Counted<W> {
+    inner: W,
+    format: Format,
+    cells: usize,
+    bytes: usize,
+}
+#[cfg(feature = "tracing")]
+impl<W: Write> Write for Counted<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes += n;
+        Ok(n)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+#[cfg(feature = "tracing")]
+impl<W> Drop for Counted<W> {
+    fn drop(&mut self) {
+        encoded(self.format, self.cells, self.bytes);
+    }
+}
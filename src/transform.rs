@@ -0,0 +1,207 @@
+//! Geometric transforms of sorted cell lists
+//!
+//! Every function takes cells sorted by y and then x, and returns them sorted the same way.
+//! Cells that would overflow [`i32`] are dropped. The same transforms are available on
+//! [`GameOfLife`](crate::engine::GameOfLife), which also keeps the cell ages in order.
+//!
+//! # Example
+//! ```rust
+//! use cgolrs::{engine::GameOfLife, patterns, transform};
+//!
+//! let cells = patterns::find("r-pentomino").unwrap().cells();
+//!
+//! // rotating four times or flipping twice is the identity
+//! let mut rotated = cells.clone();
+//! for _ in 0..4 {
+//!     rotated = transform::rotate_cw(&rotated);
+//! }
+//! assert_eq!(rotated, cells);
+//! assert_eq!(transform::flip_x(&transform::flip_x(&cells)), cells);
+//! assert_eq!(transform::rotate_ccw(&transform::rotate_cw(&cells)), cells);
+//!
+//! // transforms commute with stepping the game
+//! let mut stepped = GameOfLife::from_alive(cells.clone());
+//! stepped.step_n(10);
+//! stepped.rotate_cw();
+//! let mut rotated = GameOfLife::from_alive(transform::rotate_cw(&cells));
+//! rotated.step_n(10);
+//! assert_eq!(stepped.alive(), rotated.alive());
+//! ```
+
+use crate::{prelude::*, Pos2, Rect};
+use core::cmp::Ordering;
+
+/// Maps every cell, dropping the ones `f` can't represent, and restores the sort order
+pub(crate) fn map_sorted(cells: &[Pos2], f: impl Fn(Pos2) -> Option<Pos2>) -> Vec<Pos2> {
+    let mut mapped = cells.iter().filter_map(|&pos| f(pos)).collect::<Vec<_>>();
+    mapped.sort_unstable();
+    mapped
+}
+
+/// Rotates 90° clockwise (on screen, where y points down) around the origin
+#[inline]
+pub(crate) fn rotate_cw_pos(pos: Pos2) -> Option<Pos2> {
+    Some(Pos2::new(pos.y.checked_neg()?, pos.x))
+}
+/// Rotates 90° counter-clockwise (on screen, where y points down) around the origin
+#[inline]
+pub(crate) fn rotate_ccw_pos(pos: Pos2) -> Option<Pos2> {
+    Some(Pos2::new(pos.y, pos.x.checked_neg()?))
+}
+/// Mirrors across the y-axis
+#[inline]
+pub(crate) fn flip_x_pos(pos: Pos2) -> Option<Pos2> {
+    Some(Pos2::new(pos.x.checked_neg()?, pos.y))
+}
+/// Mirrors across the x-axis
+#[inline]
+pub(crate) fn flip_y_pos(pos: Pos2) -> Option<Pos2> {
+    Some(Pos2::new(pos.x, pos.y.checked_neg()?))
+}
+
+/// The top-left corner of the bounding box of the cells
+pub fn top_left(cells: &[Pos2]) -> Pos2 {
+    Pos2 {
+        x: cells.iter().map(|p| p.x).min().unwrap_or_default(),
+        // because the cells are sorted, the first cell has the lowest y-value
+        y: cells.first().map(|p| p.y).unwrap_or_default(),
+    }
+}
+
+/// The smallest rectangle containing all of the cells, which is empty if there are none
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{transform, Pos2, Rect};
+///
+/// let cells = [(2, -1), (0, 0), (1, 3)].map(Pos2::from);
+/// let bounds = transform::bounding_box(&cells);
+/// assert_eq!(bounds, Rect::new(Pos2::new(0, -1), Pos2::new(3, 4)));
+/// assert!(cells.iter().all(|&pos| bounds.contains(pos)));
+/// assert!(transform::bounding_box(&[]).is_empty());
+/// ```
+pub fn bounding_box(cells: &[Pos2]) -> Rect {
+    if cells.is_empty() {
+        return Rect::new(Pos2::zero(), Pos2::zero());
+    }
+    let max = Pos2 {
+        x: cells.iter().map(|p| p.x).max().unwrap_or_default(),
+        // because the cells are sorted, the last cell has the highest y-value
+        y: cells.last().map(|p| p.y).unwrap_or_default(),
+    };
+    Rect::new(top_left(cells), max.saturating_add(Pos2::new(1, 1)))
+}
+
+/// Each row of the cells with its y value and the cells on it, from the top one down
+///
+/// Rows without cells are skipped. Each row is found with a binary search past the previous
+/// one, so long rows aren't scanned cell by cell.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{transform, Pos2};
+///
+/// let cells = [(0, -2), (3, -2), (1, 5)].map(Pos2::from);
+/// let rows = transform::rows(&cells).collect::<Vec<_>>();
+/// assert_eq!(rows, [(-2, &cells[..2]), (5, &cells[2..])]);
+/// ```
+pub fn rows(cells: &[Pos2]) -> impl Iterator<Item = (i32, &[Pos2])> {
+    let mut rest = cells;
+    core::iter::from_fn(move || {
+        let y = rest.first()?.y;
+        let (row, after) = rest.split_at(rest.partition_point(|p| p.y == y));
+        rest = after;
+        Some((y, row))
+    })
+}
+/// The cells on row `y`, which is empty if there are none
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{transform, Pos2};
+///
+/// let cells = [(0, -2), (3, -2), (1, 5)].map(Pos2::from);
+/// assert_eq!(transform::row(&cells, -2), [Pos2::new(0, -2), Pos2::new(3, -2)]);
+/// assert!(transform::row(&cells, 0).is_empty());
+/// ```
+pub fn row(cells: &[Pos2], y: i32) -> &[Pos2] {
+    let start = cells.partition_point(|p| p.y < y);
+    let end = start + cells[start..].partition_point(|p| p.y == y);
+    &cells[start..end]
+}
+
+/// Moves every cell by `offset`
+///
+/// Translation keeps the order, so this doesn't need to sort.
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{patterns, transform, Pos2};
+///
+/// let cells = patterns::find("gosper-glider-gun").unwrap().cells();
+/// for offset in [(-40, -3), (7, -100), (-1, 12)].map(Pos2::from) {
+///     let moved = transform::translate(&cells, offset);
+///     assert!(moved.windows(2).all(|pair| pair[0] < pair[1]));
+///     assert_eq!(transform::translate(&moved, -offset), cells);
+/// }
+/// ```
+pub fn translate(cells: &[Pos2], offset: Pos2) -> Vec<Pos2> {
+    cells
+        .iter()
+        .filter_map(|pos| pos.checked_add(offset))
+        .collect()
+}
+/// Combines the cells of two patterns, returning them and the number of cells that are in both
+///
+/// # Example
+/// ```rust
+/// use cgolrs::{transform, Pos2};
+///
+/// let block = [(0, 0), (1, 0), (0, 1), (1, 1)].map(Pos2::from);
+/// let moved = transform::translate(&block, Pos2::new(1, 1));
+/// let (cells, overlap) = transform::union(&block, &moved);
+/// assert_eq!(overlap, 1);
+/// assert_eq!(cells, [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)].map(Pos2::from));
+/// ```
+pub fn union(a: &[Pos2], b: &[Pos2]) -> (Vec<Pos2>, usize) {
+    let mut cells = Vec::with_capacity(a.len() + b.len());
+    let mut overlap = 0;
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    while let (Some(&&pa), Some(&&pb)) = (a.peek(), b.peek()) {
+        match pa.cmp(&pb) {
+            Ordering::Less => cells.extend(a.next()),
+            Ordering::Greater => cells.extend(b.next()),
+            Ordering::Equal => {
+                overlap += 1;
+                cells.extend(a.next());
+                b.next();
+            }
+        }
+    }
+    cells.extend(a.chain(b));
+    (cells, overlap)
+}
+/// Rotates the cells 90° clockwise around the origin
+pub fn rotate_cw(cells: &[Pos2]) -> Vec<Pos2> {
+    map_sorted(cells, rotate_cw_pos)
+}
+/// Rotates the cells 90° counter-clockwise around the origin
+pub fn rotate_ccw(cells: &[Pos2]) -> Vec<Pos2> {
+    map_sorted(cells, rotate_ccw_pos)
+}
+/// Mirrors the cells horizontally, negating x
+pub fn flip_x(cells: &[Pos2]) -> Vec<Pos2> {
+    map_sorted(cells, flip_x_pos)
+}
+/// Mirrors the cells vertically, negating y
+pub fn flip_y(cells: &[Pos2]) -> Vec<Pos2> {
+    map_sorted(cells, flip_y_pos)
+}
+/// Moves the cells so the top-left corner of their bounding box is at the origin
+pub fn recenter(cells: &[Pos2]) -> Vec<Pos2> {
+    let top_left = top_left(cells);
+    cells
+        .iter()
+        .filter_map(|pos| pos.checked_sub(top_left))
+        .collect()
+}
@@ -0,0 +1,78 @@
+//! Classifying patterns as still lifes, oscillators and spaceships, along with their speed
+
+use cgolrs::{
+    enc,
+    engine::{Analysis, Direction, GameOfLife, Speed},
+    patterns, Pos2,
+};
+use std::path::Path;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+fn analyze(cells: Vec<Pos2>) -> Analysis {
+    GameOfLife::from_unsorted(cells).analyze(1000)
+}
+
+/// The speed of a spaceship, checking that it is one
+fn spaceship_speed(cells: Vec<Pos2>) -> Speed {
+    match analyze(cells) {
+        Analysis::Spaceship { speed, .. } => speed,
+        analysis => panic!("not a spaceship: {}", analysis),
+    }
+}
+
+#[test]
+fn glider_is_a_diagonal_c4_spaceship() {
+    let glider = patterns::find("glider").unwrap().cells();
+    let analysis = analyze(glider.clone());
+    let Analysis::Spaceship {
+        period,
+        displacement,
+        speed,
+    } = analysis
+    else {
+        panic!("not a spaceship: {}", analysis);
+    };
+    assert_eq!((period, displacement), (4, Pos2::new(1, 1)));
+    assert_eq!((speed.cells, speed.generations), (1, 4));
+    assert_eq!(speed.direction, Direction::Diagonal);
+    assert_eq!(speed.to_string(), "c/4 diagonal");
+    assert_eq!(speed.as_f64(), 0.25);
+
+    // every orientation moves just as fast
+    let mut game = GameOfLife::from_unsorted(glider);
+    game.rotate_cw();
+    game.flip_x();
+    assert_eq!(spaceship_speed(game.alive().to_vec()), speed);
+}
+
+#[test]
+fn lwss_is_an_orthogonal_c2_spaceship() {
+    let speed = spaceship_speed(patterns::find("lwss").unwrap().cells());
+    assert_eq!((speed.cells, speed.generations), (1, 2));
+    assert_eq!(speed.direction, Direction::Orthogonal);
+    assert_eq!(speed.to_string(), "c/2 orthogonal");
+}
+
+#[test]
+fn copperhead_is_an_orthogonal_c10_spaceship() {
+    let pattern = enc::decode_file(Path::new(FIXTURES).join("copperhead.rle"), None).unwrap();
+    assert_eq!(pattern.cells.len(), 28);
+    let analysis = analyze(pattern.cells);
+    let Analysis::Spaceship { period, speed, .. } = analysis else {
+        panic!("not a spaceship: {}", analysis);
+    };
+    assert_eq!(period, 10);
+    assert_eq!(speed.to_string(), "c/10 orthogonal");
+}
+
+#[test]
+fn stationary_and_unresolved_patterns() {
+    let block = vec![Pos2::new(0, 0), Pos2::new(1, 0), Pos2::new(0, 1), Pos2::new(1, 1)];
+    assert_eq!(analyze(block), Analysis::StillLife);
+    let blinker = patterns::find("blinker").unwrap().cells();
+    assert_eq!(analyze(blinker), Analysis::Oscillator { period: 2 });
+    assert_eq!(analyze(vec![Pos2::new(0, 0)]), Analysis::Unresolved);
+    let gun = patterns::find("gosper-glider-gun").unwrap().cells();
+    assert_eq!(analyze(gun), Analysis::Unresolved);
+}
@@ -0,0 +1,129 @@
+//! Invalid command lines are reported with a message and exit code 2 instead of a panic
+
+mod common;
+
+use std::{path::Path, process::Output};
+
+/// Runs the binary in the working directory of the tests
+fn gol(args: &[&str]) -> Output {
+    common::gol(Path::new(".")).args(args).output().unwrap()
+}
+
+/// Asserts that the arguments are rejected with an error mentioning each of `words`
+fn rejects(args: &[&str], words: &[&str]) {
+    let output = gol(args);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, stderr);
+    assert!(stderr.starts_with("error: "), "{:?}: {}", args, stderr);
+    assert!(!stderr.contains("panicked"), "{:?}: {}", args, stderr);
+    for word in words {
+        assert!(stderr.contains(word), "{:?} should mention {}: {}", args, word, stderr);
+    }
+}
+
+#[test]
+fn unknown_flags() {
+    rejects(&["--banana"], &["banana"]);
+    rejects(&["--threads"], &["threads"]);
+}
+
+#[test]
+fn invalid_numbers() {
+    rejects(&["--threads", "banana"], &["--threads", "banana"]);
+    rejects(&["--threads", "0"], &["--threads", "`0`"]);
+    rejects(&["--fps", "0"], &["--fps", "`0`"]);
+    rejects(&["--duration", "0"], &["--duration", "`0`"]);
+    rejects(&["--duration", "-1.5"], &["--duration", "-1.5"]);
+    rejects(&["--autosave", "0"], &["--autosave", "`0`"]);
+    rejects(&["--record-every", "0"], &["--record-every", "`0`"]);
+    rejects(&["--scale", "0"], &["--scale", "`0`"]);
+    rejects(&["--cull-radius", "-3"], &["--cull-radius", "-3"]);
+    rejects(&["--cull-radius", "9", "--cull-every", "0"], &["--cull-every", "`0`"]);
+    rejects(&["--gens", "-5"], &["--gens", "-5"]);
+    rejects(
+        &["--engine", "hashlife", "--gens", "18446744073709551615"],
+        &["--gens", "2^58"],
+    );
+    rejects(&["--width", "wide"], &["--width", "wide"]);
+    rejects(&["--threshold", "256"], &["--threshold", "256"]);
+    rejects(&["soup", "--count", "many"], &["--count", "many"]);
+    rejects(&["soup", "--count", "0"], &["--count", "`0`"]);
+}
+
+#[test]
+fn invalid_values() {
+    rejects(&["--fill", "checkered"], &["--fill", "checkered", "--fill list"]);
+    rejects(&["--engine", "warp"], &["--engine", "warp"]);
+    rejects(&["--format", "gif"], &["--format", "gif"]);
+    rejects(&["--age-gradient", "0=plaid"], &["--age-gradient", "0=plaid"]);
+    rejects(&["--record-window", "1,2,3"], &["--record-window", "1,2,3"]);
+    rejects(&["--record-window", "0,0,-5,10"], &["--record-window", "0,0,-5,10"]);
+    rejects(&["--record-window", "0,0,0,0"], &["--record-window", "positive width"]);
+    rejects(&["--record-window", "3000000000,0,8,8"], &["--record-window", "3000000000"]);
+    rejects(&["--record-window", "0,0,8,5000000000"], &["--record-window", "5000000000"]);
+    rejects(&["--input", "gun.rle@4,y"], &["--input", "gun.rle@4,y"]);
+    rejects(&["--pattern", "glider@4"], &["--pattern", "glider@4"]);
+    rejects(&["--offset", "4"], &["--offset", "`4`", "X,Y"]);
+    rejects(&["--pattern", "glider-gun"], &["glider-gun", "gosper-glider-gun"]);
+    rejects(&["--rule", "B9/S8"], &["--rule", "B9/S8", "B/S notation"]);
+    rejects(&["--rule", "B02/S/C3"], &["--rule", "B02/S/C3"]);
+    rejects(&["--rule", "lifelike"], &["--rule", "lifelike", "--rule list"]);
+    rejects(&["convert", "a.rle", "b.rle", "--rotate", "45"], &["--rotate", "45"]);
+}
+
+#[test]
+fn demo_without_flags() {
+    // console mode needs a terminal, so getting that far means the command line was accepted
+    let output = gol(&["demo"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_ne!(output.status.code(), Some(2), "{}", stderr);
+    assert!(stderr.contains("console mode needs a terminal"), "{}", stderr);
+    // the first demo pattern is the gun
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("alive: 36"), "{}", stdout);
+}
+
+#[test]
+fn conflicting_flags() {
+    rejects(&["--input", "glider.rle", "--fill", "all"], &["--input", "--fill"]);
+    rejects(&["--input", "glider.rle", "-w", "10"], &["--input", "--width"]);
+    rejects(&["--fps", "30", "--sleep", "10"], &["--fps", "--sleep"]);
+    rejects(&["--pattern", "glider", "demo"], &["--pattern", "demo"]);
+    let hashlife = ["--engine", "hashlife", "--gens", "10"];
+    rejects(&[&hashlife[..], &["--console"]].concat(), &["hashlife", "--console"]);
+    rejects(&[&hashlife[..], &["demo"]].concat(), &["hashlife", "demo"]);
+    rejects(&[&hashlife[..], &["--duration", "5"]].concat(), &["hashlife", "--duration"]);
+    rejects(&[&hashlife[..], &["--rule", "seeds"]].concat(), &["hashlife", "--rule"]);
+    rejects(&[&hashlife[..], &["--cull-radius", "9"]].concat(), &["hashlife", "--cull-radius"]);
+}
+
+#[test]
+fn missing_arguments() {
+    rejects(&["--engine", "hashlife"], &["--gens"]);
+    rejects(&["info"], &["info", "FILE"]);
+    rejects(&["convert", "glider.rle"], &["convert", "OUT"]);
+    rejects(&["--autosave-keep", "3"], &["--autosave-keep", "requires --autosave"]);
+    rejects(&["--cull-every", "10"], &["--cull-every", "requires --cull-radius"]);
+}
+
+#[test]
+fn help_is_not_an_error() {
+    let output = gol(&["--help"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("gol convert IN OUT"));
+}
+
+#[test]
+fn options_belong_to_their_command() {
+    rejects(&["soup", "--fps", "30"], &["fps"]);
+    rejects(&["info", "glider.rle", "--rotate", "90"], &["rotate"]);
+    rejects(&["bench", "--console"], &["console"]);
+    rejects(&["-w", "8", "soup"], &["`soup`", "commands go before the options"]);
+    rejects(&["glider.rle"], &["unexpected argument `glider.rle`"]);
+    rejects(&["info", "a.rle", "b.rle"], &["`b.rle`"]);
+    rejects(&["bench", "--threads", "1,0"], &["--threads", "1,0"]);
+    rejects(&["bench", "--gens", "0"], &["--gens", "`0`"]);
+    rejects(&["bench", "--engine", "scan,warp"], &["--engine", "scan,warp"]);
+    rejects(&["bench", "--seed", "3", "-p", "glider"], &["--seed", "--pattern"]);
+    rejects(&["bench", "-w", "30"], &["--width", "requires --fill"]);
+}
@@ -0,0 +1,54 @@
+//! Autosaves written while a run is going
+
+mod common;
+
+use cgolrs::enc::{self, Format};
+use std::path::Path;
+
+/// A short random run in `dir`, where autosaves go
+fn run(dir: &Path, flags: &[&str]) {
+    let random = ["-w", "16", "-h", "16", "-f", "random", "--gens", "10", "-q"];
+    common::succeed(common::gol(dir).args(random).args(flags));
+}
+
+/// The names of the files in `dir` other than the stats
+fn files(dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name != "perf.csv")
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn saves_every_few_generations() {
+    let dir = common::temp_dir("autosave", "every");
+    run(&dir, &["--autosave", "3"]);
+    assert_eq!(files(&dir), ["autosave-3.rle", "autosave-6.rle", "autosave-9.rle"]);
+
+    let pattern = enc::decode_file(dir.join("autosave-6.rle"), None).unwrap();
+    assert_eq!(pattern.format, Format::Rle);
+    assert_eq!(pattern.generation, 6);
+}
+
+#[test]
+fn keeps_only_the_latest() {
+    let dir = common::temp_dir("autosave", "keep");
+    let flags = ["--autosave", "2", "--autosave-path", "run-{gen}.bin", "--autosave-keep", "2"];
+    run(&dir, &flags);
+    assert_eq!(files(&dir), ["run-10.bin", "run-8.bin"]);
+
+    let pattern = enc::decode_file(dir.join("run-10.bin"), None).unwrap();
+    assert_eq!(pattern.format, Format::Binary);
+    assert_eq!(pattern.generation, 10);
+}
+
+#[test]
+fn overwrites_a_fixed_name() {
+    let dir = common::temp_dir("autosave", "fixed");
+    run(&dir, &["--autosave", "4", "--autosave-path", "latest.rle"]);
+    assert_eq!(files(&dir), ["latest.rle"]);
+    assert_eq!(enc::decode_file(dir.join("latest.rle"), None).unwrap().generation, 8);
+}
@@ -0,0 +1,127 @@
+//! A happy path through each subcommand of the compiled binary
+
+mod common;
+
+use common::{temp_dir, FIXTURES};
+use std::path::Path;
+
+/// Runs the binary in `dir` and returns its stdout, asserting that it succeeded
+fn gol(dir: &Path, args: &[&str]) -> String {
+    let output = common::succeed(common::gol(dir).args(args));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn glider() -> String {
+    Path::new(FIXTURES).join("glider.rle").display().to_string()
+}
+
+#[test]
+fn run_is_the_default_command() {
+    let dir = temp_dir("cmd", "run");
+    let flags = ["-f", "cross", "-w", "9", "-h", "9", "--gens", "4", "-q"];
+    let implicit = gol(&dir, &flags);
+    let explicit = gol(&dir, &[&["run"][..], &flags].concat());
+    assert!(implicit.contains("alive: 17"), "{}", implicit);
+    assert!(explicit.contains("alive: 17"), "{}", explicit);
+    assert!(dir.join("perf.csv").exists());
+}
+
+#[test]
+fn convert_writes_the_output() {
+    let dir = temp_dir("cmd", "convert");
+    let stdout = gol(&dir, &["convert", &glider(), "glider.cells"]);
+    assert!(stdout.contains("5 cells"), "{}", stdout);
+    assert!(dir.join("glider.cells").exists());
+}
+
+#[test]
+fn info_describes_the_pattern() {
+    let dir = temp_dir("cmd", "info");
+    let stdout = gol(&dir, &["info", &glider(), "--analyze"]);
+    assert!(stdout.contains("population: 5"), "{}", stdout);
+    assert!(stdout.contains("spaceship"), "{}", stdout);
+    assert!(stdout.contains("c/4 diagonal"), "{}", stdout);
+}
+
+#[test]
+fn soup_saves_a_census() {
+    let dir = temp_dir("cmd", "soup");
+    let stdout = gol(&dir, &["soup", "--count", "4", "--size", "8", "--threads", "2"]);
+    assert!(stdout.contains("ran 4 soups"), "{}", stdout);
+    assert!(dir.join("soup-census.csv").exists());
+    assert!(dir.join("soup-longest.rle").exists());
+    assert!(dir.join("soup-largest.rle").exists());
+}
+
+#[test]
+fn empty_soups_have_no_outliers() {
+    let dir = temp_dir("cmd", "empty-soup");
+    let stdout = gol(&dir, &["soup", "--count", "3", "--size", "0"]);
+    assert!(stdout.contains("ran 3 soups"), "{}", stdout);
+    assert!(!stdout.contains("saved"), "{}", stdout);
+    assert!(dir.join("soup-census.csv").exists());
+    assert!(!dir.join("soup-longest.rle").exists());
+    assert!(!dir.join("soup-largest.rle").exists());
+}
+
+#[test]
+fn bench_compares_thread_counts() {
+    let dir = temp_dir("cmd", "bench");
+    let flags = ["--size", "32", "--gens", "20", "--warmup", "5", "--seed", "1"];
+    let extra = ["--threads", "2,1", "--engine", "scan,hashlife", "--csv", "bench.csv"];
+    let stdout = gol(&dir, &[&["bench"][..], &flags, &extra].concat());
+    assert!(stdout.contains("soup: 32x32 of seed 1"), "{}", stdout);
+    assert!(stdout.contains("timing 20 generations after 5"), "{}", stdout);
+    for row in ["scan             1", "scan             2", "hashlife         1"] {
+        assert!(stdout.contains(row), "{}", stdout);
+    }
+
+    let csv = std::fs::read_to_string(dir.join("bench.csv")).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "engine,threads,gens,seconds,gens_per_sec,speedup");
+    assert_eq!(lines.len(), 4, "{}", csv);
+    assert!(lines[1].starts_with("scan,1,20,") && lines[1].ends_with(",1"), "{}", csv);
+    assert!(lines[3].starts_with("hashlife,1,20,"), "{}", csv);
+
+    // the same seed starts from the same soup
+    let again = gol(&dir, &[&["bench"][..], &flags].concat());
+    let alive = |stdout: &str| {
+        let line = stdout.lines().find(|line| line.starts_with("alive:"));
+        line.map(String::from)
+    };
+    assert_eq!(alive(&stdout), alive(&again));
+    let pattern = gol(&dir, &["bench", "-p", "glider", "--gens", "8"]);
+    assert!(pattern.contains("alive: 5"), "{}", pattern);
+}
+
+#[test]
+fn each_command_has_its_own_help() {
+    let dir = temp_dir("cmd", "help");
+    for (command, own, foreign) in [
+        ("run", "--fps", "--rotate"),
+        ("convert", "--rotate", "--fps"),
+        ("info", "--analyze", "--rotate"),
+        ("soup", "--seed", "--fps"),
+        ("bench", "--warmup", "--fps"),
+    ] {
+        let help = gol(&dir, &[command, "--help"]);
+        assert!(help.contains(own), "{} --help: {}", command, help);
+        assert!(!help.contains(foreign), "{} --help: {}", command, help);
+    }
+}
+
+#[test]
+fn unwritable_output_is_an_error() {
+    let dir = temp_dir("cmd", "unwritable");
+    for engine in ["scan", "hashlife"] {
+        let output = common::gol(&dir)
+            .args(["-p", "glider", "--gens", "4", "-q", "--engine", engine])
+            .args(["-o", "missing/out.rle"])
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // EX_IOERR, rather than the exit code of a panic
+        assert_eq!(output.status.code(), Some(74), "{}: {}", engine, stderr);
+        assert!(stderr.starts_with("error: "), "{}: {}", engine, stderr);
+    }
+}
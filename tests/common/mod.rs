@@ -0,0 +1,43 @@
+//! Helpers shared by the integration tests, which each only use some of them
+#![allow(dead_code)]
+
+use cgolrs::{engine::GameOfLife, Pos2, Rect};
+use rand::Rng;
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// The directory of the test fixtures
+pub const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// A fresh temporary directory for `test` of `suite`, since runs write to the working directory
+pub fn temp_dir(suite: &str, test: &str) -> PathBuf {
+    let name = format!("cgolrs-{}-{}-{}", suite, test, std::process::id());
+    let dir = std::env::temp_dir().join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The compiled binary, running in `dir`
+pub fn gol(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cgolrs"));
+    command.current_dir(dir);
+    command
+}
+
+/// Runs `command` to the end, asserting that it succeeded
+pub fn succeed(command: &mut Command) -> Output {
+    let output = command.output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{:?}: {}", command, stderr);
+    output
+}
+
+/// The cells of a random soup filling `rect`, each alive with a chance of `density`
+pub fn soup(rng: &mut impl Rng, rect: Rect, density: f64) -> Vec<Pos2> {
+    GameOfLife::random_with_rng(rect, density, rng)
+        .unwrap()
+        .alive()
+        .to_vec()
+}
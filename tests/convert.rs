@@ -0,0 +1,107 @@
+//! End to end runs of `gol convert` over the fixtures of each format
+
+mod common;
+
+use cgolrs::{
+    enc::{self, Format},
+    transform, Pos2,
+};
+use common::FIXTURES;
+use std::{
+    path::{Path, PathBuf},
+    process::Output,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(FIXTURES).join(name)
+}
+
+/// A path in a fresh temporary directory of the test
+fn temp_path(test: &str, name: &str) -> PathBuf {
+    common::temp_dir("convert", test).join(name)
+}
+
+fn convert(args: &[&Path], flags: &[&str]) -> Output {
+    common::gol(Path::new("."))
+        .arg("convert")
+        .args(args)
+        .args(flags)
+        .output()
+        .unwrap()
+}
+
+fn glider() -> Vec<Pos2> {
+    let pattern = enc::decode_file(fixture("glider.rle"), None).unwrap();
+    transform::recenter(&pattern.cells)
+}
+
+#[test]
+fn converts_between_every_format() {
+    let inputs = [
+        "glider.rle",
+        "glider.cells",
+        "glider.lif",
+        "glider-106.lif",
+        "glider.bin",
+    ];
+    let outputs = ["rle", "cells", "lif", "bin"];
+    for input in inputs {
+        for extension in outputs {
+            let output = temp_path("formats", &format!("{}.{}", input, extension));
+            let result = convert(&[&fixture(input), &output], &[]);
+            assert!(result.status.success(), "{} to {}", input, extension);
+
+            let pattern = enc::decode_file(&output, None).unwrap();
+            assert_eq!(Some(pattern.format), Format::from_path(&output));
+            assert_eq!(transform::recenter(&pattern.cells), glider(), "{}", input);
+        }
+    }
+}
+
+#[test]
+fn keeps_metadata() {
+    let output = temp_path("metadata", "glider.rle");
+    assert!(convert(&[&fixture("glider.rle"), &output], &[]).status.success());
+    let original = enc::decode_file(fixture("glider.rle"), None).unwrap();
+    let converted = enc::decode_file(&output, None).unwrap();
+    assert_eq!(converted.meta, original.meta);
+    assert_eq!(converted.rule, original.rule);
+
+    let output = temp_path("metadata", "glider.cells");
+    assert!(convert(&[&fixture("glider.rle"), &output], &[]).status.success());
+    let converted = enc::decode_file(&output, None).unwrap();
+    assert_eq!(converted.meta.name.as_deref(), Some("Glider"));
+}
+
+#[test]
+fn transforms_and_overrides_the_format() {
+    let output = temp_path("transforms", "glider.txt");
+    let flags = ["--rotate", "180", "--flip-x", "--recenter", "--format", "rle"];
+    assert!(convert(&[&fixture("glider.cells"), &output], &flags).status.success());
+
+    let pattern = enc::decode_file(&output, None).unwrap();
+    assert_eq!(pattern.format, Format::Rle);
+    let expected = transform::flip_y(&glider());
+    assert_eq!(pattern.cells, transform::recenter(&expected));
+}
+
+#[test]
+fn refuses_to_overwrite_the_input() {
+    let path = temp_path("overwrite", "glider.rle");
+    std::fs::copy(fixture("glider.rle"), &path).unwrap();
+    let result = convert(&[&path, &path], &[]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--force"));
+
+    assert!(convert(&[&path, &path], &["--force"]).status.success());
+}
+
+#[test]
+fn fails_on_malformed_files() {
+    let path = temp_path("malformed", "junk.bin");
+    std::fs::write(&path, b"CGOL\xff\xff").unwrap();
+    let result = convert(&[&path, &temp_path("malformed", "out.rle")], &[]);
+    // EX_DATAERR
+    assert_eq!(result.status.code(), Some(65));
+    assert!(!result.stderr.is_empty());
+}
@@ -0,0 +1,205 @@
+//! The row cursors of the engine, checked against bit buffers built from a set of the cells
+
+use cgolrs::{
+    cursor::{MultiRowPosCursor, PosCursor},
+    engine::GameOfLife,
+    Pos2, Rect,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
+
+/// The bit buffer at `pos`, with bit `k` set if the cell `k` columns to its left is in `cells`
+fn expected_buffer(cells: &HashSet<Pos2>, pos: Pos2) -> u64 {
+    (0..64)
+        .filter(|&k| cells.contains(&Pos2::new(pos.x - k, pos.y)))
+        .fold(0, |buffer, k| buffer | 1 << k)
+}
+
+/// Checks the state of `cursor` against the sorted `cells`, which are also in `set`
+fn check(cursor: &PosCursor<'_>, cells: &[Pos2], set: &HashSet<Pos2>) {
+    let pos = cursor.cursor();
+    assert_eq!(cursor.bit_buffer(), expected_buffer(set, pos), "at {:?}", pos);
+    let next = cells.get(cells.partition_point(|&cell| cell <= pos)).copied();
+    assert_eq!(cursor.next_present(), next, "at {:?}", pos);
+}
+
+#[test]
+fn empty_slices_have_nothing_present() {
+    let mut cursor = PosCursor::new(&[], Pos2::new(5, -5));
+    assert_eq!(cursor.bit_buffer(), 0);
+    assert_eq!(cursor.next(), 0);
+    assert_eq!(cursor.seek(Pos2::new(-100, 100)), 0);
+    assert_eq!(cursor.next_present(), None);
+
+    let mut cursor = MultiRowPosCursor::new(&[], 3);
+    assert_eq!(cursor.buffers(), [0, 0, 0]);
+    assert_eq!(cursor.seek_closest(), None);
+    assert_eq!(cursor.next(), [0, 0, 0]);
+}
+
+#[test]
+fn single_rows_shift_through_the_buffer() {
+    let cells = [0, 1, 3, 4, 5, 9].map(|x| Pos2::new(x, 7));
+    let set = cells.iter().copied().collect::<HashSet<_>>();
+    let mut cursor = PosCursor::new(&cells, Pos2::new(-3, 7));
+    for _ in 0..20 {
+        check(&cursor, &cells, &set);
+        cursor.next();
+    }
+    // the cursor over 3 rows passes over the row as each of its own rows in turn
+    let mut rows = MultiRowPosCursor::new(&cells, 3);
+    let mut seen = [false; 3];
+    while rows.seek_closest().is_some() {
+        for _ in 0..10 {
+            let pos = rows.cursor();
+            for (i, &buffer) in rows.buffers().iter().enumerate() {
+                let row = Pos2::new(pos.x, pos.y - 2 + i as i32);
+                assert_eq!(buffer, expected_buffer(&set, row), "{:?} row {}", pos, i);
+                seen[i] |= buffer != 0;
+            }
+            rows.next();
+        }
+    }
+    assert_eq!(seen, [true; 3]);
+}
+
+#[test]
+fn gaps_wider_than_the_buffer() {
+    // each gap is longer than 64 columns, so the buffer is empty in between
+    let cells = [(-150, 0), (-80, 0), (0, 0), (100, 0), (163, 0), (2, 1), (70, 1)];
+    let mut cells = cells.map(Pos2::from).to_vec();
+    cells.sort_unstable();
+    let set = cells.iter().copied().collect::<HashSet<_>>();
+    let mut cursor = PosCursor::new(&cells, Pos2::new(-160, 0));
+    for _ in 0..500 {
+        check(&cursor, &cells, &set);
+        cursor.next();
+    }
+
+    // skipping the gaps still visits every cell
+    let mut rows = MultiRowPosCursor::new(&cells, 1);
+    let mut visited = Vec::new();
+    while rows.seek_closest().is_some() {
+        visited.push(rows.cursor());
+    }
+    assert_eq!(visited, cells);
+}
+
+#[test]
+fn seeks_backwards_and_forwards() {
+    let mut rng = StdRng::seed_from_u64(367);
+    let game = GameOfLife::random(Rect::from_size(Pos2::new(-12, -6), 24, 12), 0.3, 367).unwrap();
+    let cells = game.alive();
+    let set = cells.iter().copied().collect::<HashSet<_>>();
+    let mut cursor = PosCursor::new(cells, Pos2::new(0, 0));
+    for _ in 0..500 {
+        let target = Pos2::new(rng.gen_range(-16..16), rng.gen_range(-8..8));
+        cursor.seek(target);
+        check(&cursor, cells, &set);
+        // a few steps from there, and then a jump right past the cursor
+        for _ in 0..rng.gen_range(0..4) {
+            cursor.next();
+            check(&cursor, cells, &set);
+        }
+        cursor.seek(cursor.cursor() + Pos2::new(rng.gen_range(0..3), 0));
+        check(&cursor, cells, &set);
+    }
+}
+
+#[test]
+fn rows_seek_to_the_closest_cell_of_any_row() {
+    let game = GameOfLife::random(Rect::from_size(Pos2::new(0, 0), 40, 10), 0.2, 3670).unwrap();
+    let cells = game.alive();
+    let set = cells.iter().copied().collect::<HashSet<_>>();
+    let mut rows = MultiRowPosCursor::new(cells, 3);
+    let mut last = rows.cursor();
+    while let Some(buffers) = rows.seek_closest() {
+        let buffers = buffers.to_vec();
+        let pos = rows.cursor();
+        assert!(pos > last);
+        // the buffers are the rows from the top one down
+        for (i, &buffer) in buffers.iter().enumerate() {
+            let row = Pos2::new(pos.x, pos.y - 2 + i as i32);
+            assert_eq!(buffer, expected_buffer(&set, row), "{:?} row {}", pos, i);
+        }
+        // the cursor stopped at a cell, and skipped no cell of any row on the way
+        assert!((0..3).any(|i| set.contains(&Pos2::new(pos.x, pos.y - i))));
+        let skipped = cells.iter().any(|&cell| {
+            (0..3).any(|i| {
+                let moved = cell + Pos2::new(0, i);
+                moved > last && moved < pos
+            })
+        });
+        assert!(!skipped, "{:?} to {:?}", last, pos);
+        last = pos;
+    }
+}
+
+/// Random cells sorted by y and then x, in rows with runs of every density and gaps around the
+/// width of the buffer
+fn random_slice(rng: &mut StdRng) -> Vec<Pos2> {
+    let mut cells = Vec::new();
+    for y in 0..rng.gen_range(1..6) {
+        let mut x = rng.gen_range(-100..0);
+        for _ in 0..rng.gen_range(0..6) {
+            let density = rng.gen_range(0.0..=1.0);
+            for _ in 0..rng.gen_range(1..80) {
+                if rng.gen_bool(density) {
+                    cells.push(Pos2::new(x, y));
+                }
+                x += 1;
+            }
+            x += rng.gen_range(0..140);
+        }
+    }
+    cells
+}
+
+#[test]
+fn random_slices_match_every_column() {
+    let mut rng = StdRng::seed_from_u64(368);
+    for _ in 0..100 {
+        let cells = random_slice(&mut rng);
+        let set = cells.iter().copied().collect::<HashSet<_>>();
+        let mut cursor = PosCursor::new(&cells, Pos2::new(-110, 0));
+        for _ in 0..1000 {
+            // mostly small moves, which shift the buffer, and sometimes jumps anywhere
+            match rng.gen_range(0..10) {
+                0..=5 => {
+                    cursor.next();
+                }
+                6..=8 => {
+                    let step = rng.gen_range(0..100);
+                    cursor.seek(cursor.cursor() + Pos2::new(step, 0));
+                }
+                _ => {
+                    cursor.seek(Pos2::new(rng.gen_range(-120..600), rng.gen_range(-1..7)));
+                }
+            }
+            check(&cursor, &cells, &set);
+        }
+    }
+}
+
+#[test]
+fn random_slices_match_on_every_row() {
+    let mut rng = StdRng::seed_from_u64(3680);
+    for _ in 0..100 {
+        let cells = random_slice(&mut rng);
+        let set = cells.iter().copied().collect::<HashSet<_>>();
+        let mut rows = MultiRowPosCursor::new(&cells, 3);
+        let mut visited = 0;
+        while rows.seek_closest().is_some() {
+            for _ in 0..rng.gen_range(0..70) {
+                let pos = rows.cursor();
+                for (i, &buffer) in rows.buffers().iter().enumerate() {
+                    let row = Pos2::new(pos.x, pos.y - 2 + i as i32);
+                    assert_eq!(buffer, expected_buffer(&set, row), "{:?} row {}", pos, i);
+                }
+                rows.next();
+            }
+            visited += 1;
+        }
+        assert!(visited <= cells.len() * 3);
+    }
+}
@@ -0,0 +1,126 @@
+//! Diffing and comparing engine states, which have to agree with a plain set difference
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, testing::assert_same_evolution, Pos2, Rect, Rule};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+/// A square soup of `size` cells from `min`
+fn soup(rng: &mut StdRng, min: Pos2, size: u32) -> Vec<Pos2> {
+    common::soup(rng, Rect::from_size(min, size, size), 0.35)
+}
+
+/// Checks `a.diff(b)` against the set differences of their cells
+fn check_diff(a: &GameOfLife, b: &GameOfLife) {
+    let (set_a, set_b) = (
+        a.alive().iter().copied().collect::<BTreeSet<_>>(),
+        b.alive().iter().copied().collect::<BTreeSet<_>>(),
+    );
+    let diff = a.diff(b);
+    assert!(diff.only_in_self.iter().eq(set_a.difference(&set_b)));
+    assert!(diff.only_in_other.iter().eq(set_b.difference(&set_a)));
+    // equal games can still differ in their dying cells
+    assert!(a != b || diff.is_empty());
+
+    let reversed = b.diff(a);
+    assert_eq!(reversed.only_in_self, diff.only_in_other);
+    assert_eq!(reversed.only_in_other, diff.only_in_self);
+}
+
+#[test]
+fn disjoint_states_differ_everywhere() {
+    let mut rng = StdRng::seed_from_u64(361);
+    let left = GameOfLife::from_alive(soup(&mut rng, Pos2::new(-40, -10), 20));
+    let right = GameOfLife::from_alive(soup(&mut rng, Pos2::new(20, -10), 20));
+    check_diff(&left, &right);
+    let diff = left.diff(&right);
+    assert_eq!(diff.only_in_self, left.alive());
+    assert_eq!(diff.only_in_other, right.alive());
+    assert!(left != right);
+
+    let empty = GameOfLife::from_alive(Vec::new());
+    check_diff(&left, &empty);
+    check_diff(&empty, &empty);
+}
+
+#[test]
+fn identical_states_are_equal() {
+    let mut rng = StdRng::seed_from_u64(3610);
+    let cells = soup(&mut rng, Pos2::new(-20, -20), 40);
+    let mut game = GameOfLife::from_alive(cells.clone());
+    game.set_rule(Rule::from_name("brians-brain").unwrap()).unwrap();
+    game.step_n(5);
+
+    let mut copy = GameOfLife::from_alive(game.alive().to_vec());
+    copy.set_rule(game.rule()).unwrap();
+    copy.set_dying(game.dying().to_vec());
+    check_diff(&game, &copy);
+    assert!(game.diff(&copy).is_empty());
+    // the generation only counts for the strict comparison
+    assert!(game == copy);
+    assert!(!game.eq_strict(&copy));
+    let mut stepped = GameOfLife::from_alive(cells);
+    stepped.set_rule(game.rule()).unwrap();
+    stepped.step_n(10);
+    game.step_n(5);
+    assert!(game.eq_strict(&stepped));
+
+    // and so does the rule
+    let mut highlife = GameOfLife::from_grid(&[[true, true, true]]);
+    highlife.set_rule(Rule::from_name("highlife").unwrap()).unwrap();
+    let life = GameOfLife::from_grid(&[[true, true, true]]);
+    assert!(highlife == life);
+    assert!(!highlife.eq_strict(&life));
+
+    // dying cells count even though the alive cells are the same
+    let alive_only = GameOfLife::from_alive(game.alive().to_vec());
+    assert!(game.diff(&alive_only).is_empty());
+    assert!(game != alive_only);
+}
+
+#[test]
+fn interleaved_states_differ_cell_by_cell() {
+    // every other cell of a row, shifted by one between the two games
+    let evens = GameOfLife::from_alive((0..50).map(|x| Pos2::new(2 * x, 0)).collect());
+    let odds = GameOfLife::from_alive((0..50).map(|x| Pos2::new(2 * x + 1, 0)).collect());
+    check_diff(&evens, &odds);
+    assert_eq!(evens.diff(&odds).len(), 100);
+
+    let mut rng = StdRng::seed_from_u64(3611);
+    let cells = soup(&mut rng, Pos2::new(-30, -30), 60);
+    let (a, b): (Vec<_>, Vec<_>) = cells.iter().partition(|_| rng.gen_bool(0.5));
+    let (mut a, mut b) = (GameOfLife::from_alive(a), GameOfLife::from_alive(b));
+    check_diff(&a, &b);
+    // the same cells once both halves are merged
+    a.merge(&b);
+    b.merge(&a);
+    check_diff(&a, &b);
+    assert!(a == b);
+    a.step_n(3);
+    check_diff(&a, &b);
+}
+
+#[test]
+fn parallel_and_serial_steps_are_equal() {
+    let mut rng = StdRng::seed_from_u64(3612);
+    for rule in ["B3/S23", "B36/S23", "B2/S/C3"] {
+        let rule = rule.parse::<Rule>().unwrap();
+        let cells = soup(&mut rng, Pos2::new(-24, -24), 48);
+        let steppers = [(1, false), (4, false), (1, true)];
+        let [serial, others @ ..] = steppers.map(|(threads, incremental)| {
+            let mut game = GameOfLife::from_alive(cells.clone());
+            game.set_rule(rule).unwrap();
+            game.set_threads(threads);
+            game.set_min_chunk_cells(1);
+            game.set_incremental(incremental);
+            game
+        });
+        for mut game in others {
+            let mut serial = serial.clone();
+            assert_same_evolution(&mut game, &mut serial, 10);
+            let diff = game.diff(&serial);
+            assert!(game.eq_strict(&serial), "{}: {:?}", rule, diff);
+        }
+    }
+}
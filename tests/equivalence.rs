@@ -0,0 +1,165 @@
+//! Every engine and step mode against the naive reference engine, on the canonical fixtures and
+//! on random soups
+
+mod common;
+
+use cgolrs::{
+    engine::{DenseGrid, Engine, GameOfLife, HashLife},
+    testing::{self, assert_same_evolution, Reference},
+    Pos2, Rect,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Enough generations for the fixtures to go through a few periods, and for the gun to fire
+const GENS: usize = 100;
+
+/// A [`GameOfLife`] stepping into a buffer it keeps between generations
+struct Buffered {
+    game: GameOfLife,
+    scratch: Vec<Pos2>,
+}
+
+impl Engine for Buffered {
+    fn next_generation(&mut self) {
+        self.game.next_generation_into(&mut self.scratch);
+    }
+    fn alive_count(&self) -> usize {
+        self.game.alive_count()
+    }
+    fn is_alive(&self, pos: Pos2) -> bool {
+        Engine::is_alive(&self.game, pos)
+    }
+    fn insert(&mut self, pos: Pos2) {
+        Engine::insert(&mut self.game, pos);
+    }
+    fn iter(&self) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Engine::iter(&self.game)
+    }
+    fn window(&self, rect: Rect) -> Box<dyn Iterator<Item = Pos2> + '_> {
+        Engine::window(&self.game, rect)
+    }
+}
+
+/// A 64x64 soup, different for every seed
+fn soup(seed: u64) -> Vec<Pos2> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    common::soup(&mut rng, Rect::from_size(Pos2::zero(), 64, 64), 0.5)
+}
+
+/// Checks every engine and step mode against the reference for `gens` generations
+fn check_all(name: &str, cells: &[Pos2], gens: usize) {
+    let game = GameOfLife::from_alive(cells.to_vec());
+    let reference = || Reference::from_alive(cells);
+    for (mode, threads, incremental) in [
+        ("serial", 1, false),
+        ("parallel", 4, false),
+        ("incremental", 1, true),
+    ] {
+        eprintln!("{} with the {} engine", name, mode);
+        let mut game = game.clone();
+        game.set_threads(threads);
+        game.set_incremental(incremental);
+        assert_same_evolution(&mut game, &mut reference(), gens);
+    }
+
+    eprintln!("{} with a reused buffer", name);
+    let mut buffered = Buffered {
+        game: game.clone(),
+        scratch: Vec::new(),
+    };
+    assert_same_evolution(&mut buffered, &mut reference(), gens);
+
+    // a margin wide enough that nothing reaches the edge of the dense grid in time
+    eprintln!("{} with the dense engine", name);
+    let margin = gens as i32 + 2;
+    let bounds = cgolrs::transform::bounding_box(cells);
+    let bounds = Rect::new(
+        Pos2::new(bounds.min.x - margin, bounds.min.y - margin),
+        Pos2::new(bounds.max.x + margin, bounds.max.y + margin),
+    );
+    let mut dense = DenseGrid::from_alive(bounds, cells.iter().copied());
+    assert_same_evolution(&mut dense, &mut reference(), gens);
+
+    eprintln!("{} with HashLife", name);
+    let mut hashlife = HashLife::from_alive(cells);
+    assert_same_evolution(&mut hashlife, &mut reference(), gens);
+}
+
+#[test]
+fn fixtures_decode_strictly() {
+    for fixture in testing::FIXTURES {
+        assert!(!fixture.cells().is_empty(), "{}", fixture.name);
+        assert_eq!(testing::fixture(fixture.name), Some(fixture));
+    }
+    assert_eq!(testing::fixture("unknown"), None);
+}
+
+#[test]
+fn oscillators_return_to_their_start() {
+    for (name, period) in [
+        ("block", 1),
+        ("beehive", 1),
+        ("blinker", 2),
+        ("toad", 2),
+        ("beacon", 2),
+        ("pulsar", 3),
+        ("pentadecathlon", 15),
+    ] {
+        let cells = testing::fixture(name).unwrap().cells();
+        let mut reference = Reference::from_alive(&cells);
+        for _ in 0..period {
+            reference.next_generation();
+        }
+        assert!(reference.iter().eq(cells.iter().copied()), "{}", name);
+    }
+}
+
+#[test]
+fn reference_steps_a_blinker() {
+    let horizontal = vec![vec![false; 3], vec![true; 3], vec![false; 3]];
+    let vertical = vec![vec![false, true, false]; 3];
+    assert_eq!(testing::step_reference(&horizontal), vertical);
+    assert_eq!(testing::step_reference(&vertical), horizontal);
+}
+
+#[test]
+fn every_engine_matches_the_reference_on_fixtures() {
+    for fixture in testing::FIXTURES {
+        check_all(fixture.name, &fixture.cells(), GENS);
+    }
+}
+
+#[test]
+fn every_engine_matches_the_reference_on_soups() {
+    for seed in 0..4 {
+        check_all(&format!("soup {}", seed), &soup(seed), GENS);
+    }
+}
+
+#[test]
+#[should_panic(expected = "engines differ at generation 1: (1, -1) is only alive in the second")]
+fn differences_name_the_generation_and_cell() {
+    let blinker = testing::fixture("blinker").unwrap().cells();
+    // a blinker that doesn't change is right before the first step, but not after it
+    let mut frozen = GameOfLife::from_alive(blinker.clone());
+    frozen.set_rule("B/S012345678".parse().unwrap()).unwrap();
+    assert_same_evolution(&mut frozen, &mut Reference::from_alive(&blinker), 2);
+}
+
+#[test]
+fn hashlife_stops_at_its_last_generation() {
+    let block = testing::fixture("block").unwrap().cells();
+    let mut hashlife = HashLife::from_alive(&block);
+    hashlife.advance(u64::MAX);
+    assert_eq!(hashlife.generation(), HashLife::MAX_GENERATION);
+    assert_eq!(hashlife.to_alive(), block);
+    hashlife.advance(1);
+    assert_eq!(hashlife.generation(), HashLife::MAX_GENERATION);
+
+    // the glider flies far past the coordinates of Pos2 but is still counted
+    let glider = testing::fixture("glider").unwrap().cells();
+    let mut hashlife = HashLife::from_alive(&glider);
+    hashlife.advance(i64::MAX as u64);
+    assert_eq!(hashlife.population(), 5);
+    assert!(hashlife.to_alive().is_empty());
+}
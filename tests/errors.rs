@@ -0,0 +1,106 @@
+//! The errors of the library functions that can fail, and how they convert into `cgolrs::Error`
+
+mod common;
+
+use cgolrs::{
+    enc::{self, DecodeError},
+    engine::{GameOfLife, InvariantError},
+    Error, Pos2, Rect, Rule,
+};
+use std::{error::Error as _, io, path::PathBuf};
+
+/// A path in a fresh temporary directory of the test
+fn temp_path(test: &str, name: &str) -> PathBuf {
+    common::temp_dir("errors", test).join(name)
+}
+
+fn blinker() -> GameOfLife {
+    GameOfLife::from_grid(&[[true, true, true]])
+}
+
+#[test]
+fn generations_rules_with_b0_are_rejected() {
+    let mut game = blinker();
+    let rule = Rule::generations(&[0, 2], &[], 3);
+    assert_eq!(game.set_rule(rule), Err(InvariantError::GenerationsB0));
+    assert_eq!(game.rule(), Rule::CONWAY);
+
+    // without the dying states they're fine
+    assert_eq!(game.set_rule(Rule::new(&[0, 2], &[])), Ok(()));
+}
+
+#[test]
+fn b0_rules_cannot_step_a_region() {
+    let mut game = blinker();
+    game.set_rule("B0123478/S01234678".parse().unwrap())
+        .unwrap();
+    let region = Rect::new(Pos2::new(-1, -1), Pos2::new(4, 2));
+    assert_eq!(
+        game.next_generation_in(region),
+        Err(InvariantError::RegionB0)
+    );
+    assert_eq!(game.generation(), 0);
+    assert_eq!(game.alive(), [(0, 0), (1, 0), (2, 0)].map(Pos2::from));
+}
+
+#[test]
+fn downsampling_by_zero_is_an_error() {
+    let game = blinker();
+    let window = game.window(Rect::new(Pos2::zero(), Pos2::new(3, 1)));
+    assert!(matches!(
+        window.downsample(0),
+        Err(InvariantError::ZeroFactor)
+    ));
+}
+
+#[test]
+fn missing_files_are_io_errors() {
+    let path = temp_path("missing", "nothing.rle");
+    match enc::decode_file(&path, None) {
+        Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+        other => panic!("expected an I/O error, got {:?}", other),
+    }
+}
+
+#[test]
+fn undecodable_files_are_decode_errors() {
+    let unknown = temp_path("undecodable", "hello.txt");
+    std::fs::write(&unknown, "hello world").unwrap();
+    assert!(matches!(
+        enc::decode_file(&unknown, None),
+        Err(Error::Decode(DecodeError::UnknownFormat))
+    ));
+
+    // a binary snapshot cut off after its magic bytes
+    let truncated = temp_path("undecodable", "glider.bin");
+    std::fs::write(&truncated, b"CGOL\xff\xff").unwrap();
+    assert!(matches!(
+        enc::decode_file(&truncated, None),
+        Err(Error::Decode(DecodeError::Corrupt(_)))
+    ));
+}
+
+#[test]
+fn gzip_needs_the_compress_feature() {
+    let path = temp_path("gzip", "glider.rle.gz");
+    let file = enc::create_file(&path);
+    #[cfg(feature = "compress")]
+    assert!(file.is_ok());
+    #[cfg(not(feature = "compress"))]
+    assert!(matches!(
+        file,
+        Err(Error::Encode(enc::EncodeError::GzipUnsupported))
+    ));
+}
+
+#[test]
+fn errors_keep_their_source() {
+    let parse = "B3/S9".parse::<Rule>().unwrap_err();
+    let error = Error::from(parse.clone());
+    assert!(matches!(&error, Error::Rule(e) if *e == parse));
+    assert_eq!(error.to_string(), parse.to_string());
+    assert!(error.source().is_some());
+
+    let invariant = Error::from(InvariantError::ZeroFactor);
+    assert_eq!(invariant.to_string(), "downsample factor must be positive");
+}
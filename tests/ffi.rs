@@ -0,0 +1,98 @@
+//! The C interface, called directly from Rust
+#![cfg(feature = "ffi")]
+
+use cgolrs::ffi::*;
+use std::{
+    ffi::{CStr, CString},
+    ptr, slice,
+};
+
+const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+
+/// Creates a game from `rle`, which must decode
+fn new_game(rle: &str) -> *mut Cgol {
+    let rle = CString::new(rle).unwrap();
+    let handle = unsafe { cgol_new_from_rle(rle.as_ptr()) };
+    assert!(!handle.is_null());
+    handle
+}
+
+/// The coordinates returned by `cgol_cells`, copied out before freeing them
+fn cells(handle: *const Cgol) -> Vec<i32> {
+    let (mut out, mut len) = (ptr::null_mut(), 0);
+    assert_eq!(unsafe { cgol_cells(handle, &mut out, &mut len) }, CgolStatus::Ok);
+    if out.is_null() {
+        assert_eq!(len, 0);
+        return Vec::new();
+    }
+    let coords = unsafe { slice::from_raw_parts(out, len) }.to_vec();
+    unsafe { cgol_cells_free(out, len) };
+    coords
+}
+
+#[test]
+fn gliders_move_diagonally() {
+    let handle = new_game(GLIDER);
+    let start = cells(handle);
+    assert_eq!(start, [1, 0, 2, 1, 0, 2, 1, 2, 2, 2]);
+    assert_eq!(unsafe { cgol_population(handle) }, 5);
+
+    assert_eq!(unsafe { cgol_step(handle, 8) }, CgolStatus::Ok);
+    let moved = start.iter().map(|c| c + 2).collect::<Vec<_>>();
+    assert_eq!(cells(handle), moved);
+    unsafe { cgol_free(handle) };
+}
+
+#[test]
+fn rle_round_trips() {
+    let handle = new_game(GLIDER);
+    unsafe { cgol_step(handle, 3) };
+    let rle = unsafe { cgol_to_rle(handle) };
+    assert!(!rle.is_null());
+    let text = unsafe { CStr::from_ptr(rle) }.to_str().unwrap().to_owned();
+    unsafe { cgol_string_free(rle) };
+    assert!(text.contains("Gen=3"), "{}", text);
+
+    let resumed = new_game(&text);
+    assert_eq!(cells(resumed), cells(handle));
+    unsafe {
+        cgol_free(resumed);
+        cgol_free(handle);
+    }
+}
+
+#[test]
+fn empty_games_have_no_cells() {
+    let handle = new_game("x = 0, y = 0\n!");
+    assert_eq!(unsafe { cgol_population(handle) }, 0);
+    assert_eq!(cells(handle), []);
+    unsafe { cgol_free(handle) };
+}
+
+#[test]
+fn null_pointers_are_errors() {
+    unsafe {
+        assert!(cgol_new_from_rle(ptr::null()).is_null());
+        assert_eq!(cgol_step(ptr::null_mut(), 1), CgolStatus::NullPointer);
+        assert_eq!(cgol_population(ptr::null()), -1);
+        assert!(cgol_to_rle(ptr::null()).is_null());
+        let (mut out, mut len) = (ptr::null_mut(), 0);
+        let status = cgol_cells(ptr::null(), &mut out, &mut len);
+        assert_eq!(status, CgolStatus::NullPointer);
+
+        let handle = new_game(GLIDER);
+        assert_eq!(cgol_cells(handle, ptr::null_mut(), &mut len), CgolStatus::NullPointer);
+        cgol_free(handle);
+
+        // freeing null does nothing
+        cgol_free(ptr::null_mut());
+        cgol_cells_free(ptr::null_mut(), 0);
+        cgol_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn invalid_utf8_is_an_error() {
+    let rle = CString::new(b"x = 1, y = 1\n\xff!".to_vec()).unwrap();
+    assert!(unsafe { cgol_new_from_rle(rle.as_ptr()) }.is_null());
+}
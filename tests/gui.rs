@@ -0,0 +1,47 @@
+//! Command lines the window can't run are rejected before it opens, which works without a display
+#![cfg(feature = "gui")]
+
+use std::process::Command;
+
+/// Asserts that `cgol-gui` rejects the arguments with exit code 2 and an error mentioning `word`
+fn rejects(args: &[&str], word: &str) {
+    let output = Command::new(env!("CARGO_BIN_EXE_cgol-gui"))
+        .args(args)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, stderr);
+    assert!(stderr.starts_with("error: "), "{:?}: {}", args, stderr);
+    assert!(stderr.contains(word), "{:?} should mention {}: {}", args, word, stderr);
+}
+
+#[test]
+fn console_mode() {
+    rejects(&["--console"], "console mode");
+    rejects(&["--edit"], "console mode");
+    rejects(&["demo"], "console mode");
+}
+
+#[test]
+fn other_commands_and_engines() {
+    rejects(&["soup"], "only runs patterns");
+    rejects(&["--engine", "hashlife", "--gens", "10"], "--engine hashlife");
+    rejects(&["--banana"], "cgol-gui --help");
+}
+
+#[test]
+fn lists_print_without_a_window() {
+    for (args, line) in [
+        (["--rule", "list"], "highlife"),
+        (["--pattern", "list"], "glider"),
+        (["--fill", "list"], "checkerboard"),
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_cgol-gui"))
+            .args(args)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{:?}", args);
+        assert!(stdout.lines().any(|l| l.starts_with(line)), "{:?}: {}", args, stdout);
+    }
+}
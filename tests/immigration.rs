@@ -0,0 +1,131 @@
+//! The two colors of Immigration, which ride along with the cells without changing them
+
+use cgolrs::{
+    enc::{PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+    patterns, Pos2, Rect,
+};
+
+/// A soup with colors from its seed
+fn colored_soup(seed: u64) -> GameOfLife {
+    let mut game = GameOfLife::random(Rect::from_size(Pos2::new(-20, -20), 40, 40), 0.35, seed).unwrap();
+    game.track_colors(true);
+    game
+}
+
+#[test]
+fn colors_dont_change_the_cells() {
+    for seed in 0..10 {
+        let mut colored = colored_soup(seed);
+        let mut plain = GameOfLife::from_alive(colored.alive().to_vec());
+        for _ in 0..50 {
+            colored.next_generation();
+            plain.next_generation();
+            assert_eq!(colored.alive(), plain.alive());
+            assert_eq!(colored.colors().unwrap().len(), colored.alive_count());
+        }
+        assert_eq!(colored.state_hash(), plain.state_hash());
+        assert_eq!(plain.colors(), None);
+    }
+}
+
+#[test]
+fn births_take_the_majority_color() {
+    // an L of three cells gives birth to the cell that completes the block
+    let cells = |colors: [u8; 3]| {
+        let cells = [(0, 0), (1, 0), (0, 1)].map(Pos2::from);
+        GameOfLife::from_alive_colored(cells.into_iter().zip(colors).collect())
+    };
+    for (colors, born) in [
+        ([0, 0, 1], 0),
+        ([1, 0, 1], 1),
+        ([1, 1, 1], 1),
+        ([0, 0, 0], 0),
+    ] {
+        let mut game = cells(colors);
+        game.next_generation();
+        assert_eq!(game.alive_count(), 4);
+        assert_eq!(game.color(Pos2::new(1, 1)), Some(born), "{:?}", colors);
+        // the parents survive with their own colors
+        assert_eq!(game.color(Pos2::new(0, 0)), Some(colors[0]));
+        assert_eq!(game.color(Pos2::new(1, 0)), Some(colors[1]));
+    }
+}
+
+#[test]
+fn gliders_keep_their_colors_apart() {
+    // two gliders of different colors far apart each stay one color
+    let glider = patterns::find("glider").unwrap().cells();
+    let far = glider.iter().map(|&pos| pos + Pos2::new(100, 0));
+    let mut cells = glider.iter().map(|&pos| (pos, 0)).collect::<Vec<_>>();
+    cells.extend(far.map(|pos| (pos, 1)));
+    cells.sort_by_key(|&(pos, _)| pos);
+    let mut game = GameOfLife::from_alive_colored(cells);
+    game.step_n(40);
+    for (&pos, &color) in game.alive().iter().zip(game.colors().unwrap()) {
+        assert_eq!(color, (pos.x > 50) as u8);
+    }
+}
+
+#[test]
+fn edits_and_transforms_keep_colors_in_step() {
+    let mut game = colored_soup(3);
+    let before = game
+        .alive()
+        .iter()
+        .map(|&pos| (pos, game.color(pos).unwrap()))
+        .collect::<Vec<_>>();
+    game.rotate_cw();
+    game.rotate_ccw();
+    let after = game
+        .alive()
+        .iter()
+        .map(|&pos| (pos, game.color(pos).unwrap()));
+    assert!(after.eq(before.iter().copied()));
+
+    // new cells start with color 0, and removed cells take their colors with them
+    let pos = Pos2::new(1000, 1000);
+    game.set_cell(pos, true);
+    assert_eq!(game.color(pos), Some(0));
+    game.set_cell(before[0].0, false);
+    assert_eq!(game.colors().unwrap().len(), game.alive_count());
+    game.retain_within(Rect::new(Pos2::new(-5, -5), Pos2::new(5, 5)));
+    assert_eq!(game.colors().unwrap().len(), game.alive_count());
+
+    game.track_colors(false);
+    assert_eq!(game.colors(), None);
+    assert_eq!(game.color(Pos2::new(0, 0)), None);
+}
+
+#[test]
+fn colors_round_trip_through_rle() {
+    let mut game = colored_soup(5);
+    game.step_n(10);
+    let cells = game
+        .alive()
+        .iter()
+        .copied()
+        .zip(game.colors().unwrap().iter().copied())
+        .collect::<Vec<_>>();
+    let rle = RunLengthEncoded::default()
+        .set_colors(game.colors().unwrap().to_vec())
+        .encode(game.alive());
+    assert!(rle.contains("rule = Immigration\n"));
+    assert_eq!(RunLengthEncoded::default().decode_colored(&rle), cells);
+    assert_eq!(
+        RunLengthEncoded::default()
+            .try_decode(&rle)
+            .map(|c| c.len()),
+        Ok(cells.iter().filter(|c| c.1 == 0).count())
+    );
+
+    // without colors the plain letters are written, and every cell is color 0
+    let plain = RunLengthEncoded::default().encode(game.alive());
+    assert!(!plain.contains("Immigration"));
+    let decoded = RunLengthEncoded::default().decode_colored(&plain);
+    assert!(decoded
+        .iter()
+        .map(|&(pos, _)| pos)
+        .eq(game.alive().iter().copied()));
+    assert!(decoded.iter().all(|&(_, color)| color == 0));
+}
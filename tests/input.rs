@@ -0,0 +1,162 @@
+//! Starting patterns from `--input` files, built-in `--pattern`s and `--fill`s
+
+mod common;
+
+use cgolrs::{enc, fill, patterns, transform, Pos2};
+use common::FIXTURES;
+use std::{
+    path::{Path, PathBuf},
+    process::Output,
+};
+
+/// Runs zero generations from the inputs, writing the starting pattern to `out.rle`
+fn combine(test: &str, inputs: &[&str]) -> (Output, PathBuf) {
+    let mut flags = Vec::new();
+    for input in inputs {
+        flags.extend(["--input".to_owned(), format!("{}/{}", FIXTURES, input)]);
+    }
+    start(test, &flags)
+}
+
+/// Runs zero generations with the flags, writing the starting pattern to `out.rle`
+fn start(test: &str, flags: &[String]) -> (Output, PathBuf) {
+    let dir = common::temp_dir("input", test);
+    let start = ["--gens", "0", "-q", "-o", "out.rle"];
+    let output = common::succeed(common::gol(&dir).args(start).args(flags));
+    (output, dir.join("out.rle"))
+}
+
+fn glider_at(x: i32, y: i32) -> Vec<Pos2> {
+    let glider = enc::decode_file(Path::new(FIXTURES).join("glider.rle"), None).unwrap();
+    transform::translate(&glider.cells, Pos2::new(x, y))
+}
+
+#[test]
+fn combines_files_at_their_offsets() {
+    let (output, path) = combine("offsets", &["glider.rle@0,0", "glider.cells@10,-4"]);
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut expected = [glider_at(0, 0), glider_at(10, -4)].concat();
+    expected.sort();
+    let pattern = enc::decode_file(path, None).unwrap();
+    assert_eq!(pattern.cells, expected);
+    // the metadata comes from the first file
+    assert_eq!(pattern.meta.name.as_deref(), Some("Glider"));
+}
+
+#[test]
+fn warns_about_overlapping_cells() {
+    let (output, path) = combine("overlap", &["glider.cells@1,0", "glider.rle", "glider.rle"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // the second glider is the same as the first, and two cells of the moved one overlap it
+    assert!(stderr.contains("warning: 7 cells"), "{}", stderr);
+
+    let (expected, _) = transform::union(&glider_at(0, 0), &glider_at(1, 0));
+    assert_eq!(enc::decode_file(path, None).unwrap().cells, expected);
+}
+
+#[test]
+fn starts_from_built_in_patterns() {
+    let flags = ["--pattern", "glider@-5,2,blinker@10,10"].map(String::from);
+    let (output, path) = start("builtin", &flags);
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let glider = transform::translate(&patterns::find("glider").unwrap().cells(), Pos2::new(-5, 2));
+    let blinker = [(10, 10), (11, 10), (12, 10)].map(Pos2::from);
+    let pattern = enc::decode_file(path, None).unwrap();
+    assert_eq!(pattern.cells, [&glider[..], &blinker].concat());
+    assert_eq!(pattern.meta.name.as_deref(), Some("glider"));
+}
+
+#[test]
+fn files_come_before_built_in_patterns() {
+    let flags = [
+        "--pattern".to_owned(),
+        "glider@20,0".to_owned(),
+        "--input".to_owned(),
+        format!("{}/glider.rle", FIXTURES),
+    ];
+    let (_, path) = start("order", &flags);
+    let pattern = enc::decode_file(path, None).unwrap();
+    let (expected, _) = transform::union(&glider_at(0, 0), &glider_at(20, 0));
+    assert_eq!(pattern.cells, expected);
+    assert_eq!(pattern.meta.name.as_deref(), Some("Glider"));
+}
+
+#[test]
+fn lists_the_built_in_patterns() {
+    let output = common::succeed(common::gol(Path::new(".")).args(["--pattern", "list"]));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), patterns::PATTERNS.len());
+    for pattern in patterns::PATTERNS {
+        assert!(stdout.contains(pattern.name), "{}", stdout);
+    }
+    assert!(stdout.contains("36x9"), "{}", stdout);
+}
+
+#[test]
+fn offset_moves_everything_last() {
+    let flags = [
+        "--input",
+        &format!("{}/glider.rle@3,0", FIXTURES),
+        "--pattern",
+        "blinker@0,10",
+        "--offset",
+        "-10,-20",
+    ]
+    .map(String::from);
+    let (_, path) = start("offset", &flags);
+
+    let blinker = [(-10, -10), (-9, -10), (-8, -10)].map(Pos2::from);
+    let pattern = enc::decode_file(path, None).unwrap();
+    assert_eq!(pattern.cells, [&glider_at(-7, -20)[..], &blinker].concat());
+}
+
+#[test]
+fn offset_moves_the_fill() {
+    let flags = ["-w", "3", "-h", "3", "-f", "diag", "--offset", "-1,-1"].map(String::from);
+    let (_, path) = start("fill", &flags);
+    let pattern = enc::decode_file(path, None).unwrap();
+    assert_eq!(pattern.cells, [(-1, -1), (0, 0), (1, 1)].map(Pos2::from));
+}
+
+#[test]
+fn lists_the_fills() {
+    let output = common::succeed(common::gol(Path::new(".")).args(["--fill", "list"]));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), fill::Fill::FILLS.len());
+    for line in stdout.lines() {
+        let name = line.split_whitespace().next().unwrap();
+        assert!(name.parse::<fill::Fill>().is_ok(), "{}", line);
+    }
+}
+
+#[test]
+fn unreadable_images_are_an_error() {
+    let dir = common::temp_dir("input", "image");
+    std::fs::write(dir.join("junk.png"), b"not a png").unwrap();
+    let output = common::gol(&dir)
+        .args(["--gens", "0", "-q", "--input", "junk.png"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // EX_DATAERR for an image that doesn't decode, or EX_IOERR when PNG input isn't built in
+    let expected = if cfg!(feature = "image") { 65 } else { 74 };
+    assert_eq!(output.status.code(), Some(expected), "{}", stderr);
+    assert!(stderr.starts_with("error: "), "{}", stderr);
+}
+
+#[test]
+fn undecodable_inputs_are_reported_with_their_name() {
+    let dir = common::temp_dir("input", "unknown");
+    std::fs::write(dir.join("hello.txt"), "hello world").unwrap();
+    let output = common::gol(&dir)
+        .args(["--gens", "0", "-q", "--input", "hello.txt"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // EX_DATAERR, with a hint at --format
+    assert_eq!(output.status.code(), Some(65), "{}", stderr);
+    assert!(stderr.starts_with("error: hello.txt: "), "{}", stderr);
+    assert!(stderr.contains("use --format"), "{}", stderr);
+}
@@ -0,0 +1,45 @@
+//! Ctrl+C outside of console mode ends the run like reaching `--gens` would
+#![cfg(unix)]
+
+mod common;
+
+use cgolrs::enc;
+use std::{
+    io::{BufRead, BufReader, Read},
+    process::{Command, Stdio},
+};
+
+#[test]
+fn first_interrupt_finishes_the_run() {
+    let dir = common::temp_dir("interrupt", "first");
+    let mut child = common::gol(&dir)
+        .args(["-w", "64", "-h", "64", "-f", "random", "--report-interval", "0"])
+        .args(["-o", "out.rle", "--stats-out", "run.csv"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // the handler is in place once the first generation is reported
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("gen/s") {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "the run ended early");
+    }
+    let kill = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(kill.success());
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    assert!(child.wait().unwrap().success());
+    let summary = rest.lines().find(|line| line.starts_with("ran ")).unwrap();
+
+    let pattern = enc::decode_file(dir.join("out.rle"), None).unwrap();
+    assert!(pattern.generation > 0);
+    assert!(summary.contains(&format!("gens:{},", pattern.generation)), "{}", summary);
+    let csv = std::fs::read_to_string(dir.join("run.csv")).unwrap();
+    assert_eq!(csv.lines().count() as u64, pattern.generation + 1, "{}", csv);
+}
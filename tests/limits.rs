@@ -0,0 +1,198 @@
+//! Hostile and corrupt patterns that would take the decoders gigabytes of memory or minutes of
+//! time without their limits
+
+use cgolrs::enc::{
+    self, Binary, DecodeError, DecodeLimits, Format, Limit, PositionEncoder, RunLengthEncoded,
+};
+use cgolrs::Pos2;
+use std::time::{Duration, Instant};
+
+fn exceeded(limit: Limit, max: u64) -> DecodeError {
+    DecodeError::LimitExceeded { limit, max }
+}
+
+/// Decodes `rle` with the default limits, failing the test if it takes more than a moment
+fn decode(rle: &str) -> Result<Vec<Pos2>, DecodeError> {
+    let start = Instant::now();
+    let cells = RunLengthEncoded::lenient().try_decode(rle);
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "took {:?}",
+        start.elapsed()
+    );
+    cells
+}
+
+#[test]
+fn long_runs_fail_before_pushing_cells() {
+    let defaults = DecodeLimits::default();
+    assert_eq!(
+        decode("2000000000$2000000000o!"),
+        Err(exceeded(Limit::Run, defaults.max_run as u64))
+    );
+    assert_eq!(
+        decode("99999999o!"),
+        Err(exceeded(Limit::Cells, defaults.max_cells as u64))
+    );
+    // the count saturates rather than overflowing
+    assert_eq!(
+        decode("99999999999999999999999999999o!"),
+        Err(exceeded(Limit::Run, defaults.max_run as u64))
+    );
+}
+
+#[test]
+fn coordinates_are_bounded_however_they_grow() {
+    let max = DecodeLimits::default().max_coord as u64;
+    // many runs that are each within the limits
+    let gaps = "90000000b".repeat(20) + "o!";
+    assert_eq!(decode(&gaps), Err(exceeded(Limit::Coord, max)));
+    let rows = "90000000$".repeat(20) + "o!";
+    assert_eq!(decode(&rows), Err(exceeded(Limit::Coord, max)));
+    assert_eq!(
+        decode("#CXRLE Pos=2000000000,0\no!"),
+        Err(exceeded(Limit::Coord, max))
+    );
+    // the left edge of the pattern too
+    assert_eq!(
+        decode("#CXRLE Pos=-999999999,0\no!"),
+        Ok(vec![Pos2::new(-999_999_999, 0)])
+    );
+}
+
+#[test]
+fn many_small_runs_add_up_to_the_cell_limit() {
+    let limits = DecodeLimits {
+        max_cells: 1000,
+        ..DecodeLimits::default()
+    };
+    let rle = "10o$".repeat(99) + "10A!";
+    let decoder = || RunLengthEncoded::default().with_limits(limits);
+    assert_eq!(
+        decoder().try_decode(&rle).map(|cells| cells.len()),
+        Ok(1000)
+    );
+    let rle = "10o$".repeat(100) + "o!";
+    assert_eq!(
+        decoder().try_decode(&rle),
+        Err(exceeded(Limit::Cells, 1000))
+    );
+    // dying cells count as well
+    let rle = format!("x = 0, y = 0, rule = B2/S/C3\n{}B!", "10A$".repeat(100));
+    assert_eq!(
+        decoder().try_decode(&rle),
+        Err(exceeded(Limit::Cells, 1000))
+    );
+
+    // infallible decoding has nothing to show for it
+    assert!(decoder().decode(&rle).is_empty());
+    let unlimited = RunLengthEncoded::default().with_limits(DecodeLimits::UNLIMITED);
+    assert_eq!(
+        unlimited.try_decode(&rle).map(|cells| cells.len()),
+        Ok(1000)
+    );
+}
+
+#[test]
+fn formats_and_files_report_the_limits() {
+    let max = DecodeLimits::default().max_coord as u64;
+    let rows = "90000000$".repeat(12) + "o!";
+    assert_eq!(Format::Rle.decode(&rows), Err(exceeded(Limit::Coord, max)));
+    assert_eq!(
+        enc::decode_any(format!("x = 1, y = 1\n{}", rows)),
+        Err(exceeded(Limit::Coord, max))
+    );
+
+    // every text format is bounded, not only RLE
+    let far = [
+        (
+            Format::Life105,
+            "#Life 1.05\n#P 0 1500000000\n*\n".to_owned(),
+        ),
+        (
+            Format::Life106,
+            "#Life 1.06\n0 0\n-1500000000 0\n".to_owned(),
+        ),
+    ];
+    for (format, content) in far {
+        assert_eq!(format.decode(&content), Err(exceeded(Limit::Coord, max)));
+        assert_eq!(enc::decode_any(&content), Err(exceeded(Limit::Coord, max)));
+    }
+    let limits = DecodeLimits {
+        max_cells: 10,
+        ..DecodeLimits::default()
+    };
+    let many = [
+        (
+            Format::Life105,
+            "#Life 1.05\n#P 0 0\n***********\n".to_owned(),
+        ),
+        (
+            Format::Life106,
+            "#Life 1.06\n".to_owned() + &"0 0\n".repeat(11),
+        ),
+        (
+            Format::Plaintext,
+            "!Name: row\n".to_owned() + &"O".repeat(11),
+        ),
+    ];
+    for (format, content) in many {
+        assert_eq!(
+            format.decode_limited(&content, limits),
+            Err(exceeded(Limit::Cells, 10)),
+            "{}",
+            format
+        );
+        assert!(format.decode(&content).is_ok(), "{}", format);
+    }
+}
+
+#[test]
+fn binary_snapshots_are_checked_as_they_stream() {
+    let cells = [(0, 0), (1, 0), (1_500_000_000, 1)].map(Pos2::from);
+    let mut bytes = Vec::new();
+    Binary::default().encode_to(&cells, &mut bytes).unwrap();
+    let max = DecodeLimits::default().max_coord as u64;
+    assert_eq!(
+        Format::Binary.decode(&bytes),
+        Err(exceeded(Limit::Coord, max))
+    );
+    let pattern = Binary::decode_limited(&bytes[..], DecodeLimits::UNLIMITED).unwrap();
+    assert_eq!(pattern.cells, cells);
+
+    // a count of cells far beyond the limit fails before any cell is read
+    let mut bytes = Vec::new();
+    Binary::default()
+        .with_checksum(false)
+        .encode_to(&cells[..1], &mut bytes)
+        .unwrap();
+    let count = bytes.len() - 3;
+    assert_eq!(bytes[count], 1);
+    bytes.truncate(count);
+    bytes.extend([0xff, 0xff, 0xff, 0xff, 0x0f]);
+    let limits = DecodeLimits::default();
+    assert_eq!(
+        Format::Binary.decode(&bytes),
+        Err(exceeded(Limit::Cells, limits.max_cells as u64))
+    );
+}
+
+#[test]
+fn life105_blocks_past_the_edge_drop_their_cells() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/hostile/offset-overflow.lif"
+    );
+    let content = std::fs::read_to_string(path).unwrap();
+    let edge = [Pos2::new(i32::MAX, 0)];
+    assert_eq!(enc::Life105::default().decode(&content), edge);
+    let unlimited = Format::Life105.decode_limited(&content, DecodeLimits::UNLIMITED);
+    assert_eq!(unlimited.unwrap().cells, edge);
+    // the cell left is far past the default limits
+    let max = DecodeLimits::default().max_coord as u64;
+    assert_eq!(enc::decode_any(&content), Err(exceeded(Limit::Coord, max)));
+    assert!(matches!(
+        enc::decode_file(path, None),
+        Err(cgolrs::Error::Decode(DecodeError::LimitExceeded { .. }))
+    ));
+}
@@ -0,0 +1,40 @@
+//! The library without its default `std` feature, checked for an embedded target when its
+//! standard library is installed
+
+use std::{path::Path, process::Command};
+
+/// A bare-metal target without `std`, like the microcontrollers of LED matrices
+const EMBEDDED_TARGET: &str = "thumbv7em-none-eabihf";
+
+/// Whether the standard library of `target` is installed, so cargo can build for it
+fn has_target(target: &str) -> bool {
+    let output = Command::new("rustc")
+        .args(["--print", "target-libdir", "--target", target])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            Path::new(String::from_utf8_lossy(&output.stdout).trim()).exists()
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn library_builds_without_std() {
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["check", "--lib", "--no-default-features"])
+        // a separate target directory, so this doesn't wait on the build running the test
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("no-std"),
+        );
+    // without the embedded target, `#![no_std]` still keeps `std` out of the library itself
+    if has_target(EMBEDDED_TARGET) {
+        cargo.args(["--target", EMBEDDED_TARGET]);
+    }
+    let output = cargo.output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+}
@@ -0,0 +1,51 @@
+//! Parallel steps with more threads than a pattern has use for, which have to match serial ones
+
+use cgolrs::{engine::GameOfLife, patterns, testing::assert_same_evolution, Pos2, Rect, Rule};
+
+/// Steps the cells serially and with each thread count, with the default chunk size and with
+/// chunks as small as they get, checking every generation against the serial one and the whole
+/// state at the end
+fn check_against_serial(cells: &[Pos2], rule: Rule, gens: usize) {
+    let mut serial = GameOfLife::from_unsorted(cells.to_vec());
+    serial.set_rule(rule).unwrap();
+    for threads in [2, 16, 64] {
+        for min_chunk_cells in [None, Some(1)] {
+            let mut game = serial.clone();
+            game.set_threads(threads);
+            if let Some(cells) = min_chunk_cells {
+                game.set_min_chunk_cells(cells);
+            }
+            let mut serial = serial.clone();
+            assert_same_evolution(&mut game, &mut serial, gens);
+            let context = format!("{} threads, {:?} cells", threads, min_chunk_cells);
+            assert!(game.eq_strict(&serial), "{}: {:?}", context, game.diff(&serial));
+            assert_eq!(game.state_hash(), serial.state_hash(), "{}", context);
+        }
+    }
+}
+
+#[test]
+fn tiny_patterns_with_many_threads() {
+    for name in ["glider", "blinker", "r-pentomino", "lwss"] {
+        let cells = patterns::find(name).unwrap().cells();
+        check_against_serial(&cells, Rule::CONWAY, 60);
+    }
+    // a single row, which can't be split at all
+    let row = (0..30).map(|x| Pos2::new(x, 0)).collect::<Vec<_>>();
+    check_against_serial(&row, Rule::CONWAY, 20);
+    check_against_serial(&[], Rule::CONWAY, 2);
+}
+
+#[test]
+fn small_soups_with_many_threads() {
+    for (seed, rule) in [(0, "B3/S23"), (1, "B36/S23"), (2, "B2/S/C3"), (3, "B0123478/S01234678")] {
+        let rect = Rect::from_size(Pos2::new(-3, -3), 6, 5);
+        let tiny = GameOfLife::random(rect, 0.5, 366 + seed).unwrap();
+        let rule = rule.parse::<Rule>().unwrap();
+        check_against_serial(tiny.alive(), rule, 30);
+        // big enough for a few chunks of the default size, but not for one per thread
+        let rect = Rect::from_size(Pos2::new(-40, -40), 80, 80);
+        let soup = GameOfLife::random(rect, 0.4, 3660 + seed).unwrap();
+        check_against_serial(soup.alive(), rule, 10);
+    }
+}
@@ -0,0 +1,109 @@
+//! Properties of the engine and the codecs over generated games
+#![cfg(feature = "proptest")]
+
+use cgolrs::{
+    enc::{Binary, Format, Life105, Life106, Plaintext, PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+    strategy, transform, Pos2, Rect,
+};
+use proptest::prelude::*;
+
+/// The area the games are generated in, around the origin so negative cells are covered too
+fn area() -> Rect {
+    Rect::from_size(Pos2::new(-20, -20), 40, 40)
+}
+
+/// The cells moved so that their bounding box starts at the origin, since not every format
+/// keeps the position of a pattern
+fn normalized(cells: &[Pos2]) -> Vec<Pos2> {
+    transform::translate(cells, -transform::top_left(cells))
+}
+
+/// The cells encoded in `format`
+fn encode(format: Format, cells: &[Pos2]) -> Vec<u8> {
+    match format {
+        Format::Rle => RunLengthEncoded::default().encode(cells).into_bytes(),
+        Format::Life105 => Life105::default().encode(cells).into_bytes(),
+        Format::Life106 => Life106.encode(cells).into_bytes(),
+        Format::Plaintext => Plaintext::default().encode(cells).into_bytes(),
+        Format::Binary => {
+            let mut bytes = Vec::new();
+            Binary::default().encode_to(cells, &mut bytes).unwrap();
+            bytes
+        }
+    }
+}
+
+/// A transform of a game in place
+type Transform = fn(&mut GameOfLife);
+
+/// The symmetries of the grid that every transform is made of, by name
+const TRANSFORMS: [(&str, Transform); 4] = [
+    ("rotate_cw", GameOfLife::rotate_cw),
+    ("rotate_ccw", GameOfLife::rotate_ccw),
+    ("flip_x", GameOfLife::flip_x),
+    ("flip_y", GameOfLife::flip_y),
+];
+
+proptest! {
+    #[test]
+    fn decode_inverts_encode(game in strategy::game(area(), 300)) {
+        for format in [Format::Rle, Format::Life105, Format::Life106, Format::Plaintext, Format::Binary] {
+            let pattern = format.decode(encode(format, game.alive())).unwrap();
+            prop_assert_eq!(normalized(&pattern.cells), normalized(game.alive()), "{}", format);
+        }
+    }
+
+    #[test]
+    fn parallel_steps_match_serial_ones(game in strategy::game(area(), 300), threads in 2..8usize) {
+        let mut serial = game.clone();
+        let mut parallel = game;
+        parallel.set_min_chunk_cells(1);
+        for _ in 0..16 {
+            serial.next_generation();
+            parallel.next_generation_parallel(threads);
+            prop_assert_eq!(serial.alive(), parallel.alive());
+        }
+    }
+
+    #[test]
+    fn transforms_undo_themselves(game in strategy::game(area(), 300)) {
+        let mut rotated = game.clone();
+        for _ in 0..4 {
+            rotated.rotate_cw();
+        }
+        prop_assert_eq!(rotated.alive(), game.alive());
+        let mut turned = game.clone();
+        turned.rotate_cw();
+        turned.rotate_ccw();
+        prop_assert_eq!(turned.alive(), game.alive());
+        for (name, flip) in &TRANSFORMS[2..] {
+            let mut flipped = game.clone();
+            flip(&mut flipped);
+            flip(&mut flipped);
+            prop_assert_eq!(flipped.alive(), game.alive(), "{}", name);
+        }
+
+        // the same for the cells on their own
+        let cells = game.alive();
+        let rotated = (0..4).fold(cells.to_vec(), |cells, _| transform::rotate_cw(&cells));
+        prop_assert_eq!(&rotated[..], cells);
+        prop_assert_eq!(&transform::rotate_ccw(&transform::rotate_cw(cells))[..], cells);
+        prop_assert_eq!(&transform::flip_x(&transform::flip_x(cells))[..], cells);
+        prop_assert_eq!(&transform::flip_y(&transform::flip_y(cells))[..], cells);
+    }
+
+    #[test]
+    fn steps_commute_with_transforms(game in strategy::game(area(), 300)) {
+        for (name, transform) in TRANSFORMS {
+            let (mut stepped_first, mut transformed_first) = (game.clone(), game.clone());
+            transform(&mut transformed_first);
+            for _ in 0..8 {
+                stepped_first.next_generation();
+                transformed_first.next_generation();
+            }
+            transform(&mut stepped_first);
+            prop_assert_eq!(stepped_first.alive(), transformed_first.alive(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Evolving only the cells within a region, while the rest of the universe stays frozen, and
+//! culling the cells outside of one
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect, Rule};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A copy of the state of `game`
+fn copy(game: &GameOfLife) -> GameOfLife {
+    let mut copy = GameOfLife::from_alive(game.alive().to_vec());
+    copy.set_rule(game.rule()).unwrap();
+    copy.set_dying(game.dying().to_vec());
+    copy
+}
+
+/// Steps `game` within `region` and checks it against a full step within the region and the
+/// state before it outside
+fn step_and_check(game: &mut GameOfLife, region: Rect) {
+    let before = copy(game);
+    let mut full = copy(game);
+    full.next_generation();
+    game.next_generation_in(region).unwrap();
+
+    let pick = |inside: &GameOfLife, outside: &GameOfLife| {
+        let mut alive = inside
+            .alive()
+            .iter()
+            .filter(|&&pos| region.contains(pos))
+            .chain(outside.alive().iter().filter(|&&pos| !region.contains(pos)))
+            .copied()
+            .collect::<Vec<_>>();
+        alive.sort_unstable();
+        let mut dying = inside
+            .dying()
+            .iter()
+            .filter(|&&(pos, _)| region.contains(pos))
+            .chain(outside.dying().iter().filter(|&&(pos, _)| !region.contains(pos)))
+            .copied()
+            .collect::<Vec<_>>();
+        dying.sort_unstable();
+        (alive, dying)
+    };
+    let (alive, dying) = pick(&full, &before);
+    assert_eq!(game.alive(), alive, "{} in {:?}", game.rule(), region);
+    assert_eq!(game.dying(), dying, "{} in {:?}", game.rule(), region);
+    assert_eq!(game.state_hash(), copy(game).state_hash());
+}
+
+#[test]
+fn soups_only_evolve_within_the_region() {
+    let mut rng = StdRng::seed_from_u64(358);
+    for rule in ["B3/S23", "B36/S23", "B2/S/C3", "B345/S4567/C5"] {
+        let rule = rule.parse::<Rule>().unwrap();
+        let soup = common::soup(&mut rng, Rect::from_size(Pos2::new(-20, -20), 40, 40), 0.35);
+        let mut game = GameOfLife::from_alive(soup);
+        game.set_rule(rule).unwrap();
+        for _ in 0..10 {
+            let min = Pos2::new(rng.gen_range(-25..10), rng.gen_range(-25..10));
+            let region = Rect::from_size(min, rng.gen_range(0..30), rng.gen_range(0..30));
+            step_and_check(&mut game, region);
+        }
+    }
+}
+
+#[test]
+fn a_glider_hits_frozen_cells_on_the_border() {
+    let region = Rect::new(Pos2::new(0, 0), Pos2::new(16, 16));
+    // a glider heading down and to the right, towards a frozen wall right outside the region
+    let glider = [(3, 2), (4, 3), (2, 4), (3, 4), (4, 4)].map(Pos2::from);
+    let wall = (6..16).map(|y| Pos2::new(16, y)).collect::<Vec<_>>();
+    let mut cells = glider.iter().chain(&wall).copied().collect::<Vec<_>>();
+    cells.sort_unstable();
+
+    let mut game = GameOfLife::from_alive(cells);
+    let mut free = GameOfLife::from_alive(glider.to_vec());
+    for _ in 0..60 {
+        step_and_check(&mut game, region);
+        free.next_generation_in(region).unwrap();
+    }
+    let frozen = game
+        .alive()
+        .iter()
+        .filter(|&&pos| !region.contains(pos))
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(frozen, wall);
+    // the wall changed how the glider evolved, even though it never moved itself
+    let inside = game
+        .alive()
+        .iter()
+        .filter(|&&pos| region.contains(pos))
+        .copied()
+        .collect::<Vec<_>>();
+    assert_ne!(inside, free.alive());
+    assert_eq!(game.generation(), 60);
+}
+
+#[test]
+fn rewinding_stops_at_region_steps() {
+    let mut game = GameOfLife::from_grid(&[[true, true, true]]);
+    game.enable_history(8, 1);
+    game.step_n(3);
+    game.next_generation_in(Rect::new(Pos2::new(-5, -5), Pos2::new(5, 5))).unwrap();
+    game.next_generation();
+    assert!(game.rewind(1));
+    assert_eq!(game.generation(), 4);
+    assert!(!game.rewind(1));
+}
+
+#[test]
+fn culling_leaves_a_valid_state() {
+    for (rule, pattern) in [("B3/S23", "gosper-glider-gun"), ("B2/S/C3", "r-pentomino")] {
+        let rule = rule.parse::<Rule>().unwrap();
+        let mut game = GameOfLife::from_alive(patterns::find(pattern).unwrap().cells());
+        game.set_rule(rule).unwrap();
+        game.track_ages(true);
+        game.set_incremental(true);
+        let center = Pos2::new(18, 4);
+        let square = Rect::new(Pos2::new(-2, -16), Pos2::new(39, 25));
+        let mut removed = 0;
+        for gen in 1..=300 {
+            game.next_generation();
+            if gen % 25 != 0 {
+                continue;
+            }
+            let population = game.alive_count();
+            let culled = game.cull_beyond_radius(center, 20);
+            assert_eq!(game.alive_count(), population - culled);
+            removed += culled;
+
+            assert!(GameOfLife::try_from_alive(game.alive().to_vec()).is_ok());
+            assert!(game.alive().iter().all(|&pos| square.contains(pos)));
+            assert!(game.dying().windows(2).all(|w| w[0].0 < w[1].0));
+            assert!(game.dying().iter().all(|&(pos, _)| square.contains(pos)));
+            assert_eq!(game.ages().map(<[u32]>::len), Some(game.alive_count()));
+            assert_eq!(game.state_hash(), copy(&game).state_hash());
+
+            // the culled state keeps evolving like a fresh game of it
+            let mut fresh = copy(&game);
+            fresh.next_generation();
+            game.next_generation();
+            assert_eq!(game.alive(), fresh.alive(), "{} generation {}", pattern, gen);
+            assert_eq!(game.dying(), fresh.dying(), "{} generation {}", pattern, gen);
+        }
+        assert!(removed > 0, "{}", pattern);
+    }
+}
@@ -0,0 +1,200 @@
+//! The exact text of encoded RLE, which other programs have to read
+
+use cgolrs::{
+    enc::{self, DecodeError, PositionEncoder, RunLengthEncoded},
+    patterns, Pos2, Rule,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// The body of the encoded cells, without the header lines
+fn body(encoder: RunLengthEncoded, cells: &[Pos2]) -> String {
+    encoder.with_header(false).encode(cells)
+}
+
+#[test]
+fn fixtures_encode_to_their_body() {
+    let pattern = enc::decode_file(format!("{}/copperhead.rle", FIXTURES), None).unwrap();
+    assert_eq!(
+        body(RunLengthEncoded::default(), &pattern.cells),
+        "b2o2b2o$3b2o$3b2o$obo2bobo$o6bo2$o6bo$b2o2b2o$2b4o2$3b2o$3b2o!"
+    );
+}
+
+#[test]
+fn headers_are_written_in_order() {
+    let cells = patterns::find("glider").unwrap().cells();
+    let rle = RunLengthEncoded::default()
+        .set_name("Glider")
+        .with_comment("The smallest spaceship.")
+        .with_rule(&Rule::CONWAY)
+        .set_generation(4)
+        .encode(&cells);
+    assert_eq!(
+        rle,
+        "#N Glider\n#C The smallest spaceship.\n#CXRLE Pos=0,0 Gen=4\n\
+         x = 0, y = 0, rule = B3/S23\nbo$2bo$3o!\n"
+    );
+}
+
+#[test]
+fn lines_wrap_before_the_run_that_overflows() {
+    // alternating cells, so every run is one character
+    let row = (0..20).map(|x| Pos2::new(x * 2, 0)).collect::<Vec<_>>();
+    assert_eq!(
+        body(RunLengthEncoded::default().line_width(8), &row),
+        "obobobob\nobobobob\nobobobob\nobobobob\nobobobo!"
+    );
+    // runs with counts are never split across lines
+    let blocks = (0..6).map(|x| Pos2::new(x * 13, 0)).collect::<Vec<_>>();
+    assert_eq!(
+        body(RunLengthEncoded::default().line_width(8), &blocks),
+        "o12bo12b\no12bo12b\no12bo!"
+    );
+    assert_eq!(
+        body(RunLengthEncoded::default().line_width(0), &blocks),
+        "o12bo12bo12bo12bo12bo!"
+    );
+}
+
+#[test]
+fn runs_longer_than_a_line_start_it() {
+    // a run wider than the line can't fit anywhere, and a blank line before it is wasted
+    let row = (0..1000).map(|x| Pos2::new(x, 0)).collect::<Vec<_>>();
+    assert_eq!(
+        body(RunLengthEncoded::default().line_width(3), &row),
+        "1000o!"
+    );
+
+    let far = [Pos2::new(0, 0), Pos2::new(100_000, 0)];
+    assert_eq!(
+        body(RunLengthEncoded::default().line_width(4), &far),
+        "o\n99999b\no!"
+    );
+}
+
+#[test]
+fn multi_state_runs_wrap_like_plain_ones() {
+    let cells = [Pos2::new(0, 0), Pos2::new(1, 0)];
+    let dying = (2..40).map(|x| (Pos2::new(x, 0), 30)).collect::<Vec<_>>();
+    assert_eq!(
+        body(
+            RunLengthEncoded::default().set_dying(dying).line_width(5),
+            &cells
+        ),
+        "2A\n38pF!"
+    );
+}
+
+/// The contents of a file in `tests/fixtures/messy`, with the mistakes of files in the wild
+fn messy(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/messy/{}", FIXTURES, name)).unwrap()
+}
+
+#[test]
+fn lenient_decoding_reads_messy_files() {
+    let populations = [
+        ("crlf.rle", 5),
+        ("uppercase.rle", 28),
+        ("spaced.rle", 5),
+        ("compact-header.rle", 5),
+        ("trailing.rle", 3),
+    ];
+    for (name, population) in populations {
+        let cells = RunLengthEncoded::lenient().try_decode(&messy(name));
+        assert_eq!(cells.map(|cells| cells.len()), Ok(population), "{}", name);
+    }
+
+    // the copperhead is the same as the tidy file once its case is folded
+    let tidy = enc::decode_file(format!("{}/copperhead.rle", FIXTURES), None).unwrap();
+    let cells = RunLengthEncoded::lenient().decode(&messy("uppercase.rle"));
+    assert_eq!(cells, tidy.cells);
+}
+
+#[test]
+fn strict_decoding_locates_the_mistakes() {
+    let syntax = |line, column, reason: &str| {
+        Err(DecodeError::Syntax {
+            line,
+            column,
+            reason: reason.to_owned(),
+        })
+    };
+    let errors = [
+        (
+            "crlf.rle",
+            syntax(1, 10, "carriage return, lines have to end with `\\n` alone"),
+        ),
+        (
+            "uppercase.rle",
+            syntax(3, 1, "state 2 in a rule of 2 states"),
+        ),
+        (
+            "spaced.rle",
+            syntax(3, 2, "whitespace between a run count and its tag"),
+        ),
+        (
+            "compact-header.rle",
+            syntax(1, 1, "expected `x = ` in the header"),
+        ),
+        (
+            "trailing.rle",
+            syntax(5, 1, "characters after the final `!`"),
+        ),
+    ];
+    for (name, error) in errors {
+        let cells = RunLengthEncoded::default().try_decode(&messy(name));
+        assert_eq!(cells, error, "{}", name);
+    }
+}
+
+#[test]
+fn strict_decoding_checks_the_header() {
+    let decode = |rle: &str| {
+        RunLengthEncoded::default()
+            .try_decode(rle)
+            .map_err(|e| e.to_string())
+    };
+    assert_eq!(
+        decode("x = 3, y = 1, rule = 23/3\n3o!").map(|c| c.len()),
+        Ok(3)
+    );
+    assert_eq!(decode("x = 3, y = 1\n3o!").map(|c| c.len()), Ok(3));
+    assert_eq!(
+        decode("x = 3, y = one\n3o!"),
+        Err("line 1, column 12: invalid `y` in the header".to_owned())
+    );
+    assert_eq!(
+        decode("x = 3, y = 1, rule = B9/S23\n3o!"),
+        Err("line 1, column 22: invalid `rule` in the header".to_owned())
+    );
+    assert_eq!(
+        decode("x = 3, y = 1, rule = B3/S23, z = 0\n3o!"),
+        Err("line 1, column 29: too many fields in the header".to_owned())
+    );
+    assert_eq!(
+        decode("x = 3, y = 1\n3o"),
+        Err("line 2, column 3: missing the final `!`".to_owned())
+    );
+
+    // a Generations rule has the multi-state letters of its states, and no more
+    assert_eq!(
+        decode("x = 3, y = 1, rule = B2/S/C3\n2AB!").map(|c| c.len()),
+        Ok(2)
+    );
+    assert_eq!(
+        decode("x = 3, y = 1, rule = B2/S/C3\n2AC!"),
+        Err("line 2, column 3: state 3 in a rule of 3 states".to_owned())
+    );
+}
+
+#[test]
+fn everything_encoded_decodes_strictly() {
+    for pattern in patterns::PATTERNS {
+        let cells = pattern.cells();
+        let rle = RunLengthEncoded::default()
+            .set_name(pattern.name)
+            .encode(&cells);
+        assert_eq!(RunLengthEncoded::default().try_decode(&rle), Ok(cells));
+    }
+}
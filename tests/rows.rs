@@ -0,0 +1,117 @@
+//! Grouping the alive cells by row, and the window and encoder built on it
+
+use cgolrs::{
+    enc::{PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+    transform, Pos2, Rect,
+};
+
+/// The rows of `game` from a plain scan, with the slice of each row copied out
+fn scanned_rows(game: &GameOfLife) -> Vec<(i32, Vec<Pos2>)> {
+    let mut rows: Vec<(i32, Vec<Pos2>)> = Vec::new();
+    for &pos in game.alive() {
+        match rows.last_mut() {
+            Some((y, row)) if *y == pos.y => row.push(pos),
+            _ => rows.push((pos.y, vec![pos])),
+        }
+    }
+    rows
+}
+
+fn check_rows(game: &GameOfLife) {
+    let rows = game
+        .rows()
+        .map(|(y, row)| (y, row.to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(rows, scanned_rows(game));
+    for (y, row) in &rows {
+        assert_eq!(game.row(*y), &row[..]);
+        assert!(game.row(y - 1).is_empty() || rows.iter().any(|(other, _)| *other == y - 1));
+    }
+}
+
+#[test]
+fn single_cells() {
+    let game = GameOfLife::from_alive(vec![Pos2::new(-4, 9)]);
+    assert!(game.rows().eq([(9, &[Pos2::new(-4, 9)][..])]));
+    assert!(game.row(8).is_empty());
+    assert!(game.row(10).is_empty());
+
+    let empty = GameOfLife::from_alive(Vec::new());
+    assert_eq!(empty.rows().count(), 0);
+    assert!(empty.row(0).is_empty());
+}
+
+#[test]
+fn adjacent_rows() {
+    let game = GameOfLife::from_grid(&[[true; 4], [false, true, true, false], [true; 4]]);
+    check_rows(&game);
+    let lengths = game.rows().map(|(y, row)| (y, row.len()));
+    assert!(lengths.eq([(0, 4), (1, 2), (2, 4)]));
+}
+
+#[test]
+fn large_gaps() {
+    let cells = [(0, i32::MIN + 2), (-9, -1_000_000), (5, -1_000_000), (3, 0), (1, 1 << 30)];
+    let game = GameOfLife::from_unsorted(cells.map(Pos2::from).to_vec());
+    check_rows(&game);
+    assert_eq!(game.rows().count(), 4);
+    assert_eq!(game.row(-1_000_000).len(), 2);
+    assert!(game.row(-999_999).is_empty());
+    assert!(game.row(i32::MAX).is_empty());
+    assert!(game.row(i32::MIN).is_empty());
+}
+
+#[test]
+fn soups() {
+    for seed in 0..20 {
+        let game = GameOfLife::random(Rect::from_size(Pos2::new(-30, -20), 60, 40), 0.1, seed).unwrap();
+        check_rows(&game);
+
+        // the window and the encoder go through the rows too
+        let rect = Rect::new(Pos2::new(-12, -25), Pos2::new(7, 9));
+        let filtered = game.alive().iter().filter(|&&p| rect.contains(p));
+        assert!(game.window(rect).iter().eq(filtered));
+        let rle = RunLengthEncoded::default().encode(game.alive());
+        let decoded = RunLengthEncoded::default().decode(&rle);
+        assert_eq!(transform::recenter(&decoded), transform::recenter(game.alive()));
+    }
+}
+
+#[test]
+fn window_rows_are_clipped_to_the_window() {
+    // rows 0 and 2 only have cells left and right of the window
+    let cells = [(-20, 0), (20, 0), (-3, 1), (4, 1), (-30, 2), (-2, 3), (0, 3), (7, 3), (0, 6)];
+    let game = GameOfLife::from_unsorted(cells.map(Pos2::from).to_vec());
+    let window = game.window(Rect::new(Pos2::new(-5, -1), Pos2::new(5, 5)));
+    let rows = window.rows().collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            (1, &[Pos2::new(-3, 1), Pos2::new(4, 1)][..]),
+            (3, &[Pos2::new(-2, 3), Pos2::new(0, 3)][..]),
+        ]
+    );
+    assert_eq!(window.row_counts(), [(1, 2), (3, 2)]);
+
+    // a window that only covers the cells outside of its rows' x-ranges
+    let window = game.window(Rect::new(Pos2::new(-15, -1), Pos2::new(-5, 3)));
+    assert_eq!(window.rows().count(), 0);
+    assert!(window.row_counts().is_empty());
+}
+
+#[test]
+fn window_rows_match_the_cells_of_the_window() {
+    for seed in 0..20 {
+        let game = GameOfLife::random(Rect::from_size(Pos2::new(-30, -20), 60, 40), 0.1, seed).unwrap();
+        let rect = Rect::new(Pos2::new(-25, -12), Pos2::new(-3, 9));
+        let window = game.window(rect);
+        let cells = window.rows().flat_map(|(y, row)| {
+            assert!(row.iter().all(|p| p.y == y));
+            row
+        });
+        assert!(cells.eq(window.iter()));
+        let counted = window.row_counts().iter().map(|&(_, count)| count).sum::<usize>();
+        assert_eq!(counted, window.alive_count());
+    }
+}
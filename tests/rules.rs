@@ -0,0 +1,448 @@
+//! Other rules than Conway's Life, in the engine and on the command line
+
+mod common;
+
+use cgolrs::{
+    enc,
+    engine::{GameOfLife, RunOutcome},
+    Pos2, Rect, Rule,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{collections::HashMap, path::PathBuf, process::Output};
+
+/// The next generation of `alive` under `rule`, counting the neighbors of every cell the slow way
+fn reference_step(alive: &[Pos2], rule: Rule) -> Vec<Pos2> {
+    let mut neighbors = HashMap::<Pos2, u32>::new();
+    for &pos in alive {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) {
+                    *neighbors.entry(pos + Pos2::new(dx, dy)).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut next = neighbors
+        .into_iter()
+        .filter(|&(pos, count)| match alive.binary_search(&pos) {
+            Ok(_) => rule.survives(count),
+            Err(_) => rule.is_born(count),
+        })
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>();
+    // cells without neighbors survive in rules with S0
+    if rule.survives(0) {
+        next.extend(alive.iter().filter(|&&pos| {
+            (-1..=1).all(|dy| {
+                (-1..=1).all(|dx| {
+                    (dx, dy) == (0, 0) || alive.binary_search(&(pos + Pos2::new(dx, dy))).is_err()
+                })
+            })
+        }));
+    }
+    next.sort_unstable();
+    next
+}
+
+#[test]
+fn soups_follow_their_rule() {
+    let mut rng = StdRng::seed_from_u64(352);
+    for name in ["seeds", "highlife", "day-and-night", "life-without-death", "replicator"] {
+        let rule = Rule::from_name(name).unwrap();
+        let soup = common::soup(&mut rng, Rect::from_size(Pos2::new(-24, -24), 48, 48), 0.35);
+        let mut games = [1, 4].map(|threads| {
+            let mut game = GameOfLife::from_alive(soup.clone());
+            game.set_rule(rule).unwrap();
+            game.set_threads(threads);
+            game.set_min_chunk_cells(1);
+            game
+        });
+        let mut incremental = GameOfLife::from_alive(soup.clone());
+        incremental.set_rule(rule).unwrap();
+        incremental.set_incremental(true);
+
+        let mut expected = soup;
+        for gen in 1..=3 {
+            expected = reference_step(&expected, rule);
+            for game in games.iter_mut().chain([&mut incremental]) {
+                game.next_generation();
+                assert_eq!(game.alive(), expected, "{} generation {}", name, gen);
+            }
+        }
+    }
+}
+
+/// The next generation of a Generations rule, where `cells` maps every cell that isn't dead to
+/// its state
+fn reference_generations_step(cells: &HashMap<Pos2, u8>, rule: Rule) -> HashMap<Pos2, u8> {
+    let mut alive = cells
+        .iter()
+        .filter(|&(_, &state)| state == 1)
+        .map(|(&pos, _)| pos)
+        .collect::<Vec<_>>();
+    alive.sort_unstable();
+    let mut next = cells
+        .iter()
+        .map(|(&pos, &state)| (pos, state.max(1) + 1))
+        .filter(|&(_, state)| state < rule.states())
+        .collect::<HashMap<_, _>>();
+    // dying cells can't be born again
+    for pos in reference_step(&alive, rule) {
+        if matches!(cells.get(&pos), None | Some(1)) {
+            next.insert(pos, 1);
+        }
+    }
+    next
+}
+
+#[test]
+fn generations_soups_follow_their_rule() {
+    let mut rng = StdRng::seed_from_u64(354);
+    for name in ["brians-brain", "star-wars"] {
+        let rule = Rule::from_name(name).unwrap();
+        let soup = common::soup(&mut rng, Rect::from_size(Pos2::new(-24, -24), 48, 48), 0.35);
+        let mut games = [1, 4].map(|threads| {
+            let mut game = GameOfLife::from_alive(soup.clone());
+            game.set_rule(rule).unwrap();
+            game.set_threads(threads);
+            game.set_min_chunk_cells(1);
+            game
+        });
+        let mut incremental = GameOfLife::from_alive(soup.clone());
+        incremental.set_rule(rule).unwrap();
+        incremental.set_incremental(true);
+
+        let mut expected = soup.iter().map(|&pos| (pos, 1)).collect::<HashMap<_, _>>();
+        for gen in 1..=8 {
+            expected = reference_generations_step(&expected, rule);
+            let mut cells = expected.iter().map(|(&pos, &state)| (pos, state)).collect::<Vec<_>>();
+            cells.sort_unstable();
+            let (alive, dying): (Vec<_>, Vec<_>) = cells.into_iter().partition(|&(_, s)| s == 1);
+            let alive = alive.into_iter().map(|(pos, _)| pos).collect::<Vec<_>>();
+            for game in games.iter_mut().chain([&mut incremental]) {
+                game.next_generation();
+                assert_eq!(game.alive(), alive, "{} generation {}", name, gen);
+                assert_eq!(game.dying(), dying, "{} generation {}", name, gen);
+            }
+        }
+    }
+}
+
+/// A bounded grid with a background of a single state everywhere around it, the slow way to
+/// step rules with B0
+struct DenseReference {
+    min: Pos2,
+    rows: Vec<Vec<bool>>,
+    background: bool,
+}
+impl DenseReference {
+    fn get(&self, pos: Pos2) -> bool {
+        let rel = pos - self.min;
+        let row = usize::try_from(rel.y).ok().and_then(|y| self.rows.get(y));
+        let cell = row.and_then(|row| usize::try_from(rel.x).ok().and_then(|x| row.get(x)));
+        cell.copied().unwrap_or(self.background)
+    }
+    fn step(&mut self, rule: Rule) {
+        let next = |alive: bool, neighbors: u32| match alive {
+            true => rule.survives(neighbors),
+            false => rule.is_born(neighbors),
+        };
+        let rows = (0..self.rows.len() as i32)
+            .map(|y| {
+                (0..self.rows[0].len() as i32)
+                    .map(|x| {
+                        let pos = self.min + Pos2::new(x, y);
+                        let neighbors = (-1..=1)
+                            .flat_map(|dy| (-1..=1).map(move |dx| Pos2::new(dx, dy)))
+                            .filter(|&d| d != Pos2::zero() && self.get(pos + d))
+                            .count();
+                        next(self.get(pos), neighbors as u32)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.rows = rows;
+        self.background = next(self.background, if self.background { 8 } else { 0 });
+    }
+}
+
+#[test]
+fn b0_rules_match_a_dense_reference() {
+    const GENS: i32 = 6;
+    let mut rng = StdRng::seed_from_u64(353);
+    // AntiLife, a background that flips every generation, and one that flips with few cells
+    for rule in ["B0123478/S01234678", "B0123478/S3467", "B0/S", "B013/S012"] {
+        let rule = rule.parse::<Rule>().unwrap();
+        let soup = common::soup(&mut rng, Rect::from_size(Pos2::zero(), 24, 24), 0.4);
+        // the pattern can't grow further than one cell per generation
+        let rect = Rect::new(Pos2::new(-GENS - 1, -GENS - 1), Pos2::new(25 + GENS, 25 + GENS));
+        let mut reference = DenseReference {
+            min: rect.min,
+            rows: vec![vec![false; rect.width() as usize]; rect.height() as usize],
+            background: false,
+        };
+        for &pos in &soup {
+            let rel = pos - rect.min;
+            reference.rows[rel.y as usize][rel.x as usize] = true;
+        }
+        let mut games = [(1, false), (4, false), (1, true)].map(|(threads, incremental)| {
+            let mut game = GameOfLife::from_alive(soup.clone());
+            game.set_rule(rule).unwrap();
+            game.set_threads(threads);
+            game.set_min_chunk_cells(1);
+            game.set_incremental(incremental);
+            game
+        });
+
+        for gen in 1..=GENS {
+            reference.step(rule);
+            for game in &mut games {
+                game.next_generation();
+                assert_eq!(game.background(), reference.background, "{} gen {}", rule, gen);
+                let window = game.window(rect);
+                assert_eq!(window.to_grid(), reference.rows, "{} generation {}", rule, gen);
+                let alive = reference.rows.iter().flatten().filter(|&&alive| alive).count();
+                assert_eq!(window.alive_count(), alive, "{} generation {}", rule, gen);
+                let corner = rect.min - Pos2::one();
+                assert_eq!(game.is_alive(corner), reference.background);
+            }
+        }
+    }
+}
+
+#[test]
+fn combining_games_on_an_alive_background() {
+    fn soup(rng: &mut StdRng, min: i32) -> GameOfLife {
+        let rect = Rect::from_size(Pos2::new(min, min), 16, 16);
+        GameOfLife::from_alive(common::soup(rng, rect, 0.4))
+    }
+    fn alive_background(rng: &mut StdRng) -> GameOfLife {
+        let mut game = soup(rng, 0);
+        // AntiLife keeps the background alive once it's born
+        game.set_rule("B0123478/S01234678".parse().unwrap()).unwrap();
+        game.next_generation();
+        assert!(game.background());
+        game
+    }
+    let mut rng = StdRng::seed_from_u64(3530);
+    let games = [
+        alive_background(&mut rng),
+        alive_background(&mut rng),
+        soup(&mut rng, 8),
+        soup(&mut rng, -8),
+    ];
+    let rect = Rect::new(Pos2::new(-12, -12), Pos2::new(28, 28));
+    for (i, a) in games.iter().enumerate() {
+        for (j, b) in games.iter().enumerate() {
+            for name in ["merge", "difference", "intersection"] {
+                let (combined, keep): (_, fn(bool, bool) -> bool) = match name {
+                    "merge" => {
+                        let mut merged = a.clone();
+                        merged.merge(b);
+                        (merged, |a, b| a || b)
+                    }
+                    "difference" => (a.difference(b), |a, b| a && !b),
+                    _ => (a.intersection(b), |a, b| a && b),
+                };
+                let what = format!("{} of games {} and {}", name, i, j);
+                let background = keep(a.background(), b.background());
+                assert_eq!(combined.background(), background, "{}", what);
+                assert_eq!(combined.is_alive(rect.min - Pos2::one()), background);
+                for pos in rect.iter() {
+                    let expected = keep(a.is_alive(pos), b.is_alive(pos));
+                    assert_eq!(combined.is_alive(pos), expected, "{} at {}", what, pos);
+                }
+            }
+        }
+    }
+
+    // inserted cells are alive, even where the background has dead cells
+    let mut game = alive_background(&mut rng);
+    let dead = game.alive().to_vec();
+    game.insert_pattern(&dead[..3], Pos2::zero());
+    assert!(game.background());
+    assert!(dead[..3].iter().all(|&pos| game.is_alive(pos)));
+    assert!(dead[3..].iter().all(|&pos| !game.is_alive(pos)));
+}
+
+#[test]
+fn an_alive_background_is_never_extinct() {
+    // in B0/S8 the empty grid comes alive everywhere and stays that way
+    let mut game = GameOfLife::from_alive(Vec::new());
+    game.set_rule("B0/S8".parse().unwrap()).unwrap();
+    assert_eq!(game.run_until(4, |_| false), RunOutcome::LimitReached);
+    assert!(game.background());
+    // there are no dead cells to store
+    assert_eq!(game.alive_count(), 0);
+
+    // which the stats report as infinitely many alive cells
+    assert_eq!(game.step_with(|event| event.alive_count()), None);
+}
+
+/// Runs the binary in a fresh directory of the test, writing the final cells to `out.rle`
+fn gol(test: &str, args: &[&str]) -> (Output, PathBuf) {
+    let dir = common::temp_dir("rules", test);
+    let output = common::succeed(common::gol(&dir).args(["-q", "-o", "out.rle"]).args(args));
+    (output, dir)
+}
+
+#[test]
+fn seeds_by_name() {
+    // a diagonal domino oscillates in Seeds, while it dies right away in Life
+    let diagonal = [(0, 0), (1, 1)].map(Pos2::from).to_vec();
+    let flipped = [(1, 0), (0, 1)].map(Pos2::from).to_vec();
+    let domino = ["--fill", "diag", "-w", "2", "-h", "2"];
+    for (gens, expected) in [("1", &flipped), ("2", &diagonal)] {
+        let args = [&domino[..], &["--rule", "seeds", "--gens", gens]].concat();
+        let (output, dir) = gol("seeds", &args);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rule: B2/S\n"), "{}", stdout);
+
+        let rle = std::fs::read_to_string(dir.join("out.rle")).unwrap();
+        assert!(rle.contains("rule = B2/S\n"), "{}", rle);
+        assert_eq!(&enc::decode_file(dir.join("out.rle"), None).unwrap().cells, expected);
+    }
+    let (_, dir) = gol("life", &[&domino[..], &["--gens", "1"]].concat());
+    assert!(enc::decode_file(dir.join("out.rle"), None).unwrap().cells.is_empty());
+}
+
+#[test]
+fn rules_come_from_the_input() {
+    let dir = std::env::temp_dir().join(format!("cgolrs-rules-input-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("highlife.rle");
+    std::fs::write(&input, "x = 3, y = 1, rule = B36/S23\n3o!\n").unwrap();
+    let input = input.display().to_string();
+
+    let (output, dir) = gol("input", &["-i", &input, "--gens", "2"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("rule: B36/S23\n"));
+    assert!(std::fs::read_to_string(dir.join("out.rle")).unwrap().contains("rule = B36/S23"));
+
+    // --rule wins over the input's rule, in any notation
+    let (output, _) = gol("override", &["-i", &input, "--gens", "2", "--rule", "23/3"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("rule: B3/S23\n"));
+}
+
+#[test]
+fn lists_the_rules() {
+    let (output, _) = gol("list", &["--rule", "list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), Rule::PRESETS.len());
+    assert!(stdout.contains("day-and-night"), "{}", stdout);
+    assert!(stdout.contains("B3678/S34678"), "{}", stdout);
+}
+
+#[test]
+fn b0_rules_on_the_command_line() {
+    // the background flips every generation, so even generations save exactly
+    let block = ["--fill", "cross", "-w", "3", "-h", "3"];
+    let flipping = [&block[..], &["--rule", "B0123478/S3467", "--gens", "2"]].concat();
+    let (output, dir) = gol("flipping", &flipping);
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+    let rle = std::fs::read_to_string(dir.join("out.rle")).unwrap();
+    assert!(rle.contains("rule = B0123478/S3467\n"), "{}", rle);
+
+    // in AntiLife the background stays alive, which only saves around the dead cells
+    let antilife = [&block[..], &["--rule", "B0123478/S01234678", "--gens", "1"]].concat();
+    let (output, _) = gol("antilife", &antilife);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("alive background"), "{}", stderr);
+}
+
+#[test]
+fn generations_rules_save_and_resume_their_dying_cells() {
+    let grid = ["--fill", "checkerboard", "-w", "12", "-h", "12", "--rule", "brians-brain"];
+    let (_, dir) = gol("generations-start", &[&grid[..], &["--gens", "0"]].concat());
+    let start = enc::decode_file(dir.join("out.rle"), None).unwrap();
+    let mut game = GameOfLife::from_alive(start.cells);
+    game.set_rule(Rule::from_name("brians-brain").unwrap()).unwrap();
+    for _ in 0..3 {
+        game.next_generation();
+    }
+
+    let (_, dir) = gol("generations", &[&grid[..], &["--gens", "3"]].concat());
+    let rle = std::fs::read_to_string(dir.join("out.rle")).unwrap();
+    assert!(rle.contains("rule = B2/S/C3\n"), "{}", rle);
+    let saved = enc::decode_file(dir.join("out.rle"), None).unwrap();
+    assert!(!saved.dying.is_empty());
+    assert_eq!((&saved.cells[..], &saved.dying[..]), (game.alive(), game.dying()));
+
+    // resuming keeps the dying cells, which block births next to them
+    let input = dir.join("out.rle").display().to_string();
+    let (_, dir) = gol("generations-resume", &["-i", &input, "--gens", "2"]);
+    let resumed = enc::decode_file(dir.join("out.rle"), None).unwrap();
+    for _ in 0..2 {
+        game.next_generation();
+    }
+    assert_eq!((&resumed.cells[..], &resumed.dying[..]), (game.alive(), game.dying()));
+}
+
+/// The neighborhood of [`Rule::build_table`] with the cells at the offsets from the center alive
+fn neighborhood(cells: &[(i32, i32)]) -> usize {
+    cells
+        .iter()
+        .map(|&(dx, dy)| 1 << ((dy + 1) * 3 + 1 - dx))
+        .sum()
+}
+
+#[test]
+fn hensel_letters_pick_arrangements() {
+    const CENTER: (i32, i32) = (0, 0);
+    let (nw, n, ne) = ((-1, -1), (0, -1), (1, -1));
+    let (w, e) = ((-1, 0), (1, 0));
+    let (sw, s, se) = ((-1, 1), (0, 1), (1, 1));
+
+    // in B2-a two adjacent neighbors don't give birth, in any rotation
+    let table = "B2-a/S12".parse::<Rule>().unwrap().build_table();
+    assert!(!table[neighborhood(&[n, ne])]);
+    assert!(!table[neighborhood(&[w, sw])]);
+    assert!(table[neighborhood(&[n, s])]);
+    assert!(table[neighborhood(&[nw, se])]);
+    assert!(table[neighborhood(&[CENTER, n, e])]);
+
+    // tlife keeps S2 except for two opposite neighbors, and the q shape of S4
+    let tlife: Rule = "B3/S2-i34q".parse().unwrap();
+    assert_eq!(tlife.to_string(), "B3/S2-i34q");
+    let table = tlife.build_table();
+    assert!(!table[neighborhood(&[CENTER, n, s])]);
+    assert!(!table[neighborhood(&[CENTER, w, e])]);
+    assert!(table[neighborhood(&[CENTER, n, e])]);
+    assert!(table[neighborhood(&[CENTER, n, ne, e, sw])]);
+    assert!(!table[neighborhood(&[CENTER, nw, n, ne, w])]);
+    assert!(table[neighborhood(&[n, w, se])]);
+
+    // all the letters of a count are the count itself
+    assert_eq!("B4cekainyqjrtwz/S".parse(), Ok(Rule::new(&[4], &[])));
+    let snowflakes: Rule = "B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e".parse().unwrap();
+    assert_eq!(snowflakes.to_string().parse(), Ok(snowflakes));
+    assert!("B3/S2-".parse::<Rule>().is_err());
+    assert!("B0c/S".parse::<Rule>().is_err());
+}
+
+#[test]
+fn map_rules_round_trip() {
+    let conway = concat!(
+        "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAA",
+        "aIDogIAAgACAAIAAAAAAAA"
+    );
+    assert_eq!(conway.parse(), Ok(Rule::CONWAY));
+
+    // a cell is only born below a single alive cell, so a cell falls down a row every generation
+    let falling = format!("MAP{}I{}", "A".repeat(21), "A".repeat(64));
+    let rule: Rule = falling.parse().unwrap();
+    assert_eq!(rule.to_string(), falling);
+    let mut game = GameOfLife::from_alive(vec![Pos2::new(3, -2)]);
+    game.set_rule(rule).unwrap();
+    game.set_threads(2);
+    for y in -1..5 {
+        game.next_generation();
+        assert_eq!(game.alive(), [Pos2::new(3, y)]);
+    }
+
+    // with the states of a Generations rule after the base64 slashes
+    let generations: Rule = format!("{}/C3", falling).parse().unwrap();
+    assert_eq!(generations.states(), 3);
+    assert_eq!(generations.to_string().parse(), Ok(generations));
+    assert!(format!("{}/3", falling).parse::<Rule>().is_ok());
+    assert!(falling[..falling.len() - 1].parse::<Rule>().is_err());
+}
@@ -0,0 +1,106 @@
+//! Finding sub-patterns within the alive cells, checked against trying every offset
+
+use cgolrs::{engine::GameOfLife, patterns, transform, Pos2, Rect};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The 4 phases of a glider, each moved to the origin
+fn glider_phases() -> Vec<Vec<Pos2>> {
+    let mut game = GameOfLife::from_unsorted(patterns::find("glider").unwrap().cells());
+    (0..4)
+        .map(|_| {
+            let phase = transform::recenter(game.alive());
+            game.next_generation();
+            phase
+        })
+        .collect()
+}
+
+/// A soup with every phase of a glider planted in an empty 7×7 box, and where they were planted
+fn planted_soup(seed: u64) -> (GameOfLife, Vec<Pos2>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let offsets = [(5, 5), (40, 8), (12, 30), (33, 36)].map(Pos2::from);
+    let boxes = offsets.map(|offset| Rect::from_size(offset - Pos2::new(2, 2), 7, 7));
+    let soup = (0..48)
+        .flat_map(|y| (0..48).map(move |x| Pos2::new(x, y)))
+        .filter(|&pos| !boxes.iter().any(|b| b.contains(pos)))
+        .filter(|_| rng.gen_bool(0.35))
+        .collect::<Vec<_>>();
+    let mut game = GameOfLife::from_alive(soup);
+    for (phase, &offset) in glider_phases().iter().zip(&offsets) {
+        game.insert_pattern(phase, offset);
+    }
+    (game, offsets.to_vec())
+}
+
+/// The offsets found by checking every cell of the needle at every position
+fn find_naive(game: &GameOfLife, needle: &[Pos2], isolated: bool) -> Vec<Pos2> {
+    let bounds = transform::bounding_box(needle);
+    let area = transform::bounding_box(game.alive());
+    Rect::new(area.min - bounds.max, area.max - bounds.min)
+        .iter()
+        .filter(|&offset| {
+            let needle_at = |pos: Pos2| needle.contains(&(pos - offset));
+            let moved = Rect::new(bounds.min + offset, bounds.max + offset);
+            needle.iter().all(|&cell| game.is_alive(cell + offset))
+                && (!isolated || moved.iter().all(|pos| needle_at(pos) || !game.is_alive(pos)))
+        })
+        .collect()
+}
+
+#[test]
+fn finds_every_glider_phase_in_a_soup() {
+    let (game, offsets) = planted_soup(362);
+    for (phase, &offset) in glider_phases().iter().zip(&offsets) {
+        let found = game.find(phase, true);
+        assert!(found.contains(&offset), "{:?} in {:?}", offset, found);
+        assert_eq!(found, find_naive(&game, phase, true));
+        let found = game.find(phase, false);
+        assert!(found.contains(&offset));
+        assert_eq!(found, find_naive(&game, phase, false));
+    }
+}
+
+#[test]
+fn finds_gliders_in_every_orientation() {
+    let (mut game, offsets) = planted_soup(3620);
+    // a glider flying in each other direction
+    let glider = glider_phases().swap_remove(0);
+    let turned = [
+        transform::rotate_cw(&glider),
+        transform::flip_y(&glider),
+        transform::rotate_ccw(&transform::flip_x(&glider)),
+    ];
+    let far = [(80, 0), (80, 30), (100, 60)].map(Pos2::from);
+    for (cells, &offset) in turned.iter().zip(&far) {
+        game.insert_pattern(cells, offset);
+    }
+
+    // every other phase of a glider is a reflection of the first one
+    let phases = glider_phases();
+    let mut matches = game.find_all_orientations(&phases[0], true);
+    matches.extend(game.find_all_orientations(&phases[1], true));
+    for (phase, &offset) in phases.iter().zip(&offsets) {
+        assert!(matches.contains(&transform::translate(phase, offset)));
+    }
+    for (cells, &offset) in turned.iter().zip(&far) {
+        assert!(matches.contains(&transform::translate(cells, offset)));
+    }
+
+    // and the same as looking for every orientation by hand
+    for phase in &phases[..2] {
+        let mut rotated = phase.clone();
+        let mut expected = Vec::new();
+        for _ in 0..4 {
+            for orientation in [rotated.clone(), transform::flip_x(&rotated)] {
+                let orientation = transform::recenter(&orientation);
+                for offset in find_naive(&game, &orientation, true) {
+                    expected.push(transform::translate(&orientation, offset));
+                }
+            }
+            rotated = transform::rotate_cw(&rotated);
+        }
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(game.find_all_orientations(phase, true), expected);
+    }
+}
@@ -0,0 +1,66 @@
+//! Saving and restoring games through serde, including payloads that were edited by hand
+#![cfg(feature = "serde")]
+
+use cgolrs::{engine::GameOfLife, Pos2, Rule};
+
+#[test]
+fn games_round_trip_through_json() {
+    let mut game = GameOfLife::from_alive_colored(vec![
+        (Pos2::new(0, 0), 0),
+        (Pos2::new(1, 0), 1),
+        (Pos2::new(2, 0), 1),
+    ]);
+    game.track_ages(true);
+    game.set_rule(Rule::from_name("highlife").unwrap()).unwrap();
+    game.next_generation();
+    game.next_generation();
+
+    let json = serde_json::to_string(&game).unwrap();
+    let restored: GameOfLife = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.alive(), game.alive());
+    assert_eq!(restored.generation(), 2);
+    assert_eq!(restored.rule(), game.rule());
+    assert_eq!(restored.ages(), game.ages());
+    assert_eq!(restored.colors(), game.colors());
+}
+
+#[test]
+fn dying_cells_round_trip_through_json() {
+    let mut game = GameOfLife::from_grid(&[[true, true]]);
+    game.set_rule(Rule::from_name("brians-brain").unwrap())
+        .unwrap();
+    game.next_generation();
+
+    let json = serde_json::to_string(&game).unwrap();
+    let restored: GameOfLife = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.alive(), game.alive());
+    assert_eq!(restored.dying(), game.dying());
+    assert_eq!(restored.ages(), None);
+    assert_eq!(restored.colors(), None);
+}
+
+#[test]
+fn unsorted_cells_are_sorted_with_their_ages() {
+    let json = r#"{
+        "alive": [{"x": 2, "y": 0}, {"x": 0, "y": 1}, {"x": 2, "y": 0}, {"x": 1, "y": 0}],
+        "ages": [3, 4, 5, 6]
+    }"#;
+    let game: GameOfLife = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        game.alive(),
+        [Pos2::new(1, 0), Pos2::new(2, 0), Pos2::new(0, 1)]
+    );
+    // the first of the duplicates is kept
+    assert_eq!(game.ages(), Some(&[6, 3, 4][..]));
+    assert_eq!(game.generation(), 0);
+    assert_eq!(game.rule(), Rule::CONWAY);
+}
+
+#[test]
+fn mismatched_ages_are_rejected() {
+    let json = r#"{"alive": [{"x": 0, "y": 0}], "ages": [1, 2]}"#;
+    let err = serde_json::from_str::<GameOfLife>(json).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("ages has 2 entries for 1 alive cells"));
+}
@@ -0,0 +1,112 @@
+//! The incremental state hash of the engine, which has to match a hash of the state from scratch
+//! however the state was reached
+
+mod common;
+
+use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect, Rule};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// A square soup of `size` cells around the origin
+fn soup(rng: &mut StdRng, size: u32) -> Vec<Pos2> {
+    let min = Pos2::new(-(size as i32) / 2, -(size as i32) / 2);
+    common::soup(rng, Rect::from_size(min, size, size), 0.35)
+}
+
+/// A new game with the same state as `game`, which hashes it from scratch
+fn rebuilt(game: &GameOfLife) -> GameOfLife {
+    let mut rebuilt = GameOfLife::from_alive(game.alive().to_vec());
+    rebuilt.set_rule(game.rule()).unwrap();
+    rebuilt.set_dying(game.dying().to_vec());
+    rebuilt
+}
+
+#[test]
+fn every_step_path_keeps_the_hash() {
+    let mut rng = StdRng::seed_from_u64(357);
+    // without S8 the background of the B0 rule flips back every other generation
+    let rules = ["B3/S23", "B36/S23", "B2/S/C3", "B345/S4567/C5", "B0123/S123"];
+    for rule in rules {
+        let rule = rule.parse::<Rule>().unwrap();
+        let soup = soup(&mut rng, 48);
+        let mut games = [1, 4].map(|threads| {
+            let mut game = GameOfLife::from_alive(soup.clone());
+            game.set_rule(rule).unwrap();
+            game.set_threads(threads);
+            game.set_min_chunk_cells(1);
+            game
+        });
+        let mut incremental = GameOfLife::from_alive(soup.clone());
+        incremental.set_rule(rule).unwrap();
+        incremental.set_incremental(true);
+
+        for gen in 1..=12 {
+            for game in games.iter_mut().chain([&mut incremental]) {
+                game.next_generation();
+            }
+            let hash = incremental.state_hash();
+            for game in &games {
+                assert_eq!(game.state_hash(), hash, "{} generation {}", rule, gen);
+            }
+            if !incremental.background() {
+                let expected = rebuilt(&incremental).state_hash();
+                assert_eq!(hash, expected, "{} generation {}", rule, gen);
+            }
+        }
+    }
+}
+
+#[test]
+fn equal_states_hash_equally() {
+    let glider = patterns::find("glider").unwrap().cells();
+    let mut game = GameOfLife::from_unsorted(glider.clone());
+    // a glider is back to its own shape, moved by one cell, every 4 generations
+    game.step_n(4);
+    game.translate(Pos2::new(-1, -1));
+    assert_eq!(game.alive(), GameOfLife::from_unsorted(glider.clone()).alive());
+    let expected = GameOfLife::from_unsorted(glider.clone()).state_hash();
+    assert_eq!(game.state_hash(), expected);
+
+    // set cell by cell, backwards
+    let mut game = GameOfLife::from_alive(Vec::new());
+    for &pos in glider.iter().rev() {
+        game.set_cell(pos, true);
+    }
+    assert_eq!(game.state_hash(), expected);
+
+    // merged from two halves
+    let (first, second) = glider.split_at(2);
+    let mut game = GameOfLife::from_unsorted(first.to_vec());
+    game.merge(&GameOfLife::from_unsorted(second.to_vec()));
+    assert_eq!(game.state_hash(), expected);
+
+    // rewound
+    let mut game = GameOfLife::from_unsorted(glider);
+    game.enable_history(4, 3);
+    game.step_n(10);
+    assert!(game.rewind(10));
+    assert_eq!(game.state_hash(), expected);
+}
+
+#[test]
+fn a_single_cell_changes_the_hash() {
+    let mut rng = StdRng::seed_from_u64(3570);
+    let mut game = GameOfLife::from_alive(soup(&mut rng, 16));
+    let hash = game.state_hash();
+    for y in -10..10 {
+        for x in -10..10 {
+            let pos = Pos2::new(x, y);
+            game.toggle_cell(pos);
+            assert_ne!(game.state_hash(), hash, "{:?}", pos);
+            game.toggle_cell(pos);
+            assert_eq!(game.state_hash(), hash, "{:?}", pos);
+        }
+    }
+
+    // dying cells count too
+    let mut game = GameOfLife::from_grid(&[[true, true]]);
+    game.set_rule(Rule::from_name("brians-brain").unwrap()).unwrap();
+    game.next_generation();
+    let mut alive_only = GameOfLife::from_alive(game.alive().to_vec());
+    alive_only.set_rule(game.rule()).unwrap();
+    assert_ne!(game.state_hash(), alive_only.state_hash());
+}
@@ -0,0 +1,153 @@
+//! The cadence of the periodic stats reports outside of console mode
+
+mod common;
+
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// The directory a test runs in, since runs write their stats to the working directory
+fn test_dir(test: &str) -> PathBuf {
+    common::temp_dir("stats", test)
+}
+
+/// The stdout of a short run on a small grid
+fn run(test: &str, flags: &[&str]) -> String {
+    let small = ["-w", "8", "-h", "8", "-f", "alternating", "--gens", "5"];
+    let output = common::succeed(common::gol(&test_dir(test)).args(small).args(flags));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn reports(stdout: &str) -> usize {
+    stdout.lines().filter(|line| line.contains("gen/s (avg ")).count()
+}
+
+#[test]
+fn zero_interval_reports_every_generation() {
+    let stdout = run("every", &["--report-interval", "0"]);
+    assert_eq!(reports(&stdout), 5, "{}", stdout);
+    assert!(stdout.lines().last().unwrap().starts_with("ran 5 generations"));
+}
+
+#[test]
+fn long_interval_only_prints_the_summary() {
+    let stdout = run("long", &["--report-interval", "60000"]);
+    assert_eq!(reports(&stdout), 0, "{}", stdout);
+    assert!(stdout.contains("ran 5 generations"));
+}
+
+#[test]
+fn quiet_only_prints_the_summary() {
+    let stdout = run("quiet", &["--quiet", "--report-interval", "0"]);
+    assert_eq!(reports(&stdout), 0, "{}", stdout);
+    assert!(stdout.lines().last().unwrap().starts_with("ran 5 generations"));
+}
+
+#[test]
+fn json_streams_one_object_per_generation() {
+    run("json", &["-q", "--stats-format", "json", "--stats-out", "run.ndjson"]);
+    let ndjson = std::fs::read_to_string(test_dir("json").join("run.ndjson")).unwrap();
+    let lines = ndjson.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6, "{}", ndjson);
+
+    // the alternating 8x8 grid starts with 32 alive cells
+    let mut alive = 32;
+    for (gen, line) in lines[..5].iter().enumerate() {
+        let field = |name: &str| -> i64 {
+            let start = line.find(&format!("\"{}\":", name)).unwrap() + name.len() + 3;
+            let end = line[start..].find([',', '}']).unwrap() + start;
+            line[start..end].parse().unwrap()
+        };
+        assert_eq!(field("gen"), gen as i64 + 1, "{}", line);
+        alive += field("births") - field("deaths");
+        assert_eq!(field("alive"), alive, "{}", line);
+    }
+    assert!(lines[5].starts_with(r#"{"summary":true,"gens":5,"#), "{}", lines[5]);
+}
+
+#[test]
+fn csv_has_every_recorded_generation() {
+    run("csv", &["-q", "--stats-out", "run.csv"]);
+    let csv = std::fs::read_to_string(test_dir("csv").join("run.csv")).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "gen,delta_t,alive,compute_us,render_us,idle_us,save_us");
+    // phase timing is off when quiet
+    assert!(lines[1..].iter().all(|line| line.ends_with(",,,,")), "{}", csv);
+    let gens = lines[1..]
+        .iter()
+        .map(|line| line.split(',').next().unwrap().parse::<u64>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(gens, [1, 2, 3, 4, 5], "{}", csv);
+}
+
+#[test]
+fn csv_and_reports_have_phase_timings() {
+    let stdout = run("phases", &["--stats-out", "run.csv", "--report-interval", "0"]);
+    assert!(stdout.contains("compute:"), "{}", stdout);
+
+    let csv = std::fs::read_to_string(test_dir("phases").join("run.csv")).unwrap();
+    for line in csv.lines().skip(1) {
+        let columns = line.split(',').collect::<Vec<_>>();
+        assert_eq!(columns.len(), 7, "{}", line);
+        assert!(columns[3..].iter().all(|c| c.parse::<u64>().is_ok()), "{}", line);
+    }
+}
+
+#[test]
+fn stats_go_to_every_file_and_the_console() {
+    let flags = ["--report-interval", "0", "--stats-out", "a.csv", "--stats-out", "b.ndjson"];
+    let stdout = run("multi", &flags);
+    assert_eq!(reports(&stdout), 5, "{}", stdout);
+
+    let dir = test_dir("multi");
+    let csv = std::fs::read_to_string(dir.join("a.csv")).unwrap();
+    let ndjson = std::fs::read_to_string(dir.join("b.ndjson")).unwrap();
+    assert_eq!(csv.lines().count(), 6, "{}", csv);
+    assert_eq!(ndjson.lines().count(), 6, "{}", ndjson);
+    assert!(!dir.join("perf.csv").exists());
+}
+
+#[test]
+fn duration_stops_an_endless_run() {
+    let start = Instant::now();
+    let output = common::gol(&test_dir("duration"))
+        .args(["-w", "8", "-h", "8", "-f", "alternating", "-q", "--duration", "0.3"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(10));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let summary = stdout.lines().last().unwrap();
+    assert!(summary.starts_with("ran ") && summary.contains("gen/s"), "{}", stdout);
+}
+
+/// Runs long enough to fill the write buffer of the stats file a few times over
+#[cfg(target_os = "linux")]
+fn run_into_full_disk(flags: &[&str]) -> std::process::Output {
+    common::gol(&test_dir("full-disk"))
+        .args(["-w", "8", "-h", "8", "-f", "alternating", "-q", "--gens", "5000"])
+        .args(["--stats-out", "/dev/full"])
+        .args(flags)
+        .output()
+        .unwrap()
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn unwritable_csv_stats_are_an_error() {
+    let output = run_into_full_disk(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // EX_IOERR from the final save, rather than a panic in the hook
+    assert_eq!(output.status.code(), Some(74), "{}", stderr);
+    assert!(stderr.starts_with("error: "), "{}", stderr);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn unwritable_json_stats_are_an_error() {
+    let output = run_into_full_disk(&["--stats-format", "json"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(74), "{}", stderr);
+    assert!(stderr.starts_with("error: "), "{}", stderr);
+}
@@ -0,0 +1,47 @@
+//! The spans and events of the `tracing` feature
+#![cfg(feature = "tracing")]
+
+use cgolrs::{
+    enc::{self, Format, PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+    patterns,
+};
+use tracing_test::traced_test;
+
+#[traced_test]
+#[test]
+fn steps_record_their_changes() {
+    let mut game = GameOfLife::from_alive(patterns::find("blinker").unwrap().cells());
+    game.next_generation();
+    assert!(logs_contain("next_generation{generation=0 threads=1"));
+    assert!(logs_contain("population=3 births=2 deaths=2 duration_us="));
+
+    game.next_generation_parallel(4);
+    assert!(logs_contain("next_generation{generation=1 threads=4"));
+}
+
+#[traced_test]
+#[test]
+fn codecs_log_byte_counts() {
+    let cells = patterns::find("glider").unwrap().cells();
+    let rle = RunLengthEncoded::default().encode(&cells);
+    assert!(logs_contain(&format!(
+        "encoded format=rle cells=5 bytes={}",
+        rle.len()
+    )));
+
+    let mut bytes = Vec::new();
+    enc::Binary::default()
+        .encode_to(&cells, &mut bytes)
+        .unwrap();
+    assert!(logs_contain(&format!(
+        "encoded format=bin cells=5 bytes={}",
+        bytes.len()
+    )));
+
+    Format::Binary.decode(&bytes).unwrap();
+    assert!(logs_contain(&format!(
+        "decoded format=bin bytes={} cells=5",
+        bytes.len()
+    )));
+}
@@ -0,0 +1,92 @@
+//! The standard traits of the engine: clones that are independent of the original, hashes that
+//! agree with equality, and a `Debug` summary that stays short however big the game is
+
+use cgolrs::{engine::GameOfLife, patterns, Pos2, Rect, Rule};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+#[test]
+fn debug_is_a_summary() {
+    let mut game = GameOfLife::from_unsorted(patterns::find("glider").unwrap().cells());
+    assert_eq!(
+        format!("{:?}", game),
+        "GameOfLife { alive: 5, bbox: (0, 0)..(3, 3), gen: 0 }"
+    );
+    game.step_n(4);
+    assert_eq!(
+        format!("{:?}", game),
+        "GameOfLife { alive: 5, bbox: (1, 1)..(4, 4), gen: 4 }"
+    );
+    let empty = GameOfLife::from_alive(Vec::new());
+    assert_eq!(
+        format!("{:?}", empty),
+        "GameOfLife { alive: 0, bbox: none, gen: 0 }"
+    );
+
+    // a big game is still a single short line, while the dump lists every cell
+    let soup = GameOfLife::random(Rect::new(Pos2::new(-10, -4), Pos2::new(213, 88)), 0.5, 365).unwrap();
+    let summary = format!("{:?}", soup);
+    assert!(summary.starts_with("GameOfLife { alive: "), "{}", summary);
+    assert!(summary.ends_with(", bbox: (-10, -4)..(213, 88), gen: 0 }"), "{}", summary);
+    assert!(summary.len() < 80);
+    let dump = soup.dump();
+    assert!(dump.len() > 10 * soup.alive_count());
+    assert!(dump.contains(&format!("{:?}", soup.alive()[0])));
+}
+
+#[test]
+fn clones_are_independent() {
+    let rule = Rule::from_name("brians-brain").unwrap();
+    let mut game = GameOfLife::random(Rect::from_size(Pos2::new(-16, -16), 32, 32), 0.4, 3650).unwrap();
+    game.set_rule(rule).unwrap();
+    game.track_ages(true);
+    game.enable_history(4, 2);
+    game.step_n(6);
+
+    let mut clone = game.clone();
+    assert!(clone.eq_strict(&game));
+    assert_eq!(clone.state_hash(), game.state_hash());
+    assert_eq!(clone.ages(), game.ages());
+
+    // stepping or editing one leaves the other as it was
+    let before = game.clone();
+    clone.step_n(3);
+    clone.set_cell(Pos2::new(100, 100), true);
+    assert!(game.eq_strict(&before));
+    assert_eq!(game.alive(), before.alive());
+    assert_eq!(game.dying(), before.dying());
+    assert!(clone != game);
+
+    // both keep their own history, and rewind to the same states
+    assert!(game.rewind(4));
+    assert!(clone.rewind(7));
+    assert!(clone.eq_strict(&game));
+
+    // a clone starts without the hooks of the original
+    let mut hooked = GameOfLife::from_grid(&[[true, true, true]]);
+    hooked.on_generation(|_| panic!("the clone called a hook of the original"));
+    let mut clone = hooked.clone();
+    clone.step_n(2);
+    assert_eq!(clone.alive(), hooked.alive());
+}
+
+#[test]
+fn hashes_agree_with_equality() {
+    let blinker = GameOfLife::from_grid(&[[true, true, true]]);
+    let mut stepped = blinker.clone();
+    stepped.step_n(2);
+    let mut flipped = blinker.clone();
+    flipped.next_generation();
+
+    let hash = |game: &GameOfLife| {
+        let mut hasher = DefaultHasher::new();
+        game.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert!(stepped == blinker);
+    assert_eq!(hash(&stepped), hash(&blinker));
+    assert!(flipped != blinker);
+    assert_ne!(hash(&flipped), hash(&blinker));
+}
@@ -0,0 +1,92 @@
+//! JavaScript bindings of the engine and the RLE codec, for embedding the simulator in a web
+//! page
+//!
+//! Build it with [`wasm-pack`](https://rustwasm.github.io/wasm-pack/), e.g.
+//! `wasm-pack build wasm --target web`, and drive a [`Life`] from JavaScript:
+//! ```js
+//! import init, { Life } from "./pkg/cgolrs_wasm.js";
+//!
+//! await init();
+//! const life = new Life("x = 3, y = 3\nbo$2bo$3o!");
+//! life.step(4);
+//! // interleaved x and y coordinates of the cells within the rectangle
+//! const cells = life.cells_in(0, 0, 10, 10);
+//! console.log(life.population(), life.to_rle());
+//! ```
+
+use cgolrs::{
+    enc::{Format, PositionEncoder, RunLengthEncoded},
+    engine::GameOfLife,
+    Pos2, Rect,
+};
+use js_sys::Int32Array;
+use wasm_bindgen::prelude::*;
+
+/// A game of life, with its rule and its generation
+#[wasm_bindgen]
+pub struct Life {
+    game: GameOfLife,
+}
+
+#[wasm_bindgen]
+impl Life {
+    /// Decodes an RLE pattern, along with its rule, generation and the dying cells of
+    /// Generations rules if it records them
+    #[wasm_bindgen(constructor)]
+    pub fn new(rle: &str) -> Result<Life, JsError> {
+        let pattern = Format::Rle
+            .decode(rle)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        let mut game = GameOfLife::from_alive(pattern.cells);
+        game.set_rule(pattern.rule.unwrap_or_default())
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        game.set_dying(pattern.dying);
+        game.set_generation(pattern.generation);
+        Ok(Life { game })
+    }
+
+    /// Computes the next `n` generations
+    pub fn step(&mut self, n: u32) {
+        self.game.step_n(n.into());
+    }
+
+    /// Does nothing, since a web page can't start the threads of a multi-threaded step, so
+    /// every generation is computed on one thread
+    pub fn set_threads(&mut self, _threads: usize) {}
+
+    /// The alive cells from `(x0, y0)` up to but excluding `(x1, y1)`, sorted by y and then x,
+    /// as their x and y coordinates one after the other
+    ///
+    /// Like [`GameOfLife::alive`], these are the dead cells if the background is alive.
+    pub fn cells_in(&self, x0: i32, y0: i32, x1: i32, y1: i32) -> Int32Array {
+        let rect = Rect::new(Pos2::new(x0, y0), Pos2::new(x1, y1));
+        let coords = self
+            .game
+            .window(rect)
+            .iter()
+            .flat_map(|pos| [pos.x, pos.y])
+            .collect::<Vec<_>>();
+        Int32Array::from(&coords[..])
+    }
+
+    /// The number of alive cells, or of the dead ones if the background is alive, see
+    /// [`GameOfLife::alive_count`]
+    pub fn population(&self) -> usize {
+        self.game.alive_count()
+    }
+
+    /// The current generation, counting from the one the pattern was saved at
+    pub fn generation(&self) -> f64 {
+        // JavaScript numbers are exact up to 2^53, which is plenty of generations
+        self.game.generation() as f64
+    }
+
+    /// Encodes the game as RLE, with its rule, generation and dying cells
+    pub fn to_rle(&self) -> String {
+        RunLengthEncoded::default()
+            .with_rule(&self.game.rule())
+            .set_generation(self.game.generation())
+            .set_dying(self.game.dying().to_vec())
+            .encode(&self.game.pattern_cells())
+    }
+}
@@ -0,0 +1,36 @@
+//! Runs in a JavaScript engine with `wasm-pack test --node wasm`
+#![cfg(target_arch = "wasm32")]
+
+use cgolrs_wasm::Life;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+
+#[wasm_bindgen_test]
+fn gliders_move_diagonally() {
+    let mut life = Life::new(GLIDER).unwrap();
+    let start = life.cells_in(-10, -10, 20, 20).to_vec();
+    assert_eq!(start, [1, 0, 2, 1, 0, 2, 1, 2, 2, 2]);
+
+    // a glider moves one cell down and right every 4 generations
+    life.set_threads(4);
+    life.step(8);
+    let moved = life.cells_in(-10, -10, 20, 20).to_vec();
+    let expected = start.iter().map(|c| c + 2).collect::<Vec<_>>();
+    assert_eq!(moved, expected);
+    assert_eq!(life.population(), 5);
+    assert_eq!(life.generation(), 8.0);
+
+    // only the cells within the rectangle
+    assert_eq!(life.cells_in(3, 2, 5, 3).to_vec(), [3, 2]);
+}
+
+#[wasm_bindgen_test]
+fn rle_round_trips() {
+    let mut life = Life::new(GLIDER).unwrap();
+    life.step(3);
+    let resumed = Life::new(&life.to_rle()).unwrap();
+    assert_eq!(resumed.generation(), 3.0);
+    let cells = |life: &Life| life.cells_in(-10, -10, 20, 20).to_vec();
+    assert_eq!(cells(&resumed), cells(&life));
+}